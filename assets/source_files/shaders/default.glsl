@@ -17,37 +17,108 @@ layout(location = 1) in vec3 in_normal;
 layout(location = 2) in vec2 in_uv;
 
 #io vertex fragment
-layout(location = 0) IN_OUT vec4 IN_OUT_vertex;
+layout(location = 0) IN_OUT vec3 IN_OUT_position;
 layout(location = 1) IN_OUT vec3 IN_OUT_normal;
 layout(location = 2) IN_OUT vec2 IN_OUT_uv;
 
 #fragment
-layout(binding = 1) uniform sampler2D tex_sampler;
+// must match `PushConstants` in scene_renderer.rs
+layout(push_constant) uniform PushConstants {
+    mat4 model;
+    vec4 base_color;
+    // rgb: emissive, a: alpha cutoff
+    vec4 emissive;
+    // r: metallic, g: roughness, b: normal scale, a: occlusion strength
+    vec4 surface;
+} pc;
+
+layout(set = 0, binding = 0) uniform UniformBufferObject {
+    mat4 view;
+    mat4 proj;
+} ubo;
+
+// in the order of `MaterialTextures::slots()`
+layout(set = 1, binding = 0) uniform sampler2D material_textures[5];
 
 layout(location = 0) out vec4 out_color;
 
 #vertex
 void main() {
-    out_vertex = ubo.proj * ubo.view * pc.model * vec4(in_vertex, 1.0);
+    vec4 position = pc.model * vec4(in_vertex, 1.0);
+
+    out_position = position.xyz;
     out_normal = mat3(transpose(inverse(pc.model))) * in_normal;
     out_uv = in_uv;
 
-    gl_Position = out_vertex;
+    gl_Position = ubo.proj * ubo.view * position;
 }
 
 #fragment
+/// the mesh has no tangents. the tangent frame is derived from the screen space derivatives of
+/// the position and the uv instead
+mat3 cotangent_frame(vec3 n, vec3 p, vec2 uv) {
+    vec3 dp1 = dFdx(p);
+    vec3 dp2 = dFdy(p);
+    vec2 duv1 = dFdx(uv);
+    vec2 duv2 = dFdy(uv);
+
+    vec3 dp2perp = cross(dp2, n);
+    vec3 dp1perp = cross(n, dp1);
+    vec3 t = dp2perp * duv1.x + dp1perp * duv2.x;
+    vec3 b = dp2perp * duv1.y + dp1perp * duv2.y;
+
+    float max_length_squared = max(dot(t, t), dot(b, b));
+    if (max_length_squared == 0.0) {
+        return mat3(vec3(0.0), vec3(0.0), n);
+    }
+
+    float inverse_max = inversesqrt(max_length_squared);
+    return mat3(t * inverse_max, b * inverse_max, n);
+}
+
 void main() {
-    vec3 light_direction = vec3(1.0, -1.0, 1.0);
+    vec4 base_color = pc.base_color * texture(material_textures[0], in_uv);
+    vec4 metallic_roughness = texture(material_textures[1], in_uv);
+    vec3 normal_sample = texture(material_textures[2], in_uv).rgb;
+    float occlusion_sample = texture(material_textures[3], in_uv).r;
+    vec3 emissive_sample = texture(material_textures[4], in_uv).rgb;
+
+    float alpha_cutoff = pc.emissive.a;
+    if (base_color.a < alpha_cutoff) {
+        discard;
+    }
+
+    // glTF stores roughness in g and metallic in b
+    float metallic = pc.surface.r * metallic_roughness.b;
+    float roughness = pc.surface.g * metallic_roughness.g;
+    float normal_scale = pc.surface.b;
+    float occlusion = mix(1.0, occlusion_sample, pc.surface.a);
+
+    vec3 tangent_normal = normal_sample * 2.0 - 1.0;
+    tangent_normal.xy *= normal_scale;
+    vec3 geometry_normal = normalize(in_normal);
+    mat3 tbn = cotangent_frame(geometry_normal, in_position, in_uv);
+    vec3 normal = normalize(tbn * tangent_normal);
+
+    vec3 light_direction = normalize(vec3(1.0, -1.0, 1.0));
     vec3 light_color = vec3(0.981, 0.912, 0.788);
     vec3 ambient_color = vec3(0.624, 0.321, 0.096);
-    vec3 object_color = vec3(1.0);
 
-    float diff = max(dot(in_normal, light_direction), 0.0);
-    vec3 diffuse = diff * light_color;
+    vec3 camera_position = inverse(ubo.view)[3].xyz;
+    vec3 view_direction = normalize(camera_position - in_position);
+    vec3 half_direction = normalize(light_direction + view_direction);
+
+    float diff = max(dot(normal, light_direction), 0.0);
+    vec3 lighting = ambient_color * occlusion + diff * light_color;
+    vec3 diffuse = (1.0 - metallic) * base_color.rgb * lighting;
+
+    float shininess = mix(256.0, 2.0, roughness);
+    float spec = pow(max(dot(normal, half_direction), 0.0), shininess) * float(diff > 0.0);
+    vec3 specular_color = mix(vec3(0.04), base_color.rgb, metallic);
+    vec3 specular = spec * specular_color * light_color * (1.0 - roughness * 0.5);
 
-    //out_color = texture(tex_sampler, in_uv);
-    //out_color = vec4(in_uv, 0.0, 1.0);
+    vec3 emissive = pc.emissive.rgb * emissive_sample;
 
-    vec3 result = (ambient_color + diffuse) * object_color;
+    vec3 result = diffuse + specular + emissive;
     out_color = vec4(result, 1.0);
 }
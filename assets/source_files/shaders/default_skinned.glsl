@@ -0,0 +1,55 @@
+#ris_glsl 450 vertex
+
+#vertex
+// must match `PushConstants` in scene_renderer.rs
+layout(push_constant) uniform PushConstants {
+    mat4 model;
+    vec4 base_color;
+    vec4 emissive;
+    vec4 surface;
+    // x: the index of the first joint matrix of this mesh, y: how many joint matrices it has
+    uvec4 skin;
+} pc;
+
+layout(set = 0, binding = 0) uniform UniformBufferObject {
+    mat4 view;
+    mat4 proj;
+} ubo;
+
+layout(set = 0, binding = 1) readonly buffer JointMatrices {
+    mat4 joint_matrices[];
+};
+
+layout(location = 0) in vec3 in_vertex;
+layout(location = 1) in vec3 in_normal;
+layout(location = 2) in vec2 in_uv;
+layout(location = 3) in uvec4 in_joints;
+layout(location = 4) in vec4 in_weights;
+
+// must match the io of default.glsl
+layout(location = 0) out vec3 out_position;
+layout(location = 1) out vec3 out_normal;
+layout(location = 2) out vec2 out_uv;
+
+void main() {
+    uint offset = pc.skin.x;
+
+    // a mesh may reference more joints than the skeleton of its animator has. clamp them, such
+    // that they never read the joint matrices of another mesh or past the end of the buffer
+    uvec4 joints = min(in_joints, uvec4(pc.skin.y - 1));
+
+    mat4 skin =
+        in_weights.x * joint_matrices[offset + joints.x] +
+        in_weights.y * joint_matrices[offset + joints.y] +
+        in_weights.z * joint_matrices[offset + joints.z] +
+        in_weights.w * joint_matrices[offset + joints.w];
+
+    mat4 model = pc.model * skin;
+    vec4 position = model * vec4(in_vertex, 1.0);
+
+    out_position = position.xyz;
+    out_normal = mat3(transpose(inverse(model))) * in_normal;
+    out_uv = in_uv;
+
+    gl_Position = ubo.proj * ubo.view * position;
+}
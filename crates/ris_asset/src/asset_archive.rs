@@ -0,0 +1,339 @@
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::io::Write;
+
+use ris_error::RisResult;
+use ris_io::FatPtr;
+
+// # File Format
+//
+// encoding: little-endian
+//
+// - [u8; 16]: magic `ris_assets\0\0\0\0\0\0"`
+// - u32: version
+// - FatPtr: p_original_asset_names
+// - u32: asset_count, the number of assets once this archive is applied
+// - u32: entry_count
+// - [Entry; entry_count]: entries
+//   - u32: id
+//   - FatPtr: p_content, the stored bytes
+//   - u64: uncompressed_len
+//   - u8: codec
+//   - u32: checksum, crc32 of the stored bytes
+// - [u8; ?]: assets
+// - [u8; ?]: original names (u32 count, followed by one string per entry)
+//
+// a compiled directory contains an entry for every asset, with the ids in order. a patch only
+// contains the entries that changed or were added. the ids of removed assets are at or above
+// `asset_count`, or they are not patched, when assets after them keep their ids.
+
+pub const MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5F, 0x61, 0x73, 0x73, 0x65, 0x74, 0x73, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+
+pub const VERSION: u32 = 2;
+
+const ENTRY_SIZE: u64 = 4 + 16 + 8 + 1 + 4;
+
+pub const COMPRESSION_LEVEL: u8 = 6;
+
+/// How the bytes of an entry are stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    None,
+    Deflate,
+}
+
+impl From<Codec> for u8 {
+    fn from(value: Codec) -> Self {
+        match value {
+            Codec::None => 0,
+            Codec::Deflate => 1,
+        }
+    }
+}
+
+impl TryFrom<u8> for Codec {
+    type Error = ris_error::RisError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Codec::None),
+            1 => Ok(Codec::Deflate),
+            _ => ris_error::new_result!("unknown codec: {}", value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArchiveEntry {
+    pub id: usize,
+    pub p_content: FatPtr,
+    pub uncompressed_len: u64,
+    pub codec: Codec,
+    pub checksum: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveHeader {
+    pub p_original_asset_names: FatPtr,
+    pub asset_count: usize,
+    pub entries: Vec<ArchiveEntry>,
+}
+
+/// An asset as it is stored in the archive.
+pub struct EncodedAsset {
+    pub bytes: Vec<u8>,
+    pub uncompressed_len: u64,
+    pub codec: Codec,
+    pub checksum: u32,
+}
+
+impl ArchiveHeader {
+    /// The number of bytes the header takes up, such that the assets can be written before it.
+    pub fn size(entry_count: usize) -> u64 {
+        16 + 4 + 16 + 4 + 4 + ENTRY_SIZE * entry_count as u64
+    }
+
+    pub fn read(stream: &mut impl Read) -> RisResult<Self> {
+        let mut magic = [0u8; 16];
+        ris_io::read(stream, &mut magic)?;
+        if !ris_util::testing::bytes_eq(&magic, &MAGIC) {
+            return ris_error::new_result!("unkown magic value: {:?}", magic);
+        }
+
+        let version = ris_io::read_u32(stream)?;
+        if version != VERSION {
+            return ris_error::new_result!(
+                "unsupported archive version {}, expected {}. the archive must be recompiled",
+                version,
+                VERSION,
+            );
+        }
+
+        let p_original_asset_names = ris_io::read_fat_ptr(stream)?;
+        let asset_count = ris_io::read_uint(stream)?;
+
+        let entry_count = ris_io::read_uint(stream)?;
+        let mut entries = Vec::with_capacity(entry_count);
+        for _ in 0..entry_count {
+            let id = ris_io::read_uint(stream)?;
+            if id >= asset_count {
+                return ris_error::new_result!(
+                    "entry {} is out of range, the archive contains {} assets",
+                    id,
+                    asset_count,
+                );
+            }
+
+            let p_content = ris_io::read_fat_ptr(stream)?;
+            let uncompressed_len = ris_io::read_u64(stream)?;
+            let codec = Codec::try_from(ris_io::read_u8(stream)?)?;
+            let checksum = ris_io::read_u32(stream)?;
+            entries.push(ArchiveEntry {
+                id,
+                p_content,
+                uncompressed_len,
+                codec,
+                checksum,
+            });
+        }
+
+        Ok(Self {
+            p_original_asset_names,
+            asset_count,
+            entries,
+        })
+    }
+
+    pub fn write(&self, stream: &mut (impl Write + Seek)) -> RisResult<()> {
+        ris_io::write(stream, &MAGIC)?;
+        ris_io::write_u32(stream, VERSION)?;
+        ris_io::write_fat_ptr(stream, self.p_original_asset_names)?;
+        ris_io::write_uint(stream, self.asset_count)?;
+        ris_io::write_uint(stream, self.entries.len())?;
+        for entry in self.entries.iter() {
+            ris_io::write_uint(stream, entry.id)?;
+            ris_io::write_fat_ptr(stream, entry.p_content)?;
+            ris_io::write_u64(stream, entry.uncompressed_len)?;
+            ris_io::write_u8(stream, entry.codec.into())?;
+            ris_io::write_u32(stream, entry.checksum)?;
+        }
+
+        Ok(())
+    }
+}
+
+pub fn read_original_names(
+    stream: &mut (impl Read + Seek),
+    p_original_asset_names: FatPtr,
+) -> RisResult<Vec<String>> {
+    if p_original_asset_names.len == 0 {
+        return Ok(Vec::new());
+    }
+
+    ris_io::seek(stream, SeekFrom::Start(p_original_asset_names.addr))?;
+    let original_name_count = ris_io::read_uint(stream)?;
+    let mut original_names = Vec::with_capacity(original_name_count);
+    for _ in 0..original_name_count {
+        let original_name = ris_io::read_string(stream)?;
+        original_names.push(original_name);
+    }
+
+    Ok(original_names)
+}
+
+/// Writes the assets in the given order, followed by their original names and the header.
+/// `original_names` is optional, but if it is given, it must contain a name for every asset.
+pub fn write_archive(
+    stream: &mut (impl Write + Seek),
+    asset_count: usize,
+    assets: Vec<(usize, EncodedAsset)>,
+    original_names: Option<Vec<String>>,
+) -> RisResult<()> {
+    // the header is written last, when the entries are known
+    let header_size = ArchiveHeader::size(assets.len());
+    ris_io::seek(stream, SeekFrom::Start(header_size))?;
+
+    let mut entries = Vec::with_capacity(assets.len());
+    for (id, encoded) in assets.iter() {
+        let p_content = ris_io::write(stream, &encoded.bytes)?;
+        entries.push(ArchiveEntry {
+            id: *id,
+            p_content,
+            uncompressed_len: encoded.uncompressed_len,
+            codec: encoded.codec,
+            checksum: encoded.checksum,
+        });
+    }
+
+    let p_original_asset_names = match original_names {
+        Some(original_names) => {
+            if original_names.len() != assets.len() {
+                return ris_error::new_result!(
+                    "expected {} original names but got {}",
+                    assets.len(),
+                    original_names.len(),
+                );
+            }
+
+            let begin = ris_io::seek(stream, SeekFrom::Current(0))?;
+            ris_io::write_uint(stream, original_names.len())?;
+            for original_name in original_names.iter() {
+                ris_io::write_string(stream, original_name)?;
+            }
+            let end = ris_io::seek(stream, SeekFrom::Current(0))?;
+            FatPtr::begin_end(begin, end)?
+        }
+        None => {
+            let addr = ris_io::seek(stream, SeekFrom::Current(0))?;
+            FatPtr { addr, len: 0 }
+        }
+    };
+
+    let header = ArchiveHeader {
+        p_original_asset_names,
+        asset_count,
+        entries,
+    };
+    ris_io::seek(stream, SeekFrom::Start(0))?;
+    header.write(stream)?;
+
+    Ok(())
+}
+
+/// Compresses the asset, unless it doesn't get noticeably smaller. Assets like meshes and
+/// textures compress themselves already, others like `.spv` and `.qoi` don't.
+pub fn encode(bytes: Vec<u8>) -> EncodedAsset {
+    let uncompressed_len = bytes.len() as u64;
+    let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, COMPRESSION_LEVEL);
+
+    // decompressing takes time, thus it must be worth it
+    let (bytes, codec) = if compressed.len() < bytes.len() - bytes.len() / 8 {
+        (compressed, Codec::Deflate)
+    } else {
+        (bytes, Codec::None)
+    };
+
+    let checksum = crc32(&bytes);
+    EncodedAsset {
+        bytes,
+        uncompressed_len,
+        codec,
+        checksum,
+    }
+}
+
+/// Verifies the stored bytes of an entry. `name` is used to report which asset is corrupt.
+pub fn verify(entry: &ArchiveEntry, stored: &[u8], name: &str) -> RisResult<()> {
+    let checksum = crc32(stored);
+    if checksum != entry.checksum {
+        return ris_error::new_result!(
+            "asset {} is corrupt. expected checksum {:#010x} but was {:#010x}",
+            name,
+            entry.checksum,
+            checksum,
+        );
+    }
+
+    Ok(())
+}
+
+/// Verifies and decompresses the stored bytes of an entry. `name` is used to report which asset
+/// is corrupt.
+pub fn decode(entry: &ArchiveEntry, stored: &[u8], name: &str) -> RisResult<Vec<u8>> {
+    verify(entry, stored, name)?;
+
+    let bytes = match entry.codec {
+        Codec::None => stored.to_vec(),
+        Codec::Deflate => match miniz_oxide::inflate::decompress_to_vec(stored) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                return ris_error::new_result!("asset {} is corrupt. {:?}", name, e);
+            }
+        },
+    };
+
+    if bytes.len() as u64 != entry.uncompressed_len {
+        return ris_error::new_result!(
+            "asset {} is corrupt. expected {} bytes but was {}",
+            name,
+            entry.uncompressed_len,
+            bytes.len(),
+        );
+    }
+
+    Ok(bytes)
+}
+
+const CRC32_TABLE: [u32; 256] = crc32_table();
+
+const fn crc32_table() -> [u32; 256] {
+    let mut table = [0; 256];
+    let mut i = 0;
+    while i < 256 {
+        let mut value = i as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            value = if value & 1 == 1 {
+                (value >> 1) ^ 0xEDB88320
+            } else {
+                value >> 1
+            };
+            bit += 1;
+        }
+        table[i] = value;
+        i += 1;
+    }
+    table
+}
+
+/// CRC-32, as used by zip and png.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc = CRC32_TABLE[((crc ^ *byte as u32) & 0xFF) as usize] ^ (crc >> 8);
+    }
+    !crc
+}
@@ -4,43 +4,56 @@ use std::io::Cursor;
 use std::io::SeekFrom;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use ris_asset_data::asset_id::AssetId;
+use ris_data::ecs::registry::Registry;
 use ris_error::Extensions;
 use ris_error::RisResult;
-use ris_io::FatPtr;
 
+use crate::asset_archive;
+use crate::asset_archive::ArchiveHeader;
+use crate::asset_loader_compiled;
+use crate::asset_loader_compiled::AssetLoaderCompiled;
+use crate::assets::ris_scene;
+use crate::assets::ris_scene_text;
 use crate::RisHeader;
 
-// # File Format
-//
-// encoding: little-endian
-//
-// - [u8; 16]: magic `ris_assets\0\0\0\0\0\0"`
-// - FatPtr: p_original_asset_names
-// - u32: asset_lookup_count
-// - [u64; asset_lookup_count]: asset_lookup
-// - [u8; ?]: assets
-// - [u8; ?]: original names (utf8 encoded strings, seperated by `\0`)
-
-pub const MAGIC: [u8; 16] = [
-    0x72, 0x69, 0x73, 0x5F, 0x61, 0x73, 0x73, 0x65, 0x74, 0x73, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
-];
+// the file format is described in `asset_archive`
+pub use crate::asset_archive::MAGIC;
 
 pub const DEFAULT_ASSET_DIRECTORY: &str = "assets/in_use";
 pub const DEFAULT_COMPILED_FILE: &str = "ris_assets";
 pub const DEFAULT_DECOMPILED_DIRECTORY: &str = "decompiled_assets";
 
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Default, Debug, Clone)]
 pub struct CompileOptions {
     pub include_original_paths: bool,
+    /// required to convert text scenes to binary scenes
+    pub registry: Option<Arc<Registry>>,
 }
 
 /// compiles a directory to a ris_asset file
 /// - `source`: the directory to be compiled
 /// - `target`: the path to the final compiled file. if this file exists already, it will be overwritten
+///
+/// text scenes are converted to binary scenes. their extension is replaced, such that other
+/// assets reference them as `ris_scene`
 pub fn compile(source: &str, target: &str, options: CompileOptions) -> RisResult<()> {
-    let mut assets = Vec::new();
+    compile_with_layout(source, target, options, &[])
+}
+
+/// like `compile`, but the asset with the original path `layout[i]` gets the id `i`. assets that
+/// are not part of the layout are appended in order. ids of the layout, whose asset does not exist
+/// anymore, are left empty
+fn compile_with_layout(
+    source: &str,
+    target: &str,
+    options: CompileOptions,
+    layout: &[String],
+) -> RisResult<()> {
+    let mut assets = layout.iter().map(|_| None).collect::<Vec<_>>();
+    let mut source_files = Vec::new();
     let mut asset_lookup_hashmap = HashMap::new();
     let mut directories = std::collections::VecDeque::new();
     let source_path = PathBuf::from(source);
@@ -56,8 +69,7 @@ pub fn compile(source: &str, target: &str, options: CompileOptions) -> RisResult
             let entry_path = entry.path();
 
             if metadata.is_file() {
-                asset_lookup_hashmap.insert(entry_path.clone(), assets.len());
-                assets.push(entry_path);
+                source_files.push(entry_path);
             } else if metadata.is_dir() {
                 directories.push_back(entry_path);
             } else {
@@ -69,9 +81,43 @@ pub fn compile(source: &str, target: &str, options: CompileOptions) -> RisResult
         }
     }
 
+    // the order of directory entries depends on the file system. sort them, such that the
+    // same directory always compiles to the same file
+    source_files.sort();
+
+    for source_file in source_files {
+        let asset_path = compiled_path(&source_file);
+        if asset_lookup_hashmap.contains_key(&asset_path) {
+            return ris_error::new_result!(
+                "\"{}\" and another asset both compile to \"{}\"",
+                source_file.display(),
+                asset_path.display(),
+            );
+        }
+
+        let original_path = original_path(source, &asset_path)?;
+        let id = match layout.iter().position(|x| *x == original_path) {
+            Some(id) => id,
+            None => {
+                assets.push(None);
+                assets.len() - 1
+            }
+        };
+
+        asset_lookup_hashmap.insert(asset_path.clone(), id);
+        assets[id] = Some((asset_path, source_file, original_path));
+    }
+
+    // removed assets at the end don't need an id
+    while let Some(None) = assets.last() {
+        assets.pop();
+    }
+
     ris_log::trace!("found {} assets:", assets.len());
-    for (i, file) in assets.iter().enumerate() {
-        ris_log::trace!("{}: \"{}\"", i, file.display());
+    for (i, asset) in assets.iter().enumerate() {
+        if let Some((file, _, _)) = asset {
+            ris_log::trace!("{}: \"{}\"", i, file.display());
+        }
     }
 
     // create the target file
@@ -83,136 +129,225 @@ pub fn compile(source: &str, target: &str, options: CompileOptions) -> RisResult
     let mut target_file = File::create(target_path)?;
     let target_file = &mut target_file;
 
-    // write magic
-    ris_io::seek(target_file, SeekFrom::Start(0))?;
-    ris_io::write(target_file, &MAGIC)?;
-
-    // write ptr to original paths
-    let addr_p_original_asset_names = ris_io::seek(target_file, SeekFrom::Current(0))?;
-    ris_io::write_fat_ptr(target_file, FatPtr::null())?; // placeholder
-
-    // write lookup
-    ris_io::write_uint(target_file, assets.len())?;
-    let addr_asset_lookup = ris_io::seek(target_file, SeekFrom::Current(0))?;
-    let mut asset_lookup = vec![0; assets.len()];
-    for asset_lookup_entry in asset_lookup.iter() {
-        ris_io::write_u64(target_file, *asset_lookup_entry)?; // placeholder
-    }
-
-    // compile assets
-    for (i, asset) in assets.iter().enumerate() {
+    // compile assets in parallel, but write them in order, such that the output does not
+    // depend on which job finished first
+    let asset_count = assets.len();
+    let asset_lookup_hashmap = Arc::new(asset_lookup_hashmap);
+    let jobs = assets
+        .iter()
+        .enumerate()
+        .filter_map(|(id, x)| {
+            x.clone()
+                .map(|(asset, source_file, _)| (id, asset, source_file))
+        })
+        .collect::<Vec<_>>();
+    let results = crate::util::map_parallel(jobs, move |(i, asset, source_file)| {
         ris_log::info!(
             "compiling... {}/{} \"{}\"",
             i + 1,
-            assets.len(),
+            asset_count,
             asset.display(),
         );
 
-        let mut file = File::open(asset)?;
+        let args = CompileAssetArgs {
+            asset: &asset,
+            source_file: &source_file,
+            source_path: &source_path,
+            asset_lookup_hashmap: &asset_lookup_hashmap,
+            registry: options.registry.clone(),
+        };
+        compile_asset(args).map(|x| (i, asset_archive::encode(x)))
+    });
 
-        let file_size = ris_io::seek(&mut file, SeekFrom::End(0))? as usize;
-        let mut file_content = vec![0; file_size];
-        ris_io::seek(&mut file, SeekFrom::Start(0))?;
-        ris_io::read(&mut file, &mut file_content)?;
+    let encoded = results.into_iter().collect::<RisResult<Vec<_>>>()?;
 
-        let modified_file_content = match RisHeader::deserialize(&file_content)? {
-            // asset is not a ris_asset, return unmodified
-            None => file_content,
+    // all assets are compiled, compile original paths
+    let original_paths = if options.include_original_paths {
+        let original_paths = assets
+            .into_iter()
+            .flatten()
+            .map(|(_, _, original_path)| original_path)
+            .collect();
 
-            // asset is ris_asset, change directory id to compiled id
-            Some((ris_header, ris_asset_content)) => {
-                let mut references = Vec::with_capacity(ris_header.references.len());
-                for reference in &ris_header.references {
-                    match reference {
-                        AssetId::Index(id) => {
-                            return ris_error::new_result!(
-                                "attempted to compile an already compiled asset: {}",
-                                id,
-                            );
-                        }
-                        AssetId::Path(id) => {
-                            let mut id_path = PathBuf::from(&source_path);
-                            id_path.push(id);
-                            let lookup_value = asset_lookup_hashmap.get(&id_path);
-
-                            let Some(compiled_id) = lookup_value else {
-                                return ris_error::new_result!(
-                                    "failed to find compiled id for \"{}\". this probably means that asset \"{}\" references an asset that doesn't exist or doesn't have it's original name anymore.",
-                                    id_path.display(),
-                                    asset.display(),
-                                );
-                            };
-
-                            references.push(*compiled_id);
-                        }
-                    }
-                }
+        Some(original_paths)
+    } else {
+        None
+    };
 
-                let mut modified_file_content = Cursor::new(Vec::new());
-                let stream = &mut modified_file_content;
-                ris_io::write(stream, &ris_header.magic)?;
-                ris_io::write_bool(stream, true)?;
-                ris_io::write_uint(stream, references.len())?;
-                for reference in references {
-                    ris_io::write_uint(stream, reference)?;
-                }
-                ris_io::write(stream, ris_asset_content)?;
+    asset_archive::write_archive(target_file, asset_count, encoded, original_paths)?;
 
-                modified_file_content.into_inner()
-            }
-        };
+    Ok(())
+}
 
-        // write to compiled file
-        let asset_addr = ris_io::seek(target_file, SeekFrom::Current(0))?;
-        asset_lookup[i] = asset_addr;
-        ris_io::write(target_file, &modified_file_content)?;
+/// the path of the asset relative to `source`, with forward slashes
+fn original_path(source: &str, asset_path: &Path) -> RisResult<String> {
+    let mut original_path = asset_path.to_str().into_ris_error()?.to_string();
+    original_path.replace_range(0..source.len(), "");
+    let mut original_path = original_path.replace('\\', "/");
+    if original_path.starts_with('/') {
+        original_path.remove(0);
     }
 
-    // all assets are compiled, compile original paths
-    let p_original_asset_names = if options.include_original_paths {
-        let original_paths = assets
-            .iter()
-            .map(|x| {
-                Ok({
-                    let mut original_path = x.to_str().into_ris_error()?.to_string();
-                    original_path.replace_range(0..source.len(), "");
-                    let mut original_path = original_path.replace('\\', "/");
-                    if original_path.starts_with('/') {
-                        original_path.remove(0);
-                    }
+    Ok(original_path)
+}
+
+/// compiles a patch, that turns `old` into `new`. the patch only contains the assets that were
+/// changed or added.
+/// - `old`, `new`: directories or compiled files. patches next to a compiled file are applied
+/// - `target`: the path to the patch. if this file exists already, it will be overwritten
+///
+/// assets are matched by their original path. when `new` is a directory, unchanged assets keep
+/// the id they have in `old`, added assets are appended and removed assets are not patched, such
+/// that no id after them shifts. this requires `old` to contain original paths, otherwise assets
+/// are matched by id.
+/// a compiled `new` keeps its own ids. if they differ from `old`, every asset after the first
+/// difference is likely to end up in the patch, which is logged as a warning.
+pub fn compile_patch(old: &str, new: &str, target: &str, options: CompileOptions) -> RisResult<()> {
+    let target_path = Path::new(target);
+    if target_path.exists() {
+        std::fs::remove_file(target_path)?;
+    }
+
+    // directories are compiled next to the target, such that they can be diffed
+    let temp_paths = ["old", "new"].map(|x| PathBuf::from(format!("{}.{}.tmp", target, x)));
+    let [old_temp_path, new_temp_path] = &temp_paths;
+
+    let result = write_patch(
+        old,
+        new,
+        target_path,
+        old_temp_path,
+        new_temp_path,
+        &options,
+    );
+
+    for temp_path in temp_paths.iter() {
+        if temp_path.exists() {
+            std::fs::remove_file(temp_path)?;
+        }
+    }
+
+    result
+}
+
+fn write_patch(
+    old: &str,
+    new: &str,
+    target_path: &Path,
+    old_temp_path: &Path,
+    new_temp_path: &Path,
+    options: &CompileOptions,
+) -> RisResult<()> {
+    let old = open_compiled(old, old_temp_path, &[], options)?;
+
+    // the layout is only missing names, when `old` was compiled without original paths
+    let layout = (0..old.asset_count())
+        .map(|id| old.original_name(id).map(|x| x.to_string()))
+        .collect::<Option<Vec<_>>>();
+    if layout.is_none() {
+        ris_log::warning!("old assets have no original paths, matching assets by id instead");
+    }
 
-                    original_path
-                })
-            })
-            .collect::<RisResult<Vec<_>>>()?;
+    let new = open_compiled(
+        new,
+        new_temp_path,
+        layout.as_deref().unwrap_or(&[]),
+        options,
+    )?;
 
-        let original_paths = original_paths
-            .iter()
-            .map(|x| x.as_str())
-            .collect::<Vec<_>>();
+    let mut assets = Vec::new();
+    let mut original_names = Vec::new();
+    let mut moved_assets = Vec::new();
+    for id in 0..new.asset_count() {
+        // the id of a removed asset
+        if !new.contains(id) {
+            continue;
+        }
 
-        let begin = ris_io::seek(target_file, SeekFrom::Current(0))?;
-        ris_io::write_uint(target_file, original_paths.len())?;
-        for original_path in original_paths {
-            ris_io::write_string(target_file, original_path)?;
+        let original_name = new.original_name(id).map(|x| x.to_string());
+        if let (Some(layout), Some(original_name)) = (&layout, &original_name) {
+            let old_id = layout.iter().position(|x| x == original_name);
+            if old_id.is_some_and(|x| x != id) {
+                moved_assets.push(original_name.clone());
+            }
         }
-        let end = ris_io::seek(target_file, SeekFrom::Current(0))?;
-        FatPtr::begin_end(begin, end)?
-    } else {
-        let addr = ris_io::seek(target_file, SeekFrom::Current(0))?;
-        FatPtr { addr, len: 0 }
-    };
 
-    // fill placeholder
-    ris_io::seek(target_file, SeekFrom::Start(addr_p_original_asset_names))?;
-    ris_io::write_fat_ptr(target_file, p_original_asset_names)?;
+        let bytes = new.load(id)?;
+        if old.contains(id) && old.load(id)? == bytes {
+            continue;
+        }
 
-    ris_io::seek(target_file, SeekFrom::Start(addr_asset_lookup))?;
-    for asset_lookup_entry in asset_lookup.iter() {
-        ris_io::write_u64(target_file, *asset_lookup_entry)?;
+        ris_log::info!(
+            "patching... {} \"{}\"",
+            id,
+            original_name.as_deref().unwrap_or_default(),
+        );
+
+        assets.push((id, asset_archive::encode(bytes)));
+        original_names.push(original_name);
     }
 
-    Ok(())
+    if !moved_assets.is_empty() {
+        ris_log::warning!(
+            "{} assets changed their id, which also changes the assets that reference them. \
+            diff against a directory to keep the ids stable: {:?}",
+            moved_assets.len(),
+            moved_assets,
+        );
+    }
+
+    ris_log::info!(
+        "patch contains {} of {} assets",
+        assets.len(),
+        new.asset_count(),
+    );
+
+    // names are only written when every asset has one
+    let original_names = original_names.into_iter().collect::<Option<Vec<_>>>();
+    let mut target_file = File::create(target_path)?;
+    asset_archive::write_archive(&mut target_file, new.asset_count(), assets, original_names)
+}
+
+/// opens a compiled file with its patches, or compiles a directory to `temp_path` and opens that.
+/// directories are compiled with original paths and in the given layout, such that they can be
+/// matched by path
+fn open_compiled(
+    path: &str,
+    temp_path: &Path,
+    layout: &[String],
+    options: &CompileOptions,
+) -> RisResult<AssetLoaderCompiled> {
+    let path = Path::new(path);
+    if path.is_dir() {
+        let options = CompileOptions {
+            include_original_paths: true,
+            ..options.clone()
+        };
+        compile_with_layout(
+            path.to_str().into_ris_error()?,
+            temp_path.to_str().into_ris_error()?,
+            options,
+            layout,
+        )?;
+        AssetLoaderCompiled::new(temp_path)
+    } else {
+        let patch_paths = asset_loader_compiled::find_patches(path);
+        AssetLoaderCompiled::with_patches(path, &patch_paths)
+    }
+}
+
+fn has_extension(path: &Path, extension: &str) -> bool {
+    path.extension()
+        .map(|x| x.eq_ignore_ascii_case(extension))
+        .unwrap_or(false)
+}
+
+fn compiled_path(path: &Path) -> PathBuf {
+    if has_extension(path, ris_scene_text::EXTENSION) {
+        path.with_extension(ris_scene::EXTENSION)
+    } else {
+        path.to_path_buf()
+    }
 }
 
 /// decompiles a .ris_asset file to a directory.
@@ -230,63 +365,36 @@ pub fn decompile(source: &str, target: &str) -> RisResult<()> {
     let mut source = File::open(source)?;
     let source = &mut source;
 
-    // read magic
-    let mut magic = [0; 16];
-    ris_io::read(source, &mut magic)?;
-    if !ris_util::testing::bytes_eq(&magic, &MAGIC) {
-        return ris_error::new_result!("expected magic to be {:?} but was {:?}", magic, MAGIC);
-    }
-
-    // get original paths addr
-    let p_original_asset_names = ris_io::read_fat_ptr(source)?;
+    let header = ArchiveHeader::read(source)?;
+    let original_paths = asset_archive::read_original_names(source, header.p_original_asset_names)?;
 
-    // read lookup
-    let asset_lookup_count = ris_io::read_uint(source)?;
-    let mut asset_lookup = vec![0; asset_lookup_count];
-    for asset_lookup_entry in asset_lookup.iter_mut() {
-        *asset_lookup_entry = ris_io::read_u64(source)?;
+    // a patch may reference assets it does not contain
+    let mut original_path_lookup = HashMap::new();
+    for (i, entry) in header.entries.iter().enumerate() {
+        let original_path = match original_paths.get(i) {
+            Some(original_path) => original_path.clone(),
+            None => format!("asset_{}", entry.id),
+        };
+        original_path_lookup.insert(entry.id, original_path);
     }
-
-    // read original paths
-    let mut original_paths = if p_original_asset_names.len == 0 {
-        Vec::new()
-    } else {
-        ris_io::seek(source, SeekFrom::Start(p_original_asset_names.addr))?;
-        let original_path_count = ris_io::read_uint(source)?;
-        let mut original_paths = Vec::with_capacity(original_path_count);
-        for _ in 0..original_path_count {
-            let original_path = ris_io::read_string(source)?;
-            original_paths.push(original_path);
-        }
-        original_paths
+    let get_original_path = |id: usize| match original_path_lookup.get(&id) {
+        Some(original_path) => original_path.clone(),
+        None => format!("asset_{}", id),
     };
 
-    let mut i = original_paths.len();
-    while original_paths.len() < asset_lookup.len() {
-        original_paths.push(format!("asset_{}", i));
-        i += 1;
-    }
-
     // read assets
-    for i in 0..asset_lookup.len() {
-        let asset_begin = asset_lookup[i];
-        let original_path = &original_paths[i];
+    for (i, entry) in header.entries.iter().enumerate() {
+        let original_path = &get_original_path(entry.id);
 
         ris_log::info!(
             "decompiling... {}/{} \"{}\"",
             i + 1,
-            asset_lookup.len(),
+            header.entries.len(),
             original_path,
         );
 
-        let asset_end = if i == asset_lookup.len() - 1 {
-            p_original_asset_names.addr
-        } else {
-            asset_lookup[i + 1]
-        };
-
-        let p_asset = FatPtr::begin_end(asset_begin, asset_end)?;
-        let file_content = ris_io::read_at(source, p_asset)?;
+        let stored = ris_io::read_at(source, entry.p_content)?;
+        let file_content = asset_archive::decode(entry, &stored, original_path)?;
 
         // reassign ids
         let modified_file_content = match RisHeader::deserialize(&file_content)? {
@@ -305,8 +413,7 @@ pub fn decompile(source: &str, target: &str) -> RisResult<()> {
                             );
                         }
                         AssetId::Index(id) => {
-                            let reference = &original_paths[*id];
-                            let new_asset_id = AssetId::Path(reference.clone());
+                            let new_asset_id = AssetId::Path(get_original_path(*id));
                             references.push(new_asset_id);
                         }
                     }
@@ -330,3 +437,90 @@ pub fn decompile(source: &str, target: &str) -> RisResult<()> {
 
     Ok(())
 }
+
+struct CompileAssetArgs<'a> {
+    asset: &'a Path,
+    source_file: &'a Path,
+    source_path: &'a Path,
+    asset_lookup_hashmap: &'a HashMap<PathBuf, usize>,
+    registry: Option<Arc<Registry>>,
+}
+
+/// reads a single asset and replaces its references with compiled ids
+fn compile_asset(args: CompileAssetArgs) -> RisResult<Vec<u8>> {
+    let CompileAssetArgs {
+        asset,
+        source_file,
+        source_path,
+        asset_lookup_hashmap,
+        registry,
+    } = args;
+
+    let mut file = File::open(source_file)?;
+
+    let file_size = ris_io::seek(&mut file, SeekFrom::End(0))? as usize;
+    let mut file_content = vec![0; file_size];
+    ris_io::seek(&mut file, SeekFrom::Start(0))?;
+    ris_io::read(&mut file, &mut file_content)?;
+
+    if has_extension(source_file, ris_scene_text::EXTENSION) {
+        let Some(registry) = registry else {
+            return ris_error::new_result!(
+                "cannot compile \"{}\" without a registry",
+                source_file.display(),
+            );
+        };
+
+        let text = String::from_utf8(file_content)?;
+        file_content = ris_scene_text::to_binary(registry, &text)?;
+    }
+
+    let modified_file_content = match RisHeader::deserialize(&file_content)? {
+        // asset is not a ris_asset, return unmodified
+        None => file_content,
+
+        // asset is ris_asset, change directory id to compiled id
+        Some((ris_header, ris_asset_content)) => {
+            let mut references = Vec::with_capacity(ris_header.references.len());
+            for reference in &ris_header.references {
+                match reference {
+                    AssetId::Index(id) => {
+                        return ris_error::new_result!(
+                            "attempted to compile an already compiled asset: {}",
+                            id,
+                        );
+                    }
+                    AssetId::Path(id) => {
+                        let mut id_path = source_path.to_path_buf();
+                        id_path.push(id);
+                        let lookup_value = asset_lookup_hashmap.get(&id_path);
+
+                        let Some(compiled_id) = lookup_value else {
+                            return ris_error::new_result!(
+                                "failed to find compiled id for \"{}\". this probably means that asset \"{}\" references an asset that doesn't exist or doesn't have it's original name anymore.",
+                                id_path.display(),
+                                asset.display(),
+                            );
+                        };
+
+                        references.push(*compiled_id);
+                    }
+                }
+            }
+
+            let mut modified_file_content = Cursor::new(Vec::new());
+            let stream = &mut modified_file_content;
+            ris_io::write(stream, &ris_header.magic)?;
+            ris_io::write_bool(stream, true)?;
+            ris_io::write_uint(stream, references.len())?;
+            for reference in references {
+                ris_io::write_uint(stream, reference)?;
+            }
+            ris_io::write(stream, ris_asset_content)?;
+
+            modified_file_content.into_inner()
+        }
+    };
+
+    Ok(modified_file_content)
+}
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::fs::File;
 use std::io::BufRead;
@@ -7,6 +9,11 @@ use std::path::PathBuf;
 use ris_error::Extensions;
 use ris_error::RisResult;
 
+use crate::import_cache;
+use crate::import_cache::path_to_string;
+use crate::import_cache::CacheEntry;
+use crate::import_cache::ImportCache;
+use crate::import_cache::IMPORT_CACHE_FILE;
 use crate::importer::*;
 
 pub const DEFAULT_SOURCE_DIRECTORY: &str = "assets/source_files";
@@ -16,22 +23,46 @@ pub const COPY_INSTRUCTIONS_PATH: &str = "assets/copy_instructions.ris_meta";
 pub const COPY_INSTRUCTION_COMMENT: char = '#';
 pub const COPY_INSTRUCTION_SEPARATOR: &str = ":=>";
 pub const META_COPY_TO: &str = "copy_to";
+pub const SIDECAR_EXTENSION: &str = "ris_meta";
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImporterKind {
     GLB,
+    GLTF,
     GLSL,
+    OBJ,
+    PLY,
     PNG,
 }
 
+impl ImporterKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            ImporterKind::GLB => "glb",
+            ImporterKind::GLTF => "gltf",
+            ImporterKind::GLSL => "glsl",
+            ImporterKind::OBJ => "obj",
+            ImporterKind::PLY => "ply",
+            ImporterKind::PNG => "png",
+        }
+    }
+}
+
 pub struct SpecificImporterInfo {
     pub source_file_path: PathBuf,
     pub target_directory: PathBuf,
+    /// `target_directory`, relative to the import directory. Created assets reference each
+    /// other by paths relative to the import directory.
+    pub asset_directory: PathBuf,
     pub importer: ImporterKind,
 }
 
 pub struct DeduceImporterInfo {
     pub source_file_path: PathBuf,
     pub target_directory: PathBuf,
+    /// `target_directory`, relative to the import directory. Created assets reference each
+    /// other by paths relative to the import directory.
+    pub asset_directory: PathBuf,
 }
 
 pub enum ImporterInfo {
@@ -39,6 +70,14 @@ pub enum ImporterInfo {
     DeduceFromFileName(DeduceImporterInfo),
 }
 
+/// Source files, relative to the source directory.
+#[derive(Debug, Default)]
+pub struct ImportReport {
+    pub imported: Vec<PathBuf>,
+    pub skipped: Vec<PathBuf>,
+    pub removed: Vec<PathBuf>,
+}
+
 pub fn clean(import_directory: &str) -> RisResult<()> {
     let mut directories = VecDeque::new();
     directories.push_back(PathBuf::from(import_directory));
@@ -78,67 +117,17 @@ pub fn import_all(
     in_use_directory: &str,
     temp_directory: Option<&str>,
 ) -> RisResult<()> {
-    let temp_directory = temp_directory.map(PathBuf::from);
     let mut directories = VecDeque::new();
 
     // import source files
     ris_log::info!("import source files...");
-
-    let source_path = PathBuf::from(source_directory);
-    directories.push_back(source_path);
-
-    while let Some(current) = directories.pop_front() {
-        let entries = std::fs::read_dir(&current)?;
-
-        for entry in entries {
-            let entry = entry?;
-            let metadata = entry.metadata()?;
-            let entry_path = entry.path();
-
-            if metadata.is_file() {
-                let mut source_directory = source_directory.replace('\\', "/");
-                let mut target_directory = import_directory.replace('\\', "/");
-
-                if !source_directory.ends_with('/') {
-                    source_directory.push('/');
-                }
-
-                if !target_directory.ends_with('/') {
-                    target_directory.push('/');
-                }
-
-                let source_path = entry_path.to_str().into_ris_error()?;
-                let mut target_path_part = source_path.replace('\\', "/");
-                target_path_part.replace_range(0..source_directory.len(), "");
-
-                let mut target_path = PathBuf::new();
-                target_path.push(target_directory.clone());
-                target_path.push(&target_path_part);
-                let target_path = PathBuf::from(target_path.parent().unwrap());
-
-                ris_log::debug!(
-                    "import \"{}\" to \"{}\"",
-                    entry_path.display(),
-                    target_path.display(),
-                );
-
-                let info = DeduceImporterInfo {
-                    source_file_path: entry_path,
-                    target_directory: target_path,
-                };
-                let importer_info = ImporterInfo::DeduceFromFileName(info);
-                let temp_directory = temp_directory.as_deref();
-                import(importer_info, temp_directory)?;
-            } else if metadata.is_dir() {
-                directories.push_back(entry_path);
-            } else {
-                return ris_error::new_result!(
-                    "entry \"{}\" is neither a file nor a directory",
-                    entry_path.display(),
-                );
-            }
-        }
-    }
+    let report = import_changed(source_directory, import_directory, temp_directory)?;
+    ris_log::info!(
+        "imported {}, skipped {} unchanged, removed {}",
+        report.imported.len(),
+        report.skipped.len(),
+        report.removed.len(),
+    );
 
     // copy imported files
     ris_log::info!("copy imported files...");
@@ -243,11 +232,23 @@ pub fn import_all(
     Ok(())
 }
 
+/// The optional meta file, which configures the import of `source`. It is next to the source
+/// file and has the same name, with `SIDECAR_EXTENSION` appended. For example the sidecar of
+/// `models/Suzanne.glb` is `models/Suzanne.glb.ris_meta`.
+pub fn sidecar_path(source: impl AsRef<Path>) -> PathBuf {
+    let source = source.as_ref();
+    let mut file_name = source.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(SIDECAR_EXTENSION);
+    source.with_file_name(file_name)
+}
+
+/// Creates the output file of an importer and returns it together with its path.
 pub fn create_file(
     source: impl AsRef<Path>,
     target_dir: impl AsRef<Path>,
     extension: impl AsRef<str>,
-) -> RisResult<File> {
+) -> RisResult<(File, PathBuf)> {
     let source = source.as_ref();
     let target_dir = target_dir.as_ref();
     let extension = extension.as_ref();
@@ -271,44 +272,318 @@ pub fn create_file(
         std::fs::remove_file(&target)?;
     }
 
-    let file = File::create(target)?;
-    Ok(file)
+    let file = File::create(&target)?;
+    Ok((file, target))
+}
+
+/// Imports every source file that changed since the last import, and deletes the outputs of
+/// source files that were removed. What was imported is recorded in `IMPORT_CACHE_FILE` in the
+/// import directory. A source file is imported again when its content, its importer or one of
+/// its dependencies (e.g. a header included by a shader) changed, or when one of its outputs is
+/// missing. To import everything, `clean` the import directory first.
+pub fn import_changed(
+    source_directory: &str,
+    import_directory: &str,
+    temp_directory: Option<&str>,
+) -> RisResult<ImportReport> {
+    let source_root = PathBuf::from(source_directory);
+    let import_root = PathBuf::from(import_directory);
+    let temp_directory = temp_directory.map(PathBuf::from);
+
+    let cache_path = import_root.join(IMPORT_CACHE_FILE);
+    let mut cache = ImportCache::load(&cache_path);
+    let mut report = ImportReport::default();
+
+    // the cache is saved even if an import fails, such that everything that was imported
+    // successfully doesn't need to be imported again
+    let result = import_sources(
+        &source_root,
+        &import_root,
+        temp_directory.as_deref(),
+        &mut cache,
+        &mut report,
+    );
+    cache.save(&cache_path)?;
+    result?;
+
+    Ok(report)
+}
+
+fn import_sources(
+    source_root: &Path,
+    import_root: &Path,
+    temp_directory: Option<&Path>,
+    cache: &mut ImportCache,
+    report: &mut ImportReport,
+) -> RisResult<()> {
+    let mut sources = Vec::new();
+    let mut directories = VecDeque::new();
+    directories.push_back(source_root.to_path_buf());
+
+    while let Some(current) = directories.pop_front() {
+        let entries = std::fs::read_dir(&current)?;
+
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let entry_path = entry.path();
+
+            if metadata.is_file() {
+                sources.push(entry_path);
+            } else if metadata.is_dir() {
+                directories.push_back(entry_path);
+            } else {
+                return ris_error::new_result!(
+                    "entry \"{}\" is neither a file nor a directory",
+                    entry_path.display(),
+                );
+            }
+        }
+    }
+
+    sources.sort();
+
+    let mut dependency_hashes = HashMap::new();
+    let mut found = HashSet::new();
+    let mut to_import = Vec::new();
+
+    for source_path in sources {
+        let relative_path = PathBuf::from(path_to_string(source_path.strip_prefix(source_root)?));
+
+        let Some(importer) = deduce_importer(&source_path)? else {
+            continue;
+        };
+
+        found.insert(relative_path.clone());
+
+        let hash = import_cache::hash_file(&source_path)?;
+        let previous = cache.get(&relative_path);
+        let is_up_to_date = previous.is_some_and(|entry| {
+            entry.hash == hash
+                && entry.importer == importer.name()
+                && entry.outputs.iter().all(|x| import_root.join(x).exists())
+                && entry
+                    .dependencies
+                    .iter()
+                    .all(|(dependency, dependency_hash)| {
+                        let dependency = source_root.join(dependency);
+                        let current_hash = dependency_hashes
+                            .entry(dependency.clone())
+                            .or_insert_with(|| import_cache::hash_dependency(&dependency).ok());
+                        *current_hash == Some(*dependency_hash)
+                    })
+        });
+
+        if is_up_to_date {
+            ris_log::trace!("skip unchanged \"{}\"", source_path.display());
+            report.skipped.push(relative_path);
+            continue;
+        }
+
+        if let Some(previous) = cache.remove(&relative_path) {
+            remove_outputs(import_root, &previous)?;
+        }
+
+        let asset_directory = match relative_path.parent() {
+            Some(parent) => parent.to_path_buf(),
+            None => PathBuf::new(),
+        };
+        let target_directory = import_root.join(&asset_directory);
+
+        to_import.push((
+            relative_path,
+            hash,
+            importer,
+            source_path,
+            target_directory,
+            asset_directory,
+        ));
+    }
+
+    // import in parallel. the results are handled in the order of the source files, thus the
+    // cache and the report don't depend on which job finished first
+    let jobs = to_import
+        .iter()
+        .map(
+            |(_, _, importer, source_path, target_directory, asset_directory)| {
+                SpecificImporterInfo {
+                    source_file_path: source_path.clone(),
+                    target_directory: target_directory.clone(),
+                    asset_directory: asset_directory.clone(),
+                    importer: *importer,
+                }
+            },
+        )
+        .collect::<Vec<_>>();
+
+    let temp_directory = temp_directory.map(|x| x.to_path_buf());
+    let results = crate::util::map_parallel(jobs, move |info| {
+        import_with_dependencies(info, temp_directory.as_deref())
+    });
+
+    let mut first_error = None;
+    for ((relative_path, hash, importer, ..), result) in to_import.into_iter().zip(results) {
+        let (outputs, dependencies) = match result {
+            Ok(result) => result,
+            Err(e) => {
+                first_error.get_or_insert(e);
+                continue;
+            }
+        };
+
+        let mut entry = CacheEntry {
+            hash,
+            importer: importer.name().to_string(),
+            ..Default::default()
+        };
+
+        for output in outputs {
+            let output = output.strip_prefix(import_root)?;
+            entry.outputs.push(PathBuf::from(path_to_string(output)));
+        }
+
+        for (dependency, dependency_hash) in dependencies {
+            let dependency = match dependency.strip_prefix(source_root) {
+                Ok(relative) => relative,
+                Err(_) => dependency.as_path(),
+            };
+            entry
+                .dependencies
+                .push((PathBuf::from(path_to_string(dependency)), dependency_hash));
+        }
+
+        cache.insert(relative_path.clone(), entry);
+        report.imported.push(relative_path);
+    }
+
+    // remove outputs of deleted source files
+    let stale = cache
+        .sources()
+        .filter(|x| !found.contains(*x))
+        .map(|x| x.to_path_buf())
+        .collect::<Vec<_>>();
+
+    for source in stale {
+        if let Some(entry) = cache.remove(&source) {
+            ris_log::debug!("remove outputs of deleted \"{}\"", source.display());
+            remove_outputs(import_root, &entry)?;
+        }
+
+        report.removed.push(source);
+    }
+
+    match first_error {
+        Some(e) => Err(e),
+        None => Ok(()),
+    }
+}
+
+type ImportResult = (Vec<PathBuf>, Vec<(PathBuf, u64)>);
+
+/// Returns the created files and the hashes of the files the import depends on.
+fn import_with_dependencies(
+    info: SpecificImporterInfo,
+    temp_directory: Option<&Path>,
+) -> RisResult<ImportResult> {
+    ris_log::debug!(
+        "import \"{}\" to \"{}\"",
+        info.source_file_path.display(),
+        info.target_directory.display(),
+    );
+
+    let mut dependencies = match info.importer {
+        ImporterKind::GLB => glb_importer::dependencies(&info.source_file_path)?,
+        ImporterKind::GLTF => gltf_importer::dependencies(&info.source_file_path)?,
+        ImporterKind::GLSL => glsl_to_spirv_importer::includes(&info.source_file_path)?,
+        _ => Vec::new(),
+    };
+
+    // the sidecar is a dependency even if it does not exist, such that creating it causes an
+    // import
+    dependencies.push(sidecar_path(&info.source_file_path));
+
+    let dependencies = dependencies
+        .into_iter()
+        .map(|x| {
+            let hash = import_cache::hash_dependency(&x)?;
+            Ok((x, hash))
+        })
+        .collect::<RisResult<Vec<_>>>()?;
+
+    let outputs = import(ImporterInfo::Specific(info), temp_directory)?;
+    Ok((outputs, dependencies))
+}
+
+fn remove_outputs(import_root: &Path, entry: &CacheEntry) -> RisResult<()> {
+    for output in entry.outputs.iter() {
+        let output = import_root.join(output);
+        if output.exists() {
+            std::fs::remove_file(output)?;
+        }
+    }
+
+    Ok(())
 }
 
-fn import(info: ImporterInfo, temp_directory: Option<&Path>) -> RisResult<()> {
-    let (source, target, importer) = match info {
-        ImporterInfo::Specific(info) => {
-            (info.source_file_path, info.target_directory, info.importer)
+fn deduce_importer(source_path: &Path) -> RisResult<Option<ImporterKind>> {
+    let Some(source_extension) = source_path.extension() else {
+        ris_log::debug!(
+            "failed to deduce importer, no extension \"{}\"",
+            source_path.display(),
+        );
+        return Ok(None);
+    };
+    let source_extension = source_extension.to_str().into_ris_error()?;
+    let source_extension = source_extension.to_lowercase();
+
+    let importer = match source_extension.as_str() {
+        glb_importer::IN_EXT_GLB => ImporterKind::GLB,
+        gltf_importer::IN_EXT_GLTF => ImporterKind::GLTF,
+        glsl_to_spirv_importer::IN_EXT_GLSL => ImporterKind::GLSL,
+        obj_importer::IN_EXT_OBJ => ImporterKind::OBJ,
+        ply_importer::IN_EXT_PLY => ImporterKind::PLY,
+        png_to_qoi_importer::IN_EXT_PNG => ImporterKind::PNG,
+        // insert new importer here...
+        _ => {
+            ris_log::debug!(
+                "failed to deduce importer, unknown extension \"{}\"",
+                source_path.display(),
+            );
+            return Ok(None);
         }
+    };
+
+    Ok(Some(importer))
+}
+
+fn import(info: ImporterInfo, temp_directory: Option<&Path>) -> RisResult<Vec<PathBuf>> {
+    let (source, target, asset_directory, importer) = match info {
+        ImporterInfo::Specific(info) => (
+            info.source_file_path,
+            info.target_directory,
+            info.asset_directory,
+            info.importer,
+        ),
         ImporterInfo::DeduceFromFileName(info) => {
-            let source_path = info.source_file_path;
-            let target_directory = info.target_directory;
-
-            let source_extension = source_path.extension().into_ris_error()?;
-            let source_extension = source_extension.to_str().into_ris_error()?;
-            let source_extension = source_extension.to_lowercase();
-
-            let importer = match source_extension.as_str() {
-                glb_importer::IN_EXT_GLB => ImporterKind::GLB,
-                glsl_to_spirv_importer::IN_EXT_GLSL => ImporterKind::GLSL,
-                png_to_qoi_importer::IN_EXT_PNG => ImporterKind::PNG,
-                // insert new importer here...
-                _ => {
-                    ris_log::debug!(
-                        "failed to deduce importer, unknown extension \"{}\"",
-                        source_path.display(),
-                    );
-                    return Ok(());
-                }
+            let Some(importer) = deduce_importer(&info.source_file_path)? else {
+                return Ok(Vec::new());
             };
 
-            (source_path, target_directory, importer)
+            (
+                info.source_file_path,
+                info.target_directory,
+                info.asset_directory,
+                importer,
+            )
         }
     };
 
     match importer {
-        ImporterKind::GLB => glb_importer::import(source, target),
+        ImporterKind::GLB => glb_importer::import(source, target, asset_directory),
+        ImporterKind::GLTF => gltf_importer::import(source, target, asset_directory),
         ImporterKind::GLSL => glsl_to_spirv_importer::import(source, target, temp_directory),
+        ImporterKind::OBJ => obj_importer::import(source, target),
+        ImporterKind::PLY => ply_importer::import(source, target),
         ImporterKind::PNG => png_to_qoi_importer::import(source, target),
         // insert new importers here...
     }
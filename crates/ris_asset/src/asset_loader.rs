@@ -1,9 +1,13 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::fs::File;
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
 use std::path::Path;
 use std::path::PathBuf;
-use std::sync::mpsc::channel;
-use std::sync::mpsc::Receiver;
-use std::sync::mpsc::SendError;
-use std::sync::mpsc::Sender;
+use std::sync::Arc;
 use std::sync::Mutex;
 
 use ris_asset_data::asset_id::AssetId;
@@ -14,12 +18,29 @@ use ris_async::ThreadPool;
 use ris_data::info::app_info::AppInfo;
 use ris_error::prelude::*;
 
+use crate::asset_loader_compiled;
 use crate::asset_loader_compiled::AssetLoaderCompiled;
+use crate::asset_loader_compiled::AssetSlice;
 use crate::asset_loader_directory::AssetLoaderDirectory;
+use crate::asset_watcher;
+use crate::asset_watcher::AssetWatcherGuard;
 use crate::assets::ris_god_asset;
 
+/// Requests with a higher priority are loaded first. Requests with the same priority are loaded
+/// in the order they were made.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LoadPriority {
+    /// Things that are not needed anytime soon, like streaming in far away chunks.
+    Low,
+    #[default]
+    Normal,
+    /// Things that somebody is waiting on, like the ui or a renderer being built.
+    High,
+}
+
 trait LoadRequest: Send {
     fn id(&self) -> AssetId;
+    fn is_cancelled(&self) -> bool;
     fn deserialize_and_send(&mut self, data: RisResult<Vec<u8>>);
 }
 
@@ -38,6 +59,13 @@ impl<T: Send, F: Send + FnOnce(Vec<u8>) -> RisResult<T>> LoadRequest for Generic
         self.id.clone()
     }
 
+    fn is_cancelled(&self) -> bool {
+        match self.inner.as_ref() {
+            Some(inner) => inner.sender.is_cancelled(),
+            None => true,
+        }
+    }
+
     fn deserialize_and_send(&mut self, data: RisResult<Vec<u8>>) {
         let Some(inner) = self.inner.take() else {
             ris_error::throw!("attempted to send load request multiple times");
@@ -52,21 +80,59 @@ impl<T: Send, F: Send + FnOnce(Vec<u8>) -> RisResult<T>> LoadRequest for Generic
     }
 }
 
+struct PendingRequest {
+    priority: LoadPriority,
+    sequence: usize,
+    request: Box<dyn LoadRequest>,
+}
+
+impl PartialEq for PendingRequest {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for PendingRequest {}
+
+impl PartialOrd for PendingRequest {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for PendingRequest {
+    /// The greatest request is served first. Thus earlier requests must be greater.
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+struct PendingRequests {
+    heap: BinaryHeap<PendingRequest>,
+    next_sequence: usize,
+}
+
 enum InternalLoader {
     Compiled(AssetLoaderCompiled),
     Directory(AssetLoaderDirectory),
 }
 
-static ASSET_LOADER_SENDER: Mutex<Option<Sender<Box<dyn LoadRequest>>>> = Mutex::new(None);
+static ASSET_LOADER: Mutex<Option<Arc<InternalLoader>>> = Mutex::new(None);
+static PENDING_REQUESTS: Mutex<PendingRequests> = Mutex::new(PendingRequests {
+    heap: BinaryHeap::new(),
+    next_sequence: 0,
+});
 
 pub struct AssetLoaderGuard {
     pub god_asset_id: AssetId,
+    _asset_watcher_guard: Option<AssetWatcherGuard>,
 }
 
 impl Drop for AssetLoaderGuard {
     fn drop(&mut self) {
-        let mut asset_loader_sender = ThreadPool::lock(&ASSET_LOADER_SENDER);
-        *asset_loader_sender = None;
+        *lock_loader() = None;
 
         ris_log::info!("asset loader guard dropped!");
     }
@@ -74,18 +140,25 @@ impl Drop for AssetLoaderGuard {
 
 pub fn init(app_info: &AppInfo) -> RisResult<AssetLoaderGuard> {
     let asset_path = app_info.asset_path()?;
-    let asset_path = Path::new(&asset_path);
+    init_from_path(&asset_path)
+}
 
+pub fn init_from_path(asset_path: &Path) -> RisResult<AssetLoaderGuard> {
     // create internal loader
     let metadata = asset_path.metadata()?;
-    let (internal_loader, god_asset_id) = if metadata.is_file() {
+    let (internal_loader, god_asset_id, asset_watcher_guard) = if metadata.is_file() {
         // compiled
-        let loader = AssetLoaderCompiled::new(asset_path)?;
+        let patch_paths = asset_loader_compiled::find_patches(asset_path);
+        for patch_path in patch_paths.iter() {
+            ris_log::debug!("applying patch \"{}\"", patch_path.display());
+        }
+
+        let loader = AssetLoaderCompiled::with_patches(asset_path, &patch_paths)?;
         let internal_loader = InternalLoader::Compiled(loader);
         let god_asset_id = AssetId::Index(0);
         ris_log::debug!("compiled asset loader was created");
 
-        (internal_loader, god_asset_id)
+        (internal_loader, god_asset_id, None)
     } else if metadata.is_dir() {
         // directory
         let loader = AssetLoaderDirectory::new(asset_path);
@@ -105,35 +178,56 @@ pub fn init(app_info: &AppInfo) -> RisResult<AssetLoaderGuard> {
         let god_asset_id = AssetId::Path(god_asset_path.to_string());
         ris_log::debug!("directory asset loader was created");
 
-        (internal_loader, god_asset_id)
+        // only loose files can change while the app is running
+        let asset_watcher_guard =
+            asset_watcher::spawn(asset_path, asset_watcher::DEFAULT_POLL_INTERVAL);
+
+        (internal_loader, god_asset_id, Some(asset_watcher_guard))
     } else {
         return ris_error::new_result!("assets are neither a file nor a directory");
     };
 
-    // set up thread
-    let (sender, receiver) = channel();
-    let _ = std::thread::spawn(|| load_asset_thread(receiver, internal_loader));
-
-    {
-        let mut asset_loader_sender = ThreadPool::lock(&ASSET_LOADER_SENDER);
-        *asset_loader_sender = Some(sender)
-    }
+    *lock_loader() = Some(Arc::new(internal_loader));
 
-    Ok(AssetLoaderGuard { god_asset_id })
+    Ok(AssetLoaderGuard {
+        god_asset_id,
+        _asset_watcher_guard: asset_watcher_guard,
+    })
 }
 
 pub fn load_raw_async(id: AssetId) -> OneshotReceiver<RisResult<Vec<u8>>> {
     load_async(id, Ok)
 }
 
+pub fn load_raw_async_with_priority(
+    id: AssetId,
+    priority: LoadPriority,
+) -> OneshotReceiver<RisResult<Vec<u8>>> {
+    load_async_with_priority(id, priority, Ok)
+}
+
 pub fn load_async<T, F>(id: AssetId, deserializer: F) -> OneshotReceiver<RisResult<T>>
+where
+    T: Send + 'static,
+    F: FnOnce(Vec<u8>) -> RisResult<T> + Send + 'static,
+{
+    load_async_with_priority(id, LoadPriority::Normal, deserializer)
+}
+
+/// Loads the asset and deserializes it on the thread pool. Dropping the returned receiver
+/// cancels the request, if it was not served yet.
+pub fn load_async_with_priority<T, F>(
+    id: AssetId,
+    priority: LoadPriority,
+    deserializer: F,
+) -> OneshotReceiver<RisResult<T>>
 where
     T: Send + 'static,
     F: FnOnce(Vec<u8>) -> RisResult<T> + Send + 'static,
 {
     let (sender, receiver) = oneshot_channel();
 
-    let request: Box<dyn LoadRequest> = Box::new(GenericLoadRequest {
+    let mut request: Box<dyn LoadRequest> = Box::new(GenericLoadRequest {
         id,
         inner: Some(GenericLoadRequestInner {
             deserializer,
@@ -141,46 +235,129 @@ where
         }),
     });
 
-    let result = {
-        let asset_loader_sender = ThreadPool::lock(&ASSET_LOADER_SENDER);
-        match &*asset_loader_sender {
-            Some(sender) => sender.send(request),
-            None => Err(SendError(request)),
-        }
-    };
-
-    if let Err(send_error) = result {
-        let error = ris_error::new_result!("failed to send: {}", send_error);
-        let mut request = send_error.0;
+    if lock_loader().is_none() {
+        let error = ris_error::new_result!("asset loader is not initialized");
         request.deserialize_and_send(error);
+        return receiver;
+    }
+
+    {
+        let mut pending_requests = ThreadPool::lock(&PENDING_REQUESTS);
+        let sequence = pending_requests.next_sequence;
+        pending_requests.next_sequence = sequence.wrapping_add(1);
+        pending_requests.heap.push(PendingRequest {
+            priority,
+            sequence,
+            request,
+        });
+    }
+
+    // every request submits one job. a job does not necessarily serve the request that
+    // submitted it, but the one with the highest priority at the time the job runs
+    if ThreadPool::is_worker() {
+        ThreadPool::submit(async { serve_next_request() }).ignore();
+    } else {
+        serve_next_request();
     }
 
     receiver
 }
 
-fn load_asset_thread(receiver: Receiver<Box<dyn LoadRequest>>, mut loader: InternalLoader) {
-    for mut request in receiver.iter() {
-        //ris_log::trace!("loading asset {:?}...", request.id());
-
-        let result = match &mut loader {
-            InternalLoader::Compiled(loader) => match request.id() {
-                AssetId::Index(id) => loader.load(id),
-                AssetId::Path(id) => ris_error::new_result!(
-                    "invalid id. expected compiled but was directory. id: {:?}",
-                    id
-                ),
-            },
-            InternalLoader::Directory(loader) => match request.id() {
-                AssetId::Index(id) => ris_error::new_result!(
-                    "invalid id. expected directory but was compiled. id: {:?}",
-                    id
-                ),
-                AssetId::Path(id) => loader.load(id.clone()),
-            },
-        };
+fn serve_next_request() {
+    let pending = ThreadPool::lock(&PENDING_REQUESTS).heap.pop();
+    let Some(PendingRequest { mut request, .. }) = pending else {
+        return;
+    };
 
-        request.deserialize_and_send(result);
+    if request.is_cancelled() {
+        ris_log::trace!("cancelled loading asset {:?}", request.id());
+        return;
     }
 
-    ris_log::info!("load asset thread ended");
+    let result = match current_loader() {
+        Some(loader) => loader.load(request.id()),
+        None => ris_error::new_result!("asset loader is not initialized"),
+    };
+
+    request.deserialize_and_send(result);
+}
+
+/// Opens a reader over the asset, for assets that are too large to be loaded at once. Unlike
+/// `load_async`, this blocks until the asset is opened, which is cheap in both loaders.
+pub fn open_stream(id: AssetId) -> RisResult<AssetStream> {
+    let Some(loader) = current_loader() else {
+        return ris_error::new_result!("asset loader is not initialized");
+    };
+
+    match (&*loader, id) {
+        (InternalLoader::Compiled(loader), AssetId::Index(id)) => {
+            let stream = loader.stream(id)?;
+            Ok(AssetStream::Compiled(stream))
+        }
+        (InternalLoader::Directory(loader), AssetId::Path(id)) => {
+            let file = loader.open(id)?;
+            Ok(AssetStream::Directory(file))
+        }
+        (InternalLoader::Compiled(_), id) => ris_error::new_result!(
+            "invalid id. expected compiled but was directory. id: {:?}",
+            id
+        ),
+        (InternalLoader::Directory(_), id) => ris_error::new_result!(
+            "invalid id. expected directory but was compiled. id: {:?}",
+            id
+        ),
+    }
+}
+
+/// A reader over a single asset.
+pub enum AssetStream {
+    Compiled(Cursor<AssetSlice>),
+    Directory(File),
+}
+
+impl Read for AssetStream {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        match self {
+            AssetStream::Compiled(stream) => stream.read(buf),
+            AssetStream::Directory(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Seek for AssetStream {
+    fn seek(&mut self, pos: SeekFrom) -> std::io::Result<u64> {
+        match self {
+            AssetStream::Compiled(stream) => stream.seek(pos),
+            AssetStream::Directory(stream) => stream.seek(pos),
+        }
+    }
+}
+
+impl InternalLoader {
+    fn load(&self, id: AssetId) -> RisResult<Vec<u8>> {
+        match (self, id) {
+            (InternalLoader::Compiled(loader), AssetId::Index(id)) => loader.load(id),
+            (InternalLoader::Directory(loader), AssetId::Path(id)) => loader.load(id),
+            (InternalLoader::Compiled(_), id) => ris_error::new_result!(
+                "invalid id. expected compiled but was directory. id: {:?}",
+                id
+            ),
+            (InternalLoader::Directory(_), id) => ris_error::new_result!(
+                "invalid id. expected directory but was compiled. id: {:?}",
+                id
+            ),
+        }
+    }
+}
+
+/// The loader is only locked to clone it, such that reads don't wait on each other. Both
+/// loaders can serve multiple reads at once.
+fn current_loader() -> Option<Arc<InternalLoader>> {
+    lock_loader().clone()
+}
+
+/// Blocks without running pending jobs. The loader is only locked briefly, thus running a job
+/// while waiting would delay everybody else.
+fn lock_loader() -> std::sync::MutexGuard<'static, Option<Arc<InternalLoader>>> {
+    ASSET_LOADER.lock().unwrap_or_else(|e| e.into_inner())
 }
@@ -1,63 +1,230 @@
-use std::fs::File;
-use std::io::SeekFrom;
+use std::io::Cursor;
+use std::ops::Deref;
+use std::ops::Range;
 use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
 
 use ris_error::RisResult;
+use ris_io::mapped_file::MappedFile;
 use ris_io::FatPtr;
 
+use crate::asset_archive;
+use crate::asset_archive::ArchiveEntry;
+use crate::asset_archive::ArchiveHeader;
+use crate::asset_archive::Codec;
+
+pub const PATCH_EXTENSION: &str = "patch";
+
+/// Loads assets from a compiled file and the patches applied to it. The files are mapped into
+/// memory, such that assets can be read concurrently. Uncompressed assets are not copied at all.
 pub struct AssetLoaderCompiled {
-    file: File,
-    lookup: Vec<FatPtr>,
+    archives: Vec<Archive>,
+    lookup: Vec<Option<Location>>,
 }
 
-impl AssetLoaderCompiled {
-    pub fn new(asset_path: &Path) -> RisResult<Self> {
-        let mut file = File::open(asset_path)?;
-        let f = &mut file;
+/// A single compiled file.
+struct Archive {
+    file: Arc<MappedFile>,
+    asset_count: usize,
+    entries: Vec<ArchiveEntry>,
+    ranges: Vec<Range<usize>>,
+    original_names: Vec<String>,
+}
 
-        ris_io::seek(f, SeekFrom::Start(0))?;
+/// Where an asset is stored, after all patches are applied.
+#[derive(Clone, Copy)]
+struct Location {
+    archive: usize,
+    entry: usize,
+}
 
-        let mut magic_bytes = [0u8; 16];
-        ris_io::read(f, &mut magic_bytes)?;
+/// The bytes of a single asset. Uncompressed assets point into the compiled file and keep it
+/// mapped while they are alive.
+#[derive(Clone)]
+pub struct AssetSlice {
+    bytes: SliceBytes,
+}
 
-        if !ris_util::testing::bytes_eq(&magic_bytes, &crate::asset_compiler::MAGIC) {
-            return ris_error::new_result!("unkown magic value: {:?}", magic_bytes);
+#[derive(Clone)]
+enum SliceBytes {
+    Mapped(Arc<MappedFile>, Range<usize>),
+    Decompressed(Arc<[u8]>),
+}
+
+impl Deref for AssetSlice {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        match &self.bytes {
+            SliceBytes::Mapped(file, range) => &file[range.clone()],
+            SliceBytes::Decompressed(bytes) => bytes,
         }
+    }
+}
 
-        let p_original_asset_names = ris_io::read_fat_ptr(f)?;
+impl AsRef<[u8]> for AssetSlice {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl AssetLoaderCompiled {
+    pub fn new(asset_path: &Path) -> RisResult<Self> {
+        Self::with_patches(asset_path, &[])
+    }
 
-        let asset_lookup_count = ris_io::read_uint(f)?;
-        let mut asset_lookup = vec![0; asset_lookup_count];
-        for asset_lookup_entry in asset_lookup.iter_mut() {
-            *asset_lookup_entry = ris_io::read_u64(f)?;
+    /// Applies the patches in order. Later patches override or add assets of earlier ones.
+    pub fn with_patches(asset_path: &Path, patch_paths: &[PathBuf]) -> RisResult<Self> {
+        let mut archives = Vec::with_capacity(patch_paths.len() + 1);
+        archives.push(Archive::open(asset_path)?);
+        for patch_path in patch_paths {
+            archives.push(Archive::open(patch_path)?);
         }
-        let mut fat_ptr_lookup = Vec::with_capacity(asset_lookup.len());
-
-        for i in 0..asset_lookup.len() {
-            let begin = asset_lookup[i];
-            let end = if i == asset_lookup.len() - 1 {
-                p_original_asset_names.addr
-            } else {
-                asset_lookup[i + 1]
-            };
-
-            let fat_ptr = FatPtr::begin_end(begin, end)?;
-            fat_ptr_lookup.push(fat_ptr);
+
+        let mut lookup = Vec::new();
+        for (i, archive) in archives.iter().enumerate() {
+            // removes assets that don't exist anymore
+            lookup.resize(archive.asset_count, None);
+
+            for (j, entry) in archive.entries.iter().enumerate() {
+                lookup[entry.id] = Some(Location {
+                    archive: i,
+                    entry: j,
+                });
+            }
         }
 
+        Ok(Self { archives, lookup })
+    }
+
+    pub fn asset_count(&self) -> usize {
+        self.lookup.len()
+    }
+
+    /// Whether an asset with this id exists. Ids below `asset_count()` may be unused, when
+    /// an asset was removed by a patch.
+    pub fn contains(&self, id: usize) -> bool {
+        matches!(self.lookup.get(id), Some(Some(_)))
+    }
+
+    /// The original path of the asset, if it was compiled with them.
+    pub fn original_name(&self, id: usize) -> Option<&str> {
+        let location = self.lookup.get(id).copied().flatten()?;
+        let archive = &self.archives[location.archive];
+        archive
+            .original_names
+            .get(location.entry)
+            .map(|x| x.as_str())
+    }
+
+    /// Verifies the asset and returns its bytes. Only compressed assets are copied.
+    pub fn slice(&self, id: usize) -> RisResult<AssetSlice> {
+        let (archive, entry, range, name) = self.entry(id)?;
+        let stored = &archive.file[range.clone()];
+        let bytes = match entry.codec {
+            Codec::None => {
+                asset_archive::verify(entry, stored, &name)?;
+                SliceBytes::Mapped(archive.file.clone(), range)
+            }
+            Codec::Deflate => {
+                let bytes = asset_archive::decode(entry, stored, &name)?;
+                SliceBytes::Decompressed(bytes.into())
+            }
+        };
+
+        Ok(AssetSlice { bytes })
+    }
+
+    /// Returns a reader over the asset, for assets that are too large to be loaded at once.
+    /// Compressed assets are decompressed as a whole, thus large assets should be stored
+    /// uncompressed.
+    pub fn stream(&self, id: usize) -> RisResult<Cursor<AssetSlice>> {
+        let slice = self.slice(id)?;
+        Ok(Cursor::new(slice))
+    }
+
+    pub fn load(&self, id: usize) -> RisResult<Vec<u8>> {
+        let (archive, entry, range, name) = self.entry(id)?;
+        asset_archive::decode(entry, &archive.file[range], &name)
+    }
+
+    fn entry(&self, id: usize) -> RisResult<(&Archive, &ArchiveEntry, Range<usize>, String)> {
+        let Some(location) = self.lookup.get(id).copied().flatten() else {
+            return ris_error::new_result!("asset {} does not exist", id);
+        };
+
+        let archive = &self.archives[location.archive];
+        let entry = &archive.entries[location.entry];
+        let range = archive.ranges[location.entry].clone();
+        let name = match self.original_name(id) {
+            Some(original_name) => format!("{} \"{}\"", id, original_name),
+            None => id.to_string(),
+        };
+
+        Ok((archive, entry, range, name))
+    }
+}
+
+impl Archive {
+    fn open(path: &Path) -> RisResult<Self> {
+        let file = MappedFile::open(path)?;
+        let mut cursor = Cursor::new(&file[..]);
+
+        let header = ArchiveHeader::read(&mut cursor)?;
+        let original_names =
+            asset_archive::read_original_names(&mut cursor, header.p_original_asset_names)?;
+
+        let ranges = header
+            .entries
+            .iter()
+            .map(|x| to_range(x.p_content, file.len()))
+            .collect::<RisResult<Vec<_>>>()?;
+
         Ok(Self {
-            file,
-            lookup: fat_ptr_lookup,
+            file: Arc::new(file),
+            asset_count: header.asset_count,
+            entries: header.entries,
+            ranges,
+            original_names,
         })
     }
+}
 
-    pub fn load(&mut self, id: usize) -> RisResult<Vec<u8>> {
-        let p_asset = self
-            .lookup
-            .get(id)
-            .ok_or_else(|| ris_error::new!("asset does not exist"))?;
+/// The path of the nth patch of a compiled file, starting at 1: `ris_assets.patch1`,
+/// `ris_assets.patch2` and so on.
+pub fn patch_path(asset_path: &Path, n: usize) -> PathBuf {
+    let mut path = asset_path.as_os_str().to_os_string();
+    path.push(format!(".{}{}", PATCH_EXTENSION, n));
+    PathBuf::from(path)
+}
+
+/// Returns the patches next to the compiled file, in the order they must be applied.
+pub fn find_patches(asset_path: &Path) -> Vec<PathBuf> {
+    let mut patch_paths = Vec::new();
+    loop {
+        let patch_path = patch_path(asset_path, patch_paths.len() + 1);
+        if !patch_path.is_file() {
+            break patch_paths;
+        }
+
+        patch_paths.push(patch_path);
+    }
+}
 
-        let bytes = ris_io::read_at(&mut self.file, *p_asset)?;
-        Ok(bytes)
+fn to_range(ptr: FatPtr, file_len: usize) -> RisResult<Range<usize>> {
+    let begin = usize::try_from(ptr.addr).ok();
+    let end = ptr
+        .addr
+        .checked_add(ptr.len)
+        .and_then(|x| usize::try_from(x).ok());
+    match (begin, end) {
+        (Some(begin), Some(end)) if end <= file_len => Ok(begin..end),
+        _ => ris_error::new_result!(
+            "asset at {} with {} bytes is outside of the file with {} bytes",
+            ptr.addr,
+            ptr.len,
+            file_len,
+        ),
     }
 }
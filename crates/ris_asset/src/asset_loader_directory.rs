@@ -15,12 +15,18 @@ impl AssetLoaderDirectory {
         Self { base_path }
     }
 
-    pub fn load(&self, id: String) -> RisResult<Vec<u8>> {
+    /// Opens the asset file, for assets that are too large to be loaded at once.
+    pub fn open(&self, id: String) -> RisResult<File> {
         let mut path = PathBuf::new();
         path.push(&self.base_path);
         path.push(id);
 
-        let mut file = File::open(&path)?;
+        let file = File::open(&path)?;
+        Ok(file)
+    }
+
+    pub fn load(&self, id: String) -> RisResult<Vec<u8>> {
+        let mut file = self.open(id)?;
         let file_size = ris_io::seek(&mut file, SeekFrom::End(0))? as usize;
         let mut file_content = vec![0; file_size];
         ris_io::seek(&mut file, SeekFrom::Start(0))?;
@@ -0,0 +1,311 @@
+use std::collections::HashMap;
+use std::collections::VecDeque;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::mpsc::channel;
+use std::sync::mpsc::RecvTimeoutError;
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::sync::Mutex;
+use std::sync::MutexGuard;
+use std::sync::Weak;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use ris_asset_data::asset_id::AssetId;
+use ris_error::RisResult;
+
+use crate::import_cache;
+
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// A file below the watched directory, as it is right now.
+struct WatchedFile {
+    id: String,
+    path: PathBuf,
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+/// What a file looked like the last time it was reported.
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+    hash: u64,
+}
+
+/// Detects changed files in the directory that `AssetLoaderDirectory` loads from, by polling.
+///
+/// A file is reported once its modification time and size stopped changing for one poll, such
+/// that half written files are not reloaded. A file that was rewritten with the same content,
+/// like the importer does when copying into the in use directory, is not reported at all.
+/// Removed files are not reported, since there is nothing to reload.
+pub struct AssetWatcher {
+    base_path: PathBuf,
+    stamps: HashMap<String, Stamp>,
+    pending: HashMap<String, (Option<SystemTime>, u64)>,
+}
+
+impl AssetWatcher {
+    pub fn new(base_path: impl AsRef<Path>) -> RisResult<Self> {
+        let base_path = base_path.as_ref().to_path_buf();
+
+        let mut stamps = HashMap::new();
+        for file in walk(&base_path)? {
+            let Ok(hash) = import_cache::hash_file(&file.path) else {
+                continue;
+            };
+
+            let stamp = Stamp {
+                modified: file.modified,
+                len: file.len,
+                hash,
+            };
+            stamps.insert(file.id, stamp);
+        }
+
+        Ok(Self {
+            base_path,
+            stamps,
+            pending: HashMap::new(),
+        })
+    }
+
+    /// Returns the ids of all files that changed or were added since the last poll, sorted.
+    pub fn poll(&mut self) -> RisResult<Vec<AssetId>> {
+        let mut changed = Vec::new();
+        let mut pending = HashMap::new();
+        let mut stamps = HashMap::with_capacity(self.stamps.len());
+
+        for WatchedFile {
+            id,
+            path,
+            modified,
+            len,
+        } in walk(&self.base_path)?
+        {
+            let old = self.stamps.remove(&id);
+
+            let is_unchanged = old
+                .as_ref()
+                .map(|x| x.modified == modified && x.len == len)
+                .unwrap_or(false);
+            let is_stable = self.pending.get(&id) == Some(&(modified, len));
+
+            if is_unchanged || !is_stable {
+                if !is_unchanged {
+                    pending.insert(id.clone(), (modified, len));
+                }
+
+                if let Some(old) = old {
+                    stamps.insert(id, old);
+                }
+
+                continue;
+            }
+
+            let Ok(hash) = import_cache::hash_file(&path) else {
+                // the file was removed while polling
+                continue;
+            };
+
+            let is_same_content = old.map(|x| x.hash == hash).unwrap_or(false);
+            if !is_same_content {
+                changed.push(id.clone());
+            }
+
+            stamps.insert(
+                id,
+                Stamp {
+                    modified,
+                    len,
+                    hash,
+                },
+            );
+        }
+
+        self.stamps = stamps;
+        self.pending = pending;
+
+        changed.sort();
+        Ok(changed.into_iter().map(AssetId::Path).collect())
+    }
+}
+
+fn walk(base_path: &Path) -> RisResult<Vec<WatchedFile>> {
+    let mut files = Vec::new();
+    let mut directories = VecDeque::new();
+    directories.push_back(base_path.to_path_buf());
+
+    while let Some(current) = directories.pop_front() {
+        let Ok(entries) = std::fs::read_dir(&current) else {
+            // the directory was removed while polling
+            continue;
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+            let path = entry.path();
+
+            if metadata.is_dir() {
+                directories.push_back(path);
+            } else if metadata.is_file() {
+                let id = import_cache::path_to_string(path.strip_prefix(base_path)?);
+                files.push(WatchedFile {
+                    id,
+                    path,
+                    modified: metadata.modified().ok(),
+                    len: metadata.len(),
+                });
+            }
+        }
+    }
+
+    Ok(files)
+}
+
+struct SubscriptionInner {
+    /// `None` subscribes to every asset.
+    ids: Option<Vec<AssetId>>,
+    changed: Mutex<Vec<AssetId>>,
+}
+
+static SUBSCRIPTIONS: Mutex<Vec<Weak<SubscriptionInner>>> = Mutex::new(Vec::new());
+
+/// Collects the changed assets it subscribed to. Dropping it unsubscribes.
+pub struct AssetSubscription {
+    inner: Arc<SubscriptionInner>,
+}
+
+impl AssetSubscription {
+    /// Returns the subscribed assets that changed since the last call.
+    pub fn take_changed(&self) -> Vec<AssetId> {
+        std::mem::take(&mut *lock(&self.inner.changed))
+    }
+
+    /// Whether any subscribed asset changed since the last call to `take_changed()`. Unlike
+    /// `take_changed()`, this does not consume the changes.
+    pub fn has_changed(&self) -> bool {
+        !lock(&self.inner.changed).is_empty()
+    }
+}
+
+/// Subscribes to changes of the given assets. Renderers use this to rebuild their pipelines
+/// when a shader they use changes.
+pub fn subscribe(ids: Vec<AssetId>) -> AssetSubscription {
+    subscribe_internal(Some(ids))
+}
+
+/// Subscribes to changes of every asset. Lookups use this, because the assets they hold change
+/// all the time.
+pub fn subscribe_all() -> AssetSubscription {
+    subscribe_internal(None)
+}
+
+fn subscribe_internal(ids: Option<Vec<AssetId>>) -> AssetSubscription {
+    let inner = Arc::new(SubscriptionInner {
+        ids,
+        changed: Mutex::new(Vec::new()),
+    });
+
+    lock(&SUBSCRIPTIONS).push(Arc::downgrade(&inner));
+
+    AssetSubscription { inner }
+}
+
+/// Passes the changed assets to every subscription that is interested in them.
+pub fn notify(changed: &[AssetId]) {
+    let mut subscriptions = lock(&SUBSCRIPTIONS);
+    subscriptions.retain(|x| x.strong_count() > 0);
+
+    for subscription in subscriptions.iter() {
+        let Some(subscription) = subscription.upgrade() else {
+            continue;
+        };
+
+        let mut subscribed_changes = lock(&subscription.changed);
+        for id in changed {
+            let is_subscribed = match &subscription.ids {
+                Some(ids) => ids.contains(id),
+                None => true,
+            };
+
+            if is_subscribed && !subscribed_changes.contains(id) {
+                subscribed_changes.push(id.clone());
+            }
+        }
+    }
+}
+
+/// Polls the asset directory on its own thread and notifies the subscriptions. The thread stops
+/// when this guard is dropped.
+pub struct AssetWatcherGuard {
+    stop_sender: Option<Sender<()>>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl Drop for AssetWatcherGuard {
+    fn drop(&mut self) {
+        // disconnecting the channel wakes up the thread
+        self.stop_sender.take();
+
+        if let Some(handle) = self.handle.take() {
+            if handle.join().is_err() {
+                ris_log::error!("asset watcher thread panicked");
+            }
+        }
+
+        ris_log::info!("asset watcher guard dropped!");
+    }
+}
+
+pub fn spawn(base_path: impl AsRef<Path>, poll_interval: Duration) -> AssetWatcherGuard {
+    let base_path = base_path.as_ref().to_path_buf();
+    let (stop_sender, stop_receiver) = channel::<()>();
+
+    let handle = std::thread::spawn(move || {
+        let mut watcher = match AssetWatcher::new(&base_path) {
+            Ok(watcher) => watcher,
+            Err(e) => {
+                ris_log::error!("failed to create asset watcher: {}", e);
+                return;
+            }
+        };
+
+        ris_log::debug!("watching assets in \"{}\"", base_path.display());
+
+        while let Err(RecvTimeoutError::Timeout) = stop_receiver.recv_timeout(poll_interval) {
+            match watcher.poll() {
+                Ok(changed) => {
+                    if changed.is_empty() {
+                        continue;
+                    }
+
+                    for id in changed.iter() {
+                        ris_log::debug!("asset changed: {:?}", id);
+                    }
+
+                    notify(&changed);
+                }
+                Err(e) => ris_log::error!("failed to poll assets: {}", e),
+            }
+        }
+
+        ris_log::info!("asset watcher thread ended");
+    });
+
+    AssetWatcherGuard {
+        stop_sender: Some(stop_sender),
+        handle: Some(handle),
+    }
+}
+
+// `notify()` runs on the watcher thread, which is not a worker of the thread pool. thus the lock
+// must not try to run pending jobs while it waits
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|e| e.into_inner())
+}
@@ -1,5 +1,11 @@
+pub mod ris_animation;
 pub mod ris_god_asset;
 pub mod ris_header;
+pub mod ris_material;
 pub mod ris_mesh;
+pub mod ris_prefab;
 pub mod ris_scene;
+pub mod ris_scene_text;
+pub mod ris_skeleton;
 pub mod ris_terrain;
+pub mod ris_texture;
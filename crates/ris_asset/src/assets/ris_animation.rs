@@ -0,0 +1,121 @@
+use std::io::Cursor;
+
+use ris_asset_data::animation::AnimationChannel;
+use ris_asset_data::animation::AnimationClip;
+use ris_asset_data::animation::ChannelPath;
+use ris_asset_data::animation::Interpolation;
+use ris_asset_data::AssetId;
+use ris_error::prelude::*;
+
+use super::ris_header::RisHeader;
+
+// # File Format
+//
+// encoding: little-endian
+//
+// - RisHeader. references:
+//   - the skeleton, whose joints are animated
+// - deflate compressed content:
+//   - string: name
+//   - f32: duration in seconds
+//   - u32: channel count
+//   - for each channel:
+//     - u32: joint index
+//     - u8: path
+//     - u8: interpolation
+//     - u32: keyframe count
+//     - [f32; keyframe count]: times
+//     - [f32; keyframe count * stride]: values. the stride follows from the path and the
+//       interpolation
+
+// ris_animation\0\0\0
+pub const MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5f, 0x61, 0x6e, 0x69, 0x6d, 0x61, 0x74, 0x69, 0x6f, 0x6e, 0x00, 0x00, 0x00,
+];
+pub const EXTENSION: &str = "ris_animation";
+
+pub const COMPRESSION_LEVEL: u8 = 6;
+
+pub fn serialize(clip: &AnimationClip, skeleton: &AssetId) -> RisResult<Vec<u8>> {
+    // the joint count is only known by the skeleton. the joint indices are checked when the clip
+    // is sampled
+    clip.validate(usize::MAX)?;
+
+    let mut stream = Cursor::new(Vec::new());
+    let s = &mut stream;
+
+    ris_io::write_string(s, &clip.name)?;
+    ris_io::write_f32(s, clip.duration)?;
+    ris_io::write_u32(s, u32::try_from(clip.channels.len())?)?;
+    for channel in clip.channels.iter() {
+        ris_io::write_u32(s, u32::try_from(channel.joint)?)?;
+        ris_io::write_u8(s, channel.path.to_u8())?;
+        ris_io::write_u8(s, channel.interpolation.to_u8())?;
+        ris_io::write_u32(s, u32::try_from(channel.times.len())?)?;
+        for &time in channel.times.iter() {
+            ris_io::write_f32(s, time)?;
+        }
+        for &value in channel.values.iter() {
+            ris_io::write_f32(s, value)?;
+        }
+    }
+
+    let bytes = stream.into_inner();
+    let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, COMPRESSION_LEVEL);
+
+    let header = RisHeader::new(MAGIC, vec![skeleton.clone()]);
+    header.serialize(&compressed)
+}
+
+/// Returns the clip and the skeleton it animates.
+pub fn deserialize(bytes: &[u8]) -> RisResult<(AnimationClip, AssetId)> {
+    let (header, content) = RisHeader::deserialize(bytes)?.into_ris_error()?;
+    header.assert_magic(MAGIC)?;
+    ris_error::assert!(header.references.len() == 1)?;
+    let skeleton = header.references.into_iter().next().into_ris_error()?;
+
+    let decompressed = miniz_oxide::inflate::decompress_to_vec(content)
+        .map_err(|e| ris_error::new!("failed to decompress: {:?}", e))?;
+
+    let mut stream = Cursor::new(decompressed);
+    let s = &mut stream;
+
+    let name = ris_io::read_string(s)?;
+    let duration = ris_io::read_f32(s)?;
+    let channel_count = ris_io::read_u32(s)? as usize;
+    let mut channels = Vec::with_capacity(channel_count);
+    for _ in 0..channel_count {
+        let joint = ris_io::read_u32(s)? as usize;
+        let path = ChannelPath::from_u8(ris_io::read_u8(s)?)?;
+        let interpolation = Interpolation::from_u8(ris_io::read_u8(s)?)?;
+        let keyframe_count = ris_io::read_u32(s)? as usize;
+
+        let mut channel = AnimationChannel {
+            joint,
+            path,
+            interpolation,
+            times: Vec::with_capacity(keyframe_count),
+            values: Vec::new(),
+        };
+
+        for _ in 0..keyframe_count {
+            channel.times.push(ris_io::read_f32(s)?);
+        }
+
+        let value_count = keyframe_count * channel.stride();
+        channel.values.reserve(value_count);
+        for _ in 0..value_count {
+            channel.values.push(ris_io::read_f32(s)?);
+        }
+
+        channels.push(channel);
+    }
+
+    let clip = AnimationClip {
+        name,
+        duration,
+        channels,
+    };
+    clip.validate(usize::MAX)?;
+    Ok((clip, skeleton))
+}
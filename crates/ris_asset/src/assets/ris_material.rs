@@ -0,0 +1,146 @@
+use std::io::Cursor;
+
+use ris_asset_data::material::AlphaMode;
+use ris_asset_data::material::CpuMaterial;
+use ris_asset_data::material::MaterialParams;
+use ris_asset_data::material::MaterialTextures;
+use ris_error::prelude::*;
+
+use super::ris_header::RisHeader;
+
+// # File Format
+//
+// encoding: little-endian
+//
+// - RisHeader. references:
+//   - vertex shader
+//   - fragment shader
+//   - the textures, in the order of their slots. missing textures are skipped
+// - content:
+//   - u8: texture mask. bit i is set, if texture slot i is used. slots in order: base color,
+//     metallic roughness, normal, occlusion, emissive
+//   - [f32; 4]: base color
+//   - f32: metallic
+//   - f32: roughness
+//   - [f32; 3]: emissive
+//   - f32: normal scale
+//   - f32: occlusion strength
+//   - u8: alpha mode
+//   - f32: alpha cutoff
+//   - u8 (boolean): double sided
+//
+// the content is small, thus it is not compressed
+
+// ris_material\0\0\0\0
+pub const MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5f, 0x6d, 0x61, 0x74, 0x65, 0x72, 0x69, 0x61, 0x6c, 0x00, 0x00, 0x00, 0x00,
+];
+pub const EXTENSION: &str = "ris_material";
+
+pub fn serialize(material: &CpuMaterial) -> RisResult<Vec<u8>> {
+    let mut references = vec![
+        material.vertex_shader.clone(),
+        material.fragment_shader.clone(),
+    ];
+
+    let mut texture_mask = 0u8;
+    for (i, slot) in material.textures.slots().into_iter().enumerate() {
+        if let Some(texture) = slot {
+            texture_mask |= 1 << i;
+            references.push(texture.clone());
+        }
+    }
+
+    let mut stream = Cursor::new(Vec::new());
+    let s = &mut stream;
+
+    let MaterialParams {
+        base_color,
+        metallic,
+        roughness,
+        emissive,
+        normal_scale,
+        occlusion_strength,
+        alpha_mode,
+        alpha_cutoff,
+        double_sided,
+    } = material.params;
+
+    ris_io::write_u8(s, texture_mask)?;
+    for value in base_color {
+        ris_io::write_f32(s, value)?;
+    }
+    ris_io::write_f32(s, metallic)?;
+    ris_io::write_f32(s, roughness)?;
+    for value in emissive {
+        ris_io::write_f32(s, value)?;
+    }
+    ris_io::write_f32(s, normal_scale)?;
+    ris_io::write_f32(s, occlusion_strength)?;
+    ris_io::write_u8(s, alpha_mode.to_u8())?;
+    ris_io::write_f32(s, alpha_cutoff)?;
+    ris_io::write_bool(s, double_sided)?;
+
+    let header = RisHeader::new(MAGIC, references);
+    header.serialize(&stream.into_inner())
+}
+
+pub fn deserialize(bytes: &[u8]) -> RisResult<CpuMaterial> {
+    let (header, content) = RisHeader::deserialize(bytes)?.into_ris_error()?;
+    header.assert_magic(MAGIC)?;
+
+    let mut stream = Cursor::new(content);
+    let s = &mut stream;
+
+    let texture_mask = ris_io::read_u8(s)?;
+    ris_error::assert!(texture_mask >> MaterialTextures::SLOT_COUNT == 0)?;
+
+    let texture_count = texture_mask.count_ones() as usize;
+    ris_error::assert!(header.references.len() == 2 + texture_count)?;
+
+    let mut references = header.references.into_iter();
+    let vertex_shader = references.next().into_ris_error()?;
+    let fragment_shader = references.next().into_ris_error()?;
+
+    let mut textures = MaterialTextures::default();
+    for (i, slot) in textures.slots_mut().into_iter().enumerate() {
+        if texture_mask & (1 << i) != 0 {
+            *slot = Some(references.next().into_ris_error()?);
+        }
+    }
+
+    let mut base_color = [0.0; 4];
+    for value in base_color.iter_mut() {
+        *value = ris_io::read_f32(s)?;
+    }
+    let metallic = ris_io::read_f32(s)?;
+    let roughness = ris_io::read_f32(s)?;
+    let mut emissive = [0.0; 3];
+    for value in emissive.iter_mut() {
+        *value = ris_io::read_f32(s)?;
+    }
+    let normal_scale = ris_io::read_f32(s)?;
+    let occlusion_strength = ris_io::read_f32(s)?;
+    let alpha_mode = AlphaMode::from_u8(ris_io::read_u8(s)?)?;
+    let alpha_cutoff = ris_io::read_f32(s)?;
+    let double_sided = ris_io::read_bool(s)?;
+
+    let params = MaterialParams {
+        base_color,
+        metallic,
+        roughness,
+        emissive,
+        normal_scale,
+        occlusion_strength,
+        alpha_mode,
+        alpha_cutoff,
+        double_sided,
+    };
+
+    Ok(CpuMaterial {
+        vertex_shader,
+        fragment_shader,
+        textures,
+        params,
+    })
+}
@@ -4,6 +4,7 @@ use ash::vk;
 
 use ris_asset_data::mesh::CpuMesh;
 use ris_error::prelude::*;
+use ris_io::FatPtr;
 
 use super::ris_header::RisHeader;
 
@@ -11,6 +12,23 @@ use super::ris_header::RisHeader;
 pub const MAGIC: [u8; 16] = [
     0x72, 0x69, 0x73, 0x5f, 0x6D, 0x65, 0x73, 0x68, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
 ];
+// ris_mesh_skinned
+/// Skinned meshes use a different magic, such that meshes without skin keep their format.
+pub const SKINNED_MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5f, 0x6d, 0x65, 0x73, 0x68, 0x5f, 0x73, 0x6b, 0x69, 0x6e, 0x6e, 0x65, 0x64,
+];
+// ris_mesh_tangent
+/// Meshes with tangents use yet another magic. They store the pointers to the skin as well,
+/// which are null if the mesh is not skinned.
+pub const TANGENT_MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5f, 0x6d, 0x65, 0x73, 0x68, 0x5f, 0x74, 0x61, 0x6e, 0x67, 0x65, 0x6e, 0x74,
+];
+// ris_mesh_lods\0\0\0
+/// Meshes with levels of detail store all pointers of the meshes with tangents, followed by the
+/// pointers to the indices of their levels of detail.
+pub const LOD_MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5f, 0x6d, 0x65, 0x73, 0x68, 0x5f, 0x6c, 0x6f, 0x64, 0x73, 0x00, 0x00, 0x00,
+];
 pub const EXTENSION: &str = "ris_mesh";
 
 pub const COMPRESSION_LEVEL: u8 = 6;
@@ -23,6 +41,22 @@ pub fn serialize(mesh: &CpuMesh) -> RisResult<Vec<u8>> {
     ris_io::write_fat_ptr(s, mesh.p_normals)?;
     ris_io::write_fat_ptr(s, mesh.p_uvs)?;
     ris_io::write_fat_ptr(s, mesh.p_indices)?;
+    let is_skinned = !mesh.p_joints.is_null();
+    let has_tangents = !mesh.p_tangents.is_null();
+    let has_lods = !mesh.p_lods.is_empty();
+    if is_skinned || has_tangents || has_lods {
+        ris_io::write_fat_ptr(s, mesh.p_joints)?;
+        ris_io::write_fat_ptr(s, mesh.p_weights)?;
+    }
+    if has_tangents || has_lods {
+        ris_io::write_fat_ptr(s, mesh.p_tangents)?;
+    }
+    if has_lods {
+        ris_io::write_uint(s, mesh.p_lods.len())?;
+        for &p_lod in mesh.p_lods.iter() {
+            ris_io::write_fat_ptr(s, p_lod)?;
+        }
+    }
     ris_io::write_i32(s, mesh.index_type.as_raw())?;
 
     ris_io::write(s, &mesh.data)?;
@@ -37,13 +71,27 @@ pub fn serialize(mesh: &CpuMesh) -> RisResult<Vec<u8>> {
         compressed.len() as f32 / bytes.len() as f32,
     );
 
-    let header = RisHeader::new(MAGIC, Vec::new());
+    let magic = if has_lods {
+        LOD_MAGIC
+    } else if has_tangents {
+        TANGENT_MAGIC
+    } else if is_skinned {
+        SKINNED_MAGIC
+    } else {
+        MAGIC
+    };
+    let header = RisHeader::new(magic, Vec::new());
     header.serialize(&compressed)
 }
 
 pub fn deserialize(bytes: &[u8]) -> RisResult<CpuMesh> {
     let (header, content) = RisHeader::deserialize(bytes)?.into_ris_error()?;
-    header.assert_magic(MAGIC)?;
+    let has_lods = header.magic == LOD_MAGIC;
+    let has_tangents = has_lods || header.magic == TANGENT_MAGIC;
+    let has_skin = has_tangents || header.magic == SKINNED_MAGIC;
+    if !has_skin {
+        header.assert_magic(MAGIC)?;
+    }
 
     let decompressed = miniz_oxide::inflate::decompress_to_vec(content)
         .map_err(|e| ris_error::new!("failed to decompress: {:?}", e))?;
@@ -55,6 +103,23 @@ pub fn deserialize(bytes: &[u8]) -> RisResult<CpuMesh> {
     let p_normals = ris_io::read_fat_ptr(s)?;
     let p_uvs = ris_io::read_fat_ptr(s)?;
     let p_indices = ris_io::read_fat_ptr(s)?;
+    let (p_joints, p_weights) = if has_skin {
+        (ris_io::read_fat_ptr(s)?, ris_io::read_fat_ptr(s)?)
+    } else {
+        (FatPtr::null(), FatPtr::null())
+    };
+    let p_tangents = if has_tangents {
+        ris_io::read_fat_ptr(s)?
+    } else {
+        FatPtr::null()
+    };
+    let mut p_lods = Vec::new();
+    if has_lods {
+        let lod_count = ris_io::read_uint(s)?;
+        for _ in 0..lod_count {
+            p_lods.push(ris_io::read_fat_ptr(s)?);
+        }
+    }
     let index_type = vk::IndexType::from_raw(ris_io::read_i32(s)?);
     let data = ris_io::read_to_end(s)?;
 
@@ -63,6 +128,10 @@ pub fn deserialize(bytes: &[u8]) -> RisResult<CpuMesh> {
         p_normals,
         p_uvs,
         p_indices,
+        p_joints,
+        p_weights,
+        p_tangents,
+        p_lods,
         index_type,
         data,
     })
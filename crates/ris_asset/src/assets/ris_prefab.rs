@@ -0,0 +1,162 @@
+use ris_asset_data::asset_id::AssetId;
+use ris_data::ecs::components::script_component::Script;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::id::GameObjectKind;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene_stream::SceneReader;
+use ris_data::ecs::scene_stream::SceneWriter;
+use ris_data::ecs::scene_stream::ScriptOverride;
+use ris_error::Extensions;
+use ris_error::RisResult;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+
+use super::ris_scene;
+
+// ris_prefab\0\0\0\0\0\0
+pub const MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5f, 0x70, 0x72, 0x65, 0x66, 0x61, 0x62, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+pub const EXTENSION: &str = "ris_prefab";
+
+/// Values that replace the serialized ones of a single instance.
+#[derive(Default)]
+pub struct PrefabOverrides {
+    pub parent: Option<GameObjectHandle>,
+    pub name: Option<String>,
+    pub position: Option<Vec3>,
+    pub rotation: Option<Quat>,
+    pub scale: Option<Vec3>,
+    scripts: Vec<(String, ScriptOverride)>,
+}
+
+impl PrefabOverrides {
+    /// Modifies every script of type `T` on the game object at `path`, after it was deserialized
+    /// but before it is started.
+    ///
+    /// `path` consists of the names of the game objects below the root, separated by `/`. An
+    /// empty path refers to the root itself.
+    pub fn script<T: Script + 'static>(
+        mut self,
+        path: impl Into<String>,
+        callback: impl FnMut(&mut T) -> RisResult<()> + 'static,
+    ) -> Self {
+        let script_override = ScriptOverride::new(GameObjectHandle::null(), callback);
+        self.scripts.push((path.into(), script_override));
+        self
+    }
+}
+
+/// Serializes `root` and all its descendants. Game objects may only reference game objects in
+/// the same subtree.
+pub fn serialize(scene: &Scene, root: GameObjectHandle) -> RisResult<Vec<u8>> {
+    let handles = root.subtree(scene)?;
+
+    let kind = root.0.scene_id().kind;
+    let mut stream = SceneWriter::new_with_kind(kind, scene);
+    let lookup = ris_scene::write_game_objects(&mut stream, &handles)?;
+    let (bytes, references) = stream.resolve(lookup)?;
+
+    ris_scene::compress(MAGIC, &bytes, references)
+}
+
+/// Creates a new instance of the prefab, made of dynamic game objects. Returns the root of the
+/// instance.
+pub fn instantiate(
+    scene: &Scene,
+    bytes: &[u8],
+    overrides: PrefabOverrides,
+) -> RisResult<GameObjectHandle> {
+    let (references, uncompressed) = ris_scene::decompress(MAGIC, bytes)?;
+
+    let mut created = Vec::new();
+    let result = instantiate_game_objects(scene, uncompressed, references, overrides, &mut created);
+
+    if result.is_err() {
+        // a partial instance must not stay behind in the scene. destroying a game object that was
+        // already destroyed with its parent does nothing
+        for game_object in created.into_iter().rev() {
+            game_object.destroy(scene);
+        }
+    }
+
+    result
+}
+
+fn instantiate_game_objects(
+    scene: &Scene,
+    uncompressed: Vec<u8>,
+    references: Vec<AssetId>,
+    overrides: PrefabOverrides,
+    created: &mut Vec<GameObjectHandle>,
+) -> RisResult<GameObjectHandle> {
+    let mut stream = SceneReader::new(scene, uncompressed, references);
+    let pending = ris_scene::read_game_objects(&mut stream, || {
+        let game_object = GameObjectHandle::new_with_kind(scene, GameObjectKind::Dynamic)?;
+        created.push(game_object);
+        Ok(game_object)
+    })?;
+
+    let root = *stream.lookup.first().into_ris_error()?;
+
+    let PrefabOverrides {
+        parent,
+        name,
+        position,
+        rotation,
+        scale,
+        scripts,
+    } = overrides;
+
+    if let Some(name) = name {
+        root.set_name(scene, name)?;
+    }
+
+    if let Some(position) = position {
+        root.set_position(scene, position)?;
+    }
+
+    if let Some(rotation) = rotation {
+        root.set_rotation(scene, rotation)?;
+    }
+
+    if let Some(scale) = scale {
+        root.set_scale(scene, scale)?;
+    }
+
+    if let Some(parent) = parent {
+        let sibling_index = parent.children(scene)?.len();
+        root.set_parent(scene, Some(parent), sibling_index)?;
+    }
+
+    for (path, mut script_override) in scripts {
+        script_override.game_object = find_by_path(scene, root, &path)?;
+        stream.script_overrides.push(script_override);
+    }
+
+    ris_scene::read_components(&mut stream, pending)?;
+
+    Ok(root)
+}
+
+fn find_by_path(scene: &Scene, root: GameObjectHandle, path: &str) -> RisResult<GameObjectHandle> {
+    let mut current = root;
+
+    for name in path.split('/').filter(|x| !x.is_empty()) {
+        let mut found = None;
+        for child in current.children(scene)? {
+            if child.name(scene)? == name {
+                found = Some(child);
+                break;
+            }
+        }
+
+        let Some(child) = found else {
+            return ris_error::new_result!("prefab does not contain a game object at \"{}\"", path);
+        };
+
+        current = child;
+    }
+
+    Ok(current)
+}
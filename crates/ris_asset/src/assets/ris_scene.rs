@@ -1,6 +1,8 @@
 use std::io::SeekFrom;
 
+use ris_asset_data::asset_id::AssetId;
 use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::handle::DynComponentHandle;
 use ris_data::ecs::scene::Scene;
 use ris_data::ecs::scene_stream::SceneReader;
 use ris_data::ecs::scene_stream::SceneWriter;
@@ -26,18 +28,57 @@ pub fn serialize(scene: &Scene, chunk_index: usize) -> RisResult<Vec<u8>> {
         .game_objects
         .iter()
         .filter(|x| x.borrow().is_alive)
-        .map(|x| x.borrow().handle)
+        .map(|x| GameObjectHandle::from(x.borrow().handle))
         .collect::<Vec<_>>();
 
     let mut stream = SceneWriter::new(chunk_index, scene);
-    let s = &mut stream;
+    let lookup = write_game_objects(&mut stream, &handles)?;
+    let (bytes, references) = stream.resolve(lookup)?;
+
+    compress(MAGIC, &bytes, references)
+}
+
+/// Reserves a chunk and creates the game objects of the scene in it. Returns `None` when no chunk
+/// is available. If the scene fails to deserialize, the chunk is cleared again.
+pub fn deserialize(scene: &Scene, bytes: &[u8]) -> RisResult<Option<usize>> {
+    let (references, uncompressed) = decompress(MAGIC, bytes)?;
 
-    let mut lookup = Vec::new();
+    let reserved = scene.reserve_chunk();
+    let Some(index) = reserved else {
+        return Ok(None);
+    };
+
+    let mut stream = SceneReader::new(scene, uncompressed, references);
+    let result = read_game_objects(&mut stream, || {
+        let game_object = GameObjectHandle::new_static(scene, index)?;
+        Ok(game_object)
+    })
+    .and_then(|pending| read_components(&mut stream, pending));
+
+    if result.is_err() {
+        scene.clear_chunk(index);
+    }
+
+    result.map(|()| Some(index))
+}
+
+/// Components of a deserialized game object, that are not yet deserialized.
+pub struct PendingComponents {
+    pub game_object: GameObjectHandle,
+    ptrs: Vec<FatPtr>,
+}
+
+/// Writes the given game objects, their components and references to their children. Returns the
+/// lookup to resolve the stream with.
+pub fn write_game_objects(
+    s: &mut SceneWriter,
+    handles: &[GameObjectHandle],
+) -> RisResult<Vec<usize>> {
+    let scene = s.scene;
+    let mut lookup = Vec::with_capacity(handles.len());
 
-    // serialize game objects
     ris_io::write_uint(s, handles.len())?;
-    for generic_handle in handles.into_iter() {
-        let handle: GameObjectHandle = generic_handle.into();
+    for &handle in handles.iter() {
         let scene_index = handle.0.scene_id().index;
         lookup.push(scene_index);
 
@@ -53,15 +94,7 @@ pub fn serialize(scene: &Scene, chunk_index: usize) -> RisResult<Vec<u8>> {
             let ptr_addr = ris_io::write_fat_ptr(s, FatPtr::null())?.addr; // placeholder ptr
             let addr = ris_io::seek(s, SeekFrom::Current(0))?;
 
-            let position = scene
-                .registry
-                .component_factories()
-                .iter()
-                .position(|x| x.component_id() == component.type_id())
-                .into_ris_error()?;
-
-            ris_io::write_uint(s, position)?;
-            scene.deref_mut_component(component, |x| x.serialize(s))??;
+            write_component(s, component)?;
 
             // fill placeholder ptr
             let end = ris_io::seek(s, SeekFrom::Current(0))?;
@@ -79,41 +112,17 @@ pub fn serialize(scene: &Scene, chunk_index: usize) -> RisResult<Vec<u8>> {
         }
     }
 
-    // resolve
-    let (bytes, references) = stream.resolve(lookup)?;
-
-    // compress
-    let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, COMPRESSION_LEVEL);
-    ris_log::trace!(
-        "compressed {} to {}. percentage: {}",
-        bytes.len(),
-        compressed.len(),
-        compressed.len() as f32 / bytes.len() as f32,
-    );
-
-    // add header
-    let header = RisHeader::new(MAGIC, references);
-    let result = header.serialize(&compressed)?;
-    Ok(result)
+    Ok(lookup)
 }
 
-pub fn deserialize(scene: &Scene, bytes: &[u8]) -> RisResult<Option<usize>> {
-    let reserved = scene.reserve_chunk();
-    let Some(index) = reserved else {
-        return Ok(None);
-    };
-
-    let chunk = &scene.static_chunks[index];
-
-    let (header, content) = RisHeader::deserialize(bytes)?.into_ris_error()?;
-    header.assert_magic(MAGIC)?;
-
-    let uncompressed = miniz_oxide::inflate::decompress_to_vec(content)
-        .map_err(|e| ris_error::new!("failed to decompress: {:?}", e))?;
-
-    let mut stream = SceneReader::new(index, scene, uncompressed, header.references);
-    let s = &mut stream;
-
+/// Reads the game objects written by `write_game_objects`, creates them using `create` and
+/// restores their hierarchy. Components are deserialized separately by `read_components`, such
+/// that the caller may modify the game objects and the reader in between.
+pub fn read_game_objects(
+    s: &mut SceneReader,
+    mut create: impl FnMut() -> RisResult<GameObjectHandle>,
+) -> RisResult<Vec<PendingComponents>> {
+    let scene = s.scene;
     let game_object_count = ris_io::read_uint(s)?;
 
     s.lookup = Vec::with_capacity(game_object_count);
@@ -144,9 +153,8 @@ pub fn deserialize(scene: &Scene, bytes: &[u8]) -> RisResult<Option<usize>> {
             child_ids.push(child_id);
         }
 
-        let game_object = GameObjectHandle::new_static(scene, index)?;
-        let id = game_object.0.scene_id().index;
-        s.lookup.push(id);
+        let game_object = create()?;
+        s.lookup.push(game_object);
 
         game_object.set_name(scene, &name)?;
         game_object.set_active(scene, is_active)?;
@@ -155,43 +163,91 @@ pub fn deserialize(scene: &Scene, bytes: &[u8]) -> RisResult<Option<usize>> {
         game_object.set_scale(scene, local_scale)?;
 
         children_to_assign.push((game_object, child_ids));
-        components_to_deserialize.push((game_object, component_ptrs));
+        components_to_deserialize.push(PendingComponents {
+            game_object,
+            ptrs: component_ptrs,
+        });
     }
 
     // assign children
     for (game_object, child_ids) in children_to_assign {
         for (i, &child_id) in child_ids.iter().enumerate() {
-            let actual_id = s.lookup.get(child_id).into_ris_error()?;
-
-            let child: GameObjectHandle = chunk
-                .game_objects
-                .iter()
-                .find(|x| x.borrow().handle.scene_id().index == *actual_id)
-                .into_ris_error()?
-                .borrow()
-                .handle
-                .into();
-
+            let child = s.lookup.get(child_id).into_ris_error()?;
             child.set_parent(scene, Some(game_object), i)?;
         }
     }
 
-    // deserialize components
-    for (game_object, component_ptrs) in components_to_deserialize {
-        for FatPtr { addr, len: _ } in component_ptrs {
-            ris_io::seek(s, SeekFrom::Start(addr))?;
-
-            let position = ris_io::read_uint(s)?;
-            let factory = scene
-                .registry
-                .component_factories()
-                .get(position)
-                .into_ris_error()?;
+    Ok(components_to_deserialize)
+}
 
-            let component = factory.make(scene, game_object)?;
-            scene.deref_mut_component(component, |x| x.deserialize(s))??;
+pub fn read_components(s: &mut SceneReader, pending: Vec<PendingComponents>) -> RisResult<()> {
+    for PendingComponents { game_object, ptrs } in pending {
+        for FatPtr { addr, len: _ } in ptrs {
+            ris_io::seek(s, SeekFrom::Start(addr))?;
+            read_component(s, game_object)?;
         }
     }
 
-    Ok(Some(index))
+    Ok(())
+}
+
+/// Writes the factory and the data of a single component.
+pub fn write_component(s: &mut SceneWriter, component: DynComponentHandle) -> RisResult<()> {
+    let scene = s.scene;
+
+    let position = scene
+        .registry
+        .component_factories()
+        .iter()
+        .position(|x| x.component_id() == component.type_id())
+        .into_ris_error()?;
+
+    ris_io::write_uint(s, position)?;
+    scene.deref_mut_component(component, |x| x.serialize(s))??;
+    Ok(())
+}
+
+/// Reads a component written by `write_component` and adds it to the game object.
+pub fn read_component(
+    s: &mut SceneReader,
+    game_object: GameObjectHandle,
+) -> RisResult<DynComponentHandle> {
+    let scene = s.scene;
+
+    let position = ris_io::read_uint(s)?;
+    let factory = scene
+        .registry
+        .component_factories()
+        .get(position)
+        .into_ris_error()?;
+
+    let component = factory.make(scene, game_object)?;
+    scene.deref_mut_component(component, |x| x.deserialize(s))??;
+    Ok(component)
+}
+
+/// Compresses the bytes of a resolved stream and prepends a header.
+pub fn compress(magic: [u8; 16], bytes: &[u8], references: Vec<AssetId>) -> RisResult<Vec<u8>> {
+    let compressed = miniz_oxide::deflate::compress_to_vec(bytes, COMPRESSION_LEVEL);
+    ris_log::trace!(
+        "compressed {} to {}. percentage: {}",
+        bytes.len(),
+        compressed.len(),
+        compressed.len() as f32 / bytes.len() as f32,
+    );
+
+    let header = RisHeader::new(magic, references);
+    let result = header.serialize(&compressed)?;
+    Ok(result)
+}
+
+/// Reverses `compress`. Returns the references and the uncompressed bytes.
+pub fn decompress(magic: [u8; 16], bytes: &[u8]) -> RisResult<(Vec<AssetId>, Vec<u8>)> {
+    let (header, content) = RisHeader::deserialize(bytes)?.into_ris_error()?;
+    header.assert_magic(magic)?;
+
+    let uncompressed = miniz_oxide::inflate::decompress_to_vec(content)
+        .map_err(|e| ris_error::new!("failed to decompress: {:?}", e))?;
+
+    Ok((header.references, uncompressed))
 }
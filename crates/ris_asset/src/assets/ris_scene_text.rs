@@ -0,0 +1,701 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::io::SeekFrom;
+use std::sync::Arc;
+
+use ris_asset_data::asset_id::AssetId;
+use ris_data::ecs::components::script_component::DynScriptComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::registry::IComponentFactory;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::scene_stream::SceneField;
+use ris_data::ecs::scene_stream::SceneReader;
+use ris_data::ecs::scene_stream::SceneValue;
+use ris_data::ecs::scene_stream::SceneWriter;
+use ris_data::ris_yaml;
+use ris_data::ris_yaml::RisYaml;
+use ris_error::Extensions;
+use ris_error::RisResult;
+use ris_io::FatPtr;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+
+use super::ris_scene;
+
+// # File Format
+//
+// A `RisYaml` file. Each game object starts with a `game_object` entry, followed by its
+// properties and components. Each component starts with a `component` or `script` entry,
+// followed by its fields in the order they are serialized:
+//
+// format: ris_scene_text
+// version: 1
+//
+// game_object: 0
+// name: player
+// is_active: true
+// position: 0.0 0.0 0.0
+// rotation: 0.0 0.0 0.0 1.0
+// scale: 1.0 1.0 1.0
+// children: 1
+// component: MeshComponent
+// field.has_asset: bool true
+// field.asset: asset models/Suzanne.ris_mesh
+// script: TestRotationScript
+// field.rotation_axis: vec3 0.0 1.0 0.0
+// field.rotation_speed: f32 2.5
+//
+// game_object: 1
+// ...
+//
+// Game objects reference each other by the number of their `game_object` entry. Fields that a
+// component writes without a name are listed as `field`, bytes that it writes without
+// `SceneWriter::write_field` are listed as hex encoded `bytes`.
+//
+// Strings are percent encoded, such that they may contain `:`, `#` and line breaks.
+
+pub const FORMAT: &str = "ris_scene_text";
+pub const VERSION: usize = 1;
+pub const EXTENSION: &str = "ris_scene_text";
+
+const KEY_FORMAT: &str = "format";
+const KEY_VERSION: &str = "version";
+const KEY_GAME_OBJECT: &str = "game_object";
+const KEY_NAME: &str = "name";
+const KEY_IS_ACTIVE: &str = "is_active";
+const KEY_POSITION: &str = "position";
+const KEY_ROTATION: &str = "rotation";
+const KEY_SCALE: &str = "scale";
+const KEY_CHILDREN: &str = "children";
+const KEY_COMPONENT: &str = "component";
+const KEY_SCRIPT: &str = "script";
+const KEY_FIELD: &str = "field";
+const FIELD_PREFIX: &str = "field.";
+
+const TYPE_BOOL: &str = "bool";
+const TYPE_I32: &str = "i32";
+const TYPE_UINT: &str = "uint";
+const TYPE_F32: &str = "f32";
+const TYPE_STRING: &str = "string";
+const TYPE_VEC3: &str = "vec3";
+const TYPE_QUAT: &str = "quat";
+const TYPE_GAME_OBJECT: &str = "game_object";
+const TYPE_ASSET: &str = "asset";
+const TYPE_BYTES: &str = "bytes";
+
+struct TextGameObject {
+    id: usize,
+    line: usize,
+    name: String,
+    is_active: bool,
+    position: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+    children: Vec<usize>,
+    components: Vec<TextComponent>,
+}
+
+struct TextComponent {
+    line: usize,
+    is_script: bool,
+    name: String,
+    values: Vec<TextValue>,
+}
+
+enum TextValue {
+    Bool(bool),
+    I32(i32),
+    Uint(usize),
+    F32(f32),
+    String(String),
+    Vec3(Vec3),
+    Quat(Quat),
+    GameObject(usize),
+    Asset(String),
+    Bytes(Vec<u8>),
+}
+
+pub fn serialize(scene: &Scene, chunk_index: usize) -> RisResult<String> {
+    ris_error::debug_assert!(chunk_index < scene.static_chunks.len())?;
+    let chunk = &scene.static_chunks[chunk_index];
+
+    let handles = chunk
+        .game_objects
+        .iter()
+        .filter(|x| x.borrow().is_alive)
+        .map(|x| GameObjectHandle::from(x.borrow().handle))
+        .collect::<Vec<_>>();
+
+    let lookup = handles
+        .iter()
+        .map(|x| x.0.scene_id().index)
+        .collect::<Vec<_>>();
+
+    let id_of = |handle: GameObjectHandle| {
+        let scene_index = handle.0.scene_id().index;
+        lookup
+            .iter()
+            .position(|&x| x == scene_index)
+            .into_ris_error()
+    };
+
+    let mut yaml = RisYaml::default();
+    add(&mut yaml, KEY_FORMAT, FORMAT);
+    add(&mut yaml, KEY_VERSION, VERSION.to_string());
+
+    for (id, &handle) in handles.iter().enumerate() {
+        yaml.add_entry(None, None);
+        add(&mut yaml, KEY_GAME_OBJECT, id.to_string());
+        add(&mut yaml, KEY_NAME, escape(&handle.name(scene)?));
+        add(
+            &mut yaml,
+            KEY_IS_ACTIVE,
+            handle.is_active(scene)?.to_string(),
+        );
+        add(
+            &mut yaml,
+            KEY_POSITION,
+            format_vec3(handle.position(scene)?),
+        );
+        add(
+            &mut yaml,
+            KEY_ROTATION,
+            format_quat(handle.rotation(scene)?),
+        );
+        add(&mut yaml, KEY_SCALE, format_vec3(handle.scale(scene)?));
+
+        let children = handle
+            .children(scene)?
+            .into_iter()
+            .map(|x| id_of(x).map(|x| x.to_string()))
+            .collect::<RisResult<Vec<_>>>()?;
+        if !children.is_empty() {
+            add(&mut yaml, KEY_CHILDREN, children.join(" "));
+        }
+
+        for component in handle.components(scene)? {
+            let mut stream = SceneWriter::new(chunk_index, scene);
+            scene.deref_mut_component(component, |x| x.serialize(&mut stream))??;
+            let fields = stream.fields().to_vec();
+            let (bytes, _) = stream.resolve(lookup.clone())?;
+
+            // scripts all share the same component, which writes the index of the script
+            // before the script itself
+            let start = if component.type_id() == TypeId::of::<DynScriptComponent>() {
+                let mut cursor = Cursor::new(&bytes);
+                let script_index = ris_io::read_uint(&mut cursor)?;
+                let factory = scene
+                    .registry
+                    .script_factories()
+                    .get(script_index)
+                    .into_ris_error()?;
+                add(&mut yaml, KEY_SCRIPT, factory.script_name());
+                ris_io::seek(&mut cursor, SeekFrom::Current(0))? as usize
+            } else {
+                let factory = scene
+                    .registry
+                    .component_factories()
+                    .iter()
+                    .find(|x| x.component_id() == component.type_id())
+                    .into_ris_error()?;
+                add(&mut yaml, KEY_COMPONENT, factory.component_name());
+                0
+            };
+
+            add_fields(&mut yaml, &bytes, start, &fields, &id_of)?;
+        }
+    }
+
+    yaml.serialize()
+}
+
+/// Reserves a chunk and creates the game objects of the text scene in it. Returns `None` when no
+/// chunk is available.
+///
+/// The text is validated before the chunk is reserved. If a component still fails to
+/// deserialize, the chunk is cleared again, such that no half populated chunk is left behind.
+pub fn deserialize(scene: &Scene, text: &str) -> RisResult<Option<usize>> {
+    let game_objects = parse(text)?;
+    let position_of = validate(scene, &game_objects)?;
+
+    let reserved = scene.reserve_chunk();
+    let Some(chunk_index) = reserved else {
+        return Ok(None);
+    };
+
+    let result = populate(scene, chunk_index, &game_objects, &position_of);
+    if result.is_err() {
+        scene.clear_chunk(chunk_index);
+    }
+
+    result.map(|()| Some(chunk_index))
+}
+
+/// Checks everything that can be checked without creating game objects. Returns the position
+/// of each game object by its id.
+fn validate(scene: &Scene, game_objects: &[TextGameObject]) -> RisResult<HashMap<usize, usize>> {
+    let mut position_of = HashMap::new();
+    for (i, game_object) in game_objects.iter().enumerate() {
+        if position_of.insert(game_object.id, i).is_some() {
+            return ris_yaml::error_on_line(
+                game_object.line,
+                &format!("game object {} is declared twice", game_object.id),
+            );
+        }
+    }
+
+    let mut has_parent = vec![false; game_objects.len()];
+    for game_object in game_objects.iter() {
+        for &child_id in game_object.children.iter() {
+            let child = resolve(&position_of, child_id, game_object.line)?;
+            if std::mem::replace(&mut has_parent[child], true) {
+                return ris_yaml::error_on_line(
+                    game_object.line,
+                    &format!("game object {} has multiple parents", child_id),
+                );
+            }
+        }
+
+        for component in game_object.components.iter() {
+            if component.is_script {
+                script_index(scene, component)?;
+            }
+
+            factory(scene, component)?;
+
+            for value in component.values.iter() {
+                if let TextValue::GameObject(id) = value {
+                    resolve(&position_of, *id, component.line)?;
+                }
+            }
+        }
+    }
+
+    Ok(position_of)
+}
+
+fn populate(
+    scene: &Scene,
+    chunk_index: usize,
+    game_objects: &[TextGameObject],
+    position_of: &HashMap<usize, usize>,
+) -> RisResult<()> {
+    let mut handles = Vec::with_capacity(game_objects.len());
+    for game_object in game_objects.iter() {
+        let handle = GameObjectHandle::new_static(scene, chunk_index)?;
+        handle.set_name(scene, &game_object.name)?;
+        handle.set_active(scene, game_object.is_active)?;
+        handle.set_position(scene, game_object.position)?;
+        handle.set_rotation(scene, game_object.rotation)?;
+        handle.set_scale(scene, game_object.scale)?;
+        handles.push(handle);
+    }
+
+    for (i, game_object) in game_objects.iter().enumerate() {
+        for (sibling_index, &child_id) in game_object.children.iter().enumerate() {
+            let child = resolve(position_of, child_id, game_object.line)?;
+            handles[child].set_parent(scene, Some(handles[i]), sibling_index)?;
+        }
+    }
+
+    for (i, game_object) in game_objects.iter().enumerate() {
+        for component in game_object.components.iter() {
+            let mut bytes = Cursor::new(Vec::new());
+            let mut asset_ids = Vec::new();
+
+            if component.is_script {
+                ris_io::write_uint(&mut bytes, script_index(scene, component)?)?;
+            }
+
+            let factory = factory(scene, component)?;
+
+            for value in component.values.iter() {
+                let bytes = &mut bytes;
+                match value {
+                    TextValue::Bool(value) => ris_io::write_bool(bytes, *value)?,
+                    TextValue::I32(value) => ris_io::write_i32(bytes, *value)?,
+                    TextValue::Uint(value) => ris_io::write_uint(bytes, *value)?,
+                    TextValue::F32(value) => ris_io::write_f32(bytes, *value)?,
+                    TextValue::String(value) => ris_io::write_string(bytes, value)?,
+                    TextValue::Vec3(value) => ris_io::write_vec3(bytes, *value)?,
+                    TextValue::Quat(value) => ris_io::write_quat(bytes, *value)?,
+                    TextValue::GameObject(id) => {
+                        let position = resolve(position_of, *id, component.line)?;
+                        ris_io::write_uint(bytes, position)?
+                    }
+                    TextValue::Asset(path) => {
+                        let asset_id = AssetId::Path(path.clone());
+                        let index = match asset_ids.iter().position(|x| *x == asset_id) {
+                            Some(index) => index,
+                            None => {
+                                asset_ids.push(asset_id);
+                                asset_ids.len() - 1
+                            }
+                        };
+                        ris_io::write_uint(bytes, index)?
+                    }
+                    TextValue::Bytes(value) => ris_io::write(bytes, value)?,
+                };
+            }
+
+            let bytes = bytes.into_inner();
+            let len = bytes.len();
+
+            let handle = factory.make(scene, handles[i])?;
+            let mut stream = SceneReader::new(scene, bytes, asset_ids);
+            stream.lookup = handles.clone();
+            scene.deref_mut_component(handle, |x| x.deserialize(&mut stream))??;
+
+            let read = ris_io::seek(&mut stream, SeekFrom::Current(0))? as usize;
+            if read != len {
+                return ris_yaml::error_on_line(
+                    component.line,
+                    &format!(
+                        "\"{}\" read {} bytes, but its fields are {} bytes long",
+                        component.name, read, len,
+                    ),
+                );
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn resolve(position_of: &HashMap<usize, usize>, id: usize, line: usize) -> RisResult<usize> {
+    match position_of.get(&id) {
+        Some(&position) => Ok(position),
+        None => ris_yaml::error_on_line(line, &format!("game object {} does not exist", id)),
+    }
+}
+
+fn script_index(scene: &Scene, component: &TextComponent) -> RisResult<usize> {
+    let script_index = scene
+        .registry
+        .script_factories()
+        .iter()
+        .position(|x| x.script_name() == component.name);
+
+    match script_index {
+        Some(script_index) => Ok(script_index),
+        None => ris_yaml::error_on_line(
+            component.line,
+            &format!("script \"{}\" is not registered", component.name),
+        ),
+    }
+}
+
+fn factory<'a>(
+    scene: &'a Scene,
+    component: &TextComponent,
+) -> RisResult<&'a dyn IComponentFactory> {
+    let factory = if component.is_script {
+        scene
+            .registry
+            .component_factories()
+            .iter()
+            .find(|x| x.component_id() == TypeId::of::<DynScriptComponent>())
+    } else {
+        scene
+            .registry
+            .component_factories()
+            .iter()
+            .find(|x| x.component_name() == component.name)
+    };
+
+    match factory {
+        Some(factory) => Ok(factory.as_ref()),
+        None => ris_yaml::error_on_line(
+            component.line,
+            &format!("component \"{}\" is not registered", component.name),
+        ),
+    }
+}
+
+/// Converts a text scene to a binary `ris_scene`. `registry` must contain the components and
+/// scripts the text scene refers to.
+pub fn to_binary(registry: Arc<Registry>, text: &str) -> RisResult<Vec<u8>> {
+    let scene_create_info = SceneCreateInfo::with_single_static_chunk(registry);
+    let scene = Scene::new(scene_create_info)?;
+
+    let chunk_index = deserialize(&scene, text)?.into_ris_error()?;
+    ris_scene::serialize(&scene, chunk_index)
+}
+
+fn add(yaml: &mut RisYaml, key: &str, value: impl AsRef<str>) {
+    yaml.add_entry(Some((key, value.as_ref())), None);
+}
+
+fn add_fields(
+    yaml: &mut RisYaml,
+    bytes: &[u8],
+    start: usize,
+    fields: &[(FatPtr, SceneField)],
+    id_of: &impl Fn(GameObjectHandle) -> RisResult<usize>,
+) -> RisResult<()> {
+    let mut cursor = start;
+
+    for (ptr, field) in fields.iter() {
+        let begin = ptr.addr as usize;
+        let end = ptr.end() as usize;
+
+        if begin < cursor {
+            return ris_error::new_result!(
+                "field \"{}\" overlaps with previous fields",
+                field.name
+            );
+        }
+
+        add_bytes(yaml, &bytes[cursor..begin]);
+        cursor = end;
+
+        let value = match &field.value {
+            SceneValue::Bool(value) => format!("{} {}", TYPE_BOOL, value),
+            SceneValue::I32(value) => format!("{} {}", TYPE_I32, value),
+            SceneValue::Uint(value) => format!("{} {}", TYPE_UINT, value),
+            SceneValue::F32(value) => format!("{} {:?}", TYPE_F32, value),
+            SceneValue::String(value) => format!("{} {}", TYPE_STRING, escape(value)),
+            SceneValue::Vec3(value) => format!("{} {}", TYPE_VEC3, format_vec3(*value)),
+            SceneValue::Quat(value) => format!("{} {}", TYPE_QUAT, format_quat(*value)),
+            SceneValue::GameObject(value) => format!("{} {}", TYPE_GAME_OBJECT, id_of(*value)?),
+            SceneValue::Asset(AssetId::Path(path)) => format!("{} {}", TYPE_ASSET, escape(path)),
+            SceneValue::Asset(AssetId::Index(index)) => {
+                return ris_error::new_result!(
+                    "text scenes reference assets by path, but field \"{}\" references asset {}",
+                    field.name,
+                    index,
+                );
+            }
+        };
+
+        let key = match field.name.is_empty() {
+            true => KEY_FIELD.to_string(),
+            false => format!("{}{}", FIELD_PREFIX, escape(&field.name)),
+        };
+        add(yaml, &key, value);
+    }
+
+    add_bytes(yaml, &bytes[cursor..]);
+
+    Ok(())
+}
+
+fn add_bytes(yaml: &mut RisYaml, bytes: &[u8]) {
+    if bytes.is_empty() {
+        return;
+    }
+
+    let hex = bytes
+        .iter()
+        .map(|x| format!("{:02x}", x))
+        .collect::<String>();
+    add(yaml, KEY_FIELD, format!("{} {}", TYPE_BYTES, hex));
+}
+
+fn parse(text: &str) -> RisResult<Vec<TextGameObject>> {
+    let yaml = RisYaml::deserialize(text)?;
+
+    let format = yaml.get_value(KEY_FORMAT);
+    if format != Some(FORMAT) {
+        return ris_error::new_result!("expected format \"{}\" but was {:?}", FORMAT, format);
+    }
+
+    let version = yaml.get_value(KEY_VERSION);
+    if version != Some(VERSION.to_string().as_str()) {
+        return ris_error::new_result!("unsupported version {:?}", version);
+    }
+
+    let mut game_objects = Vec::<TextGameObject>::new();
+
+    for entry in yaml.entries.iter() {
+        let Some((key, value)) = entry.key_value.as_ref() else {
+            continue;
+        };
+
+        let line = entry.line;
+        let key = key.as_str();
+
+        if key == KEY_FORMAT || key == KEY_VERSION {
+            continue;
+        }
+
+        if key == KEY_GAME_OBJECT {
+            game_objects.push(TextGameObject {
+                id: parse_value(value, line)?,
+                line,
+                name: String::new(),
+                is_active: true,
+                position: Vec3::init(0.0),
+                rotation: Quat::identity(),
+                scale: Vec3::init(1.0),
+                children: Vec::new(),
+                components: Vec::new(),
+            });
+            continue;
+        }
+
+        let Some(game_object) = game_objects.last_mut() else {
+            return ris_yaml::error_on_line(
+                line,
+                &format!("\"{}\" must follow a game object", key),
+            );
+        };
+
+        match key {
+            KEY_NAME => game_object.name = unescape(value, line)?,
+            KEY_IS_ACTIVE => game_object.is_active = parse_value(value, line)?,
+            KEY_POSITION => game_object.position = parse_vec3(value, line)?,
+            KEY_ROTATION => game_object.rotation = parse_quat(value, line)?,
+            KEY_SCALE => game_object.scale = parse_vec3(value, line)?,
+            KEY_CHILDREN => {
+                game_object.children = value
+                    .split_whitespace()
+                    .map(|x| parse_value(x, line))
+                    .collect::<RisResult<_>>()?;
+            }
+            KEY_COMPONENT | KEY_SCRIPT => game_object.components.push(TextComponent {
+                line,
+                is_script: key == KEY_SCRIPT,
+                name: value.clone(),
+                values: Vec::new(),
+            }),
+            _ if key == KEY_FIELD || key.starts_with(FIELD_PREFIX) => {
+                let Some(component) = game_object.components.last_mut() else {
+                    return ris_yaml::error_on_line(line, "field must follow a component");
+                };
+
+                let value = parse_field(value, line)?;
+                component.values.push(value);
+            }
+            _ => {
+                return ris_yaml::error_on_line(line, &format!("unknown key \"{}\"", key));
+            }
+        }
+    }
+
+    Ok(game_objects)
+}
+
+fn parse_field(value: &str, line: usize) -> RisResult<TextValue> {
+    let (value_type, value) = value.split_once(' ').unwrap_or((value, ""));
+    let value = value.trim();
+
+    let result = match value_type {
+        TYPE_BOOL => TextValue::Bool(parse_value(value, line)?),
+        TYPE_I32 => TextValue::I32(parse_value(value, line)?),
+        TYPE_UINT => TextValue::Uint(parse_value(value, line)?),
+        TYPE_F32 => TextValue::F32(parse_value(value, line)?),
+        TYPE_STRING => TextValue::String(unescape(value, line)?),
+        TYPE_VEC3 => TextValue::Vec3(parse_vec3(value, line)?),
+        TYPE_QUAT => TextValue::Quat(parse_quat(value, line)?),
+        TYPE_GAME_OBJECT => TextValue::GameObject(parse_value(value, line)?),
+        TYPE_ASSET => TextValue::Asset(unescape(value, line)?),
+        TYPE_BYTES => {
+            if value.len() % 2 != 0 {
+                return ris_yaml::error_on_line(line, "bytes must consist of pairs of hex digits");
+            }
+
+            let bytes = (0..value.len())
+                .step_by(2)
+                .map(|i| u8::from_str_radix(value.get(i..i + 2).unwrap_or_default(), 16))
+                .collect::<Result<Vec<_>, _>>();
+            match bytes {
+                Ok(bytes) => TextValue::Bytes(bytes),
+                Err(e) => return ris_yaml::error_on_line(line, &e.to_string()),
+            }
+        }
+        _ => {
+            return ris_yaml::error_on_line(line, &format!("unknown type \"{}\"", value_type));
+        }
+    };
+
+    Ok(result)
+}
+
+fn parse_value<T: std::str::FromStr>(value: &str, line: usize) -> RisResult<T> {
+    match value.trim().parse() {
+        Ok(value) => Ok(value),
+        Err(_) => ris_yaml::error_on_line(line, &format!("invalid value \"{}\"", value)),
+    }
+}
+
+fn parse_floats<const N: usize>(value: &str, line: usize) -> RisResult<[f32; N]> {
+    let floats = value
+        .split_whitespace()
+        .map(|x| parse_value(x, line))
+        .collect::<RisResult<Vec<f32>>>()?;
+
+    match floats.try_into() {
+        Ok(floats) => Ok(floats),
+        Err(_) => ris_yaml::error_on_line(line, &format!("expected {} numbers", N)),
+    }
+}
+
+fn parse_vec3(value: &str, line: usize) -> RisResult<Vec3> {
+    let [x, y, z] = parse_floats(value, line)?;
+    Ok(Vec3(x, y, z))
+}
+
+fn parse_quat(value: &str, line: usize) -> RisResult<Quat> {
+    let [x, y, z, w] = parse_floats(value, line)?;
+    Ok(Quat(x, y, z, w))
+}
+
+fn format_vec3(value: Vec3) -> String {
+    format!("{:?} {:?} {:?}", value.0, value.1, value.2)
+}
+
+fn format_quat(value: Quat) -> String {
+    format!("{:?} {:?} {:?} {:?}", value.0, value.1, value.2, value.3)
+}
+
+fn escape(value: &str) -> String {
+    let mut result = String::with_capacity(value.len());
+    let last = value.chars().count().saturating_sub(1);
+
+    for (i, c) in value.chars().enumerate() {
+        let is_edge = i == 0 || i == last;
+        let must_escape = matches!(c, '%' | ':' | '#' | '\n' | '\r') || (is_edge && c == ' ');
+
+        if must_escape {
+            result.push_str(&format!("%{:02X}", c as u32));
+        } else {
+            result.push(c);
+        }
+    }
+
+    result
+}
+
+fn unescape(value: &str, line: usize) -> RisResult<String> {
+    let mut bytes = Vec::with_capacity(value.len());
+    let mut iter = value.bytes();
+
+    while let Some(byte) = iter.next() {
+        if byte != b'%' {
+            bytes.push(byte);
+            continue;
+        }
+
+        let hex = [iter.next(), iter.next()];
+        let decoded = match hex {
+            [Some(a), Some(b)] => std::str::from_utf8(&[a, b])
+                .ok()
+                .and_then(|x| u8::from_str_radix(x, 16).ok()),
+            _ => None,
+        };
+
+        match decoded {
+            Some(decoded) => bytes.push(decoded),
+            None => return ris_yaml::error_on_line(line, "invalid percent encoding"),
+        }
+    }
+
+    match String::from_utf8(bytes) {
+        Ok(result) => Ok(result),
+        Err(e) => ris_yaml::error_on_line(line, &e.to_string()),
+    }
+}
@@ -0,0 +1,99 @@
+use std::io::Cursor;
+
+use ris_asset_data::skeleton::Joint;
+use ris_asset_data::skeleton::JointTransform;
+use ris_asset_data::skeleton::Skeleton;
+use ris_error::prelude::*;
+
+use super::ris_header::RisHeader;
+
+// # File Format
+//
+// encoding: little-endian
+//
+// - RisHeader
+// - deflate compressed content:
+//   - u32: joint count
+//   - for each joint:
+//     - string: name
+//     - u8 (boolean): has parent
+//     - u32: parent index. only present if the joint has a parent
+//     - vec3: rest translation
+//     - quat: rest rotation
+//     - vec3: rest scale
+//     - mat4: inverse bind matrix
+
+// ris_skeleton\0\0\0\0
+pub const MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5f, 0x73, 0x6b, 0x65, 0x6c, 0x65, 0x74, 0x6f, 0x6e, 0x00, 0x00, 0x00, 0x00,
+];
+pub const EXTENSION: &str = "ris_skeleton";
+
+pub const COMPRESSION_LEVEL: u8 = 6;
+
+pub fn serialize(skeleton: &Skeleton) -> RisResult<Vec<u8>> {
+    skeleton.validate()?;
+
+    let mut stream = Cursor::new(Vec::new());
+    let s = &mut stream;
+
+    ris_io::write_u32(s, u32::try_from(skeleton.joints.len())?)?;
+    for joint in skeleton.joints.iter() {
+        ris_io::write_string(s, &joint.name)?;
+        ris_io::write_bool(s, joint.parent.is_some())?;
+        if let Some(parent) = joint.parent {
+            ris_io::write_u32(s, u32::try_from(parent)?)?;
+        }
+        ris_io::write_vec3(s, joint.rest.translation)?;
+        ris_io::write_quat(s, joint.rest.rotation)?;
+        ris_io::write_vec3(s, joint.rest.scale)?;
+        ris_io::write_mat4(s, joint.inverse_bind_matrix)?;
+    }
+
+    let bytes = stream.into_inner();
+    let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, COMPRESSION_LEVEL);
+
+    let header = RisHeader::new(MAGIC, Vec::new());
+    header.serialize(&compressed)
+}
+
+pub fn deserialize(bytes: &[u8]) -> RisResult<Skeleton> {
+    let (header, content) = RisHeader::deserialize(bytes)?.into_ris_error()?;
+    header.assert_magic(MAGIC)?;
+
+    let decompressed = miniz_oxide::inflate::decompress_to_vec(content)
+        .map_err(|e| ris_error::new!("failed to decompress: {:?}", e))?;
+
+    let mut stream = Cursor::new(decompressed);
+    let s = &mut stream;
+
+    let joint_count = ris_io::read_u32(s)? as usize;
+    let mut joints = Vec::with_capacity(joint_count);
+    for _ in 0..joint_count {
+        let name = ris_io::read_string(s)?;
+        let parent = if ris_io::read_bool(s)? {
+            Some(ris_io::read_u32(s)? as usize)
+        } else {
+            None
+        };
+        let translation = ris_io::read_vec3(s)?;
+        let rotation = ris_io::read_quat(s)?;
+        let scale = ris_io::read_vec3(s)?;
+        let inverse_bind_matrix = ris_io::read_mat4(s)?;
+
+        joints.push(Joint {
+            name,
+            parent,
+            rest: JointTransform {
+                translation,
+                rotation,
+                scale,
+            },
+            inverse_bind_matrix,
+        });
+    }
+
+    let skeleton = Skeleton { joints };
+    skeleton.validate()?;
+    Ok(skeleton)
+}
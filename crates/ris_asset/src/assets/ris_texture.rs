@@ -0,0 +1,112 @@
+use std::io::Cursor;
+
+use ris_asset_data::texture::AddressMode;
+use ris_asset_data::texture::CpuTexture;
+use ris_asset_data::texture::Filter;
+use ris_asset_data::texture::SamplerSettings;
+use ris_asset_data::texture::TextureFormat;
+use ris_error::prelude::*;
+
+use super::ris_header::RisHeader;
+
+// # File Format
+//
+// encoding: little-endian
+//
+// - RisHeader
+// - deflate compressed content:
+//   - u32: width
+//   - u32: height
+//   - u8: format
+//   - u8 (boolean): srgb
+//   - u8: mag filter
+//   - u8: min filter
+//   - u8: mipmap filter
+//   - u8: address mode
+//   - u32: mip_count
+//   - [[u8; ?]; mip_count]: mips, largest first. the size of each mip follows from the
+//     dimensions and the format
+
+// ris_texture\0\0\0\0\0
+pub const MAGIC: [u8; 16] = [
+    0x72, 0x69, 0x73, 0x5f, 0x74, 0x65, 0x78, 0x74, 0x75, 0x72, 0x65, 0x00, 0x00, 0x00, 0x00, 0x00,
+];
+pub const EXTENSION: &str = "ris_texture";
+
+pub const COMPRESSION_LEVEL: u8 = 6;
+
+pub fn serialize(texture: &CpuTexture) -> RisResult<Vec<u8>> {
+    texture.validate()?;
+
+    let mut stream = Cursor::new(Vec::new());
+    let s = &mut stream;
+
+    ris_io::write_u32(s, u32::try_from(texture.width)?)?;
+    ris_io::write_u32(s, u32::try_from(texture.height)?)?;
+    ris_io::write_u8(s, texture.format.to_u8())?;
+    ris_io::write_bool(s, texture.srgb)?;
+    ris_io::write_u8(s, texture.sampler.mag_filter.to_u8())?;
+    ris_io::write_u8(s, texture.sampler.min_filter.to_u8())?;
+    ris_io::write_u8(s, texture.sampler.mipmap_filter.to_u8())?;
+    ris_io::write_u8(s, texture.sampler.address_mode.to_u8())?;
+    ris_io::write_u32(s, u32::try_from(texture.mips.len())?)?;
+    for mip in texture.mips.iter() {
+        ris_io::write(s, mip)?;
+    }
+
+    let bytes = stream.into_inner();
+    let compressed = miniz_oxide::deflate::compress_to_vec(&bytes, COMPRESSION_LEVEL);
+
+    ris_log::trace!(
+        "compressed {} to {}. percentage: {}",
+        bytes.len(),
+        compressed.len(),
+        compressed.len() as f32 / bytes.len() as f32,
+    );
+
+    let header = RisHeader::new(MAGIC, Vec::new());
+    header.serialize(&compressed)
+}
+
+pub fn deserialize(bytes: &[u8]) -> RisResult<CpuTexture> {
+    let (header, content) = RisHeader::deserialize(bytes)?.into_ris_error()?;
+    header.assert_magic(MAGIC)?;
+
+    let decompressed = miniz_oxide::inflate::decompress_to_vec(content)
+        .map_err(|e| ris_error::new!("failed to decompress: {:?}", e))?;
+
+    let mut stream = Cursor::new(decompressed);
+    let s = &mut stream;
+
+    let width = ris_io::read_u32(s)? as usize;
+    let height = ris_io::read_u32(s)? as usize;
+    let format = TextureFormat::from_u8(ris_io::read_u8(s)?)?;
+    let srgb = ris_io::read_bool(s)?;
+    let sampler = SamplerSettings {
+        mag_filter: Filter::from_u8(ris_io::read_u8(s)?)?,
+        min_filter: Filter::from_u8(ris_io::read_u8(s)?)?,
+        mipmap_filter: Filter::from_u8(ris_io::read_u8(s)?)?,
+        address_mode: AddressMode::from_u8(ris_io::read_u8(s)?)?,
+    };
+    let mip_count = ris_io::read_u32(s)? as usize;
+    ris_error::assert!(mip_count <= ris_asset_data::texture::mip_level_count(width, height))?;
+
+    let mut texture = CpuTexture {
+        width,
+        height,
+        format,
+        srgb,
+        sampler,
+        mips: Vec::with_capacity(mip_count),
+    };
+
+    for i in 0..mip_count {
+        let len = texture.mip_width(i) * texture.mip_height(i) * format.pixel_width();
+        let mut mip = vec![0; len];
+        ris_io::read(s, &mut mip)?;
+        texture.mips.push(mip);
+    }
+
+    texture.validate()?;
+    Ok(texture)
+}
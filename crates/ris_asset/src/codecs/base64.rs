@@ -0,0 +1,71 @@
+// base64 as defined by RFC 4648, with the standard alphabet and padding. used by data uris
+
+use ris_error::prelude::*;
+
+const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const PADDING: u8 = b'=';
+
+pub fn encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b0 = chunk[0];
+        let b1 = chunk.get(1).copied().unwrap_or(0);
+        let b2 = chunk.get(2).copied().unwrap_or(0);
+        let group = (u32::from(b0) << 16) | (u32::from(b1) << 8) | u32::from(b2);
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let sextet = (group >> (18 - 6 * i)) & 0x3F;
+                encoded.push(ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push(PADDING as char);
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Padding is optional.
+pub fn decode(encoded: &str) -> RisResult<Vec<u8>> {
+    let encoded = encoded.as_bytes();
+    let unpadded_len = encoded
+        .iter()
+        .rposition(|&x| x != PADDING)
+        .map(|x| x + 1)
+        .unwrap_or(0);
+    ris_error::assert!(encoded.len() - unpadded_len <= 2)?;
+    let encoded = &encoded[..unpadded_len];
+
+    let mut decoded = Vec::with_capacity(encoded.len() * 3 / 4);
+    for chunk in encoded.chunks(4) {
+        if chunk.len() == 1 {
+            return ris_error::new_result!("invalid base64 length");
+        }
+
+        let mut group = 0u32;
+        for (i, &character) in chunk.iter().enumerate() {
+            let sextet = match character {
+                b'A'..=b'Z' => character - b'A',
+                b'a'..=b'z' => character - b'a' + 26,
+                b'0'..=b'9' => character - b'0' + 52,
+                b'+' => 62,
+                b'/' => 63,
+                _ => {
+                    return ris_error::new_result!(
+                        "invalid base64 character: {:?}",
+                        character as char,
+                    )
+                }
+            };
+            group |= u32::from(sextet) << (18 - 6 * i);
+        }
+
+        let byte_count = chunk.len() - 1;
+        for i in 0..byte_count {
+            decoded.push((group >> (16 - 8 * i)) as u8);
+        }
+    }
+
+    Ok(decoded)
+}
@@ -1260,13 +1260,14 @@ impl Gltf {
                         .unwrap_or(1.0);
                     ris_error::assert!(roughness_factor >= 0.0)?;
                     ris_error::assert!(roughness_factor <= 1.0)?;
-                    let metallic_roughness_texture =
-                        match json_pbr_metallic_roughness.get::<&JsonObject>("baseColorTexture") {
-                            Some(json_metallic_roughness_texture) => {
-                                Some(parse_texture_info(json_metallic_roughness_texture)?)
-                            }
-                            None => None,
-                        };
+                    let metallic_roughness_texture = match json_pbr_metallic_roughness
+                        .get::<&JsonObject>("metallicRoughnessTexture")
+                    {
+                        Some(json_metallic_roughness_texture) => {
+                            Some(parse_texture_info(json_metallic_roughness_texture)?)
+                        }
+                        None => None,
+                    };
                     let extensions = json_pbr_metallic_roughness
                         .get::<&JsonObject>("extensions")
                         .cloned();
@@ -1513,7 +1514,7 @@ impl Gltf {
             for json_sampler in json_samplers {
                 let input = json_sampler.get::<usize>("input").into_ris_error()?;
                 let interpolation = match json_sampler.get::<&str>("interpolation") {
-                    Some("LINEAR") => AnimationSamplerInterpolation::Linear,
+                    None | Some("LINEAR") => AnimationSamplerInterpolation::Linear,
                     Some("STEP") => AnimationSamplerInterpolation::Step,
                     Some("CUBICSPLINE") => AnimationSamplerInterpolation::CubicSpline,
                     interpolation => {
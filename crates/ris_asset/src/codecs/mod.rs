@@ -1,3 +1,4 @@
+pub mod base64;
 pub mod gltf;
 pub mod json;
 pub mod qoi;
@@ -0,0 +1,200 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ris_data::ris_yaml;
+use ris_data::ris_yaml::RisYaml;
+use ris_error::RisResult;
+
+pub const IMPORT_CACHE_FILE: &str = "import_cache.ris_meta";
+
+const KEY_SOURCE: &str = "source";
+const KEY_HASH: &str = "hash";
+const KEY_IMPORTER: &str = "importer";
+const KEY_OUTPUT: &str = "output";
+const KEY_DEPENDENCY: &str = "dependency";
+
+/// What the importer produced the last time a source file was imported. All paths use '/' as
+/// separator. `source` and `dependencies` are relative to the source directory, `outputs` are
+/// relative to the import directory.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct CacheEntry {
+    pub hash: u64,
+    pub importer: String,
+    pub outputs: Vec<PathBuf>,
+    pub dependencies: Vec<(PathBuf, u64)>,
+}
+
+#[derive(Debug, Default, PartialEq, Eq)]
+pub struct ImportCache {
+    entries: BTreeMap<PathBuf, CacheEntry>,
+}
+
+/// 64 bit FNV-1a. Not cryptographically secure, but more than enough to detect changed files.
+pub fn hash(bytes: &[u8]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for byte in bytes {
+        hash ^= *byte as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+pub fn hash_file(path: impl AsRef<Path>) -> RisResult<u64> {
+    let bytes = std::fs::read(path)?;
+    Ok(hash(&bytes))
+}
+
+/// Like `hash_file()`, but a file that does not exist has the hash 0. Thus optional
+/// dependencies, like sidecar meta files, are up to date as long as they don't exist.
+pub fn hash_dependency(path: impl AsRef<Path>) -> RisResult<u64> {
+    let path = path.as_ref();
+    if !path.exists() {
+        return Ok(0);
+    }
+
+    hash_file(path)
+}
+
+impl ImportCache {
+    /// A cache that does not exist or cannot be read is treated as empty, which causes
+    /// everything to be imported again.
+    pub fn load(path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Self::default();
+        }
+
+        let result = std::fs::read_to_string(path)
+            .map_err(|e| ris_error::new!("{}", e))
+            .and_then(Self::deserialize);
+
+        match result {
+            Ok(cache) => cache,
+            Err(e) => {
+                ris_log::warning!(
+                    "failed to load import cache \"{}\", importing everything: {}",
+                    path.display(),
+                    e,
+                );
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> RisResult<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let text = self.serialize()?;
+        std::fs::write(path, text)?;
+        Ok(())
+    }
+
+    pub fn get(&self, source: impl AsRef<Path>) -> Option<&CacheEntry> {
+        self.entries.get(source.as_ref())
+    }
+
+    pub fn insert(&mut self, source: impl Into<PathBuf>, entry: CacheEntry) {
+        self.entries.insert(source.into(), entry);
+    }
+
+    pub fn remove(&mut self, source: impl AsRef<Path>) -> Option<CacheEntry> {
+        self.entries.remove(source.as_ref())
+    }
+
+    pub fn sources(&self) -> impl Iterator<Item = &Path> {
+        self.entries.keys().map(|x| x.as_path())
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn serialize(&self) -> RisResult<String> {
+        let mut yaml = RisYaml::default();
+        yaml.add_entry(None, Some("generated by the asset importer, do not edit"));
+
+        for (source, entry) in self.entries.iter() {
+            yaml.add_entry(None, None);
+            yaml.add_entry(Some((KEY_SOURCE, &path_to_string(source))), None);
+            yaml.add_entry(Some((KEY_HASH, &format!("{:016x}", entry.hash))), None);
+            yaml.add_entry(Some((KEY_IMPORTER, &entry.importer)), None);
+
+            for output in entry.outputs.iter() {
+                yaml.add_entry(Some((KEY_OUTPUT, &path_to_string(output))), None);
+            }
+
+            for (dependency, hash) in entry.dependencies.iter() {
+                let value = format!("{:016x} {}", hash, path_to_string(dependency));
+                yaml.add_entry(Some((KEY_DEPENDENCY, &value)), None);
+            }
+        }
+
+        yaml.serialize()
+    }
+
+    pub fn deserialize(text: impl AsRef<str>) -> RisResult<Self> {
+        let yaml = RisYaml::deserialize(text)?;
+
+        let mut cache = Self::default();
+        let mut current: Option<(PathBuf, CacheEntry)> = None;
+
+        for entry in yaml.entries.iter() {
+            let Some((key, value)) = &entry.key_value else {
+                continue;
+            };
+            let line = entry.line;
+
+            if key == KEY_SOURCE {
+                if let Some((source, entry)) = current.take() {
+                    cache.insert(source, entry);
+                }
+
+                current = Some((PathBuf::from(value), CacheEntry::default()));
+                continue;
+            }
+
+            let Some((_, current)) = current.as_mut() else {
+                return ris_yaml::error_on_line(line, &format!("\"{}\" must follow a source", key));
+            };
+
+            match key.as_str() {
+                KEY_HASH => current.hash = parse_hash(value, line)?,
+                KEY_IMPORTER => current.importer = value.clone(),
+                KEY_OUTPUT => current.outputs.push(PathBuf::from(value)),
+                KEY_DEPENDENCY => {
+                    let Some((hash, path)) = value.split_once(' ') else {
+                        return ris_yaml::error_on_line(line, "expected a hash and a path");
+                    };
+                    let hash = parse_hash(hash, line)?;
+                    current.dependencies.push((PathBuf::from(path), hash));
+                }
+                key => return ris_yaml::error_on_line(line, &format!("unknown key \"{}\"", key)),
+            }
+        }
+
+        if let Some((source, entry)) = current {
+            cache.insert(source, entry);
+        }
+
+        Ok(cache)
+    }
+}
+
+pub fn path_to_string(path: impl AsRef<Path>) -> String {
+    path.as_ref().to_string_lossy().replace('\\', "/")
+}
+
+fn parse_hash(value: &str, line: usize) -> RisResult<u64> {
+    match u64::from_str_radix(value, 16) {
+        Ok(hash) => Ok(hash),
+        Err(_) => ris_yaml::error_on_line(line, &format!("invalid hash \"{}\"", value)),
+    }
+}
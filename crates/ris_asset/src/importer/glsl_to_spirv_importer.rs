@@ -177,11 +177,13 @@ impl ShaderStage {
     }
 }
 
+/// Returns the paths of the created files. Headers are only included by other shaders and
+/// produce no output.
 pub fn import(
     source: impl AsRef<Path>,
     target_dir: impl AsRef<Path>,
     temp_dir: Option<impl AsRef<Path>>,
-) -> RisResult<()> {
+) -> RisResult<Vec<PathBuf>> {
     let source = source.as_ref();
     let target_dir = target_dir.as_ref();
     let temp_dir = temp_dir.as_ref().map(|x| x.as_ref());
@@ -212,7 +214,7 @@ pub fn import(
     let second_paramter = splits.get(1);
     if let Some(parameter) = second_paramter {
         if *parameter == HEADER {
-            return Ok(());
+            return Ok(Vec::new());
         }
     }
 
@@ -336,19 +338,61 @@ pub fn import(
     // save to file
     let extensions = [OUT_EXT_VERT, OUT_EXT_GEOM, OUT_EXT_FRAG];
     debug_assert_eq!(artifacts.len(), extensions.len());
+    let mut outputs = Vec::new();
     for i in 0..artifacts.len() {
         let artifact = &artifacts[i];
         let extension = extensions[i];
 
         if let Some(artifact) = artifact {
-            let mut output = crate::asset_importer::create_file(source, target_dir, extension)?;
+            let (mut output, output_path) =
+                crate::asset_importer::create_file(source, target_dir, extension)?;
             let bytes = artifact.as_binary_u8();
 
             ris_io::write(&mut output, bytes)?;
+            outputs.push(output_path);
         }
     }
 
-    Ok(())
+    Ok(outputs)
+}
+
+/// Returns every header that `source` includes, directly or through other headers. Like
+/// `#include` itself, all paths are resolved relative to the directory of `source`. Headers are
+/// not compiled on their own, thus they have no includes.
+pub fn includes(source: impl AsRef<Path>) -> RisResult<Vec<PathBuf>> {
+    let source = source.as_ref();
+    let root_dir = source.parent().into_ris_error()?;
+
+    let content = std::fs::read_to_string(source)?;
+    let first_line = content.lines().next().unwrap_or_default();
+    if first_line.split(' ').nth(1) == Some(HEADER) {
+        return Ok(Vec::new());
+    }
+
+    let mut result = Vec::<PathBuf>::new();
+    let mut to_scan = vec![source.to_path_buf()];
+    while let Some(current) = to_scan.pop() {
+        let content = std::fs::read_to_string(&current)?;
+        for (i, input_line) in content.lines().enumerate() {
+            let splits = input_line.split(' ').collect::<Vec<_>>();
+            if splits[0] != MACRO_INCLUDE {
+                continue;
+            }
+
+            let file = current.to_str().into_ris_error()?;
+            preproc_assert_arg_count(splits.len(), 2, file, i + 1)?;
+            let include_path = root_dir.join(splits[1]);
+
+            // circular includes are reported by the import itself, scanning each file once is
+            // enough to find all of them
+            if include_path != source && !result.contains(&include_path) {
+                result.push(include_path.clone());
+                to_scan.push(include_path);
+            }
+        }
+    }
+
+    Ok(result)
 }
 
 fn string_to_region_kind(value: &str, file: &str, line: usize) -> RisResult<ShaderKind> {
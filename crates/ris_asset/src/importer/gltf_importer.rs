@@ -0,0 +1,1224 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::io::Cursor;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use png::ColorType;
+
+use ris_asset_data::animation::AnimationChannel;
+use ris_asset_data::animation::AnimationClip;
+use ris_asset_data::animation::ChannelPath;
+use ris_asset_data::animation::Interpolation;
+use ris_asset_data::material::AlphaMode;
+use ris_asset_data::material::CpuMaterial;
+use ris_asset_data::mesh::CpuMesh;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_asset_data::skeleton::Joint;
+use ris_asset_data::skeleton::JointTransform;
+use ris_asset_data::skeleton::Skeleton;
+use ris_asset_data::texture::AddressMode;
+use ris_asset_data::texture::CpuTexture;
+use ris_asset_data::texture::Filter;
+use ris_asset_data::texture::SamplerSettings;
+use ris_asset_data::AssetId;
+use ris_data::ecs::components::animator_component::AnimatorClip;
+use ris_data::ecs::decl::AnimatorComponentHandle;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::decl::MaterialComponentHandle;
+use ris_data::ecs::decl::MeshComponentHandle;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_error::prelude::*;
+use ris_math::affine;
+use ris_math::matrix::Mat4;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec2;
+use ris_math::vector::Vec3;
+use ris_math::vector::Vec4;
+
+use crate::assets::ris_animation;
+use crate::assets::ris_material;
+use crate::assets::ris_mesh;
+use crate::assets::ris_prefab;
+use crate::assets::ris_skeleton;
+use crate::assets::ris_texture;
+use crate::codecs::base64;
+use crate::codecs::gltf::Accessor;
+use crate::codecs::gltf::AccessorComponentType;
+use crate::codecs::gltf::AccessorSparseIndicesComponentType;
+use crate::codecs::gltf::AccessorType;
+use crate::codecs::gltf::AnimationChannelTargetPath;
+use crate::codecs::gltf::AnimationSamplerInterpolation;
+use crate::codecs::gltf::Gltf;
+use crate::codecs::gltf::ImageData;
+use crate::codecs::gltf::ImageMimeType;
+use crate::codecs::gltf::MaterialAlphaMode;
+use crate::codecs::gltf::MeshPrimitive;
+use crate::codecs::gltf::MeshPrimitiveAttributeName;
+use crate::codecs::gltf::MeshPrimitiveMode;
+use crate::codecs::gltf::NodeTransform;
+use crate::codecs::gltf::SamplerMagFilter;
+use crate::codecs::gltf::SamplerMinFilter;
+use crate::codecs::gltf::SamplerWrap;
+use crate::mesh_processing::mesh_processor;
+use crate::mesh_processing::mesh_processor::MeshProcessorSettings;
+
+pub const IN_EXT_GLTF: &str = "gltf";
+
+const PREFAB_OBJECTS_PER_PAGE: usize = 64;
+
+struct Context<'a> {
+    gltf: &'a Gltf,
+    buffers: &'a [Vec<u8>],
+    base_dir: &'a Path,
+    source_file_stem: &'a str,
+    target_dir: &'a Path,
+    asset_dir: &'a Path,
+    outputs: Vec<PathBuf>,
+    /// maps a gltf texture and whether it is srgb to the imported texture. `None` if the texture
+    /// could not be imported
+    textures: HashMap<(usize, bool), Option<AssetId>>,
+}
+
+/// Imports a gltf json file. Buffers and images may be external files, relative to the gltf, or
+/// data uris. See `import_gltf()`.
+///
+/// Returns the paths of the created files.
+pub fn import(
+    source: impl AsRef<Path>,
+    target_dir: impl AsRef<Path>,
+    asset_dir: impl AsRef<Path>,
+) -> RisResult<Vec<PathBuf>> {
+    let source = source.as_ref();
+    let base_dir = source.parent().into_ris_error()?;
+
+    let json = std::fs::read_to_string(source)?;
+    let gltf = Gltf::deserialize(json)?;
+    let buffers = load_buffers(&gltf, None, base_dir)?;
+    import_gltf(
+        &gltf,
+        &buffers,
+        source,
+        target_dir.as_ref(),
+        asset_dir.as_ref(),
+    )
+}
+
+/// Returns the external files, which are referenced by the gltf.
+pub fn dependencies(source: impl AsRef<Path>) -> RisResult<Vec<PathBuf>> {
+    let source = source.as_ref();
+    let base_dir = source.parent().into_ris_error()?;
+
+    let json = std::fs::read_to_string(source)?;
+    let gltf = Gltf::deserialize(json)?;
+    external_files(&gltf, base_dir)
+}
+
+/// Returns the external files, which are referenced by buffers and images. Data uris are not
+/// files and thus skipped.
+pub fn external_files(gltf: &Gltf, base_dir: &Path) -> RisResult<Vec<PathBuf>> {
+    let buffer_uris = gltf.buffers.iter().filter_map(|x| x.uri.as_ref());
+    let image_uris = gltf.images.iter().filter_map(|x| match &x.data {
+        ImageData::Uri(uri) => Some(uri),
+        ImageData::BufferView { .. } => None,
+    });
+
+    let mut files = Vec::new();
+    for uri in buffer_uris.chain(image_uris) {
+        if uri.starts_with("data:") {
+            continue;
+        }
+
+        let file = base_dir.join(decode_uri_path(uri)?);
+        if !files.contains(&file) {
+            files.push(file);
+        }
+    }
+
+    Ok(files)
+}
+
+/// Loads the data of every buffer. A buffer without uri refers to `glb_bin`, the bin chunk of a
+/// glb file. Other buffers are loaded from their uri, which may be a data uri or a path relative
+/// to `base_dir`.
+pub fn load_buffers(
+    gltf: &Gltf,
+    glb_bin: Option<Vec<u8>>,
+    base_dir: &Path,
+) -> RisResult<Vec<Vec<u8>>> {
+    let mut glb_bin = glb_bin;
+    let mut buffers = Vec::with_capacity(gltf.buffers.len());
+    for (i, buffer) in gltf.buffers.iter().enumerate() {
+        let data = match &buffer.uri {
+            Some(uri) => load_uri(uri, base_dir)?,
+            // only the first buffer may refer to the bin chunk
+            None if i == 0 => glb_bin.take().into_ris_error()?,
+            None => return ris_error::new_result!("buffer {} has no uri", i),
+        };
+
+        ris_error::assert!(data.len() >= buffer.byte_length)?;
+        buffers.push(data);
+    }
+
+    Ok(buffers)
+}
+
+/// Writes a `ris_mesh` for each mesh primitive and a `ris_material` for each material. Textures,
+/// which are referenced by materials and stored as png, are written as `ris_texture`. Each skin
+/// is written as `ris_skeleton` and each animation, which animates the joints of a skin, as
+/// `ris_animation`. Each scene is written as `ris_prefab`, which recreates the node tree as game
+/// objects.
+///
+/// Triangle strips and fans are converted to triangle lists. Primitives made of points or lines
+/// are skipped. Meshes are processed as configured by the sidecar of `source`, see
+/// `MeshProcessorSettings`.
+///
+/// `source` is the path of the imported file. Created files are named after it. `asset_dir` is
+/// `target_dir` relative to the import directory. Materials reference their textures relative
+/// to the import directory.
+///
+/// Returns the paths of the created files.
+pub fn import_gltf(
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+    source: &Path,
+    target_dir: &Path,
+    asset_dir: &Path,
+) -> RisResult<Vec<PathBuf>> {
+    ris_error::assert!(buffers.len() == gltf.buffers.len())?;
+
+    let source_file_stem = source
+        .file_stem()
+        .into_ris_error()?
+        .to_str()
+        .into_ris_error()?;
+    let base_dir = source.parent().into_ris_error()?;
+
+    // meshes
+    let mesh_processor_settings = MeshProcessorSettings::load(source)?;
+    let mut outputs = Vec::new();
+    let mut mesh_ids = Vec::with_capacity(gltf.meshes.len());
+    for (mesh_index, mesh) in gltf.meshes.iter().enumerate() {
+        let mut primitive_ids = Vec::with_capacity(mesh.primitives.len());
+        for (primitive_index, primitive) in mesh.primitives.iter().enumerate() {
+            let Some(mesh_prototype) = mesh_prototype(primitive, gltf, buffers)? else {
+                ris_log::warning!(
+                    "cannot import primitive {} of mesh {}. unsupported mode: {:?}",
+                    primitive_index,
+                    mesh_index,
+                    primitive.mode,
+                );
+                primitive_ids.push(None);
+                continue;
+            };
+
+            let mesh_prototype = mesh_processor::process(mesh_prototype, &mesh_processor_settings)?;
+            let cpu_mesh = CpuMesh::try_from(mesh_prototype)?;
+            let bytes = ris_mesh::serialize(&cpu_mesh)?;
+
+            let mesh_name = if let Some(name) = &mesh.name {
+                name.clone()
+            } else {
+                "none".to_string()
+            };
+
+            let target_name = format!(
+                "{}-{}-{:03}-{:03}",
+                source_file_stem, mesh_name, mesh_index, primitive_index,
+            );
+            let (mut output, output_path) =
+                crate::asset_importer::create_file(target_name, target_dir, ris_mesh::EXTENSION)?;
+            ris_io::write(&mut output, &bytes)?;
+            primitive_ids.push(Some(asset_id(asset_dir, &output_path)?));
+            outputs.push(output_path);
+        }
+
+        mesh_ids.push(primitive_ids);
+    }
+
+    // materials
+    let mut context = Context {
+        gltf,
+        buffers,
+        base_dir,
+        source_file_stem,
+        target_dir,
+        asset_dir,
+        outputs,
+        textures: HashMap::new(),
+    };
+
+    let mut material_ids = Vec::with_capacity(gltf.materials.len());
+    for (material_index, material) in gltf.materials.iter().enumerate() {
+        let mut cpu_material = CpuMaterial::default();
+        let textures = &mut cpu_material.textures;
+        let params = &mut cpu_material.params;
+
+        if let Some(pbr) = &material.pbr_metallic_roughness {
+            params.base_color = pbr.base_color_factor;
+            params.metallic = pbr.metallic_factor;
+            params.roughness = pbr.roughness_factor;
+
+            if let Some(info) = &pbr.base_color_texture {
+                textures.base_color = import_texture(&mut context, info.index, true)?;
+            }
+
+            if let Some(info) = &pbr.metallic_roughness_texture {
+                textures.metallic_roughness = import_texture(&mut context, info.index, false)?;
+            }
+        }
+
+        if let Some(info) = &material.normal_texture {
+            params.normal_scale = info.scale;
+            textures.normal = import_texture(&mut context, info.index, false)?;
+        }
+
+        if let Some(info) = &material.occlusion_texture {
+            params.occlusion_strength = info.strength;
+            textures.occlusion = import_texture(&mut context, info.index, false)?;
+        }
+
+        if let Some(info) = &material.emissive_texture {
+            textures.emissive = import_texture(&mut context, info.index, true)?;
+        }
+
+        params.emissive = material.emissive_factor;
+        params.alpha_mode = match material.alpha_mode {
+            MaterialAlphaMode::Opaque => AlphaMode::Opaque,
+            MaterialAlphaMode::Mask => AlphaMode::Mask,
+            MaterialAlphaMode::Blend => AlphaMode::Blend,
+        };
+        params.alpha_cutoff = material.alpha_cutoff;
+        params.double_sided = material.double_sided;
+
+        let bytes = ris_material::serialize(&cpu_material)?;
+
+        let material_name = if let Some(name) = &material.name {
+            name.clone()
+        } else {
+            "none".to_string()
+        };
+
+        let target_name = format!(
+            "{}-{}-{:03}",
+            source_file_stem, material_name, material_index,
+        );
+        let (mut output, output_path) =
+            crate::asset_importer::create_file(target_name, target_dir, ris_material::EXTENSION)?;
+        ris_io::write(&mut output, &bytes)?;
+        material_ids.push(asset_id(asset_dir, &output_path)?);
+        context.outputs.push(output_path);
+    }
+
+    let mut outputs = context.outputs;
+
+    // skins. the joints of a skin become a skeleton
+    let mut node_parents = vec![None; gltf.nodes.len()];
+    for (node_index, node) in gltf.nodes.iter().enumerate() {
+        for &child_index in node.children.iter() {
+            *node_parents.get_mut(child_index).into_ris_error()? = Some(node_index);
+        }
+    }
+
+    let mut skeleton_ids = Vec::with_capacity(gltf.skins.len());
+    for (skin_index, skin) in gltf.skins.iter().enumerate() {
+        let inverse_bind_matrices = match skin.inverse_bind_matrices {
+            Some(accessor_index) => {
+                let accessor = gltf.accessors.get(accessor_index).into_ris_error()?;
+                ris_error::assert!(accessor.accessor_type == AccessorType::Mat4)?;
+                ris_error::assert!(accessor.count == skin.joints.len())?;
+
+                read_floats(accessor, buffers, gltf)?
+                    .chunks_exact(16)
+                    .map(|m| engine_matrix(mat4_from_slice(m)))
+                    .collect::<Vec<_>>()
+            }
+            None => vec![Mat4::identity(); skin.joints.len()],
+        };
+
+        let mut joints = Vec::with_capacity(skin.joints.len());
+        for (&node_index, &inverse_bind_matrix) in
+            skin.joints.iter().zip(inverse_bind_matrices.iter())
+        {
+            let node = gltf.nodes.get(node_index).into_ris_error()?;
+
+            // the parent is the closest ancestor, which is a joint of the same skin. ancestors,
+            // which are not joints, are ignored
+            let mut parent = None;
+            let mut ancestor = *node_parents.get(node_index).into_ris_error()?;
+            let mut steps = 0;
+            while let Some(ancestor_index) = ancestor {
+                steps += 1;
+                if steps > gltf.nodes.len() {
+                    return ris_error::new_result!("node {} is part of a cycle", node_index);
+                }
+
+                parent = skin.joints.iter().position(|&x| x == ancestor_index);
+                if parent.is_some() {
+                    break;
+                }
+
+                ancestor = *node_parents.get(ancestor_index).into_ris_error()?;
+            }
+
+            let name = match &node.name {
+                Some(name) => name.clone(),
+                None => format!("node-{:03}", node_index),
+            };
+
+            let (translation, rotation, scale) = engine_trs(&node.transform);
+            joints.push(Joint {
+                name,
+                parent,
+                rest: JointTransform {
+                    translation,
+                    rotation,
+                    scale,
+                },
+                inverse_bind_matrix,
+            });
+        }
+
+        let skeleton = Skeleton { joints };
+        let bytes = ris_skeleton::serialize(&skeleton)?;
+
+        let skin_name = if let Some(name) = &skin.name {
+            name.clone()
+        } else {
+            "none".to_string()
+        };
+
+        let target_name = format!("{}-{}-{:03}", source_file_stem, skin_name, skin_index);
+        let (mut output, output_path) =
+            crate::asset_importer::create_file(target_name, target_dir, ris_skeleton::EXTENSION)?;
+        ris_io::write(&mut output, &bytes)?;
+        skeleton_ids.push(asset_id(asset_dir, &output_path)?);
+        outputs.push(output_path);
+    }
+
+    // animations. an animation animates the skin of the first joint it targets. channels, which
+    // target other nodes or morph target weights, are dropped
+    let mut clip_ids = vec![Vec::new(); gltf.skins.len()];
+    for (animation_index, animation) in gltf.animations.iter().enumerate() {
+        let skin_index = animation
+            .channels
+            .iter()
+            .filter_map(|x| x.target.node)
+            .find_map(|node| gltf.skins.iter().position(|x| x.joints.contains(&node)));
+        let Some(skin_index) = skin_index else {
+            ris_log::warning!(
+                "cannot import animation {}. it does not animate any joints",
+                animation_index,
+            );
+            continue;
+        };
+        let skin = gltf.skins.get(skin_index).into_ris_error()?;
+
+        let animation_name = if let Some(name) = &animation.name {
+            name.clone()
+        } else {
+            "none".to_string()
+        };
+
+        let mut clip = AnimationClip {
+            name: animation_name.clone(),
+            duration: 0.0,
+            channels: Vec::new(),
+        };
+
+        for channel in animation.channels.iter() {
+            let path = match channel.target.path {
+                AnimationChannelTargetPath::Translation => ChannelPath::Translation,
+                AnimationChannelTargetPath::Rotation => ChannelPath::Rotation,
+                AnimationChannelTargetPath::Scale => ChannelPath::Scale,
+                AnimationChannelTargetPath::Weights => continue,
+            };
+
+            let joint = channel
+                .target
+                .node
+                .and_then(|node| skin.joints.iter().position(|&x| x == node));
+            let Some(joint) = joint else {
+                continue;
+            };
+
+            let sampler = animation.samplers.get(channel.sampler).into_ris_error()?;
+            let interpolation = match sampler.interpolation {
+                AnimationSamplerInterpolation::Step => Interpolation::Step,
+                AnimationSamplerInterpolation::Linear => Interpolation::Linear,
+                AnimationSamplerInterpolation::CubicSpline => Interpolation::CubicSpline,
+            };
+
+            let input_accessor = gltf.accessors.get(sampler.input).into_ris_error()?;
+            let output_accessor = gltf.accessors.get(sampler.output).into_ris_error()?;
+            ris_error::assert!(input_accessor.accessor_type == AccessorType::Scalar)?;
+            ris_error::assert!(
+                output_accessor.accessor_type.number_of_components() == path.component_count()
+            )?;
+
+            let times = read_floats(input_accessor, buffers, gltf)?;
+            let mut values = read_floats(output_accessor, buffers, gltf)?;
+
+            // correct coordinate system. the tangents of cubic splines are corrected the same way
+            // as the values
+            let correction = coordinate_correction();
+            for v in values.chunks_exact_mut(path.component_count()) {
+                match path {
+                    ChannelPath::Translation => {
+                        let t = correction.rotate(Vec3(v[0], v[1], v[2]));
+                        v.copy_from_slice(&[t.0, t.1, t.2]);
+                    }
+                    ChannelPath::Rotation => {
+                        let r = correction * Quat(v[0], v[1], v[2], v[3]) * correction.conjugate();
+                        v.copy_from_slice(&[r.0, r.1, r.2, r.3]);
+                    }
+                    ChannelPath::Scale => v.swap(1, 2),
+                }
+            }
+
+            if let Some(&last) = times.last() {
+                clip.duration = f32::max(clip.duration, last);
+            }
+
+            clip.channels.push(AnimationChannel {
+                joint,
+                path,
+                interpolation,
+                times,
+                values,
+            });
+        }
+
+        let skeleton_id = skeleton_ids.get(skin_index).into_ris_error()?;
+        let bytes = ris_animation::serialize(&clip, skeleton_id)?;
+
+        let target_name = format!(
+            "{}-{}-{:03}",
+            source_file_stem, animation_name, animation_index,
+        );
+        let (mut output, output_path) =
+            crate::asset_importer::create_file(target_name, target_dir, ris_animation::EXTENSION)?;
+        ris_io::write(&mut output, &bytes)?;
+        clip_ids
+            .get_mut(skin_index)
+            .into_ris_error()?
+            .push(asset_id(asset_dir, &output_path)?);
+        outputs.push(output_path);
+    }
+
+    // node hierarchies. each gltf scene becomes a prefab, with a root game object that holds
+    // the root nodes of the scene
+    let registry = Arc::new(Registry::new(Vec::new(), Vec::new())?);
+    for (scene_index, gltf_scene) in gltf.scenes.iter().enumerate() {
+        let scene_create_info = SceneCreateInfo {
+            dynamic_game_objects_per_page: PREFAB_OBJECTS_PER_PAGE,
+            static_chunks: 0,
+            static_game_objects_per_page: 1,
+            components_per_page: PREFAB_OBJECTS_PER_PAGE,
+            registry: Some(registry.clone()),
+        };
+        let scene = Scene::new(scene_create_info)?;
+
+        let scene_name = if let Some(name) = &gltf_scene.name {
+            name.clone()
+        } else {
+            "none".to_string()
+        };
+
+        let root = GameObjectHandle::new(&scene)?;
+        root.set_name(&scene, &scene_name)?;
+
+        let hierarchy = Hierarchy {
+            gltf,
+            scene: &scene,
+            mesh_ids: &mesh_ids,
+            material_ids: &material_ids,
+            skeleton_ids: &skeleton_ids,
+            clip_ids: &clip_ids,
+        };
+
+        let mut visited = vec![false; gltf.nodes.len()];
+        for (sibling_index, &node_index) in gltf_scene.nodes.iter().enumerate() {
+            hierarchy.add_node(node_index, root, sibling_index, &mut visited)?;
+        }
+
+        let bytes = ris_prefab::serialize(&scene, root)?;
+
+        let target_name = format!("{}-{}-{:03}", source_file_stem, scene_name, scene_index);
+        let (mut output, output_path) =
+            crate::asset_importer::create_file(target_name, target_dir, ris_prefab::EXTENSION)?;
+        ris_io::write(&mut output, &bytes)?;
+        outputs.push(output_path);
+    }
+
+    Ok(outputs)
+}
+
+struct Hierarchy<'a> {
+    gltf: &'a Gltf,
+    scene: &'a Scene,
+    /// `None` for primitives, which could not be imported
+    mesh_ids: &'a [Vec<Option<AssetId>>],
+    material_ids: &'a [AssetId],
+    skeleton_ids: &'a [AssetId],
+    /// the clips of each skin
+    clip_ids: &'a [Vec<AssetId>],
+}
+
+impl Hierarchy<'_> {
+    fn add_node(
+        &self,
+        node_index: usize,
+        parent: GameObjectHandle,
+        sibling_index: usize,
+        visited: &mut [bool],
+    ) -> RisResult<()> {
+        let scene = self.scene;
+        let node = self.gltf.nodes.get(node_index).into_ris_error()?;
+
+        let is_visited = visited.get_mut(node_index).into_ris_error()?;
+        if *is_visited {
+            return ris_error::new_result!("node {} appears more than once", node_index);
+        }
+        *is_visited = true;
+
+        let name = match &node.name {
+            Some(name) => name.clone(),
+            None => format!("node-{:03}", node_index),
+        };
+
+        let game_object = GameObjectHandle::new(scene)?;
+        game_object.set_name(scene, name)?;
+        game_object.set_parent(scene, Some(parent), sibling_index)?;
+
+        let (translation, rotation, scale) = engine_trs(&node.transform);
+        game_object.set_position(scene, translation)?;
+        game_object.set_rotation(scene, rotation)?;
+        game_object.set_scale(scene, scale)?;
+
+        if let Some(mesh_index) = node.mesh {
+            let mesh = self.gltf.meshes.get(mesh_index).into_ris_error()?;
+            let primitive_ids = self.mesh_ids.get(mesh_index).into_ris_error()?;
+
+            // a game object describes a single surface. thus, when a mesh consists of multiple
+            // primitives, each is placed on its own child
+            let single_primitive = mesh.primitives.len() == 1;
+            for (primitive_index, primitive) in mesh.primitives.iter().enumerate() {
+                let target = if single_primitive {
+                    game_object
+                } else {
+                    let child = GameObjectHandle::new(scene)?;
+                    child.set_name(scene, format!("primitive-{:03}", primitive_index))?;
+                    child.set_parent(scene, Some(game_object), primitive_index)?;
+                    child
+                };
+
+                let Some(mesh_id) = primitive_ids.get(primitive_index).into_ris_error()? else {
+                    continue;
+                };
+                let handle: MeshComponentHandle = target.add_component(scene)?.into();
+                scene
+                    .deref(handle.0)?
+                    .borrow_mut()
+                    .set_asset_id(Some(mesh_id.clone()));
+
+                if let Some(material_index) = primitive.material {
+                    let material_id = self.material_ids.get(material_index).into_ris_error()?;
+                    let handle: MaterialComponentHandle = target.add_component(scene)?.into();
+                    scene
+                        .deref(handle.0)?
+                        .borrow_mut()
+                        .set_asset_id(Some(material_id.clone()));
+                }
+            }
+        }
+
+        // the first clip plays, the others can be blended in by raising their weight
+        if let Some(skin_index) = node.skin {
+            let skeleton_id = self.skeleton_ids.get(skin_index).into_ris_error()?;
+            let clip_ids = self.clip_ids.get(skin_index).into_ris_error()?;
+
+            let handle: AnimatorComponentHandle = game_object.add_component(scene)?.into();
+            let ptr = scene.deref(handle.0)?;
+            let mut animator = ptr.borrow_mut();
+            animator.set_skeleton(Some(skeleton_id.clone()));
+            for (i, clip_id) in clip_ids.iter().enumerate() {
+                let mut clip = AnimatorClip::new(clip_id.clone());
+                clip.weight = if i == 0 { 1.0 } else { 0.0 };
+                animator.clips_mut().push(clip);
+            }
+        }
+
+        let sibling_offset = game_object.children(scene)?.len();
+        for (i, &child_index) in node.children.iter().enumerate() {
+            self.add_node(child_index, game_object, sibling_offset + i, visited)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Returns the local transform of a node in the gltf coordinate system.
+fn node_trs(transform: &NodeTransform) -> (Vec3, Quat, Vec3) {
+    match transform {
+        NodeTransform::Matrix(m) => {
+            let decomposed = affine::decompose_trs(mat4_from_slice(m));
+            (
+                decomposed.translation,
+                decomposed.rotation,
+                decomposed.scale,
+            )
+        }
+        NodeTransform::TRS {
+            translation: t,
+            rotation: r,
+            scale: s,
+        } => (
+            Vec3(t[0], t[1], t[2]),
+            Quat(r[0], r[1], r[2], r[3]),
+            Vec3(s[0], s[1], s[2]),
+        ),
+    }
+}
+
+/// Returns the local transform of a node in the coordinate system of ris_engine.
+fn engine_trs(transform: &NodeTransform) -> (Vec3, Quat, Vec3) {
+    let (translation, rotation, scale) = node_trs(transform);
+    let correction = coordinate_correction();
+    (
+        correction.rotate(translation),
+        correction * rotation * correction.conjugate(),
+        // the correction is a quarter turn around x, which swaps the y and z axes
+        Vec3(scale.0, scale.2, scale.1),
+    )
+}
+
+/// Converts a transformation matrix from the gltf coordinate system to the one of ris_engine.
+fn engine_matrix(m: Mat4) -> Mat4 {
+    let correction = coordinate_correction();
+    let to_engine = affine::trs(Vec3::init(0.0), correction, Vec3::init(1.0));
+    let to_gltf = affine::trs(Vec3::init(0.0), correction.conjugate(), Vec3::init(1.0));
+    to_engine * m * to_gltf
+}
+
+/// gltf stores matrices in column-major order.
+fn mat4_from_slice(m: &[f32]) -> Mat4 {
+    Mat4(
+        Vec4(m[0], m[1], m[2], m[3]),
+        Vec4(m[4], m[5], m[6], m[7]),
+        Vec4(m[8], m[9], m[10], m[11]),
+        Vec4(m[12], m[13], m[14], m[15]),
+    )
+}
+
+/// Rotates from the gltf coordinate system to the one of ris_engine.
+fn coordinate_correction() -> Quat {
+    Quat::angle_axis(0.5 * PI, Vec3::right())
+}
+
+fn asset_id(asset_dir: &Path, output_path: &Path) -> RisResult<AssetId> {
+    let file_name = output_path.file_name().into_ris_error()?;
+    let asset_path = crate::import_cache::path_to_string(asset_dir.join(file_name));
+    Ok(AssetId::Path(asset_path))
+}
+
+/// Imports a texture the first time it is referenced. Returns the asset id of the imported
+/// texture, or `None` if the texture is not embedded as png.
+fn import_texture(
+    context: &mut Context,
+    texture_index: usize,
+    srgb: bool,
+) -> RisResult<Option<AssetId>> {
+    if let Some(asset_id) = context.textures.get(&(texture_index, srgb)) {
+        return Ok(asset_id.clone());
+    }
+
+    let gltf = context.gltf;
+    let texture = gltf.textures.get(texture_index).into_ris_error()?;
+    let image_index = texture.source.into_ris_error()?;
+    let image = gltf.images.get(image_index).into_ris_error()?;
+
+    let png_data = match &image.data {
+        ImageData::BufferView {
+            mime_type: ImageMimeType::Png,
+            buffer_view,
+        } => Some(buffer_view_data(*buffer_view, context.buffers, gltf)?.to_vec()),
+        ImageData::BufferView { .. } => None,
+        ImageData::Uri(uri) => {
+            let is_png = match uri.strip_prefix("data:") {
+                Some(data_uri) => data_uri.starts_with("image/png"),
+                None => uri.to_lowercase().ends_with(".png"),
+            };
+
+            if is_png {
+                Some(load_uri(uri, context.base_dir)?)
+            } else {
+                None
+            }
+        }
+    };
+
+    let Some(png_data) = png_data else {
+        ris_log::warning!(
+            "cannot import texture {}. only png is supported",
+            texture_index,
+        );
+        context.textures.insert((texture_index, srgb), None);
+        return Ok(None);
+    };
+
+    // decode png
+    let mut decoder = png::Decoder::new(Cursor::new(png_data));
+    decoder.set_transformations(png::Transformations::normalize_to_color8());
+    let mut reader = decoder.read_info()?;
+    let mut pixels = vec![0; reader.output_buffer_size()];
+    let info = reader.next_frame(&mut pixels)?;
+    pixels.truncate(info.buffer_size());
+
+    let pixels_rgba = match info.color_type {
+        ColorType::Rgb => crate::util::add_alpha_channel(&pixels)?,
+        ColorType::Rgba => pixels,
+        color_type => {
+            return ris_error::new_result!(
+                "cannot import texture {}. unsupported color type: {:?}",
+                texture_index,
+                color_type,
+            )
+        }
+    };
+
+    // sampler
+    let mut sampler = SamplerSettings::default();
+    if let Some(sampler_index) = texture.sampler {
+        let gltf_sampler = gltf.samplers.get(sampler_index).into_ris_error()?;
+
+        if let Some(mag_filter) = &gltf_sampler.mag_filter {
+            sampler.mag_filter = match mag_filter {
+                SamplerMagFilter::Nearest => Filter::Nearest,
+                SamplerMagFilter::Linear => Filter::Linear,
+            };
+        }
+
+        if let Some(min_filter) = &gltf_sampler.min_filter {
+            let (min_filter, mipmap_filter) = match min_filter {
+                SamplerMinFilter::Nearest => (Filter::Nearest, Filter::Nearest),
+                SamplerMinFilter::Linear => (Filter::Linear, Filter::Linear),
+                SamplerMinFilter::NearestMipmapNearest => (Filter::Nearest, Filter::Nearest),
+                SamplerMinFilter::LinearMipmapNearest => (Filter::Linear, Filter::Nearest),
+                SamplerMinFilter::NearestMipmapLinear => (Filter::Nearest, Filter::Linear),
+                SamplerMinFilter::LinearMipmapLinear => (Filter::Linear, Filter::Linear),
+            };
+            sampler.min_filter = min_filter;
+            sampler.mipmap_filter = mipmap_filter;
+        }
+
+        // ris_texture has a single address mode for both axes
+        sampler.address_mode = match gltf_sampler.wrap_s {
+            SamplerWrap::ClampToEdge => AddressMode::ClampToEdge,
+            SamplerWrap::MirroredRepeat => AddressMode::MirroredRepeat,
+            SamplerWrap::Repeat => AddressMode::Repeat,
+        };
+    }
+
+    let cpu_texture = CpuTexture::with_mips(
+        info.width as usize,
+        info.height as usize,
+        srgb,
+        sampler,
+        pixels_rgba,
+    )?;
+    let bytes = ris_texture::serialize(&cpu_texture)?;
+
+    let texture_name = texture
+        .name
+        .as_ref()
+        .or(image.name.as_ref())
+        .cloned()
+        .unwrap_or_else(|| "none".to_string());
+    let color_space = if srgb { "srgb" } else { "linear" };
+
+    let target_name = format!(
+        "{}-{}-{:03}-{}",
+        context.source_file_stem, texture_name, texture_index, color_space,
+    );
+    let (mut output, output_path) = crate::asset_importer::create_file(
+        target_name,
+        context.target_dir,
+        ris_texture::EXTENSION,
+    )?;
+    ris_io::write(&mut output, &bytes)?;
+
+    let asset_id = Some(asset_id(context.asset_dir, &output_path)?);
+
+    context.outputs.push(output_path);
+    context
+        .textures
+        .insert((texture_index, srgb), asset_id.clone());
+    Ok(asset_id)
+}
+
+/// Returns the vertices and indices of a primitive in the coordinate system of ris_engine.
+/// Returns `None`, if the primitive does not consist of triangles.
+fn mesh_prototype(
+    primitive: &MeshPrimitive,
+    gltf: &Gltf,
+    buffers: &[Vec<u8>],
+) -> RisResult<Option<MeshPrototype>> {
+    let attribute_accessor = |name| match primitive.get_attribute(name) {
+        Some(attribute) => gltf
+            .accessors
+            .get(attribute.accessor)
+            .into_ris_error()
+            .map(Some),
+        None => Ok(None),
+    };
+
+    // vertices
+    let vertex_accessor =
+        attribute_accessor(MeshPrimitiveAttributeName::Position)?.into_ris_error()?;
+    ris_error::assert!(vertex_accessor.accessor_type == AccessorType::Vec3)?;
+    let vertex_count = vertex_accessor.count;
+    let vertices = read_floats(vertex_accessor, buffers, gltf)?
+        .chunks_exact(3)
+        .map(|x| Vec3(x[0], x[1], x[2]))
+        .collect::<Vec<_>>();
+
+    let normals = match attribute_accessor(MeshPrimitiveAttributeName::Normal)? {
+        Some(normal_accessor) => {
+            ris_error::assert!(normal_accessor.accessor_type == AccessorType::Vec3)?;
+            ris_error::assert!(normal_accessor.count == vertex_count)?;
+            read_floats(normal_accessor, buffers, gltf)?
+                .chunks_exact(3)
+                .map(|x| Vec3(x[0], x[1], x[2]))
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    let uvs = match attribute_accessor(MeshPrimitiveAttributeName::TexCoord(0))? {
+        Some(uv_accessor) => {
+            ris_error::assert!(uv_accessor.accessor_type == AccessorType::Vec2)?;
+            ris_error::assert!(uv_accessor.count == vertex_count)?;
+            read_floats(uv_accessor, buffers, gltf)?
+                .chunks_exact(2)
+                .map(|x| Vec2(x[0], x[1]))
+                .collect()
+        }
+        None => vec![Vec2::init(0.0); vertex_count],
+    };
+
+    let tangents = match attribute_accessor(MeshPrimitiveAttributeName::Tangent)? {
+        Some(tangent_accessor) => {
+            ris_error::assert!(tangent_accessor.accessor_type == AccessorType::Vec4)?;
+            ris_error::assert!(tangent_accessor.count == vertex_count)?;
+            read_floats(tangent_accessor, buffers, gltf)?
+                .chunks_exact(4)
+                .map(|x| Vec4(x[0], x[1], x[2], x[3]))
+                .collect()
+        }
+        None => Vec::new(),
+    };
+
+    // indices. a primitive without indices draws its vertices in order
+    let (indices, is_u32) = match primitive.indices {
+        Some(index_accessor_index) => {
+            let index_accessor = gltf.accessors.get(index_accessor_index).into_ris_error()?;
+            ris_error::assert!(index_accessor.accessor_type == AccessorType::Scalar)?;
+            let is_u32 = index_accessor.component_type == AccessorComponentType::U32;
+            (read_uints(index_accessor, buffers, gltf)?, is_u32)
+        }
+        None => {
+            let is_u32 = vertex_count > usize::from(u16::MAX) + 1;
+            ((0..u32::try_from(vertex_count)?).collect(), is_u32)
+        }
+    };
+
+    // convert to a triangle list, as defined by the gltf spec
+    let triangles = match primitive.mode {
+        MeshPrimitiveMode::Triangles => indices,
+        MeshPrimitiveMode::TriangleStrip => {
+            let mut triangles = Vec::new();
+            for i in 0..indices.len().saturating_sub(2) {
+                let flip = i % 2;
+                triangles.push(indices[i]);
+                triangles.push(indices[i + 1 + flip]);
+                triangles.push(indices[i + 2 - flip]);
+            }
+            triangles
+        }
+        MeshPrimitiveMode::TriangleFan => {
+            let mut triangles = Vec::new();
+            for i in 0..indices.len().saturating_sub(2) {
+                triangles.push(indices[i + 1]);
+                triangles.push(indices[i + 2]);
+                triangles.push(indices[0]);
+            }
+            triangles
+        }
+        _ => return Ok(None),
+    };
+    ris_error::assert!(triangles.len() % 3 == 0)?;
+
+    // u8 indices require a vulkan extension, thus they are widened to u16
+    let indices = if is_u32 {
+        Indices::U32(triangles)
+    } else {
+        let triangles = triangles
+            .into_iter()
+            .map(u16::try_from)
+            .collect::<Result<Vec<_>, _>>()?;
+        Indices::U16(triangles)
+    };
+
+    let mut mesh_prototype = MeshPrototype {
+        vertices,
+        normals,
+        uvs,
+        indices,
+        joints: Vec::new(),
+        weights: Vec::new(),
+        tangents,
+        lods: Vec::new(),
+    };
+
+    // skin
+    let joints_accessor = attribute_accessor(MeshPrimitiveAttributeName::Joints(0))?;
+    let weights_accessor = attribute_accessor(MeshPrimitiveAttributeName::Weights(0))?;
+    if let (Some(joints_accessor), Some(weights_accessor)) = (joints_accessor, weights_accessor) {
+        ris_error::assert!(joints_accessor.count == vertex_count)?;
+        ris_error::assert!(weights_accessor.count == vertex_count)?;
+        ris_error::assert!(joints_accessor.accessor_type == AccessorType::Vec4)?;
+        ris_error::assert!(weights_accessor.accessor_type == AccessorType::Vec4)?;
+
+        let joints = read_uints(joints_accessor, buffers, gltf)?;
+        for chunk in joints.chunks_exact(4) {
+            let mut joint = [0; 4];
+            for (index, &raw) in joint.iter_mut().zip(chunk.iter()) {
+                *index = u16::try_from(raw)?;
+            }
+            mesh_prototype.joints.push(joint);
+        }
+
+        // normalized integers lose precision, thus the weights may not add up to 1
+        let weights = read_floats(weights_accessor, buffers, gltf)?;
+        for chunk in weights.chunks_exact(4) {
+            let sum = chunk.iter().sum::<f32>();
+            let mut weight = [0.0; 4];
+            for (value, &raw) in weight.iter_mut().zip(chunk.iter()) {
+                *value = if sum > 0.0 { raw / sum } else { raw };
+            }
+            mesh_prototype.weights.push(weight);
+        }
+    }
+
+    // correct coordinate system
+    let rotation = coordinate_correction();
+    for vertex in mesh_prototype.vertices.iter_mut() {
+        *vertex = rotation.rotate(*vertex);
+    }
+    for normal in mesh_prototype.normals.iter_mut() {
+        *normal = rotation.rotate(*normal);
+    }
+    for tangent in mesh_prototype.tangents.iter_mut() {
+        // a rotation keeps the handedness
+        let rotated = rotation.rotate(Vec3(tangent.0, tangent.1, tangent.2));
+        *tangent = Vec4(rotated.0, rotated.1, rotated.2, tangent.3);
+    }
+
+    if mesh_prototype.normals.is_empty() {
+        mesh_prototype.generate_normals();
+    }
+
+    Ok(Some(mesh_prototype))
+}
+
+/// Loads the data of a uri, which is either a base64 data uri or a path relative to `base_dir`.
+fn load_uri(uri: &str, base_dir: &Path) -> RisResult<Vec<u8>> {
+    if let Some(data_uri) = uri.strip_prefix("data:") {
+        let (media_type, data) = data_uri.split_once(',').into_ris_error()?;
+        if !media_type.ends_with(";base64") {
+            return ris_error::new_result!("unsupported data uri encoding: {}", media_type);
+        }
+
+        return base64::decode(data);
+    }
+
+    let path = base_dir.join(decode_uri_path(uri)?);
+    let data = std::fs::read(&path)
+        .map_err(|e| ris_error::new!("failed to read \"{}\": {}", path.display(), e))?;
+    Ok(data)
+}
+
+/// Decodes the percent-encoded characters of a relative uri, like `%20` for a space.
+fn decode_uri_path(uri: &str) -> RisResult<String> {
+    let bytes = uri.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = uri.get(i + 1..i + 3).into_ris_error()?;
+            decoded.push(u8::from_str_radix(hex, 16)?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    Ok(String::from_utf8(decoded)?)
+}
+
+fn buffer_view_data<'a>(
+    buffer_view_index: usize,
+    buffers: &'a [Vec<u8>],
+    gltf: &Gltf,
+) -> RisResult<&'a [u8]> {
+    let buffer_view = gltf.buffer_views.get(buffer_view_index).into_ris_error()?;
+    let buffer = buffers.get(buffer_view.buffer).into_ris_error()?;
+
+    let start = buffer_view.byte_offset;
+    let end = start + buffer_view.byte_length;
+    ris_error::assert!(end <= buffer.len())?;
+
+    Ok(&buffer[start..end])
+}
+
+/// Returns the elements of an accessor, tightly packed. Resolves the byte stride of the buffer
+/// view and sparse substitutions. An accessor without buffer view starts out as zeros.
+fn read_accessor(accessor: &Accessor, buffers: &[Vec<u8>], gltf: &Gltf) -> RisResult<Vec<u8>> {
+    let component_size = accessor.component_type.size_in_bytes();
+    let is_matrix = matches!(
+        accessor.accessor_type,
+        AccessorType::Mat2 | AccessorType::Mat3 | AccessorType::Mat4,
+    );
+    if is_matrix && component_size < 4 {
+        // the columns of these matrices are padded to 4 bytes
+        return ris_error::new_result!(
+            "unsupported matrix component type: {:?}",
+            accessor.component_type,
+        );
+    }
+
+    let element_size = component_size * accessor.accessor_type.number_of_components();
+    let mut data = vec![0; accessor.count * element_size];
+
+    if let Some(buffer_view_index) = accessor.buffer_view {
+        let view = buffer_view_data(buffer_view_index, buffers, gltf)?;
+        let buffer_view = gltf.buffer_views.get(buffer_view_index).into_ris_error()?;
+        let byte_stride = buffer_view.byte_stride.unwrap_or(element_size);
+        ris_error::assert!(byte_stride >= element_size)?;
+
+        for (i, element) in data.chunks_exact_mut(element_size).enumerate() {
+            let start = accessor.byte_offset + i * byte_stride;
+            let source = view.get(start..start + element_size).into_ris_error()?;
+            element.copy_from_slice(source);
+        }
+    }
+
+    if let Some(sparse) = &accessor.sparse {
+        let indices = buffer_view_data(sparse.indices.buffer_view, buffers, gltf)?;
+        let values = buffer_view_data(sparse.values.buffer_view, buffers, gltf)?;
+        let index_size = match sparse.indices.component_type {
+            AccessorSparseIndicesComponentType::U8 => 1,
+            AccessorSparseIndicesComponentType::U16 => 2,
+            AccessorSparseIndicesComponentType::U32 => 4,
+        };
+
+        for i in 0..sparse.count {
+            let start = sparse.indices.byte_offset + i * index_size;
+            let index_bytes = indices.get(start..start + index_size).into_ris_error()?;
+            // little-endian
+            let index = index_bytes
+                .iter()
+                .rev()
+                .fold(0, |index, &byte| (index << 8) | usize::from(byte));
+            ris_error::assert!(index < accessor.count)?;
+
+            let start = sparse.values.byte_offset + i * element_size;
+            let value = values.get(start..start + element_size).into_ris_error()?;
+            let target = index * element_size;
+            data[target..target + element_size].copy_from_slice(value);
+        }
+    }
+
+    Ok(data)
+}
+
+/// Reads the components of an accessor as floats. Normalized integers are mapped to [0, 1] or
+/// [-1, 1], as defined by the gltf spec. Other integers keep their value.
+fn read_floats(accessor: &Accessor, buffers: &[Vec<u8>], gltf: &Gltf) -> RisResult<Vec<f32>> {
+    let data = read_accessor(accessor, buffers, gltf)?;
+    let component_size = accessor.component_type.size_in_bytes();
+    let normalized = accessor.normalized;
+
+    let floats = data
+        .chunks_exact(component_size)
+        .map(|b| match accessor.component_type {
+            AccessorComponentType::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]),
+            AccessorComponentType::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f32,
+            AccessorComponentType::U8 => {
+                let value = f32::from(b[0]);
+                if normalized {
+                    value / 255.0
+                } else {
+                    value
+                }
+            }
+            AccessorComponentType::I8 => {
+                let value = f32::from(b[0] as i8);
+                if normalized {
+                    f32::max(value / 127.0, -1.0)
+                } else {
+                    value
+                }
+            }
+            AccessorComponentType::U16 => {
+                let value = f32::from(u16::from_le_bytes([b[0], b[1]]));
+                if normalized {
+                    value / 65535.0
+                } else {
+                    value
+                }
+            }
+            AccessorComponentType::I16 => {
+                let value = f32::from(i16::from_le_bytes([b[0], b[1]]));
+                if normalized {
+                    f32::max(value / 32767.0, -1.0)
+                } else {
+                    value
+                }
+            }
+        })
+        .collect();
+
+    Ok(floats)
+}
+
+/// Reads the components of an accessor of unsigned integers, like indices or joints.
+fn read_uints(accessor: &Accessor, buffers: &[Vec<u8>], gltf: &Gltf) -> RisResult<Vec<u32>> {
+    let data = read_accessor(accessor, buffers, gltf)?;
+
+    let uints = match accessor.component_type {
+        AccessorComponentType::U8 => data.iter().map(|&x| u32::from(x)).collect(),
+        AccessorComponentType::U16 => data
+            .chunks_exact(2)
+            .map(|b| u32::from(u16::from_le_bytes([b[0], b[1]])))
+            .collect(),
+        AccessorComponentType::U32 => data
+            .chunks_exact(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect(),
+        ref component_type => {
+            return ris_error::new_result!(
+                "expected unsigned integers, but was: {:?}",
+                component_type,
+            )
+        }
+    };
+
+    Ok(uints)
+}
@@ -1,3 +1,6 @@
 pub mod glb_importer;
 pub mod glsl_to_spirv_importer;
+pub mod gltf_importer;
+pub mod obj_importer;
+pub mod ply_importer;
 pub mod png_to_qoi_importer;
@@ -0,0 +1,204 @@
+use std::collections::HashMap;
+use std::f32::consts::PI;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ris_asset_data::mesh::CpuMesh;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_error::prelude::*;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec2;
+use ris_math::vector::Vec3;
+
+use crate::assets::ris_mesh;
+use crate::mesh_processing::mesh_processor;
+use crate::mesh_processing::mesh_processor::MeshProcessorSettings;
+
+pub const IN_EXT_OBJ: &str = "obj";
+
+/// A corner of a face: the 0 based indices of its position, uv and normal.
+type Corner = (usize, Option<usize>, Option<usize>);
+
+/// Imports all faces of the obj file as a single `ris_mesh`. Groups, objects, materials, lines
+/// and points are ignored.
+///
+/// Polygons are triangulated. If a corner of any face has no normal, the normals of the whole
+/// mesh are generated. Missing uvs are 0. Like most tools export it, obj is assumed to be y up,
+/// with the origin of the uvs at the bottom left.
+///
+/// The mesh is processed as configured by the sidecar of the source file, see
+/// `MeshProcessorSettings`.
+///
+/// Returns the paths of the created files.
+pub fn import(source: impl AsRef<Path>, target_dir: impl AsRef<Path>) -> RisResult<Vec<PathBuf>> {
+    let source = source.as_ref();
+    let target_dir = target_dir.as_ref();
+
+    let text = std::fs::read_to_string(source)?;
+    let mesh_prototype = parse(&text)?;
+    let settings = MeshProcessorSettings::load(source)?;
+    let mesh_prototype = mesh_processor::process(mesh_prototype, &settings)?;
+
+    let cpu_mesh = CpuMesh::try_from(mesh_prototype)?;
+    let bytes = ris_mesh::serialize(&cpu_mesh)?;
+
+    let (mut output, output_path) =
+        crate::asset_importer::create_file(source, target_dir, ris_mesh::EXTENSION)?;
+    ris_io::write(&mut output, &bytes)?;
+
+    Ok(vec![output_path])
+}
+
+/// Parses the content of an obj file. See `import()`.
+pub fn parse(text: &str) -> RisResult<MeshPrototype> {
+    let mut positions = Vec::new();
+    let mut uvs = Vec::new();
+    let mut normals = Vec::new();
+    let mut faces = Vec::new();
+
+    for (line_number, line) in text.lines().enumerate() {
+        let line_number = line_number + 1;
+        let line = match line.split_once('#') {
+            Some((content, _comment)) => content,
+            None => line,
+        };
+
+        let mut tokens = line.split_whitespace();
+        let Some(keyword) = tokens.next() else {
+            continue;
+        };
+
+        let result = match keyword {
+            "v" => parse_floats(tokens, 3).map(|x| positions.push(Vec3(x[0], x[1], x[2]))),
+            "vt" => parse_floats(tokens, 1).map(|x| {
+                let v = x.get(1).copied().unwrap_or(0.0);
+                uvs.push(Vec2(x[0], 1.0 - v));
+            }),
+            "vn" => parse_floats(tokens, 3).map(|x| normals.push(Vec3(x[0], x[1], x[2]))),
+            "f" => tokens
+                .map(|x| parse_corner(x, positions.len(), uvs.len(), normals.len()))
+                .collect::<RisResult<Vec<_>>>()
+                .map(|x| faces.push(x)),
+            _ => Ok(()),
+        };
+
+        if let Err(e) = result {
+            return ris_error::new_result!("failed to parse line {}: {}", line_number, e);
+        }
+    }
+
+    ris_error::assert!(!faces.is_empty())?;
+
+    // every distinct combination of position, uv and normal becomes a vertex
+    let mut vertex_indices = HashMap::<Corner, u32>::new();
+    let mut corners = Vec::new();
+    let mut polygons = Vec::with_capacity(faces.len());
+    for face in faces.iter() {
+        let mut polygon = Vec::with_capacity(face.len());
+        for &corner in face.iter() {
+            let index = *vertex_indices.entry(corner).or_insert_with(|| {
+                corners.push(corner);
+                (corners.len() - 1) as u32
+            });
+            polygon.push(index);
+        }
+        polygons.push(polygon);
+    }
+
+    let has_normals = corners.iter().all(|(_, _, normal)| normal.is_some());
+
+    let mut mesh_prototype = MeshPrototype {
+        vertices: Vec::with_capacity(corners.len()),
+        normals: Vec::new(),
+        uvs: Vec::with_capacity(corners.len()),
+        indices: Indices::None,
+        joints: Vec::new(),
+        weights: Vec::new(),
+        tangents: Vec::new(),
+        lods: Vec::new(),
+    };
+
+    for &(position, uv, normal) in corners.iter() {
+        mesh_prototype.vertices.push(positions[position]);
+        mesh_prototype
+            .uvs
+            .push(uv.map(|x| uvs[x]).unwrap_or(Vec2::init(0.0)));
+        if has_normals {
+            let normal = normal.into_ris_error()?;
+            mesh_prototype.normals.push(normals[normal]);
+        }
+    }
+
+    let mut triangles = Vec::new();
+    for polygon in polygons.iter() {
+        let polygon_triangles = crate::util::triangulate(polygon, &mesh_prototype.vertices)?;
+        triangles.extend(polygon_triangles);
+    }
+    mesh_prototype.indices = Indices::with_smallest_type(triangles, corners.len())?;
+
+    // correct coordinate system
+    let rotation = Quat::angle_axis(0.5 * PI, Vec3::right());
+    for vertex in mesh_prototype.vertices.iter_mut() {
+        *vertex = rotation.rotate(*vertex);
+    }
+    for normal in mesh_prototype.normals.iter_mut() {
+        *normal = rotation.rotate(*normal).normalize();
+    }
+
+    if !has_normals {
+        mesh_prototype.generate_normals();
+    }
+
+    Ok(mesh_prototype)
+}
+
+/// Parses at least `min_count` floats. Additional values, like the w component or vertex
+/// colors, are returned as well.
+fn parse_floats<'a>(
+    tokens: impl Iterator<Item = &'a str>,
+    min_count: usize,
+) -> RisResult<Vec<f32>> {
+    let values = tokens
+        .map(|x| x.parse::<f32>())
+        .collect::<Result<Vec<_>, _>>()?;
+    ris_error::assert!(values.len() >= min_count)?;
+    Ok(values)
+}
+
+/// Parses a corner in the form of `v`, `v/vt`, `v//vn` or `v/vt/vn`.
+fn parse_corner(
+    token: &str,
+    position_count: usize,
+    uv_count: usize,
+    normal_count: usize,
+) -> RisResult<Corner> {
+    let mut splits = token.split('/');
+    let position = splits.next().into_ris_error()?;
+    let uv = splits.next().filter(|x| !x.is_empty());
+    let normal = splits.next().filter(|x| !x.is_empty());
+    ris_error::assert!(splits.next().is_none())?;
+
+    let position = resolve_index(position, position_count)?;
+    let uv = uv.map(|x| resolve_index(x, uv_count)).transpose()?;
+    let normal = normal.map(|x| resolve_index(x, normal_count)).transpose()?;
+
+    Ok((position, uv, normal))
+}
+
+/// obj indices start at 1. negative indices are relative to the end of the elements, which
+/// were defined so far.
+fn resolve_index(token: &str, count: usize) -> RisResult<usize> {
+    let index = token.parse::<i64>()?;
+    let resolved = if index < 0 {
+        count as i64 + index
+    } else {
+        index - 1
+    };
+
+    if resolved < 0 || resolved >= count as i64 {
+        return ris_error::new_result!("index {} is out of range. count: {}", index, count);
+    }
+
+    Ok(resolved as usize)
+}
@@ -0,0 +1,384 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use ris_asset_data::mesh::CpuMesh;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_error::prelude::*;
+use ris_math::vector::Vec2;
+use ris_math::vector::Vec3;
+
+use crate::assets::ris_mesh;
+use crate::mesh_processing::mesh_processor;
+use crate::mesh_processing::mesh_processor::MeshProcessorSettings;
+
+pub const IN_EXT_PLY: &str = "ply";
+
+const END_HEADER: &str = "end_header";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Ascii,
+    BinaryLittleEndian,
+    BinaryBigEndian,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScalarType {
+    I8,
+    U8,
+    I16,
+    U16,
+    I32,
+    U32,
+    F32,
+    F64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PropertyType {
+    Scalar(ScalarType),
+    List {
+        count_type: ScalarType,
+        item_type: ScalarType,
+    },
+}
+
+#[derive(Debug)]
+struct Property {
+    name: String,
+    property_type: PropertyType,
+}
+
+#[derive(Debug)]
+struct Element {
+    name: String,
+    count: usize,
+    properties: Vec<Property>,
+}
+
+/// Reads the values of the body, one after another.
+struct Body<'a> {
+    format: Format,
+    bytes: &'a [u8],
+    position: usize,
+    tokens: std::str::SplitAsciiWhitespace<'a>,
+}
+
+/// Imports the `vertex` and `face` elements of the ply file as a `ris_mesh`. Other elements and
+/// properties are ignored. Ascii and both binary formats are supported.
+///
+/// Vertices require the properties `x`, `y` and `z`. Normals are read from `nx`, `ny` and `nz`,
+/// and generated if they are missing. uvs are read from `u` and `v`, `s` and `t`, or
+/// `texture_u` and `texture_v`, and are 0 if they are missing. Faces are polygons, given by the
+/// list `vertex_indices` or `vertex_index`, and are triangulated.
+///
+/// Unlike obj, ply is assumed to be z up, which is what blender exports by default. Like obj,
+/// the origin of the uvs is at the bottom left.
+///
+/// The mesh is processed as configured by the sidecar of the source file, see
+/// `MeshProcessorSettings`.
+///
+/// Returns the paths of the created files.
+pub fn import(source: impl AsRef<Path>, target_dir: impl AsRef<Path>) -> RisResult<Vec<PathBuf>> {
+    let source = source.as_ref();
+    let target_dir = target_dir.as_ref();
+
+    let bytes = std::fs::read(source)?;
+    let mesh_prototype = parse(&bytes)?;
+    let settings = MeshProcessorSettings::load(source)?;
+    let mesh_prototype = mesh_processor::process(mesh_prototype, &settings)?;
+
+    let cpu_mesh = CpuMesh::try_from(mesh_prototype)?;
+    let bytes = ris_mesh::serialize(&cpu_mesh)?;
+
+    let (mut output, output_path) =
+        crate::asset_importer::create_file(source, target_dir, ris_mesh::EXTENSION)?;
+    ris_io::write(&mut output, &bytes)?;
+
+    Ok(vec![output_path])
+}
+
+/// Parses the content of a ply file. See `import()`.
+pub fn parse(bytes: &[u8]) -> RisResult<MeshPrototype> {
+    let (format, elements, body_start) = parse_header(bytes)?;
+
+    let body_bytes = &bytes[body_start..];
+    let body_text = match format {
+        Format::Ascii => std::str::from_utf8(body_bytes)?,
+        Format::BinaryLittleEndian | Format::BinaryBigEndian => "",
+    };
+    let mut body = Body {
+        format,
+        bytes: body_bytes,
+        position: 0,
+        tokens: body_text.split_ascii_whitespace(),
+    };
+
+    let mut vertices = Vec::new();
+    let mut normals = Vec::new();
+    let mut uvs = Vec::new();
+    let mut polygons = Vec::new();
+
+    for element in elements.iter() {
+        let index_of = |names: &[&str]| {
+            element
+                .properties
+                .iter()
+                .position(|x| names.contains(&x.name.as_str()))
+        };
+
+        let is_vertex = element.name == "vertex";
+        let is_face = element.name == "face";
+        if !is_vertex && !is_face {
+            skip_element(&mut body, element)?;
+            continue;
+        }
+
+        let position = match [index_of(&["x"]), index_of(&["y"]), index_of(&["z"])] {
+            [Some(x), Some(y), Some(z)] => [x, y, z],
+            _ if is_face => [0; 3],
+            _ => return ris_error::new_result!("vertices require the properties x, y and z"),
+        };
+        let normal = [index_of(&["nx"]), index_of(&["ny"]), index_of(&["nz"])];
+        let uv = [
+            index_of(&["u", "s", "texture_u", "texture_s"]),
+            index_of(&["v", "t", "texture_v", "texture_t"]),
+        ];
+        let face_indices = index_of(&["vertex_indices", "vertex_index"]);
+        if is_face && face_indices.is_none() {
+            return ris_error::new_result!("faces require the property vertex_indices");
+        }
+
+        for _ in 0..element.count {
+            let mut scalars = vec![0.0; element.properties.len()];
+            let mut polygon = Vec::new();
+            for (i, property) in element.properties.iter().enumerate() {
+                match property.property_type {
+                    PropertyType::Scalar(scalar_type) => scalars[i] = body.read(scalar_type)?,
+                    PropertyType::List {
+                        count_type,
+                        item_type,
+                    } => {
+                        let count = body.read_count(count_type)?;
+                        let is_face_indices = is_face && face_indices == Some(i);
+                        for _ in 0..count {
+                            let value = body.read(item_type)?;
+                            if is_face_indices {
+                                polygon.push(to_index(value)?);
+                            }
+                        }
+                    }
+                }
+            }
+
+            if is_face {
+                polygons.push(polygon);
+                continue;
+            }
+
+            let [x, y, z] = position;
+            vertices.push(Vec3(
+                scalars[x] as f32,
+                scalars[y] as f32,
+                scalars[z] as f32,
+            ));
+            if let [Some(nx), Some(ny), Some(nz)] = normal {
+                let normal = Vec3(scalars[nx] as f32, scalars[ny] as f32, scalars[nz] as f32);
+                normals.push(normal.normalize());
+            }
+            if let [Some(u), Some(v)] = uv {
+                uvs.push(Vec2(scalars[u] as f32, 1.0 - scalars[v] as f32));
+            }
+        }
+    }
+
+    ris_error::assert!(!polygons.is_empty())?;
+
+    let mut triangles = Vec::new();
+    for polygon in polygons.iter() {
+        triangles.extend(crate::util::triangulate(polygon, &vertices)?);
+    }
+
+    let vertex_count = vertices.len();
+    if uvs.is_empty() {
+        uvs = vec![Vec2::init(0.0); vertex_count];
+    }
+
+    let mut mesh_prototype = MeshPrototype {
+        vertices,
+        normals,
+        uvs,
+        indices: Indices::with_smallest_type(triangles, vertex_count)?,
+        joints: Vec::new(),
+        weights: Vec::new(),
+        tangents: Vec::new(),
+        lods: Vec::new(),
+    };
+
+    if mesh_prototype.normals.is_empty() {
+        mesh_prototype.generate_normals();
+    }
+
+    Ok(mesh_prototype)
+}
+
+/// Returns the format, the elements and the position at which the body starts.
+fn parse_header(bytes: &[u8]) -> RisResult<(Format, Vec<Element>, usize)> {
+    // the header is ascii, but the body may be binary. thus the header is read line by line
+    let mut position = 0;
+    let mut next_line = || -> RisResult<&str> {
+        let remaining = bytes.get(position..).into_ris_error()?;
+        let length = remaining
+            .iter()
+            .position(|&x| x == b'\n')
+            .ok_or_else(|| ris_error::new!("header has no \"{}\"", END_HEADER))?;
+        position += length + 1;
+        let line = std::str::from_utf8(&remaining[..length])?;
+        Ok(line.trim_end_matches('\r'))
+    };
+
+    ris_error::assert!(next_line()? == "ply")?;
+
+    let mut format = None;
+    let mut elements = Vec::<Element>::new();
+    loop {
+        let line = next_line()?;
+        let tokens = line.split_whitespace().collect::<Vec<_>>();
+        match tokens.as_slice() {
+            [END_HEADER] => break,
+            [] | ["comment", ..] | ["obj_info", ..] => (),
+            ["format", name, "1.0"] => {
+                format = Some(match *name {
+                    "ascii" => Format::Ascii,
+                    "binary_little_endian" => Format::BinaryLittleEndian,
+                    "binary_big_endian" => Format::BinaryBigEndian,
+                    name => return ris_error::new_result!("unknown format: {}", name),
+                })
+            }
+            ["element", name, count] => elements.push(Element {
+                name: name.to_string(),
+                count: count.parse()?,
+                properties: Vec::new(),
+            }),
+            ["property", "list", count_type, item_type, name] => {
+                let element = elements.last_mut().into_ris_error()?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    property_type: PropertyType::List {
+                        count_type: ScalarType::parse(count_type)?,
+                        item_type: ScalarType::parse(item_type)?,
+                    },
+                });
+            }
+            ["property", scalar_type, name] => {
+                let element = elements.last_mut().into_ris_error()?;
+                element.properties.push(Property {
+                    name: name.to_string(),
+                    property_type: PropertyType::Scalar(ScalarType::parse(scalar_type)?),
+                });
+            }
+            _ => return ris_error::new_result!("invalid header line: {}", line),
+        }
+    }
+
+    let format = format.into_ris_error()?;
+    Ok((format, elements, position))
+}
+
+fn skip_element(body: &mut Body, element: &Element) -> RisResult<()> {
+    for _ in 0..element.count {
+        for property in element.properties.iter() {
+            match property.property_type {
+                PropertyType::Scalar(scalar_type) => {
+                    body.read(scalar_type)?;
+                }
+                PropertyType::List {
+                    count_type,
+                    item_type,
+                } => {
+                    let count = body.read_count(count_type)?;
+                    for _ in 0..count {
+                        body.read(item_type)?;
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+fn to_index(value: f64) -> RisResult<u32> {
+    if value < 0.0 || value > u32::MAX as f64 || value.fract() != 0.0 {
+        return ris_error::new_result!("invalid vertex index: {}", value);
+    }
+
+    Ok(value as u32)
+}
+
+impl ScalarType {
+    fn parse(name: &str) -> RisResult<Self> {
+        match name {
+            "char" | "int8" => Ok(Self::I8),
+            "uchar" | "uint8" => Ok(Self::U8),
+            "short" | "int16" => Ok(Self::I16),
+            "ushort" | "uint16" => Ok(Self::U16),
+            "int" | "int32" => Ok(Self::I32),
+            "uint" | "uint32" => Ok(Self::U32),
+            "float" | "float32" => Ok(Self::F32),
+            "double" | "float64" => Ok(Self::F64),
+            name => ris_error::new_result!("unknown property type: {}", name),
+        }
+    }
+
+    fn size(self) -> usize {
+        match self {
+            Self::I8 | Self::U8 => 1,
+            Self::I16 | Self::U16 => 2,
+            Self::I32 | Self::U32 | Self::F32 => 4,
+            Self::F64 => 8,
+        }
+    }
+}
+
+impl Body<'_> {
+    fn read(&mut self, scalar_type: ScalarType) -> RisResult<f64> {
+        if self.format == Format::Ascii {
+            let token = self.tokens.next().into_ris_error()?;
+            return Ok(token.parse::<f64>()?);
+        }
+
+        let size = scalar_type.size();
+        let end = self.position + size;
+        let Some(slice) = self.bytes.get(self.position..end) else {
+            return ris_error::new_result!("unexpected end of body");
+        };
+        self.position = end;
+
+        // bytes in little-endian order
+        let mut b = [0u8; 8];
+        b[..size].copy_from_slice(slice);
+        if self.format == Format::BinaryBigEndian {
+            b[..size].reverse();
+        }
+
+        let value = match scalar_type {
+            ScalarType::I8 => i8::from_le_bytes([b[0]]) as f64,
+            ScalarType::U8 => b[0] as f64,
+            ScalarType::I16 => i16::from_le_bytes([b[0], b[1]]) as f64,
+            ScalarType::U16 => u16::from_le_bytes([b[0], b[1]]) as f64,
+            ScalarType::I32 => i32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            ScalarType::U32 => u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            ScalarType::F32 => f32::from_le_bytes([b[0], b[1], b[2], b[3]]) as f64,
+            ScalarType::F64 => f64::from_le_bytes(b),
+        };
+
+        Ok(value)
+    }
+
+    fn read_count(&mut self, count_type: ScalarType) -> RisResult<usize> {
+        let value = self.read(count_type)?;
+        Ok(to_index(value)? as usize)
+    }
+}
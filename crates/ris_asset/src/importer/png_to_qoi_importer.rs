@@ -1,10 +1,15 @@
 use std::fs::File;
 use std::path::Path;
+use std::path::PathBuf;
 
 use png::ColorType;
 
 use ris_error::prelude::*;
 
+use ris_asset_data::texture::CpuTexture;
+use ris_asset_data::texture::SamplerSettings;
+
+use crate::assets::ris_texture;
 use crate::codecs::qoi;
 use crate::codecs::qoi::Channels;
 use crate::codecs::qoi::ColorSpace;
@@ -13,7 +18,11 @@ use crate::codecs::qoi::QoiDesc;
 pub const IN_EXT_PNG: &str = "png";
 pub const OUT_EXT_QOI: &str = "qoi";
 
-pub fn import(source: impl AsRef<Path>, target_dir: impl AsRef<Path>) -> RisResult<()> {
+/// Writes the image as `qoi` and as `ris_texture` with a full mip chain. Textures are treated as
+/// sRGB and use the default sampler settings.
+///
+/// Returns the paths of the created files.
+pub fn import(source: impl AsRef<Path>, target_dir: impl AsRef<Path>) -> RisResult<Vec<PathBuf>> {
     let source = source.as_ref();
     let target_dir = target_dir.as_ref();
 
@@ -50,8 +59,28 @@ pub fn import(source: impl AsRef<Path>, target_dir: impl AsRef<Path>) -> RisResu
 
     let encoded = qoi::encode(&pixels, desc)?;
 
-    let mut output = crate::asset_importer::create_file(source, target_dir, OUT_EXT_QOI)?;
+    let (mut output, qoi_path) =
+        crate::asset_importer::create_file(source, target_dir, OUT_EXT_QOI)?;
     ris_io::write(&mut output, &encoded)?;
 
-    Ok(())
+    // encode ris_texture
+    let pixels_rgba = match desc.channels {
+        Channels::RGB => crate::util::add_alpha_channel(&pixels)?,
+        Channels::RGBA => pixels,
+    };
+
+    let texture = CpuTexture::with_mips(
+        width as usize,
+        height as usize,
+        true,
+        SamplerSettings::default(),
+        pixels_rgba,
+    )?;
+    let bytes = ris_texture::serialize(&texture)?;
+
+    let (mut output, texture_path) =
+        crate::asset_importer::create_file(source, target_dir, ris_texture::EXTENSION)?;
+    ris_io::write(&mut output, &bytes)?;
+
+    Ok(vec![qoi_path, texture_path])
 }
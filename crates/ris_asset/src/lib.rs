@@ -2,12 +2,17 @@ pub mod assets;
 pub mod codecs;
 pub mod importer;
 pub mod lookup;
+pub mod mesh_processing;
 
+pub mod asset_archive;
 pub mod asset_compiler;
 pub mod asset_importer;
 pub mod asset_loader;
 pub mod asset_loader_compiled;
 pub mod asset_loader_directory;
+pub mod asset_watcher;
+pub mod import_cache;
+pub mod scene_diff;
 
 pub mod util;
 
@@ -15,4 +20,9 @@ pub use assets::ris_god_asset::RisGodAsset;
 pub use assets::ris_header::RisHeader;
 
 pub use asset_loader::load_async;
+pub use asset_loader::load_async_with_priority;
 pub use asset_loader::load_raw_async;
+pub use asset_loader::load_raw_async_with_priority;
+pub use asset_loader::open_stream;
+pub use asset_loader::AssetStream;
+pub use asset_loader::LoadPriority;
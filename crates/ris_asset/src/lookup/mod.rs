@@ -1 +1,4 @@
+pub mod ris_animation_lookup;
+pub mod ris_material_lookup;
 pub mod ris_mesh_lookup;
+pub mod ris_texture_lookup;
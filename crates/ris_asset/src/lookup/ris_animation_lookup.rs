@@ -0,0 +1,141 @@
+use ris_asset_data::animation::AnimationClip;
+use ris_asset_data::skeleton::Skeleton;
+use ris_asset_data::AssetId;
+use ris_async::OneshotReceiver;
+use ris_error::prelude::*;
+
+use crate::assets::ris_animation;
+use crate::assets::ris_skeleton;
+
+/// Skeletons and animation clips live on the cpu only. Unlike the gpu lookups, entries are not
+/// reference counted: an entry that was not requested between two calls to `free_unused()` is
+/// dropped.
+#[derive(Default)]
+pub struct AnimationLookup {
+    skeletons: Vec<Entry<Skeleton>>,
+    clips: Vec<Entry<AnimationClip>>,
+}
+
+struct Entry<T> {
+    asset_id: AssetId,
+    used: bool,
+    state: EntryState<T>,
+}
+
+enum EntryState<T> {
+    Loading(OneshotReceiver<RisResult<T>>),
+    Loaded(T),
+    /// not retried until the asset changes
+    Failed,
+}
+
+impl AnimationLookup {
+    /// Reloads the entries of the given assets. Everything else stays untouched.
+    pub fn reload(&mut self, changed: &[AssetId]) {
+        for entry in self.skeletons.iter_mut() {
+            if changed.contains(&entry.asset_id) {
+                ris_log::debug!("reloading skeleton {:?}", entry.asset_id);
+                entry.state = EntryState::Loading(load_skeleton(entry.asset_id.clone()));
+            }
+        }
+
+        for entry in self.clips.iter_mut() {
+            if changed.contains(&entry.asset_id) {
+                ris_log::debug!("reloading animation {:?}", entry.asset_id);
+                entry.state = EntryState::Loading(load_clip(entry.asset_id.clone()));
+            }
+        }
+    }
+
+    pub fn free_unused(&mut self) {
+        free_unused(&mut self.skeletons);
+        free_unused(&mut self.clips);
+    }
+
+    /// Starts loading the skeleton, if it is not cached yet. Returns whether it is loaded.
+    pub fn request_skeleton(&mut self, asset_id: &AssetId) -> bool {
+        request(&mut self.skeletons, asset_id, load_skeleton)
+    }
+
+    /// Starts loading the clip, if it is not cached yet. Returns whether it is loaded.
+    pub fn request_clip(&mut self, asset_id: &AssetId) -> bool {
+        request(&mut self.clips, asset_id, load_clip)
+    }
+
+    pub fn skeleton(&self, asset_id: &AssetId) -> Option<&Skeleton> {
+        get(&self.skeletons, asset_id)
+    }
+
+    pub fn clip(&self, asset_id: &AssetId) -> Option<&AnimationClip> {
+        get(&self.clips, asset_id)
+    }
+}
+
+fn load_skeleton(asset_id: AssetId) -> OneshotReceiver<RisResult<Skeleton>> {
+    crate::load_async(asset_id, move |bytes| ris_skeleton::deserialize(&bytes))
+}
+
+fn load_clip(asset_id: AssetId) -> OneshotReceiver<RisResult<AnimationClip>> {
+    crate::load_async(asset_id, move |bytes| {
+        let (clip, _skeleton) = ris_animation::deserialize(&bytes)?;
+        Ok(clip)
+    })
+}
+
+fn free_unused<T>(entries: &mut Vec<Entry<T>>) {
+    entries.retain(|x| {
+        if !x.used {
+            ris_log::trace!("freed {:?}", x.asset_id);
+        }
+
+        x.used
+    });
+
+    for entry in entries.iter_mut() {
+        entry.used = false;
+    }
+}
+
+fn request<T>(
+    entries: &mut Vec<Entry<T>>,
+    asset_id: &AssetId,
+    load: fn(AssetId) -> OneshotReceiver<RisResult<T>>,
+) -> bool {
+    let position = entries.iter().position(|x| x.asset_id == *asset_id);
+    let entry = match position {
+        Some(position) => &mut entries[position],
+        None => {
+            entries.push(Entry {
+                asset_id: asset_id.clone(),
+                used: false,
+                state: EntryState::Loading(load(asset_id.clone())),
+            });
+            entries.last_mut().unwrap()
+        }
+    };
+
+    entry.used = true;
+
+    let state = std::mem::replace(&mut entry.state, EntryState::Failed);
+    entry.state = match state {
+        EntryState::Loading(receiver) => match receiver.receive() {
+            Ok(Ok(value)) => EntryState::Loaded(value),
+            Ok(Err(e)) => {
+                ris_log::error!("failed to load {:?}: {}", entry.asset_id, e);
+                EntryState::Failed
+            }
+            Err(receiver) => EntryState::Loading(receiver),
+        },
+        state => state,
+    };
+
+    matches!(entry.state, EntryState::Loaded(_))
+}
+
+fn get<'a, T>(entries: &'a [Entry<T>], asset_id: &AssetId) -> Option<&'a T> {
+    let entry = entries.iter().find(|x| x.asset_id == *asset_id)?;
+    match &entry.state {
+        EntryState::Loaded(value) => Some(value),
+        _ => None,
+    }
+}
@@ -0,0 +1,143 @@
+use ris_asset_data::material::CpuMaterial;
+use ris_asset_data::material::GpuMaterial;
+use ris_asset_data::material::MaterialLookupId;
+use ris_asset_data::AssetId;
+use ris_async::OneshotReceiver;
+use ris_error::prelude::*;
+
+use crate::assets::ris_material;
+use crate::lookup::ris_texture_lookup::TextureLookup;
+
+/// Materials own no gpu resources themselves. Their textures are allocated in a `TextureLookup`,
+/// which frees them, once no material references them anymore.
+#[derive(Default)]
+pub struct MaterialLookup {
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    asset_id: AssetId,
+    lookup_id: MaterialLookupId,
+    value: Option<EntryState>,
+}
+
+enum EntryState {
+    Loading(OneshotReceiver<RisResult<CpuMaterial>>),
+    Loaded(GpuMaterial),
+}
+
+impl MaterialLookup {
+    /// Reloads the entries of the given assets, that are loaded or loading. Everything else
+    /// stays untouched. Textures that changed are reloaded by the `TextureLookup`.
+    pub fn reload(&mut self, changed: &[AssetId]) {
+        for entry in self.entries.iter_mut() {
+            if entry.value.is_none() || !changed.contains(&entry.asset_id) {
+                continue;
+            }
+
+            ris_log::debug!("reloading material {:?}", entry.asset_id);
+            let state = EntryState::load(entry.asset_id.clone());
+            entry.value = Some(state);
+        }
+    }
+
+    pub fn alloc(&mut self, asset_id: AssetId) -> MaterialLookupId {
+        let position = self.entries.iter().position(|x| x.asset_id == asset_id);
+
+        let entry = match position {
+            Some(position) => &mut self.entries[position],
+            None => {
+                let position = self
+                    .entries
+                    .iter_mut()
+                    .position(|x| x.lookup_id.is_unique());
+
+                match position {
+                    Some(position) => {
+                        let entry = &mut self.entries[position];
+                        entry.asset_id = asset_id;
+                        entry
+                    }
+                    None => {
+                        let index = self.entries.len();
+                        let entry = Entry {
+                            asset_id,
+                            lookup_id: MaterialLookupId::new(index),
+                            value: None,
+                        };
+                        self.entries.push(entry);
+                        let entry = self.entries.last_mut().unwrap();
+                        entry
+                    }
+                }
+            }
+        };
+
+        if entry.value.is_none() {
+            let state = EntryState::load(entry.asset_id.clone());
+            entry.value = Some(state);
+        }
+
+        entry.lookup_id.clone()
+    }
+
+    /// Drops the materials, that are not referenced anymore, and with them their textures.
+    pub fn free_unused_materials(&mut self) {
+        for entry in self.entries.iter_mut() {
+            if !entry.lookup_id.is_unique() {
+                continue;
+            }
+
+            if let Some(EntryState::Loaded(_)) = entry.value.take() {
+                ris_log::trace!("freed material {:?}", entry.asset_id);
+            }
+        }
+    }
+
+    /// Returns the material, once it and its references are loaded. Its textures are allocated
+    /// in `texture_lookup`, but may still be loading.
+    pub fn get(
+        &mut self,
+        texture_lookup: &mut TextureLookup,
+        id: &MaterialLookupId,
+    ) -> Option<&GpuMaterial> {
+        let entry = self.entries.get_mut(id.index())?;
+
+        match entry.value.take() {
+            Some(EntryState::Loading(receiver)) => match receiver.receive() {
+                Ok(Ok(cpu_material)) => {
+                    let textures = cpu_material
+                        .textures
+                        .slots()
+                        .map(|x| x.clone().map(|x| texture_lookup.alloc(x)));
+
+                    let gpu_material = GpuMaterial {
+                        params: cpu_material.params,
+                        textures,
+                    };
+
+                    entry.value = Some(EntryState::Loaded(gpu_material));
+                }
+                Ok(Err(e)) => {
+                    ris_log::error!("failed to load material {:?}: {}", entry.asset_id, e);
+                    entry.value = None;
+                }
+                Err(receiver) => entry.value = Some(EntryState::Loading(receiver)),
+            },
+            value => entry.value = value,
+        }
+
+        match entry.value.as_ref() {
+            Some(EntryState::Loaded(gpu_material)) => Some(gpu_material),
+            _ => None,
+        }
+    }
+}
+
+impl EntryState {
+    fn load(asset_id: AssetId) -> Self {
+        let receiver = crate::load_async(asset_id, move |bytes| ris_material::deserialize(&bytes));
+
+        EntryState::Loading(receiver)
+    }
+}
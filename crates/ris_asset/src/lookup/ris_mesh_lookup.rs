@@ -50,6 +50,33 @@ impl MeshLookup {
         }
     }
 
+    /// Reloads the entries of the given assets, that are loaded or loading. Everything else
+    /// stays untouched. Waits for the device to be idle, if a loaded mesh must be freed.
+    pub fn reload(&mut self, device: &ash::Device, changed: &[AssetId]) -> RisResult<()> {
+        let mut must_wait = true;
+
+        for entry in self.entries.iter_mut() {
+            if entry.value.is_none() || !changed.contains(&entry.asset_id) {
+                continue;
+            }
+
+            if let Some(mut gpu_mesh) = entry.take_gpu_mesh() {
+                if must_wait {
+                    unsafe { device.device_wait_idle() }?;
+                    must_wait = false;
+                }
+
+                gpu_mesh.free(device);
+            }
+
+            ris_log::debug!("reloading mesh {:?}", entry.asset_id);
+            let state = EntryState::load(entry.asset_id.clone());
+            entry.value = Some(state);
+        }
+
+        Ok(())
+    }
+
     pub fn alloc(&mut self, asset_id: AssetId) -> MeshLookupId {
         let position = self.entries.iter().position(|x| x.asset_id == asset_id);
 
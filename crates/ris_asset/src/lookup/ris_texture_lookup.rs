@@ -0,0 +1,208 @@
+use ash::vk;
+
+use ris_asset_data::texture::CpuTexture;
+use ris_asset_data::texture::GpuTexture;
+use ris_asset_data::texture::TextureLookupId;
+use ris_asset_data::AssetId;
+use ris_async::OneshotReceiver;
+use ris_error::prelude::*;
+use ris_gpu::transient_command::TransientCommandArgs;
+
+use crate::assets::ris_texture;
+
+#[derive(Default)]
+pub struct TextureLookup {
+    entries: Vec<Entry>,
+}
+
+struct Entry {
+    asset_id: AssetId,
+    lookup_id: TextureLookupId,
+    value: Option<EntryState>,
+}
+
+enum EntryState {
+    Loading(OneshotReceiver<RisResult<CpuTexture>>),
+    Loaded(GpuTexture),
+}
+
+impl TextureLookup {
+    pub fn free(&mut self, device: &ash::Device) {
+        for entry in self.entries.iter_mut() {
+            if let Some(mut gpu_texture) = entry.take_gpu_texture() {
+                gpu_texture.free(device);
+            }
+        }
+    }
+
+    pub fn reimport_everything(&mut self, transient_command_args: TransientCommandArgs) {
+        for entry in self.entries.iter_mut() {
+            if entry.value.is_none() {
+                continue;
+            }
+
+            if let Some(mut gpu_texture) = entry.take_gpu_texture() {
+                gpu_texture.free(&transient_command_args.device);
+            }
+
+            let state = EntryState::load(entry.asset_id.clone());
+            entry.value = Some(state);
+        }
+    }
+
+    /// Reloads the entries of the given assets, that are loaded or loading. Everything else
+    /// stays untouched. Waits for the device to be idle, if a loaded texture must be freed.
+    pub fn reload(&mut self, device: &ash::Device, changed: &[AssetId]) -> RisResult<()> {
+        let mut must_wait = true;
+
+        for entry in self.entries.iter_mut() {
+            if entry.value.is_none() || !changed.contains(&entry.asset_id) {
+                continue;
+            }
+
+            if let Some(mut gpu_texture) = entry.take_gpu_texture() {
+                if must_wait {
+                    unsafe { device.device_wait_idle() }?;
+                    must_wait = false;
+                }
+
+                gpu_texture.free(device);
+            }
+
+            ris_log::debug!("reloading texture {:?}", entry.asset_id);
+            let state = EntryState::load(entry.asset_id.clone());
+            entry.value = Some(state);
+        }
+
+        Ok(())
+    }
+
+    pub fn alloc(&mut self, asset_id: AssetId) -> TextureLookupId {
+        let position = self.entries.iter().position(|x| x.asset_id == asset_id);
+
+        let entry = match position {
+            Some(position) => &mut self.entries[position],
+            None => {
+                let position = self
+                    .entries
+                    .iter_mut()
+                    .position(|x| x.lookup_id.is_unique());
+
+                match position {
+                    Some(position) => {
+                        let entry = &mut self.entries[position];
+                        entry.asset_id = asset_id;
+                        entry
+                    }
+                    None => {
+                        let index = self.entries.len();
+                        let entry = Entry {
+                            asset_id,
+                            lookup_id: TextureLookupId::new(index),
+                            value: None,
+                        };
+                        self.entries.push(entry);
+                        let entry = self.entries.last_mut().unwrap();
+                        entry
+                    }
+                }
+            }
+        };
+
+        if entry.value.is_none() {
+            let state = EntryState::load(entry.asset_id.clone());
+            entry.value = Some(state);
+        }
+
+        entry.lookup_id.clone()
+    }
+
+    pub fn free_unused_textures(&mut self, device: &ash::Device) -> RisResult<()> {
+        let mut must_wait = true;
+
+        for entry in self.entries.iter_mut() {
+            if !entry.lookup_id.is_unique() {
+                continue;
+            }
+
+            if must_wait {
+                unsafe { device.device_wait_idle() }?;
+                must_wait = false;
+            }
+
+            if let Some(mut gpu_texture) = entry.take_gpu_texture() {
+                gpu_texture.free(device);
+                ris_log::trace!("freed texture {:?}", entry.asset_id);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// # Safety
+    ///
+    /// only pass TextureLookupIds that were returned from this lookup container. this is
+    /// because the id is tied to the gpu texture. if the id goes out of scope, the texture
+    /// will be freed, potentially screwing a currently running render pipeline.
+    ///
+    /// additionaly, also because of the reason given above, the TextureLookupId must live
+    /// longer than the entire time the GpuTexture is bound.
+    pub unsafe fn get(
+        &mut self,
+        transient_command_args: TransientCommandArgs,
+        physical_device_memory_properties: vk::PhysicalDeviceMemoryProperties,
+        physical_device_properties: vk::PhysicalDeviceProperties,
+        id: &TextureLookupId,
+    ) -> Option<&GpuTexture> {
+        let entry = self.entries.get_mut(id.index())?;
+
+        match entry.value.take() {
+            Some(EntryState::Loading(receiver)) => match receiver.receive() {
+                Ok(Ok(cpu_texture)) => {
+                    let value = match GpuTexture::from_cpu_texture(
+                        transient_command_args,
+                        physical_device_memory_properties,
+                        physical_device_properties,
+                        cpu_texture,
+                    ) {
+                        Ok(gpu_texture) => Some(EntryState::Loaded(gpu_texture)),
+                        Err(e) => {
+                            ris_log::error!("failed to convert cpu texture to gpu texture: {}", e);
+                            None
+                        }
+                    };
+
+                    entry.value = value;
+                }
+                Ok(Err(e)) => {
+                    ris_log::error!("failed to load texture {:?}: {}", entry.asset_id, e);
+                    entry.value = None;
+                }
+                Err(receiver) => entry.value = Some(EntryState::Loading(receiver)),
+            },
+            value => entry.value = value,
+        }
+
+        match entry.value.as_ref() {
+            Some(EntryState::Loaded(gpu_texture)) => Some(gpu_texture),
+            _ => None,
+        }
+    }
+}
+
+impl Entry {
+    fn take_gpu_texture(&mut self) -> Option<GpuTexture> {
+        match self.value.take() {
+            Some(EntryState::Loaded(gpu_texture)) => Some(gpu_texture),
+            _ => None,
+        }
+    }
+}
+
+impl EntryState {
+    fn load(asset_id: AssetId) -> Self {
+        let receiver = crate::load_async(asset_id, move |bytes| ris_texture::deserialize(&bytes));
+
+        EntryState::Loading(receiver)
+    }
+}
@@ -0,0 +1,352 @@
+use std::collections::VecDeque;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ris_asset_data::mesh::CpuMesh;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_data::ris_yaml;
+use ris_data::ris_yaml::RisYaml;
+use ris_error::prelude::*;
+
+use crate::assets::ris_mesh;
+
+use super::normals;
+use super::simplify;
+use super::tangents;
+use super::vertex_cache;
+use super::weld;
+
+const KEY_WELD: &str = "weld";
+const KEY_WELD_EPSILON: &str = "weld_epsilon";
+const KEY_GENERATE_NORMALS: &str = "generate_normals";
+const KEY_GENERATE_TANGENTS: &str = "generate_tangents";
+const KEY_OPTIMIZE_VERTEX_CACHE: &str = "optimize_vertex_cache";
+const KEY_COMPACT_INDICES: &str = "compact_indices";
+const KEY_LOD_COUNT: &str = "lod_count";
+const KEY_LOD_RATIO: &str = "lod_ratio";
+
+/// Configures the processing of the meshes of a single source file. The settings are read from
+/// the sidecar meta file of the source file, see `asset_importer::sidecar_path()`. Keys, which
+/// are missing in the sidecar, keep their default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeshProcessorSettings {
+    /// merges duplicate vertices
+    pub weld: bool,
+    /// attributes are compared after rounding them to multiples of this. 0 welds exact
+    /// duplicates only
+    pub weld_epsilon: f32,
+    /// replaces the imported normals with smooth normals
+    pub generate_normals: bool,
+    /// generates tangents for meshes, which have none. if the normals are generated, the
+    /// tangents are generated as well
+    pub generate_tangents: bool,
+    /// reorders the triangles for the post-transform vertex cache, and the vertices in the order
+    /// in which they are used
+    pub optimize_vertex_cache: bool,
+    /// uses u16 indices, when the vertex count allows it. otherwise u32 indices are used
+    pub compact_indices: bool,
+    /// the number of simplified levels of detail, which are generated in addition to the full
+    /// mesh. fewer are generated, if the mesh can't be simplified any further
+    pub lod_count: usize,
+    /// each level of detail aims for this fraction of the triangles of the level before
+    pub lod_ratio: f32,
+}
+
+impl Default for MeshProcessorSettings {
+    fn default() -> Self {
+        Self {
+            weld: true,
+            weld_epsilon: 0.0,
+            generate_normals: false,
+            generate_tangents: true,
+            optimize_vertex_cache: true,
+            compact_indices: true,
+            lod_count: 3,
+            lod_ratio: 0.5,
+        }
+    }
+}
+
+impl MeshProcessorSettings {
+    /// Returns the default settings, if the source file has no sidecar.
+    pub fn load(source: impl AsRef<Path>) -> RisResult<Self> {
+        let path = crate::asset_importer::sidecar_path(source);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let text = std::fs::read_to_string(&path)?;
+        Self::deserialize(text)
+            .map_err(|e| ris_error::new!("failed to load \"{}\": {}", path.display(), e))
+    }
+
+    pub fn serialize(&self) -> RisResult<String> {
+        let mut yaml = RisYaml::default();
+        yaml.add_entry(None, Some("mesh processing"));
+        yaml.add_entry(Some((KEY_WELD, &self.weld.to_string())), None);
+        yaml.add_entry(
+            Some((KEY_WELD_EPSILON, &self.weld_epsilon.to_string())),
+            None,
+        );
+        yaml.add_entry(
+            Some((KEY_GENERATE_NORMALS, &self.generate_normals.to_string())),
+            None,
+        );
+        yaml.add_entry(
+            Some((KEY_GENERATE_TANGENTS, &self.generate_tangents.to_string())),
+            None,
+        );
+        yaml.add_entry(
+            Some((
+                KEY_OPTIMIZE_VERTEX_CACHE,
+                &self.optimize_vertex_cache.to_string(),
+            )),
+            None,
+        );
+        yaml.add_entry(
+            Some((KEY_COMPACT_INDICES, &self.compact_indices.to_string())),
+            None,
+        );
+        yaml.add_entry(Some((KEY_LOD_COUNT, &self.lod_count.to_string())), None);
+        yaml.add_entry(Some((KEY_LOD_RATIO, &self.lod_ratio.to_string())), None);
+        yaml.serialize()
+    }
+
+    pub fn deserialize(text: impl AsRef<str>) -> RisResult<Self> {
+        let yaml = RisYaml::deserialize(text)?;
+
+        let mut settings = Self::default();
+        for entry in yaml.entries.iter() {
+            let Some((key, value)) = &entry.key_value else {
+                continue;
+            };
+            let line = entry.line;
+
+            match key.as_str() {
+                KEY_WELD => settings.weld = parse(value, line)?,
+                KEY_WELD_EPSILON => settings.weld_epsilon = parse(value, line)?,
+                KEY_GENERATE_NORMALS => settings.generate_normals = parse(value, line)?,
+                KEY_GENERATE_TANGENTS => settings.generate_tangents = parse(value, line)?,
+                KEY_OPTIMIZE_VERTEX_CACHE => settings.optimize_vertex_cache = parse(value, line)?,
+                KEY_COMPACT_INDICES => settings.compact_indices = parse(value, line)?,
+                KEY_LOD_COUNT => settings.lod_count = parse(value, line)?,
+                KEY_LOD_RATIO => settings.lod_ratio = parse(value, line)?,
+                key => return ris_yaml::error_on_line(line, &format!("unknown key \"{}\"", key)),
+            }
+        }
+
+        if settings.weld_epsilon.is_nan() || settings.weld_epsilon < 0.0 {
+            return ris_error::new_result!(
+                "{} must be at least 0, but was {}",
+                KEY_WELD_EPSILON,
+                settings.weld_epsilon,
+            );
+        }
+
+        if !(settings.lod_ratio > 0.0 && settings.lod_ratio < 1.0) {
+            return ris_error::new_result!(
+                "{} must be between 0 and 1 exclusive, but was {}",
+                KEY_LOD_RATIO,
+                settings.lod_ratio,
+            );
+        }
+
+        Ok(settings)
+    }
+}
+
+/// Runs the enabled steps in this order: normals, welding, tangents, vertex cache optimization,
+/// level of detail generation and index compaction. Afterwards, the indices are a triangle list.
+pub fn process(
+    mut mesh: MeshPrototype,
+    settings: &MeshProcessorSettings,
+) -> RisResult<MeshPrototype> {
+    mesh.indices = Indices::U32(triangle_list(&mesh)?);
+    // the steps below change the vertices, thus existing lods would be invalid
+    mesh.lods.clear();
+
+    if settings.generate_normals {
+        normals::smooth_normals(&mut mesh)?;
+    }
+
+    if settings.weld {
+        weld::weld(&mut mesh, settings.weld_epsilon)?;
+    }
+
+    let has_tangents = !mesh.tangents.is_empty();
+    if settings.generate_tangents && (!has_tangents || settings.generate_normals) {
+        tangents::generate_tangents(&mut mesh)?;
+    }
+
+    if settings.optimize_vertex_cache {
+        vertex_cache::optimize(&mut mesh)?;
+    }
+
+    generate_lods(&mut mesh, settings)?;
+
+    Ok(mesh)
+}
+
+/// Replaces the levels of detail of the mesh with a chain of simplified ones. Each level is
+/// simplified from the level before, see `simplify::simplify()`. A level, which isn't
+/// smaller than the one before, ends the chain. Afterwards, the indices are compacted or u32,
+/// depending on the settings.
+pub fn generate_lods(mesh: &mut MeshPrototype, settings: &MeshProcessorSettings) -> RisResult<()> {
+    let indices = triangle_list(mesh)?;
+    let vertex_count = mesh.vertices.len();
+
+    let mut lods = Vec::with_capacity(settings.lod_count);
+    let mut previous = indices.clone();
+    for _ in 0..settings.lod_count {
+        let target_index_count = (previous.len() / 3) as f32 * settings.lod_ratio;
+        let target_index_count = target_index_count as usize * 3;
+        let simplified = simplify::simplify(mesh, &previous, target_index_count)?;
+        if simplified.is_empty() || simplified.len() >= previous.len() {
+            break;
+        }
+
+        let lod = if settings.optimize_vertex_cache {
+            vertex_cache::optimize_triangles(&simplified, vertex_count)
+        } else {
+            simplified.clone()
+        };
+
+        ris_log::trace!(
+            "generated lod {} with {} of {} triangles",
+            lods.len() + 1,
+            simplified.len() / 3,
+            indices.len() / 3,
+        );
+
+        lods.push(lod);
+        previous = simplified;
+    }
+
+    if settings.compact_indices {
+        mesh.indices = Indices::with_smallest_type(indices, vertex_count)?;
+        mesh.lods = lods
+            .into_iter()
+            .map(|x| Indices::with_smallest_type(x, vertex_count))
+            .collect::<RisResult<Vec<_>>>()?;
+    } else {
+        mesh.indices = Indices::U32(indices);
+        mesh.lods = lods.into_iter().map(Indices::U32).collect();
+    }
+
+    Ok(())
+}
+
+/// Runs `process()` on a `ris_mesh` file, which was imported already, and overwrites it. This
+/// allows meshes without a source file to get levels of detail. The settings are loaded from the
+/// sidecar of the `ris_mesh` file.
+///
+/// Flat shaded meshes have a vertex per triangle corner, which can't be welded. To simplify them,
+/// their normals must be generated.
+pub fn process_file(path: impl AsRef<Path>) -> RisResult<()> {
+    let path = path.as_ref();
+    let settings = MeshProcessorSettings::load(path)?;
+
+    let bytes = std::fs::read(path)?;
+    let cpu_mesh = ris_mesh::deserialize(&bytes)?;
+    let mesh = MeshPrototype::try_from(cpu_mesh)?;
+    let mesh = process(mesh, &settings)?;
+
+    let cpu_mesh = CpuMesh::try_from(mesh)?;
+    let bytes = ris_mesh::serialize(&cpu_mesh)?;
+    std::fs::write(path, bytes)?;
+
+    Ok(())
+}
+
+/// Runs `process_file()` on every `ris_mesh` file in the directory and its subdirectories.
+/// Returns the paths of the changed files.
+pub fn process_directory(directory: impl AsRef<Path>) -> RisResult<Vec<PathBuf>> {
+    let mut directories = VecDeque::new();
+    directories.push_back(directory.as_ref().to_path_buf());
+
+    let mut changed = Vec::new();
+    while let Some(current) = directories.pop_front() {
+        let entries = std::fs::read_dir(&current)?;
+
+        for entry in entries {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            let entry_path = entry.path();
+
+            if metadata.is_dir() {
+                directories.push_back(entry_path);
+                continue;
+            }
+
+            let is_mesh = entry_path
+                .extension()
+                .is_some_and(|x| x.eq_ignore_ascii_case(ris_mesh::EXTENSION));
+            if !is_mesh {
+                continue;
+            }
+
+            ris_log::debug!("processing \"{}\"...", entry_path.display());
+            process_file(&entry_path)
+                .map_err(|e| ris_error::new!("\"{}\": {}", entry_path.display(), e))?;
+            changed.push(entry_path);
+        }
+    }
+
+    Ok(changed)
+}
+
+/// Returns the indices as u32. A mesh without indices draws its vertices in order.
+pub fn triangle_list(mesh: &MeshPrototype) -> RisResult<Vec<u32>> {
+    let vertex_count = mesh.vertices.len();
+    let indices = match mesh.indices {
+        Indices::None => (0..u32::try_from(vertex_count)?).collect::<Vec<_>>(),
+        _ => mesh
+            .indices
+            .usize_iter()
+            .map(u32::try_from)
+            .collect::<Result<Vec<_>, _>>()?,
+    };
+
+    ris_error::assert!(indices.len() % 3 == 0)?;
+    if let Some(&index) = indices.iter().find(|&&x| x as usize >= vertex_count) {
+        return ris_error::new_result!(
+            "index {} is out of range. vertex count: {}",
+            index,
+            vertex_count,
+        );
+    }
+
+    Ok(indices)
+}
+
+/// Replaces the vertices, such that the new vertex `i` is the old vertex `selection[i]`. The
+/// indices are not changed.
+pub fn select_vertices(mesh: &mut MeshPrototype, selection: &[usize]) -> RisResult<()> {
+    mesh.vertices = select(&mesh.vertices, selection)?;
+    mesh.normals = select(&mesh.normals, selection)?;
+    mesh.uvs = select(&mesh.uvs, selection)?;
+    mesh.joints = select(&mesh.joints, selection)?;
+    mesh.weights = select(&mesh.weights, selection)?;
+    mesh.tangents = select(&mesh.tangents, selection)?;
+    Ok(())
+}
+
+/// Attributes, which the mesh does not have, stay empty.
+fn select<T: Copy>(values: &[T], selection: &[usize]) -> RisResult<Vec<T>> {
+    if values.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    selection
+        .iter()
+        .map(|&i| values.get(i).copied().into_ris_error())
+        .collect()
+}
+
+fn parse<T: std::str::FromStr>(value: &str, line: usize) -> RisResult<T> {
+    match value.parse() {
+        Ok(value) => Ok(value),
+        Err(_) => ris_yaml::error_on_line(line, &format!("invalid value \"{}\"", value)),
+    }
+}
@@ -0,0 +1,6 @@
+pub mod mesh_processor;
+pub mod normals;
+pub mod simplify;
+pub mod tangents;
+pub mod vertex_cache;
+pub mod weld;
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use ris_asset_data::mesh::MeshPrototype;
+use ris_error::prelude::*;
+use ris_math::vector::Vec3;
+
+use super::mesh_processor;
+
+/// Replaces the normals with smooth normals. Unlike `MeshPrototype::generate_normals()`,
+/// vertices at the same position share their normal, even if their other attributes differ,
+/// like the uvs along a seam. Each triangle contributes by the angle of its corner, such that
+/// the normals don't depend on how a surface is triangulated. Vertices, which are not part of a
+/// triangle, point up.
+pub fn smooth_normals(mesh: &mut MeshPrototype) -> RisResult<()> {
+    let indices = mesh_processor::triangle_list(mesh)?;
+
+    // vertices at the same position share the first of them
+    let mut first_at_position = HashMap::new();
+    let shared = mesh
+        .vertices
+        .iter()
+        .enumerate()
+        .map(|(i, v)| {
+            let key = [v.0, v.1, v.2].map(|x| (x + 0.0).to_bits());
+            *first_at_position.entry(key).or_insert(i)
+        })
+        .collect::<Vec<_>>();
+
+    let mut normals = vec![Vec3::init(0.0); mesh.vertices.len()];
+    for triangle in indices.chunks_exact(3) {
+        let corners = [
+            triangle[0] as usize,
+            triangle[1] as usize,
+            triangle[2] as usize,
+        ];
+        let p = corners.map(|i| mesh.vertices[i]);
+
+        let face_normal = (p[1] - p[0]).cross(p[2] - p[0]);
+        if face_normal.length_squared() == 0.0 {
+            continue;
+        }
+        let face_normal = face_normal.normalize();
+
+        for i in 0..3 {
+            let angle = corner_angle(p[i], p[(i + 1) % 3], p[(i + 2) % 3]);
+            normals[shared[corners[i]]] += face_normal * angle;
+        }
+    }
+
+    mesh.normals = shared
+        .iter()
+        .map(|&i| {
+            let normal = normals[i];
+            if normal.length_squared() > 0.0 {
+                normal.normalize()
+            } else {
+                Vec3::up()
+            }
+        })
+        .collect();
+
+    Ok(())
+}
+
+/// The angle at `corner` of the triangle.
+pub fn corner_angle(corner: Vec3, a: Vec3, b: Vec3) -> f32 {
+    let ea = a - corner;
+    let eb = b - corner;
+    let length = ea.length() * eb.length();
+    if length == 0.0 {
+        return 0.0;
+    }
+
+    f32::acos((ea.dot(eb) / length).clamp(-1.0, 1.0))
+}
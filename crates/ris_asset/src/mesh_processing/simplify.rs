@@ -0,0 +1,525 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::collections::HashMap;
+
+use ris_asset_data::mesh::MeshPrototype;
+use ris_error::prelude::*;
+use ris_math::vector::Vec3;
+
+/// Border edges are kept in place by planes, which are perpendicular to their triangle. These
+/// planes weigh more than the planes of the triangles, such that moving a border costs more than
+/// moving the surface.
+const BORDER_WEIGHT: f64 = 10.0;
+
+/// A position on a border is only removed, if the cosine of the angle between its two border
+/// edges is smaller than this, i.e. if the border is almost straight. Otherwise it is a corner.
+const MAX_BORDER_COS: f32 = -0.95;
+
+/// A collapse is rejected, if the cosine of the angle between the old and the new normal of a
+/// remaining triangle is smaller than this.
+const MIN_NORMAL_DOT: f32 = 0.25;
+
+/// A symmetric 4x4 matrix, which sums the squared distances to a set of planes.
+#[derive(Debug, Clone, Copy, Default)]
+struct Quadric([f64; 10]);
+
+impl Quadric {
+    fn from_plane(normal: Vec3, point: Vec3, weight: f64) -> Self {
+        let a = normal.x() as f64;
+        let b = normal.y() as f64;
+        let c = normal.z() as f64;
+        let d = -(a * point.x() as f64 + b * point.y() as f64 + c * point.z() as f64);
+
+        Self([
+            a * a * weight,
+            a * b * weight,
+            a * c * weight,
+            a * d * weight,
+            b * b * weight,
+            b * c * weight,
+            b * d * weight,
+            c * c * weight,
+            c * d * weight,
+            d * d * weight,
+        ])
+    }
+
+    fn add(&mut self, other: &Self) {
+        for (lhs, rhs) in self.0.iter_mut().zip(other.0.iter()) {
+            *lhs += rhs;
+        }
+    }
+
+    fn error(&self, point: Vec3) -> f64 {
+        let [aa, ab, ac, ad, bb, bc, bd, cc, cd, dd] = self.0;
+        let x = point.x() as f64;
+        let y = point.y() as f64;
+        let z = point.z() as f64;
+
+        aa * x * x
+            + 2.0 * ab * x * y
+            + 2.0 * ac * x * z
+            + 2.0 * ad * x
+            + bb * y * y
+            + 2.0 * bc * y * z
+            + 2.0 * bd * y
+            + cc * z * z
+            + 2.0 * cd * z
+            + dd
+    }
+}
+
+/// Collapses the edge from the position `from` to the position `to`, such that every vertex at
+/// `from` is replaced by a vertex at `to`.
+#[derive(Debug)]
+struct Collapse {
+    cost: f64,
+    from: usize,
+    to: usize,
+}
+
+impl PartialEq for Collapse {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Collapse {}
+
+impl PartialOrd for Collapse {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Collapse {
+    // reversed, such that the binary heap pops the cheapest collapse first
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .cost
+            .total_cmp(&self.cost)
+            .then_with(|| other.from.cmp(&self.from))
+            .then_with(|| other.to.cmp(&self.to))
+    }
+}
+
+/// Simplifies the triangle list `indices` of `mesh` to at most `target_index_count` indices,
+/// using the quadric error metric by Michael Garland and Paul S. Heckbert. Edges collapse into
+/// one of their positions, thus the result indexes the vertices of the mesh and no vertex is
+/// created or changed.
+///
+/// Vertices with the same position are collapsed together. A position with more than one vertex
+/// lies on a seam, where an attribute like the uv is discontinuous. Every vertex is replaced by
+/// the vertex of the target, which it shares a triangle with, thus seams only collapse along
+/// themselves. Borders only collapse along themselves as well, and their corners are never
+/// removed. Collapses, which would flip a triangle, create a non-manifold edge or duplicate a
+/// triangle, are rejected. If no collapse
+/// is left, the result has more than `target_index_count` indices.
+pub fn simplify(
+    mesh: &MeshPrototype,
+    indices: &[u32],
+    target_index_count: usize,
+) -> RisResult<Vec<u32>> {
+    let vertex_count = mesh.vertices.len();
+    ris_error::assert!(indices.len().is_multiple_of(3))?;
+    if let Some(&index) = indices.iter().find(|&&x| x as usize >= vertex_count) {
+        return ris_error::new_result!(
+            "index {} is out of range. vertex count: {}",
+            index,
+            vertex_count,
+        );
+    }
+
+    // the vertices of a position are its wedges
+    let mut position_lookup = HashMap::new();
+    let mut position_ids = Vec::with_capacity(vertex_count);
+    let mut wedges = Vec::<Vec<usize>>::new();
+    for (i, vertex) in mesh.vertices.iter().enumerate() {
+        let key = [vertex.x(), vertex.y(), vertex.z()].map(f32::to_bits);
+        let position_id = *position_lookup.entry(key).or_insert_with(|| {
+            wedges.push(Vec::new());
+            wedges.len() - 1
+        });
+        wedges[position_id].push(i);
+        position_ids.push(position_id);
+    }
+    let position_count = wedges.len();
+    let position = |position_id: usize| mesh.vertices[wedges[position_id][0]];
+
+    let mut triangles = indices
+        .chunks_exact(3)
+        .map(|x| [x[0] as usize, x[1] as usize, x[2] as usize])
+        .filter(|x| {
+            let [a, b, c] = x.map(|x| position_ids[x]);
+            a != b && b != c && c != a
+        })
+        .collect::<Vec<_>>();
+
+    // the quadric of a position sums the planes of its triangles, weighted by their area
+    let mut quadrics = vec![Quadric::default(); position_count];
+    let mut position_edge_counts = HashMap::<(usize, usize), usize>::new();
+    for triangle in triangles.iter() {
+        let normal = triangle_normal(mesh, *triangle);
+        let double_area = normal.length();
+        if double_area > 0.0 {
+            let plane = Quadric::from_plane(
+                normal / double_area,
+                mesh.vertices[triangle[0]],
+                0.5 * double_area as f64,
+            );
+            for &corner in triangle.iter() {
+                quadrics[position_ids[corner]].add(&plane);
+            }
+        }
+
+        for (a, b) in edges(*triangle) {
+            let key = edge_key(position_ids[a], position_ids[b]);
+            *position_edge_counts.entry(key).or_default() += 1;
+        }
+    }
+
+    // borders are edges with a single triangle
+    let mut border_neighbours = vec![Vec::new(); position_count];
+    for triangle in triangles.iter() {
+        let normal = triangle_normal(mesh, *triangle);
+
+        for (a, b) in edges(*triangle) {
+            let pa = position_ids[a];
+            let pb = position_ids[b];
+            if position_edge_counts.get(&edge_key(pa, pb)) != Some(&1) {
+                continue;
+            }
+
+            border_neighbours[pa].push(pb);
+            border_neighbours[pb].push(pa);
+
+            // borders are kept in place by planes, which are perpendicular to the triangle
+            let edge = mesh.vertices[b] - mesh.vertices[a];
+            let border_normal = edge.cross(normal);
+            if normal.length_squared() > 0.0 && border_normal.length_squared() > 0.0 {
+                let weight = BORDER_WEIGHT * edge.length_squared() as f64;
+                let plane =
+                    Quadric::from_plane(border_normal.normalize(), mesh.vertices[a], weight);
+                quadrics[pa].add(&plane);
+                quadrics[pb].add(&plane);
+            }
+        }
+    }
+
+    let is_straight_border = |position_id: usize, neighbours: &mut Vec<usize>| {
+        neighbours.sort();
+        neighbours.dedup();
+        let &[a, b] = neighbours.as_slice() else {
+            return false;
+        };
+
+        let to_a = position(a) - position(position_id);
+        let to_b = position(b) - position(position_id);
+        if to_a.length_squared() == 0.0 || to_b.length_squared() == 0.0 {
+            return false;
+        }
+
+        to_a.normalize().dot(to_b.normalize()) < MAX_BORDER_COS
+    };
+
+    // where a seam meets a border, it can't collapse along both
+    let mut is_locked = vec![false; position_count];
+    let mut is_border = vec![false; position_count];
+    for i in 0..position_count {
+        is_border[i] = !border_neighbours[i].is_empty();
+        is_locked[i] = is_border[i]
+            && (wedges[i].len() > 1 || !is_straight_border(i, &mut border_neighbours[i]));
+    }
+
+    let mut vertex_triangles = vec![Vec::new(); vertex_count];
+    for (i, triangle) in triangles.iter().enumerate() {
+        for &corner in triangle.iter() {
+            vertex_triangles[corner].push(i);
+        }
+    }
+
+    let collapse_cost = |quadrics: &[Quadric], from: usize, to: usize| {
+        let mut quadric = quadrics[from];
+        quadric.add(&quadrics[to]);
+        quadric.error(position(to))
+    };
+
+    let mut heap = BinaryHeap::new();
+    for (a, b) in position_edge_counts.keys().copied() {
+        for (from, to) in [(a, b), (b, a)] {
+            if !is_locked[from] {
+                let cost = collapse_cost(&quadrics, from, to);
+                heap.push(Collapse { cost, from, to });
+            }
+        }
+    }
+
+    let mut is_alive = vec![true; triangles.len()];
+    let mut is_removed = vec![false; position_count];
+    let mut alive_count = triangles.len();
+
+    while alive_count * 3 > target_index_count {
+        let Some(collapse) = heap.pop() else {
+            break;
+        };
+        let Collapse { from, to, .. } = collapse;
+
+        if is_removed[from] || is_removed[to] {
+            continue;
+        }
+
+        // the quadrics only grow. if the cost changed, the collapse is queued again
+        let cost = collapse_cost(&quadrics, from, to);
+        if cost > collapse.cost {
+            heap.push(Collapse { cost, from, to });
+            continue;
+        }
+
+        for &wedge in wedges[from].iter().chain(wedges[to].iter()) {
+            vertex_triangles[wedge].retain(|&x| is_alive[x]);
+        }
+
+        let from_triangles = wedges[from]
+            .iter()
+            .flat_map(|&x| vertex_triangles[x].iter().copied())
+            .collect::<Vec<_>>();
+        let to_triangles = wedges[to]
+            .iter()
+            .flat_map(|&x| vertex_triangles[x].iter().copied())
+            .collect::<Vec<_>>();
+        let contains_to = |triangle: [usize; 3]| triangle.iter().any(|&x| position_ids[x] == to);
+
+        let shared_count = from_triangles
+            .iter()
+            .filter(|&&x| contains_to(triangles[x]))
+            .count();
+        if shared_count == 0 {
+            // the edge doesn't exist anymore
+            continue;
+        }
+
+        if is_border[from] && shared_count != 1 {
+            // the collapse would move the border inwards
+            continue;
+        }
+
+        // every wedge of `from` must be replaced by the single wedge of `to`, which it shares a
+        // triangle with. otherwise the collapse would cross a seam
+        let Some(replacements) = wedge_replacements(
+            &triangles,
+            &vertex_triangles,
+            &position_ids,
+            &wedges[from],
+            to,
+        ) else {
+            continue;
+        };
+        let replace = |vertex: usize| {
+            replacements
+                .iter()
+                .find(|&&(wedge, _)| wedge == vertex)
+                .map(|&(_, replacement)| replacement)
+                .unwrap_or(vertex)
+        };
+
+        let triangle_positions = |triangle: usize| triangles[triangle].map(|x| position_ids[x]);
+        if !is_manifold_collapse(
+            &from_triangles,
+            &to_triangles,
+            triangle_positions,
+            from,
+            to,
+            shared_count,
+        ) || duplicates_triangle(&from_triangles, &to_triangles, triangle_positions, from, to)
+        {
+            continue;
+        }
+
+        let flips = from_triangles
+            .iter()
+            .filter(|&&x| !contains_to(triangles[x]))
+            .any(|&x| flips_triangle(mesh, triangles[x], triangles[x].map(replace)));
+        if flips {
+            continue;
+        }
+
+        // collapse
+        is_removed[from] = true;
+        let quadric = quadrics[from];
+        quadrics[to].add(&quadric);
+
+        for &(wedge, replacement) in replacements.iter() {
+            let wedge_triangles = std::mem::take(&mut vertex_triangles[wedge]);
+            for triangle in wedge_triangles {
+                if !is_alive[triangle] {
+                    continue;
+                }
+
+                if contains_to(triangles[triangle]) {
+                    is_alive[triangle] = false;
+                    alive_count -= 1;
+                    continue;
+                }
+
+                for corner in triangles[triangle].iter_mut() {
+                    if *corner == wedge {
+                        *corner = replacement;
+                    }
+                }
+                vertex_triangles[replacement].push(triangle);
+            }
+        }
+
+        // the edges around `to` changed
+        let mut neighbours = wedges[to]
+            .iter()
+            .flat_map(|&x| vertex_triangles[x].iter())
+            .filter(|&&x| is_alive[x])
+            .flat_map(|&x| triangles[x].map(|x| position_ids[x]))
+            .filter(|&x| x != to)
+            .collect::<Vec<_>>();
+        neighbours.sort();
+        neighbours.dedup();
+        for other in neighbours {
+            for (from, to) in [(other, to), (to, other)] {
+                if !is_locked[from] {
+                    let cost = collapse_cost(&quadrics, from, to);
+                    heap.push(Collapse { cost, from, to });
+                }
+            }
+        }
+    }
+
+    let mut simplified = Vec::with_capacity(alive_count * 3);
+    for (triangle, _) in triangles
+        .iter()
+        .zip(is_alive.iter())
+        .filter(|(_, &is_alive)| is_alive)
+    {
+        for &corner in triangle.iter() {
+            simplified.push(u32::try_from(corner)?);
+        }
+    }
+
+    Ok(simplified)
+}
+
+/// Not normalized. The length is twice the area of the triangle.
+fn triangle_normal(mesh: &MeshPrototype, triangle: [usize; 3]) -> Vec3 {
+    let [a, b, c] = triangle.map(|x| mesh.vertices[x]);
+    (b - a).cross(c - a)
+}
+
+fn edges(triangle: [usize; 3]) -> [(usize, usize); 3] {
+    let [a, b, c] = triangle;
+    [(a, b), (b, c), (c, a)]
+}
+
+fn edge_key(a: usize, b: usize) -> (usize, usize) {
+    (usize::min(a, b), usize::max(a, b))
+}
+
+/// Pairs every wedge of `from`, which is still in use, with the wedge of the position `to`,
+/// which it shares its triangles with. Returns `None` if a wedge shares no triangle with `to`,
+/// or triangles with more than one of its wedges.
+fn wedge_replacements(
+    triangles: &[[usize; 3]],
+    vertex_triangles: &[Vec<usize>],
+    position_ids: &[usize],
+    from_wedges: &[usize],
+    to: usize,
+) -> Option<Vec<(usize, usize)>> {
+    let mut replacements = Vec::with_capacity(from_wedges.len());
+    for &wedge in from_wedges.iter() {
+        if vertex_triangles[wedge].is_empty() {
+            continue;
+        }
+
+        let mut replacement = None;
+        for &triangle in vertex_triangles[wedge].iter() {
+            for &corner in triangles[triangle].iter() {
+                if position_ids[corner] != to {
+                    continue;
+                }
+
+                match replacement {
+                    None => replacement = Some(corner),
+                    Some(existing) if existing != corner => return None,
+                    Some(_) => (),
+                }
+            }
+        }
+
+        replacements.push((wedge, replacement?));
+    }
+
+    Some(replacements)
+}
+
+/// The positions, which are adjacent to both `from` and `to`, must be exactly the opposite
+/// positions of their shared triangles. Otherwise the collapse would merge two edges.
+fn is_manifold_collapse(
+    from_triangles: &[usize],
+    to_triangles: &[usize],
+    triangle_positions: impl Fn(usize) -> [usize; 3],
+    from: usize,
+    to: usize,
+    shared_count: usize,
+) -> bool {
+    let neighbours = |triangles: &[usize]| {
+        let mut neighbours = triangles
+            .iter()
+            .flat_map(|&x| triangle_positions(x))
+            .filter(|&x| x != from && x != to)
+            .collect::<Vec<_>>();
+        neighbours.sort();
+        neighbours.dedup();
+        neighbours
+    };
+
+    let to_neighbours = neighbours(to_triangles);
+    let common_count = neighbours(from_triangles)
+        .iter()
+        .filter(|x| to_neighbours.binary_search(x).is_ok())
+        .count();
+
+    common_count == shared_count
+}
+
+/// Whether a triangle of `from`, which remains after the collapse, would have the same
+/// positions as a triangle of `to`.
+fn duplicates_triangle(
+    from_triangles: &[usize],
+    to_triangles: &[usize],
+    triangle_positions: impl Fn(usize) -> [usize; 3],
+    from: usize,
+    to: usize,
+) -> bool {
+    let sorted = |triangle: usize| {
+        let mut positions = triangle_positions(triangle).map(|x| if x == from { to } else { x });
+        positions.sort();
+        positions
+    };
+
+    let to_triangles = to_triangles.iter().map(|&x| sorted(x)).collect::<Vec<_>>();
+
+    from_triangles
+        .iter()
+        .filter(|&&x| !triangle_positions(x).contains(&to))
+        .any(|&x| to_triangles.contains(&sorted(x)))
+}
+
+/// Whether the triangle would turn too far or degenerate.
+fn flips_triangle(mesh: &MeshPrototype, old: [usize; 3], new: [usize; 3]) -> bool {
+    let old_normal = triangle_normal(mesh, old);
+    let new_normal = triangle_normal(mesh, new);
+    if new_normal.length_squared() == 0.0 {
+        return true;
+    }
+
+    if old_normal.length_squared() == 0.0 {
+        return false;
+    }
+
+    old_normal.normalize().dot(new_normal.normalize()) < MIN_NORMAL_DOT
+}
@@ -0,0 +1,141 @@
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_error::prelude::*;
+use ris_math::vector::Vec3;
+use ris_math::vector::Vec4;
+
+use super::mesh_processor;
+use super::normals;
+
+/// Replaces the tangents, similar to how MikkTSpace generates them. The tangent of a triangle
+/// points along its u axis. The tangent of a vertex is the sum of the tangents of its
+/// triangles, projected onto the plane of its normal and weighted by the angle of their corner.
+///
+/// Triangles with mirrored uvs have a bitangent of the opposite handedness. A vertex, which is
+/// shared by triangles of both handednesses, is split. Triangles without uv area don't
+/// contribute. Vertices, which end up without a tangent, get an arbitrary one.
+pub fn generate_tangents(mesh: &mut MeshPrototype) -> RisResult<()> {
+    let vertex_count = mesh.vertices.len();
+    ris_error::assert!(mesh.normals.len() == vertex_count)?;
+    ris_error::assert!(mesh.uvs.len() == vertex_count)?;
+
+    let mut indices = mesh_processor::triangle_list(mesh)?;
+
+    // the tangent and the handedness of each triangle
+    let mut triangle_tangents = Vec::with_capacity(indices.len() / 3);
+    for triangle in indices.chunks_exact(3) {
+        let corners = triangle.iter().map(|&x| x as usize).collect::<Vec<_>>();
+        let p = [0, 1, 2].map(|i| mesh.vertices[corners[i]]);
+        let uv = [0, 1, 2].map(|i| mesh.uvs[corners[i]]);
+
+        let e1 = p[1] - p[0];
+        let e2 = p[2] - p[0];
+        let (du1, dv1) = (uv[1].0 - uv[0].0, uv[1].1 - uv[0].1);
+        let (du2, dv2) = (uv[2].0 - uv[0].0, uv[2].1 - uv[0].1);
+
+        // the sign of the uv area is the handedness
+        let r = du1 * dv2 - du2 * dv1;
+        if r == 0.0 || !r.is_finite() {
+            triangle_tangents.push(None);
+            continue;
+        }
+
+        let tangent = (e1 * dv2 - e2 * dv1) / r;
+        let handedness = if r < 0.0 { -1.0 } else { 1.0 };
+        triangle_tangents.push(Some((tangent, handedness)));
+    }
+
+    // split vertices, which are shared by triangles of both handednesses
+    let mut handedness = vec![None; vertex_count];
+    let mut split = vec![None; vertex_count];
+    let mut selection = (0..vertex_count).collect::<Vec<_>>();
+    for (triangle, triangle_tangent) in indices.chunks_exact_mut(3).zip(triangle_tangents.iter()) {
+        let Some((_, triangle_handedness)) = *triangle_tangent else {
+            continue;
+        };
+
+        for index in triangle.iter_mut() {
+            let i = *index as usize;
+            match handedness[i] {
+                None => handedness[i] = Some(triangle_handedness),
+                Some(x) if x == triangle_handedness => (),
+                Some(_) => {
+                    let split_index = match split[i] {
+                        Some(split_index) => split_index,
+                        None => {
+                            let split_index = selection.len();
+                            selection.push(i);
+                            handedness.push(Some(triangle_handedness));
+                            split[i] = Some(split_index);
+                            split_index
+                        }
+                    };
+                    *index = u32::try_from(split_index)?;
+                }
+            }
+        }
+    }
+
+    if selection.len() > vertex_count {
+        ris_log::trace!(
+            "split {} vertices with mirrored uvs",
+            selection.len() - vertex_count,
+        );
+        mesh_processor::select_vertices(mesh, &selection)?;
+    }
+
+    // accumulate
+    let mut sums = vec![Vec3::init(0.0); mesh.vertices.len()];
+    for (triangle, triangle_tangent) in indices.chunks_exact(3).zip(triangle_tangents.iter()) {
+        let Some((tangent, _)) = *triangle_tangent else {
+            continue;
+        };
+
+        let corners = triangle.iter().map(|&x| x as usize).collect::<Vec<_>>();
+        let p = [0, 1, 2].map(|i| mesh.vertices[corners[i]]);
+        for i in 0..3 {
+            let normal = mesh.normals[corners[i]];
+            let projected = tangent - normal * normal.dot(tangent);
+            if projected.length_squared() == 0.0 {
+                continue;
+            }
+
+            let angle = normals::corner_angle(p[i], p[(i + 1) % 3], p[(i + 2) % 3]);
+            sums[corners[i]] += projected.normalize() * angle;
+        }
+    }
+
+    mesh.tangents = sums
+        .into_iter()
+        .zip(mesh.normals.iter())
+        .zip(handedness.iter())
+        .map(|((sum, &normal), handedness)| {
+            let tangent = if sum.length_squared() > 0.0 {
+                sum.normalize()
+            } else {
+                perpendicular(normal)
+            };
+            let w = handedness.unwrap_or(1.0);
+            Vec4(tangent.0, tangent.1, tangent.2, w)
+        })
+        .collect();
+    mesh.indices = Indices::U32(indices);
+
+    Ok(())
+}
+
+/// An arbitrary unit vector, which is perpendicular to `normal`.
+fn perpendicular(normal: Vec3) -> Vec3 {
+    let axis = if normal.0.abs() < 0.9 {
+        Vec3::right()
+    } else {
+        Vec3::forward()
+    };
+
+    let perpendicular = normal.cross(axis);
+    if perpendicular.length_squared() > 0.0 {
+        perpendicular.normalize()
+    } else {
+        Vec3::right()
+    }
+}
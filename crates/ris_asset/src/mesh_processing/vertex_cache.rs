@@ -0,0 +1,215 @@
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_error::prelude::*;
+
+use super::mesh_processor;
+
+/// The size of the simulated cache. Real caches are smaller, but a larger one doesn't hurt them.
+pub const CACHE_SIZE: usize = 32;
+
+const CACHE_DECAY_POWER: f32 = 1.5;
+const LAST_TRIANGLE_SCORE: f32 = 0.75;
+const VALENCE_BOOST_SCALE: f32 = 2.0;
+const VALENCE_BOOST_POWER: f32 = 0.5;
+
+/// Reorders the triangles, such that a post-transform vertex cache hits more often, using the
+/// algorithm by Tom Forsyth. Afterwards, the vertices are reordered in the order in which the
+/// triangles use them, which helps the pre-transform cache. Vertices, which are not used by any
+/// triangle, are removed.
+pub fn optimize(mesh: &mut MeshPrototype) -> RisResult<()> {
+    let indices = mesh_processor::triangle_list(mesh)?;
+    let vertex_count = mesh.vertices.len();
+    let mut optimized = optimize_triangles(&indices, vertex_count);
+
+    // reorder the vertices by their first use
+    let mut new_indices = vec![None; vertex_count];
+    let mut selection = Vec::with_capacity(vertex_count);
+    for index in optimized.iter_mut() {
+        let old = *index as usize;
+        let new = match new_indices[old] {
+            Some(new) => new,
+            None => {
+                let new = u32::try_from(selection.len())?;
+                selection.push(old);
+                new_indices[old] = Some(new);
+                new
+            }
+        };
+        *index = new;
+    }
+
+    ris_log::trace!(
+        "optimized vertex cache. acmr before: {} after: {}",
+        average_cache_miss_ratio(&indices, CACHE_SIZE),
+        average_cache_miss_ratio(&optimized, CACHE_SIZE),
+    );
+
+    mesh_processor::select_vertices(mesh, &selection)?;
+    mesh.indices = Indices::U32(optimized);
+    Ok(())
+}
+
+/// Reorders the triangles like `optimize()`, but keeps the vertices. The indices must be a
+/// triangle list, which does not index outside of `vertex_count`.
+pub fn optimize_triangles(indices: &[u32], vertex_count: usize) -> Vec<u32> {
+    let triangle_count = indices.len() / 3;
+
+    // the triangles of each vertex
+    let mut offsets = vec![0; vertex_count + 1];
+    for &index in indices.iter() {
+        offsets[index as usize + 1] += 1;
+    }
+    for i in 0..vertex_count {
+        offsets[i + 1] += offsets[i];
+    }
+    let mut vertex_triangles = vec![0; indices.len()];
+    let mut fill = offsets.clone();
+    for (i, &index) in indices.iter().enumerate() {
+        let index = index as usize;
+        vertex_triangles[fill[index]] = i / 3;
+        fill[index] += 1;
+    }
+
+    let mut remaining = (0..vertex_count)
+        .map(|i| offsets[i + 1] - offsets[i])
+        .collect::<Vec<_>>();
+    let mut cache_position = vec![None; vertex_count];
+    let mut vertex_scores = (0..vertex_count)
+        .map(|i| vertex_score(None, remaining[i]))
+        .collect::<Vec<_>>();
+    let mut triangle_scores = indices
+        .chunks_exact(3)
+        .map(|x| x.iter().map(|&i| vertex_scores[i as usize]).sum::<f32>())
+        .collect::<Vec<_>>();
+    let mut is_added = vec![false; triangle_count];
+
+    let mut cache = Vec::<usize>::with_capacity(CACHE_SIZE + 3);
+    let mut optimized = Vec::with_capacity(indices.len());
+    let mut next_unadded = 0;
+    let mut best = best_triangle(&triangle_scores, &is_added, 0..triangle_count);
+
+    while let Some(triangle) = best {
+        is_added[triangle] = true;
+        let corners = &indices[triangle * 3..triangle * 3 + 3];
+        optimized.extend_from_slice(corners);
+
+        // the vertices of the triangle move to the front of the cache
+        let mut touched = Vec::with_capacity(CACHE_SIZE + 3);
+        for &corner in corners.iter() {
+            let corner = corner as usize;
+            remaining[corner] -= 1;
+            cache.retain(|&x| x != corner);
+        }
+        for &corner in corners.iter().rev() {
+            cache.insert(0, corner as usize);
+        }
+        for &evicted in cache.iter().skip(CACHE_SIZE) {
+            cache_position[evicted] = None;
+            touched.push(evicted);
+        }
+        cache.truncate(CACHE_SIZE);
+        for (position, &vertex) in cache.iter().enumerate() {
+            cache_position[vertex] = Some(position);
+            touched.push(vertex);
+        }
+
+        // update the scores of the touched vertices and their triangles, and choose the best
+        // triangle among them
+        for &vertex in touched.iter() {
+            let score = vertex_score(cache_position[vertex], remaining[vertex]);
+            let delta = score - vertex_scores[vertex];
+            vertex_scores[vertex] = score;
+            for &t in vertex_triangles[offsets[vertex]..offsets[vertex + 1]].iter() {
+                triangle_scores[t] += delta;
+            }
+        }
+
+        let candidates = cache
+            .iter()
+            .flat_map(|&v| vertex_triangles[offsets[v]..offsets[v + 1]].iter().copied());
+        best = best_triangle(&triangle_scores, &is_added, candidates);
+
+        // nothing in the cache is left, continue with the next triangle in order
+        if best.is_none() {
+            while next_unadded < triangle_count && is_added[next_unadded] {
+                next_unadded += 1;
+            }
+            if next_unadded < triangle_count {
+                best = Some(next_unadded);
+            }
+        }
+    }
+
+    optimized
+}
+
+/// The average number of vertices, which miss a FIFO cache of `cache_size`, per triangle. The
+/// best possible value is about 0.5, the worst is 3.
+pub fn average_cache_miss_ratio(indices: &[u32], cache_size: usize) -> f32 {
+    let triangle_count = indices.len() / 3;
+    if triangle_count == 0 {
+        return 0.0;
+    }
+
+    let mut cache = std::collections::VecDeque::with_capacity(cache_size);
+    let mut misses = 0;
+    for &index in indices.iter() {
+        if cache.contains(&index) {
+            continue;
+        }
+
+        misses += 1;
+        cache.push_back(index);
+        if cache.len() > cache_size {
+            cache.pop_front();
+        }
+    }
+
+    misses as f32 / triangle_count as f32
+}
+
+fn best_triangle(
+    triangle_scores: &[f32],
+    is_added: &[bool],
+    candidates: impl Iterator<Item = usize>,
+) -> Option<usize> {
+    let mut best = None;
+    let mut best_score = f32::MIN;
+    for triangle in candidates {
+        if is_added[triangle] {
+            continue;
+        }
+
+        let score = triangle_scores[triangle];
+        if score > best_score {
+            best = Some(triangle);
+            best_score = score;
+        }
+    }
+
+    best
+}
+
+fn vertex_score(cache_position: Option<usize>, remaining_triangles: usize) -> f32 {
+    if remaining_triangles == 0 {
+        return -1.0;
+    }
+
+    let cache_score = match cache_position {
+        None => 0.0,
+        // the vertices of the last triangle get a fixed score, such that it doesn't matter in
+        // which order they were added
+        Some(position) if position < 3 => LAST_TRIANGLE_SCORE,
+        Some(position) => {
+            let scale = 1.0 / (CACHE_SIZE - 3) as f32;
+            let score = 1.0 - (position - 3) as f32 * scale;
+            score.powf(CACHE_DECAY_POWER)
+        }
+    };
+
+    // vertices with few remaining triangles are preferred, such that they leave the cache
+    let valence_boost =
+        VALENCE_BOOST_SCALE * (remaining_triangles as f32).powf(-VALENCE_BOOST_POWER);
+
+    cache_score + valence_boost
+}
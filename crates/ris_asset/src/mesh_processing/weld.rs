@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_error::prelude::*;
+
+use super::mesh_processor;
+
+/// Merges vertices, whose attributes are all equal. Attributes are compared after rounding
+/// them to multiples of `epsilon`, or exactly if `epsilon` is 0. The first of the merged
+/// vertices is kept. Vertices, which are not used by any triangle, are removed.
+pub fn weld(mesh: &mut MeshPrototype, epsilon: f32) -> RisResult<()> {
+    let indices = mesh_processor::triangle_list(mesh)?;
+
+    let mut welded_indices = vec![None; mesh.vertices.len()];
+    let mut keys = HashMap::new();
+    let mut selection = Vec::new();
+    let mut welded = Vec::with_capacity(indices.len());
+    for &index in indices.iter() {
+        let index = index as usize;
+        let welded_index = match welded_indices[index] {
+            Some(welded_index) => welded_index,
+            None => {
+                let key = vertex_key(mesh, index, epsilon);
+                let welded_index = *keys.entry(key).or_insert_with(|| {
+                    selection.push(index);
+                    selection.len() as u32 - 1
+                });
+                welded_indices[index] = Some(welded_index);
+                welded_index
+            }
+        };
+
+        welded.push(welded_index);
+    }
+
+    ris_log::trace!(
+        "welded {} vertices to {}",
+        mesh.vertices.len(),
+        selection.len(),
+    );
+
+    mesh_processor::select_vertices(mesh, &selection)?;
+    mesh.indices = Indices::U32(welded);
+    Ok(())
+}
+
+fn vertex_key(mesh: &MeshPrototype, index: usize, epsilon: f32) -> Vec<u64> {
+    let mut key = Vec::with_capacity(24);
+    let mut push = |value: f32| key.push(quantize(value, epsilon));
+
+    let vertex = mesh.vertices[index];
+    push(vertex.0);
+    push(vertex.1);
+    push(vertex.2);
+    if let Some(normal) = mesh.normals.get(index) {
+        push(normal.0);
+        push(normal.1);
+        push(normal.2);
+    }
+    if let Some(uv) = mesh.uvs.get(index) {
+        push(uv.0);
+        push(uv.1);
+    }
+    if let Some(tangent) = mesh.tangents.get(index) {
+        push(tangent.0);
+        push(tangent.1);
+        push(tangent.2);
+        push(tangent.3);
+    }
+    if let Some(weight) = mesh.weights.get(index) {
+        for &value in weight.iter() {
+            push(value);
+        }
+    }
+    if let Some(joint) = mesh.joints.get(index) {
+        key.extend(joint.iter().map(|&x| u64::from(x)));
+    }
+
+    key
+}
+
+fn quantize(value: f32, epsilon: f32) -> u64 {
+    if epsilon > 0.0 {
+        (value / epsilon).round() as i64 as u64
+    } else {
+        // adding 0 turns -0 into 0, such that both are equal
+        (value + 0.0).to_bits() as u64
+    }
+}
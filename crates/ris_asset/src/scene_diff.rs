@@ -0,0 +1,1075 @@
+use std::any::TypeId;
+use std::collections::HashMap;
+use std::io::Cursor;
+use std::io::SeekFrom;
+use std::sync::Arc;
+
+use ris_asset_data::asset_id::AssetId;
+use ris_data::ecs::components::script_component::DynScriptComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::handle::DynComponentHandle;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::scene_stream::SceneReader;
+use ris_data::ecs::scene_stream::SceneValue;
+use ris_data::ecs::scene_stream::SceneWriter;
+use ris_error::Extensions;
+use ris_error::RisResult;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+
+use crate::assets::ris_scene;
+
+// the size of a serialized game object reference, see `ris_io::write_uint`
+const REFERENCE_SIZE: usize = 4;
+
+/// A structural difference between two scenes. Game objects are identified by their path, which
+/// consists of the names of their ancestors and their own name, separated by `/`. Siblings with
+/// the same name are told apart by an index, like `enemy[1]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Added {
+        path: String,
+    },
+    Removed {
+        path: String,
+    },
+    Moved {
+        from: String,
+        to: String,
+    },
+    PropertyChanged {
+        path: String,
+        property: &'static str,
+        old: String,
+        new: String,
+    },
+    ComponentAdded {
+        path: String,
+        component: String,
+    },
+    ComponentRemoved {
+        path: String,
+        component: String,
+    },
+    ComponentChanged {
+        path: String,
+        component: String,
+        details: Vec<String>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Conflict {
+    pub path: String,
+    pub reason: String,
+}
+
+pub enum MergeOutcome {
+    Merged(Vec<u8>),
+    Conflicted(Vec<Conflict>),
+}
+
+struct Document {
+    chunk: usize,
+    objects: Vec<Object>,
+}
+
+struct Object {
+    handle: GameObjectHandle,
+    name: String,
+    path: String,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    components: Vec<DynComponentHandle>,
+}
+
+#[derive(Clone, PartialEq)]
+struct ObjectState {
+    is_active: bool,
+    position: Vec3,
+    rotation: Quat,
+    scale: Vec3,
+    // identity of the parent
+    parent: Option<usize>,
+    sibling_index: usize,
+    components: Vec<(ComponentKey, ComponentState)>,
+}
+
+// the name of the component or script, and how many of the same name precede it
+type ComponentKey = (String, usize);
+
+#[derive(Clone, PartialEq)]
+struct ComponentState {
+    factory: usize,
+    // game object references are serialized as identities, such that components of different
+    // documents can be compared byte by byte
+    bytes: Vec<u8>,
+    asset_ids: Vec<AssetId>,
+    references: Vec<usize>,
+    reference_addrs: Vec<usize>,
+    fields: Vec<ComponentField>,
+}
+
+/// A field recorded by the `SceneWriter`.
+#[derive(Clone, PartialEq)]
+struct ComponentField {
+    name: String,
+    // game object references are compared by identity instead
+    value: Option<String>,
+    is_asset: bool,
+    addr: usize,
+    len: usize,
+}
+
+/// Game objects of multiple documents, matched by identity.
+struct Identities {
+    count: usize,
+    // per document, the identity of each game object
+    ids: Vec<Vec<usize>>,
+    // per document, the game object of each identity
+    objects: Vec<Vec<Option<usize>>>,
+}
+
+impl std::fmt::Display for Change {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Added { path } => write!(f, "+ {}", path),
+            Self::Removed { path } => write!(f, "- {}", path),
+            Self::Moved { from, to } => write!(f, "> {} -> {}", from, to),
+            Self::PropertyChanged {
+                path,
+                property,
+                old,
+                new,
+            } => write!(f, "~ {}: {} {} -> {}", path, property, old, new),
+            Self::ComponentAdded { path, component } => write!(f, "+ {}: {}", path, component),
+            Self::ComponentRemoved { path, component } => write!(f, "- {}: {}", path, component),
+            Self::ComponentChanged {
+                path,
+                component,
+                details,
+            } => write!(f, "~ {}: {} ({})", path, component, details.join("; ")),
+        }
+    }
+}
+
+impl std::fmt::Display for Conflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "! {}: {}", self.path, self.reason)
+    }
+}
+
+/// Decodes two `ris_scene` files and lists what changed from `old` to `new`. `registry` must
+/// contain the components and scripts the scenes were serialized with.
+pub fn diff(registry: Arc<Registry>, old: &[u8], new: &[u8]) -> RisResult<Vec<Change>> {
+    let scene = scratch_scene(registry, 2)?;
+    let documents = vec![load(&scene, old)?, load(&scene, new)?];
+    let identities = identify(&documents);
+    let states = capture_all(&scene, &documents, &identities)?;
+    let paths = paths(&documents, &identities);
+
+    let mut changes = Vec::new();
+    for id in 0..identities.count {
+        let old_index = identities.objects[0][id];
+        let new_index = identities.objects[1][id];
+
+        let (old_index, new_index) = match (old_index, new_index) {
+            (Some(old_index), Some(new_index)) => (old_index, new_index),
+            (Some(old_index), None) => {
+                let path = documents[0].objects[old_index].path.clone();
+                changes.push(Change::Removed { path });
+                continue;
+            }
+            (None, Some(new_index)) => {
+                let path = documents[1].objects[new_index].path.clone();
+                changes.push(Change::Added { path });
+                continue;
+            }
+            (None, None) => continue,
+        };
+
+        let old_path = &documents[0].objects[old_index].path;
+        let new_path = &documents[1].objects[new_index].path;
+        let old_state = &states[0][old_index];
+        let new_state = &states[1][new_index];
+
+        let moved = old_path != new_path || old_state.parent != new_state.parent;
+        if moved {
+            changes.push(Change::Moved {
+                from: old_path.clone(),
+                to: new_path.clone(),
+            });
+        }
+
+        let mut property_changed = |property: &'static str, old: String, new: String| {
+            if old != new {
+                changes.push(Change::PropertyChanged {
+                    path: new_path.clone(),
+                    property,
+                    old,
+                    new,
+                });
+            }
+        };
+
+        if !moved {
+            property_changed(
+                "sibling index",
+                old_state.sibling_index.to_string(),
+                new_state.sibling_index.to_string(),
+            );
+        }
+
+        property_changed(
+            "is active",
+            old_state.is_active.to_string(),
+            new_state.is_active.to_string(),
+        );
+        property_changed(
+            "position",
+            format!("{:?}", old_state.position),
+            format!("{:?}", new_state.position),
+        );
+        property_changed(
+            "rotation",
+            format!("{:?}", old_state.rotation),
+            format!("{:?}", new_state.rotation),
+        );
+        property_changed(
+            "scale",
+            format!("{:?}", old_state.scale),
+            format!("{:?}", new_state.scale),
+        );
+
+        for key in component_keys(&[Some(old_state), Some(new_state)]) {
+            let old_component = find_component(old_state, &key);
+            let new_component = find_component(new_state, &key);
+            let component = component_label(&key);
+            let path = new_path.clone();
+
+            match (old_component, new_component) {
+                (Some(_), None) => changes.push(Change::ComponentRemoved { path, component }),
+                (None, Some(_)) => changes.push(Change::ComponentAdded { path, component }),
+                (Some(old_component), Some(new_component)) => {
+                    if old_component == new_component {
+                        continue;
+                    }
+
+                    let details = describe_component_change(old_component, new_component, &paths);
+                    changes.push(Change::ComponentChanged {
+                        path,
+                        component,
+                        details,
+                    });
+                }
+                (None, None) => (),
+            }
+        }
+    }
+
+    Ok(changes)
+}
+
+/// Three-way merges two `ris_scene` files, that were both derived from `base`. Changes that touch
+/// different game objects, different properties or different components are combined. When
+/// both sides change the same thing differently, the conflicts are returned instead.
+pub fn merge(
+    registry: Arc<Registry>,
+    base: &[u8],
+    ours: &[u8],
+    theirs: &[u8],
+) -> RisResult<MergeOutcome> {
+    let scene = scratch_scene(registry, 4)?;
+    let documents = vec![
+        load(&scene, base)?,
+        load(&scene, ours)?,
+        load(&scene, theirs)?,
+    ];
+    let identities = identify(&documents);
+    let mut states = capture_all(&scene, &documents, &identities)?;
+    let paths = paths(&documents, &identities);
+
+    // adding or removing a sibling shifts the indices of all siblings after it. thus the order of
+    // siblings is merged per parent by `order_children()`, instead of comparing indices
+    for state in states.iter_mut().flatten() {
+        state.sibling_index = 0;
+    }
+
+    let mut conflicts = Vec::new();
+    let mut merged: Vec<Option<(String, ObjectState)>> = vec![None; identities.count];
+    let mut conflicted = vec![false; identities.count];
+
+    for (id, (merged_object, conflicted)) in
+        merged.iter_mut().zip(conflicted.iter_mut()).enumerate()
+    {
+        let [base, ours, theirs] =
+            [0, 1, 2].map(|d| identities.objects[d][id].map(|i| &states[d][i]));
+        let path = &paths[id];
+
+        // a game object, that is missing on one side, was either added or removed
+        if ours.is_none() || theirs.is_none() {
+            match pick([base, ours, theirs]) {
+                Ok(Some(_)) => (),
+                Ok(None) => continue,
+                Err(()) => {
+                    let reason = match base {
+                        Some(_) => "modified on one side, but removed on the other",
+                        None => "added differently on both sides",
+                    };
+                    conflicts.push(Conflict {
+                        path: path.clone(),
+                        reason: reason.to_string(),
+                    });
+                    *conflicted = true;
+                    continue;
+                }
+            }
+        }
+
+        let name = [1, 2, 0]
+            .into_iter()
+            .find_map(|d| identities.objects[d][id].map(|i| &documents[d].objects[i].name))
+            .into_ris_error()?
+            .clone();
+
+        macro_rules! merge_property {
+            ($field:ident, $property:literal) => {{
+                let values = [base, ours, theirs].map(|x| x.map(|x| &x.$field));
+                match pick(values) {
+                    Ok(side) => side.and_then(|side| values[side]).cloned(),
+                    Err(()) => {
+                        conflicts.push(Conflict {
+                            path: path.clone(),
+                            reason: format!("{} was changed on both sides", $property),
+                        });
+                        *conflicted = true;
+                        None
+                    }
+                }
+            }};
+        }
+
+        let is_active = merge_property!(is_active, "is active");
+        let position = merge_property!(position, "position");
+        let rotation = merge_property!(rotation, "rotation");
+        let scale = merge_property!(scale, "scale");
+        let parent = merge_property!(parent, "parent");
+
+        let mut components = Vec::new();
+        for key in component_keys(&[ours, theirs, base]) {
+            let values = [base, ours, theirs].map(|x| x.and_then(|x| find_component(x, &key)));
+            match pick(values) {
+                Ok(Some(side)) => {
+                    let component = values[side].into_ris_error()?.clone();
+                    components.push((key, component));
+                }
+                Ok(None) => (),
+                Err(()) => {
+                    conflicts.push(Conflict {
+                        path: path.clone(),
+                        reason: format!("{} was changed on both sides", component_label(&key)),
+                    });
+                    *conflicted = true;
+                }
+            }
+        }
+
+        if *conflicted {
+            continue;
+        }
+
+        let state = ObjectState {
+            is_active: is_active.into_ris_error()?,
+            position: position.into_ris_error()?,
+            rotation: rotation.into_ris_error()?,
+            scale: scale.into_ris_error()?,
+            parent: parent.into_ris_error()?,
+            sibling_index: 0,
+            components,
+        };
+        *merged_object = Some((name, state));
+    }
+
+    // changes on one side may depend on game objects, that were removed on the other. game objects
+    // that are in conflict already are not reported again
+    let is_removed = |id: usize| merged[id].is_none() && !conflicted[id];
+    for (id, merged_object) in merged.iter().enumerate() {
+        let Some((_, state)) = merged_object else {
+            continue;
+        };
+
+        if let Some(parent) = state.parent {
+            if is_removed(parent) {
+                conflicts.push(Conflict {
+                    path: paths[id].clone(),
+                    reason: format!("parent {} was removed", paths[parent]),
+                });
+            }
+        }
+
+        for (key, component) in state.components.iter() {
+            for &reference in component.references.iter() {
+                if is_removed(reference) {
+                    conflicts.push(Conflict {
+                        path: paths[id].clone(),
+                        reason: format!(
+                            "{} references removed game object {}",
+                            component_label(key),
+                            paths.get(reference).map(|x| x.as_str()).unwrap_or("?"),
+                        ),
+                    });
+                }
+            }
+        }
+    }
+
+    order_children(&documents, &identities, &paths, &mut merged, &mut conflicts);
+
+    if !conflicts.is_empty() {
+        return Ok(MergeOutcome::Conflicted(conflicts));
+    }
+
+    // build the merged scene
+    let chunk = scene.reserve_chunk().into_ris_error()?;
+    let mut handles = vec![GameObjectHandle::null(); identities.count];
+    for (id, merged_object) in merged.iter().enumerate() {
+        let Some((name, state)) = merged_object else {
+            continue;
+        };
+
+        let handle = GameObjectHandle::new_static(&scene, chunk)?;
+        handle.set_name(&scene, name)?;
+        handle.set_active(&scene, state.is_active)?;
+        handle.set_position(&scene, state.position)?;
+        handle.set_rotation(&scene, state.rotation)?;
+        handle.set_scale(&scene, state.scale)?;
+        handles[id] = handle;
+    }
+
+    let mut children = merged
+        .iter()
+        .enumerate()
+        .filter_map(|(id, x)| x.as_ref().map(|(_, state)| (id, state)))
+        .filter_map(|(id, state)| state.parent.map(|parent| (parent, state.sibling_index, id)))
+        .collect::<Vec<_>>();
+    children.sort();
+
+    let mut sibling_index = 0;
+    let mut previous_parent = None;
+    for (parent, _, id) in children {
+        if previous_parent != Some(parent) {
+            previous_parent = Some(parent);
+            sibling_index = 0;
+        }
+
+        let result = handles[id].set_parent(&scene, Some(handles[parent]), sibling_index);
+        if let Err(e) = result {
+            conflicts.push(Conflict {
+                path: paths[id].clone(),
+                reason: format!("cannot be moved to {}: {}", paths[parent], e),
+            });
+        }
+
+        sibling_index += 1;
+    }
+
+    if !conflicts.is_empty() {
+        return Ok(MergeOutcome::Conflicted(conflicts));
+    }
+
+    for (id, merged_object) in merged.iter().enumerate() {
+        let Some((_, state)) = merged_object else {
+            continue;
+        };
+
+        for (_, component) in state.components.iter() {
+            let factory = scene
+                .registry
+                .component_factories()
+                .get(component.factory)
+                .into_ris_error()?;
+            let handle = factory.make(&scene, handles[id])?;
+
+            let mut stream =
+                SceneReader::new(&scene, component.bytes.clone(), component.asset_ids.clone());
+            stream.lookup = handles.clone();
+            scene.deref_mut_component(handle, |x| x.deserialize(&mut stream))??;
+        }
+    }
+
+    let bytes = ris_scene::serialize(&scene, chunk)?;
+    Ok(MergeOutcome::Merged(bytes))
+}
+
+fn scratch_scene(registry: Arc<Registry>, static_chunks: usize) -> RisResult<Scene> {
+    let scene_create_info = SceneCreateInfo {
+        static_chunks,
+        registry: Some(registry),
+        ..Default::default()
+    };
+    let scene = Scene::new(scene_create_info)?;
+    Ok(scene)
+}
+
+fn load(scene: &Scene, bytes: &[u8]) -> RisResult<Document> {
+    let chunk = ris_scene::deserialize(scene, bytes)?.into_ris_error()?;
+
+    let mut objects = Vec::new();
+    for game_object in scene.static_chunks[chunk].game_objects.iter() {
+        let aref = game_object.borrow();
+        if !aref.is_alive {
+            continue;
+        }
+
+        let handle = GameObjectHandle::from(aref.handle);
+        drop(aref);
+
+        objects.push(Object {
+            handle,
+            name: handle.name(scene)?,
+            path: String::new(),
+            parent: None,
+            children: Vec::new(),
+            components: handle.components(scene)?,
+        });
+    }
+
+    let index_of = objects
+        .iter()
+        .enumerate()
+        .map(|(i, x)| (x.handle.0.scene_id().index, i))
+        .collect::<HashMap<_, _>>();
+    let lookup = |handle: GameObjectHandle| index_of.get(&handle.0.scene_id().index).copied();
+
+    for object in objects.iter_mut() {
+        let handle = object.handle;
+        object.parent = handle.parent(scene)?.and_then(lookup);
+        object.children = handle
+            .children(scene)?
+            .into_iter()
+            .map(|x| lookup(x).into_ris_error())
+            .collect::<RisResult<_>>()?;
+    }
+
+    let roots = (0..objects.len())
+        .filter(|&i| objects[i].parent.is_none())
+        .collect::<Vec<_>>();
+    assign_paths(&mut objects, &roots, "");
+
+    Ok(Document { chunk, objects })
+}
+
+fn assign_paths(objects: &mut [Object], siblings: &[usize], prefix: &str) {
+    let mut occurrences = HashMap::<String, usize>::new();
+
+    for &i in siblings {
+        let name = objects[i].name.clone();
+        let occurrence = occurrences.entry(name.clone()).or_default();
+        let segment = match *occurrence {
+            0 => name,
+            occurrence => format!("{}[{}]", name, occurrence),
+        };
+        *occurrence += 1;
+
+        let path = match prefix.is_empty() {
+            true => segment,
+            false => format!("{}/{}", prefix, segment),
+        };
+
+        let children = objects[i].children.clone();
+        assign_paths(objects, &children, &path);
+        objects[i].path = path;
+    }
+}
+
+/// Matches the game objects of all documents. The first document is the reference, game objects
+/// of later documents which match none of it may still match each other.
+fn identify(documents: &[Document]) -> Identities {
+    let mut count = 0;
+    let mut ids: Vec<Vec<usize>> = Vec::with_capacity(documents.len());
+
+    for (d, document) in documents.iter().enumerate() {
+        let mut document_ids = vec![None; document.objects.len()];
+
+        for (p, previous) in documents.iter().enumerate().take(d) {
+            let unassigned = (0..document.objects.len())
+                .filter(|&i| document_ids[i].is_none())
+                .collect::<Vec<_>>();
+            let available = (0..previous.objects.len())
+                .filter(|&i| !document_ids.contains(&Some(ids[p][i])))
+                .collect::<Vec<_>>();
+
+            for (previous_index, index) in
+                match_objects(previous, &available, document, &unassigned)
+            {
+                document_ids[index] = Some(ids[p][previous_index]);
+            }
+        }
+
+        let document_ids = document_ids
+            .into_iter()
+            .map(|id| {
+                id.unwrap_or_else(|| {
+                    count += 1;
+                    count - 1
+                })
+            })
+            .collect::<Vec<_>>();
+        ids.push(document_ids);
+    }
+
+    let objects = ids
+        .iter()
+        .map(|document_ids| {
+            let mut objects = vec![None; count];
+            for (i, &id) in document_ids.iter().enumerate() {
+                objects[id] = Some(i);
+            }
+            objects
+        })
+        .collect();
+
+    Identities {
+        count,
+        ids,
+        objects,
+    }
+}
+
+/// Matches game objects by path first. Of the remaining ones, those whose name is unique on both
+/// sides are considered to be moved.
+fn match_objects(
+    a: &Document,
+    a_candidates: &[usize],
+    b: &Document,
+    b_candidates: &[usize],
+) -> Vec<(usize, usize)> {
+    let mut pairs = Vec::new();
+
+    let a_paths = a_candidates
+        .iter()
+        .map(|&i| (a.objects[i].path.as_str(), i))
+        .collect::<HashMap<_, _>>();
+    for &j in b_candidates {
+        if let Some(&i) = a_paths.get(b.objects[j].path.as_str()) {
+            pairs.push((i, j));
+        }
+    }
+
+    let unique_names = |document: &Document, candidates: &[usize], matched: Vec<usize>| {
+        let mut names = HashMap::<String, Option<usize>>::new();
+        for &i in candidates.iter().filter(|i| !matched.contains(i)) {
+            names
+                .entry(document.objects[i].name.clone())
+                .and_modify(|x| *x = None)
+                .or_insert(Some(i));
+        }
+        names
+    };
+    let a_names = unique_names(a, a_candidates, pairs.iter().map(|x| x.0).collect());
+    let b_names = unique_names(b, b_candidates, pairs.iter().map(|x| x.1).collect());
+
+    for (name, j) in b_names {
+        if let (Some(j), Some(Some(i))) = (j, a_names.get(&name)) {
+            pairs.push((*i, j));
+        }
+    }
+
+    pairs.sort_by_key(|x| x.1);
+    pairs
+}
+
+fn capture_all(
+    scene: &Scene,
+    documents: &[Document],
+    identities: &Identities,
+) -> RisResult<Vec<Vec<ObjectState>>> {
+    documents
+        .iter()
+        .enumerate()
+        .map(|(d, document)| capture(scene, document, &identities.ids[d], identities.count))
+        .collect()
+}
+
+fn capture(
+    scene: &Scene,
+    document: &Document,
+    ids: &[usize],
+    count: usize,
+) -> RisResult<Vec<ObjectState>> {
+    // serializing with this lookup writes identities instead of indices
+    let mut lookup = vec![usize::MAX; count];
+    for (i, object) in document.objects.iter().enumerate() {
+        lookup[ids[i]] = object.handle.0.scene_id().index;
+    }
+
+    let factories = scene.registry.component_factories();
+    let mut states = Vec::with_capacity(document.objects.len());
+
+    for object in document.objects.iter() {
+        let handle = object.handle;
+
+        let mut components = Vec::with_capacity(object.components.len());
+        let mut occurrences = HashMap::<String, usize>::new();
+        for &component in object.components.iter() {
+            let factory = factories
+                .iter()
+                .position(|x| x.component_id() == component.type_id())
+                .into_ris_error()?;
+
+            let mut stream = SceneWriter::new(document.chunk, scene);
+            scene.deref_mut_component(component, |x| x.serialize(&mut stream))??;
+            let reference_addrs = stream
+                .game_object_references()
+                .iter()
+                .map(|x| usize::try_from(x.addr))
+                .collect::<Result<Vec<_>, _>>()?;
+            let fields = stream
+                .fields()
+                .iter()
+                .map(|(ptr, field)| {
+                    let value = match &field.value {
+                        SceneValue::GameObject(_) => None,
+                        SceneValue::Bool(x) => Some(x.to_string()),
+                        SceneValue::I32(x) => Some(x.to_string()),
+                        SceneValue::Uint(x) => Some(x.to_string()),
+                        SceneValue::F32(x) => Some(format!("{:?}", x)),
+                        SceneValue::String(x) => Some(format!("{:?}", x)),
+                        SceneValue::Vec3(x) => Some(format!("{:?}", x)),
+                        SceneValue::Quat(x) => Some(format!("{:?}", x)),
+                        SceneValue::Asset(x) => Some(format!("{:?}", x)),
+                    };
+
+                    Ok(ComponentField {
+                        name: field.name.clone(),
+                        value,
+                        is_asset: matches!(field.value, SceneValue::Asset(_)),
+                        addr: usize::try_from(ptr.addr)?,
+                        len: usize::try_from(ptr.len)?,
+                    })
+                })
+                .collect::<RisResult<Vec<_>>>()?;
+            let (bytes, asset_ids) = stream.resolve(lookup.clone())?;
+
+            let mut cursor = Cursor::new(&bytes);
+            let mut references = Vec::with_capacity(reference_addrs.len());
+            for &addr in reference_addrs.iter() {
+                ris_io::seek(&mut cursor, SeekFrom::Start(addr as u64))?;
+                references.push(ris_io::read_uint(&mut cursor)?);
+            }
+
+            // scripts all share the same component, name them after the script instead
+            let name = if component.type_id() == TypeId::of::<DynScriptComponent>() {
+                ris_io::seek(&mut cursor, SeekFrom::Start(0))?;
+                let script = ris_io::read_uint(&mut cursor)?;
+                scene
+                    .registry
+                    .script_factories()
+                    .get(script)
+                    .into_ris_error()?
+                    .script_name()
+                    .to_string()
+            } else {
+                factories[factory].component_name().to_string()
+            };
+
+            let occurrence = occurrences.entry(name.clone()).or_default();
+            let key = (name, *occurrence);
+            *occurrence += 1;
+
+            components.push((
+                key,
+                ComponentState {
+                    factory,
+                    bytes,
+                    asset_ids,
+                    references,
+                    reference_addrs,
+                    fields,
+                },
+            ));
+        }
+
+        states.push(ObjectState {
+            is_active: handle.is_active(scene)?,
+            position: handle.position(scene)?,
+            rotation: handle.rotation(scene)?,
+            scale: handle.scale(scene)?,
+            parent: object.parent.map(|x| ids[x]),
+            sibling_index: handle.sibling_index(scene)?,
+            components,
+        });
+    }
+
+    Ok(states)
+}
+
+/// Assigns the sibling indices of the merged game objects. The order of the side, that reordered
+/// the children of a parent, is kept. Children that were added or moved there on the other side
+/// are inserted before their next sibling.
+fn order_children(
+    documents: &[Document],
+    identities: &Identities,
+    paths: &[String],
+    merged: &mut [Option<(String, ObjectState)>],
+    conflicts: &mut Vec<Conflict>,
+) {
+    for (parent, path) in paths.iter().enumerate() {
+        let children = merged
+            .iter()
+            .enumerate()
+            .filter(|(_, x)| {
+                x.as_ref()
+                    .is_some_and(|(_, state)| state.parent == Some(parent))
+            })
+            .map(|(id, _)| id)
+            .collect::<Vec<_>>();
+        if children.is_empty() {
+            continue;
+        }
+
+        let lists = [0, 1, 2].map(|d| match identities.objects[d][parent] {
+            Some(i) => documents[d].objects[i]
+                .children
+                .iter()
+                .map(|&child| identities.ids[d][child])
+                .collect::<Vec<_>>(),
+            None => Vec::new(),
+        });
+
+        // only the relative order of the children, that exist on all sides, can be reordered
+        let common = |list: &Vec<usize>| {
+            list.iter()
+                .copied()
+                .filter(|id| lists.iter().all(|x| x.contains(id)))
+                .collect::<Vec<_>>()
+        };
+        let [base, ours, theirs] = &lists;
+        let ours_reordered = common(ours) != common(base);
+        let theirs_reordered = common(theirs) != common(base);
+
+        if ours_reordered && theirs_reordered && common(ours) != common(theirs) {
+            conflicts.push(Conflict {
+                path: path.clone(),
+                reason: "children were reordered differently on both sides".to_string(),
+            });
+            continue;
+        }
+
+        let (primary, secondary) = if theirs_reordered && !ours_reordered {
+            (theirs, ours)
+        } else {
+            (ours, theirs)
+        };
+
+        let mut order = primary
+            .iter()
+            .copied()
+            .filter(|id| children.contains(id))
+            .collect::<Vec<_>>();
+        for list in [secondary, base] {
+            for (i, &id) in list.iter().enumerate() {
+                if !children.contains(&id) || order.contains(&id) {
+                    continue;
+                }
+
+                let index = list[i + 1..]
+                    .iter()
+                    .find_map(|next| order.iter().position(|x| x == next))
+                    .unwrap_or(order.len());
+                order.insert(index, id);
+            }
+        }
+
+        for (sibling_index, id) in order.into_iter().enumerate() {
+            if let Some((_, state)) = merged[id].as_mut() {
+                state.sibling_index = sibling_index;
+            }
+        }
+    }
+}
+
+/// The path of every identity, preferring the later documents.
+fn paths(documents: &[Document], identities: &Identities) -> Vec<String> {
+    (0..identities.count)
+        .map(|id| {
+            (0..documents.len())
+                .rev()
+                .find_map(|d| identities.objects[d][id].map(|i| &documents[d].objects[i].path))
+                .cloned()
+                .unwrap_or_default()
+        })
+        .collect()
+}
+
+/// Returns which of base, ours and theirs holds the merged value, or an error when ours and
+/// theirs changed it differently.
+fn pick<T: PartialEq>(values: [Option<&T>; 3]) -> Result<Option<usize>, ()> {
+    let [base, ours, theirs] = values;
+
+    if ours == theirs || theirs == base {
+        Ok(ours.map(|_| 1))
+    } else if ours == base {
+        Ok(theirs.map(|_| 2))
+    } else {
+        Err(())
+    }
+}
+
+fn component_keys(states: &[Option<&ObjectState>]) -> Vec<ComponentKey> {
+    let mut keys = Vec::new();
+    for state in states.iter().flatten() {
+        for (key, _) in state.components.iter() {
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+    }
+
+    keys
+}
+
+fn find_component<'a>(state: &'a ObjectState, key: &ComponentKey) -> Option<&'a ComponentState> {
+    state
+        .components
+        .iter()
+        .find(|(x, _)| x == key)
+        .map(|(_, x)| x)
+}
+
+fn component_label((name, occurrence): &ComponentKey) -> String {
+    match occurrence {
+        0 => name.clone(),
+        occurrence => format!("{}[{}]", name, occurrence),
+    }
+}
+
+fn describe_component_change(
+    old: &ComponentState,
+    new: &ComponentState,
+    paths: &[String],
+) -> Vec<String> {
+    let mut details = Vec::new();
+
+    if old.references != new.references {
+        let to_paths = |references: &[usize]| {
+            references
+                .iter()
+                .map(|&x| paths.get(x).map(|x| x.as_str()).unwrap_or("?"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        };
+
+        details.push(format!(
+            "references [{}] -> [{}]",
+            to_paths(&old.references),
+            to_paths(&new.references),
+        ));
+    }
+
+    // named fields can only be compared, when both components were written in the same layout
+    let layout = |state: &ComponentState| {
+        state
+            .fields
+            .iter()
+            .map(|x| (x.name.clone(), x.value.is_some(), x.is_asset))
+            .collect::<Vec<_>>()
+    };
+    let is_same_layout = layout(old) == layout(new);
+
+    let has_unnamed_assets = old.fields.iter().any(|x| x.is_asset && x.name.is_empty());
+    let assets_are_named = is_same_layout && !has_unnamed_assets;
+    if old.asset_ids != new.asset_ids && !assets_are_named {
+        details.push(format!("assets {:?} -> {:?}", old.asset_ids, new.asset_ids));
+    }
+
+    if is_same_layout {
+        for (old_field, new_field) in old.fields.iter().zip(new.fields.iter()) {
+            if old_field.name.is_empty() {
+                continue;
+            }
+
+            let (Some(old_value), Some(new_value)) = (&old_field.value, &new_field.value) else {
+                continue;
+            };
+
+            if old_value != new_value {
+                details.push(format!(
+                    "{}: {} -> {}",
+                    old_field.name, old_value, new_value
+                ));
+            }
+        }
+
+        // bytes that were written by `ris_io` directly are not covered by any field
+        let unrecorded = |state: &ComponentState| {
+            let mut bytes = Vec::with_capacity(state.bytes.len());
+            let mut begin = 0;
+            for field in state.fields.iter() {
+                let end = usize::min(field.addr, state.bytes.len());
+                bytes.extend_from_slice(&state.bytes[begin.min(end)..end]);
+                begin = field.addr + field.len;
+            }
+            bytes.extend_from_slice(&state.bytes[begin.min(state.bytes.len())..]);
+            bytes
+        };
+
+        if unrecorded(old) == unrecorded(new) {
+            return details;
+        }
+    }
+
+    // without a matching layout, only the bytes can be compared. fields that were already
+    // described are masked, such that they are not reported twice
+    let masked = |state: &ComponentState| {
+        let mut bytes = state.bytes.clone();
+        for &addr in state.reference_addrs.iter() {
+            let end = usize::min(addr + REFERENCE_SIZE, bytes.len());
+            bytes[addr..end].fill(0);
+        }
+
+        if is_same_layout {
+            for field in state.fields.iter().filter(|x| !x.name.is_empty()) {
+                let end = usize::min(field.addr + field.len, bytes.len());
+                bytes[field.addr.min(end)..end].fill(0);
+            }
+        }
+
+        bytes
+    };
+    let old_bytes = masked(old);
+    let new_bytes = masked(new);
+
+    if old_bytes.len() != new_bytes.len() {
+        details.push(format!(
+            "size {} -> {} bytes",
+            old_bytes.len(),
+            new_bytes.len()
+        ));
+    } else {
+        let mut ranges = Vec::<(usize, usize)>::new();
+        for (i, (a, b)) in old_bytes.iter().zip(new_bytes.iter()).enumerate() {
+            if a == b {
+                continue;
+            }
+
+            match ranges.last_mut() {
+                Some((_, end)) if *end == i => *end = i + 1,
+                _ => ranges.push((i, i + 1)),
+            }
+        }
+
+        if !ranges.is_empty() {
+            let ranges = ranges
+                .iter()
+                .map(|(start, end)| format!("{}..{}", start, end))
+                .collect::<Vec<_>>()
+                .join(", ");
+            details.push(format!("bytes {} changed", ranges));
+        }
+    }
+
+    details
+}
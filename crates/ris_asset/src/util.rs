@@ -1,4 +1,7 @@
+use ris_async::ThreadPool;
 use ris_error::RisResult;
+use ris_math::vector::Vec2;
+use ris_math::vector::Vec3;
 
 pub fn add_alpha_channel(pixels: &[u8]) -> RisResult<Vec<u8>> {
     ris_error::assert!(pixels.len().is_multiple_of(3))?;
@@ -19,3 +22,127 @@ pub fn add_alpha_channel(pixels: &[u8]) -> RisResult<Vec<u8>> {
 
     Ok(pixels_rgba)
 }
+
+/// Calls `job` for every item and returns the results in the same order as `items`. If the
+/// caller is a worker of the thread pool, every item is submitted as its own job. Otherwise the
+/// items are processed one after another.
+pub fn map_parallel<T, R, F>(items: Vec<T>, job: F) -> Vec<R>
+where
+    T: 'static,
+    R: 'static,
+    F: Fn(T) -> R + Clone + 'static,
+{
+    if !ThreadPool::is_worker() {
+        return items.into_iter().map(job).collect();
+    }
+
+    let futures = items
+        .into_iter()
+        .map(|item| {
+            let job = job.clone();
+            ThreadPool::submit(async move { job(item) })
+        })
+        .collect::<Vec<_>>();
+
+    futures.into_iter().map(|x| x.wait()).collect()
+}
+
+/// Splits a polygon into triangles by ear clipping, which also handles concave polygons. The
+/// polygon is given by indices into `vertices` and may not be planar, in which case it is
+/// projected onto its best fitting plane. The triangles keep the winding of the polygon.
+///
+/// Returns a triangle list. Degenerate polygons, which have no ears, are triangulated as a fan.
+pub fn triangulate(polygon: &[u32], vertices: &[Vec3]) -> RisResult<Vec<u32>> {
+    let mut points = Vec::with_capacity(polygon.len());
+    for &index in polygon.iter() {
+        let Some(&point) = vertices.get(index as usize) else {
+            return ris_error::new_result!(
+                "index {} is out of range. vertex count: {}",
+                index,
+                vertices.len(),
+            );
+        };
+        points.push(point);
+    }
+
+    let mut triangles = Vec::with_capacity(polygon.len().saturating_sub(2) * 3);
+    if polygon.len() < 3 {
+        return Ok(triangles);
+    }
+
+    // the normal of the polygon, by newell's method
+    let mut normal = Vec3::init(0.0);
+    for (i, &current) in points.iter().enumerate() {
+        let next = points[(i + 1) % points.len()];
+        normal.0 += (current.1 - next.1) * (current.2 + next.2);
+        normal.1 += (current.2 - next.2) * (current.0 + next.0);
+        normal.2 += (current.0 - next.0) * (current.1 + next.1);
+    }
+
+    // drop the axis, along which the polygon is the largest. the remaining axes are kept in
+    // cyclic order, such that the sign of the dropped normal component is the winding in 2d
+    let (x, y, z) = (normal.0.abs(), normal.1.abs(), normal.2.abs());
+    let (projected, winding) = if x >= y && x >= z {
+        let projected = points.iter().map(|p| Vec2(p.1, p.2)).collect::<Vec<_>>();
+        (projected, normal.0)
+    } else if y >= z {
+        let projected = points.iter().map(|p| Vec2(p.2, p.0)).collect::<Vec<_>>();
+        (projected, normal.1)
+    } else {
+        let projected = points.iter().map(|p| Vec2(p.0, p.1)).collect::<Vec<_>>();
+        (projected, normal.2)
+    };
+    let winding = if winding < 0.0 { -1.0 } else { 1.0 };
+
+    let cross = |o: Vec2, a: Vec2, b: Vec2| {
+        ((a.0 - o.0) * (b.1 - o.1) - (a.1 - o.1) * (b.0 - o.0)) * winding
+    };
+
+    let same = |a: Vec2, b: Vec2| a.0 == b.0 && a.1 == b.1;
+
+    let mut remaining = (0..polygon.len()).collect::<Vec<_>>();
+    while remaining.len() > 3 {
+        let count = remaining.len();
+        let ear = (0..count).find(|&i| {
+            let a = remaining[(i + count - 1) % count];
+            let b = remaining[i];
+            let c = remaining[(i + 1) % count];
+            let (pa, pb, pc) = (projected[a], projected[b], projected[c]);
+
+            // reflex or degenerate corners are no ears
+            if cross(pa, pb, pc) <= 0.0 {
+                return false;
+            }
+
+            // an ear may not contain any other corner. corners, which coincide with the ear,
+            // occur when holes are bridged and are ignored
+            !remaining.iter().any(|&j| {
+                let p = projected[j];
+                if j == a || j == b || j == c || same(p, pa) || same(p, pb) || same(p, pc) {
+                    return false;
+                }
+
+                cross(pa, pb, p) >= 0.0 && cross(pb, pc, p) >= 0.0 && cross(pc, pa, p) >= 0.0
+            })
+        });
+
+        let Some(i) = ear else {
+            break;
+        };
+
+        let a = remaining[(i + count - 1) % count];
+        let b = remaining[i];
+        let c = remaining[(i + 1) % count];
+        triangles.extend([polygon[a], polygon[b], polygon[c]]);
+        remaining.remove(i);
+    }
+
+    for i in 1..remaining.len() - 1 {
+        let a = remaining[0];
+        let b = remaining[i];
+        let c = remaining[i + 1];
+        triangles.extend([polygon[a], polygon[b], polygon[c]]);
+    }
+
+    Ok(triangles)
+}
@@ -0,0 +1,224 @@
+use ris_error::prelude::*;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+
+use crate::skeleton::Pose;
+use crate::skeleton::Skeleton;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Interpolation {
+    Step,
+    Linear,
+    /// every keyframe stores an in-tangent, a value and an out-tangent, in this order
+    CubicSpline,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChannelPath {
+    Translation,
+    Rotation,
+    Scale,
+}
+
+/// Animates a single property of a single joint.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationChannel {
+    pub joint: usize,
+    pub path: ChannelPath,
+    pub interpolation: Interpolation,
+    /// keyframe times in seconds, in ascending order
+    pub times: Vec<f32>,
+    /// the keyframe values, flattened. translation and scale have 3 components, rotation has 4
+    pub values: Vec<f32>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimationClip {
+    pub name: String,
+    /// in seconds
+    pub duration: f32,
+    pub channels: Vec<AnimationChannel>,
+}
+
+impl Interpolation {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            Interpolation::Step => 0,
+            Interpolation::Linear => 1,
+            Interpolation::CubicSpline => 2,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> RisResult<Self> {
+        match value {
+            0 => Ok(Interpolation::Step),
+            1 => Ok(Interpolation::Linear),
+            2 => Ok(Interpolation::CubicSpline),
+            value => ris_error::new_result!("unknown interpolation: {}", value),
+        }
+    }
+}
+
+impl ChannelPath {
+    pub fn component_count(self) -> usize {
+        match self {
+            ChannelPath::Translation => 3,
+            ChannelPath::Rotation => 4,
+            ChannelPath::Scale => 3,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        match self {
+            ChannelPath::Translation => 0,
+            ChannelPath::Rotation => 1,
+            ChannelPath::Scale => 2,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> RisResult<Self> {
+        match value {
+            0 => Ok(ChannelPath::Translation),
+            1 => Ok(ChannelPath::Rotation),
+            2 => Ok(ChannelPath::Scale),
+            value => ris_error::new_result!("unknown channel path: {}", value),
+        }
+    }
+}
+
+impl AnimationChannel {
+    /// the number of floats per keyframe
+    pub fn stride(&self) -> usize {
+        let component_count = self.path.component_count();
+        match self.interpolation {
+            Interpolation::CubicSpline => 3 * component_count,
+            _ => component_count,
+        }
+    }
+
+    pub fn validate(&self, joint_count: usize) -> RisResult<()> {
+        ris_error::assert!(self.joint < joint_count)?;
+        ris_error::assert!(!self.times.is_empty())?;
+        ris_error::assert!(self.values.len() == self.times.len() * self.stride())?;
+
+        for window in self.times.windows(2) {
+            if window[0] > window[1] {
+                return ris_error::new_result!("keyframe times are not in ascending order");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the value at `time`. Times outside of the keyframes are clamped. Unused
+    /// components are 0.
+    pub fn sample(&self, time: f32) -> [f32; 4] {
+        let count = self.path.component_count();
+        let stride = self.stride();
+        let value_offset = match self.interpolation {
+            Interpolation::CubicSpline => count,
+            _ => 0,
+        };
+
+        let value_at = |keyframe: usize, offset: usize| {
+            let start = keyframe * stride + offset;
+            let mut result = [0.0; 4];
+            result[..count].copy_from_slice(&self.values[start..start + count]);
+            result
+        };
+
+        let next = self.times.partition_point(|&x| x <= time);
+        if next == 0 {
+            return value_at(0, value_offset);
+        }
+        if next == self.times.len() {
+            return value_at(next - 1, value_offset);
+        }
+
+        let previous = next - 1;
+        let t0 = self.times[previous];
+        let t1 = self.times[next];
+        let delta = t1 - t0;
+        let a = if delta > 0.0 {
+            (time - t0) / delta
+        } else {
+            0.0
+        };
+
+        let result = match self.interpolation {
+            Interpolation::Step => value_at(previous, 0),
+            Interpolation::Linear => {
+                let v0 = value_at(previous, 0);
+                let v1 = value_at(next, 0);
+                if self.path == ChannelPath::Rotation {
+                    let q0 = Quat(v0[0], v0[1], v0[2], v0[3]);
+                    let q1 = Quat(v1[0], v1[1], v1[2], v1[3]);
+                    let q = q0.slerp(q1, a);
+                    return [q.0, q.1, q.2, q.3];
+                }
+
+                let mut result = [0.0; 4];
+                for i in 0..count {
+                    result[i] = v0[i] + (v1[i] - v0[i]) * a;
+                }
+                result
+            }
+            Interpolation::CubicSpline => {
+                // hermite spline, as defined by the gltf spec
+                let p0 = value_at(previous, count);
+                let m0 = value_at(previous, 2 * count);
+                let p1 = value_at(next, count);
+                let m1 = value_at(next, 0);
+
+                let a2 = a * a;
+                let a3 = a2 * a;
+                let h00 = 2.0 * a3 - 3.0 * a2 + 1.0;
+                let h10 = a3 - 2.0 * a2 + a;
+                let h01 = -2.0 * a3 + 3.0 * a2;
+                let h11 = a3 - a2;
+
+                let mut result = [0.0; 4];
+                for i in 0..count {
+                    result[i] =
+                        h00 * p0[i] + h10 * delta * m0[i] + h01 * p1[i] + h11 * delta * m1[i];
+                }
+                result
+            }
+        };
+
+        if self.path == ChannelPath::Rotation {
+            let q = Quat(result[0], result[1], result[2], result[3]).normalize();
+            return [q.0, q.1, q.2, q.3];
+        }
+
+        result
+    }
+}
+
+impl AnimationClip {
+    pub fn validate(&self, joint_count: usize) -> RisResult<()> {
+        ris_error::assert!(self.duration >= 0.0)?;
+        for channel in self.channels.iter() {
+            channel.validate(joint_count)?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the pose at `time`. Joints, which are not animated, keep their rest transform.
+    pub fn sample(&self, skeleton: &Skeleton, time: f32) -> RisResult<Pose> {
+        let mut pose = skeleton.rest_pose();
+
+        for channel in self.channels.iter() {
+            let joint = pose.joints.get_mut(channel.joint).into_ris_error()?;
+            let v = channel.sample(time);
+            match channel.path {
+                ChannelPath::Translation => joint.translation = Vec3(v[0], v[1], v[2]),
+                ChannelPath::Rotation => joint.rotation = Quat(v[0], v[1], v[2], v[3]),
+                ChannelPath::Scale => joint.scale = Vec3(v[0], v[1], v[2]),
+            }
+        }
+
+        Ok(pose)
+    }
+}
@@ -1,5 +1,9 @@
+pub mod animation;
 pub mod asset_id;
+pub mod material;
 pub mod mesh;
+pub mod skeleton;
 pub mod terrain_mesh;
+pub mod texture;
 
 pub use asset_id::AssetId;
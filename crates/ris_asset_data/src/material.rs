@@ -0,0 +1,154 @@
+use std::sync::Arc;
+
+use ris_error::prelude::*;
+
+use crate::asset_id::AssetId;
+use crate::texture::TextureLookupId;
+
+pub const DEFAULT_VERTEX_SHADER: &str = "shaders/default.vert.spv";
+pub const DEFAULT_FRAGMENT_SHADER: &str = "shaders/default.frag.spv";
+
+#[derive(Debug, Clone)]
+pub struct MaterialLookupId {
+    index: usize,
+    references: Arc<()>,
+}
+
+impl MaterialLookupId {
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            references: Arc::default(),
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_unique(&mut self) -> bool {
+        Arc::get_mut(&mut self.references).is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AlphaMode {
+    Opaque,
+    Mask,
+    Blend,
+}
+
+/// The typed parameters of a material. The defaults match the defaults of glTF.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MaterialParams {
+    /// linear rgba. multiplied with the base color texture
+    pub base_color: [f32; 4],
+    pub metallic: f32,
+    pub roughness: f32,
+    /// linear rgb. multiplied with the emissive texture
+    pub emissive: [f32; 3],
+    pub normal_scale: f32,
+    pub occlusion_strength: f32,
+    pub alpha_mode: AlphaMode,
+    /// fragments with a lower alpha are discarded. only used by `AlphaMode::Mask`
+    pub alpha_cutoff: f32,
+    pub double_sided: bool,
+}
+
+/// Every slot is optional. A shader samples a neutral default for missing textures.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaterialTextures {
+    pub base_color: Option<AssetId>,
+    pub metallic_roughness: Option<AssetId>,
+    pub normal: Option<AssetId>,
+    pub occlusion: Option<AssetId>,
+    pub emissive: Option<AssetId>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuMaterial {
+    pub vertex_shader: AssetId,
+    pub fragment_shader: AssetId,
+    pub textures: MaterialTextures,
+    pub params: MaterialParams,
+}
+
+/// A material, whose textures are allocated in a `TextureLookup`. The textures are kept alive as
+/// long as the material is.
+#[derive(Debug, Clone)]
+pub struct GpuMaterial {
+    pub params: MaterialParams,
+    /// in the same order as `MaterialTextures::slots()`
+    pub textures: [Option<TextureLookupId>; MaterialTextures::SLOT_COUNT],
+}
+
+impl AlphaMode {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            AlphaMode::Opaque => 0,
+            AlphaMode::Mask => 1,
+            AlphaMode::Blend => 2,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> RisResult<Self> {
+        match value {
+            0 => Ok(AlphaMode::Opaque),
+            1 => Ok(AlphaMode::Mask),
+            2 => Ok(AlphaMode::Blend),
+            value => ris_error::new_result!("unknown alpha mode: {}", value),
+        }
+    }
+}
+
+impl Default for MaterialParams {
+    fn default() -> Self {
+        Self {
+            base_color: [1.0, 1.0, 1.0, 1.0],
+            metallic: 1.0,
+            roughness: 1.0,
+            emissive: [0.0, 0.0, 0.0],
+            normal_scale: 1.0,
+            occlusion_strength: 1.0,
+            alpha_mode: AlphaMode::Opaque,
+            alpha_cutoff: 0.5,
+            double_sided: false,
+        }
+    }
+}
+
+impl MaterialTextures {
+    pub const SLOT_COUNT: usize = 5;
+
+    /// The slots in the order they are serialized.
+    pub fn slots(&self) -> [&Option<AssetId>; Self::SLOT_COUNT] {
+        [
+            &self.base_color,
+            &self.metallic_roughness,
+            &self.normal,
+            &self.occlusion,
+            &self.emissive,
+        ]
+    }
+
+    pub fn slots_mut(&mut self) -> [&mut Option<AssetId>; Self::SLOT_COUNT] {
+        [
+            &mut self.base_color,
+            &mut self.metallic_roughness,
+            &mut self.normal,
+            &mut self.occlusion,
+            &mut self.emissive,
+        ]
+    }
+}
+
+impl Default for CpuMaterial {
+    fn default() -> Self {
+        Self {
+            vertex_shader: AssetId::Path(DEFAULT_VERTEX_SHADER.to_string()),
+            fragment_shader: AssetId::Path(DEFAULT_FRAGMENT_SHADER.to_string()),
+            textures: MaterialTextures::default(),
+            params: MaterialParams::default(),
+        }
+    }
+}
@@ -1,3 +1,4 @@
+use std::io::Cursor;
 use std::io::SeekFrom;
 use std::sync::Arc;
 
@@ -9,6 +10,7 @@ use ris_gpu::transient_command::TransientCommandArgs;
 use ris_io::FatPtr;
 use ris_math::vector::Vec2;
 use ris_math::vector::Vec3;
+use ris_math::vector::Vec4;
 
 #[derive(Debug, Clone)]
 pub struct MeshLookupId {
@@ -78,6 +80,46 @@ pub const VERTEX_ATTRIBUTE_DESCRIPTIONS: [vk::VertexInputAttributeDescription; 3
     },
 ];
 
+/// The vertex input of skinned meshes. Extends `VERTEX_BINDING_DESCRIPTIONS` by the joints and
+/// the weights.
+pub const SKINNED_VERTEX_BINDING_DESCRIPTIONS: [vk::VertexInputBindingDescription; 5] = [
+    VERTEX_BINDING_DESCRIPTIONS[0],
+    VERTEX_BINDING_DESCRIPTIONS[1],
+    VERTEX_BINDING_DESCRIPTIONS[2],
+    // joints
+    vk::VertexInputBindingDescription {
+        binding: 3,
+        stride: std::mem::size_of::<[u16; 4]>() as u32,
+        input_rate: vk::VertexInputRate::VERTEX,
+    },
+    // weights
+    vk::VertexInputBindingDescription {
+        binding: 4,
+        stride: std::mem::size_of::<[f32; 4]>() as u32,
+        input_rate: vk::VertexInputRate::VERTEX,
+    },
+];
+
+pub const SKINNED_VERTEX_ATTRIBUTE_DESCRIPTIONS: [vk::VertexInputAttributeDescription; 5] = [
+    VERTEX_ATTRIBUTE_DESCRIPTIONS[0],
+    VERTEX_ATTRIBUTE_DESCRIPTIONS[1],
+    VERTEX_ATTRIBUTE_DESCRIPTIONS[2],
+    // joints
+    vk::VertexInputAttributeDescription {
+        location: 3,
+        binding: 3,
+        format: vk::Format::R16G16B16A16_UINT,
+        offset: 0,
+    },
+    // weights
+    vk::VertexInputAttributeDescription {
+        location: 4,
+        binding: 4,
+        format: vk::Format::R32G32B32A32_SFLOAT,
+        offset: 0,
+    },
+];
+
 #[derive(Debug)]
 pub enum Indices {
     U16(Vec<u16>),
@@ -87,6 +129,25 @@ pub enum Indices {
 }
 
 impl Indices {
+    /// Uses the smallest index type, which can address `vertex_count` vertices. u8 indices
+    /// require a vulkan extension, thus the smallest type is u16.
+    pub fn with_smallest_type(indices: Vec<u32>, vertex_count: usize) -> RisResult<Self> {
+        if let Some(&index) = indices.iter().find(|&&x| x as usize >= vertex_count) {
+            return ris_error::new_result!(
+                "index {} is out of range. vertex count: {}",
+                index,
+                vertex_count,
+            );
+        }
+
+        if vertex_count > usize::from(u16::MAX) + 1 {
+            return Ok(Self::U32(indices));
+        }
+
+        let indices = indices.into_iter().map(|x| x as u16).collect();
+        Ok(Self::U16(indices))
+    }
+
     pub fn index_type(&self) -> vk::IndexType {
         match *self {
             Self::U16(_) => vk::IndexType::UINT16,
@@ -169,6 +230,17 @@ pub struct MeshPrototype {
     pub normals: Vec<Vec3>,
     pub uvs: Vec<Vec2>,
     pub indices: Indices,
+    /// the indices of the 4 joints, which influence a vertex. empty if the mesh is not skinned
+    pub joints: Vec<[u16; 4]>,
+    /// the weights of the 4 joints, which influence a vertex. empty if the mesh is not skinned
+    pub weights: Vec<[f32; 4]>,
+    /// xyz is the tangent, w is the handedness of the bitangent, which is
+    /// `cross(normal, tangent) * w`. empty if the mesh has no tangents
+    pub tangents: Vec<Vec4>,
+    /// simplified levels of detail, each coarser than the one before. they are triangle lists
+    /// into the same vertices and use the same index type as `indices`. empty if the mesh has
+    /// no lods
+    pub lods: Vec<Indices>,
 }
 
 #[derive(Debug)]
@@ -177,6 +249,14 @@ pub struct CpuMesh {
     pub p_normals: FatPtr,
     pub p_uvs: FatPtr,
     pub p_indices: FatPtr,
+    /// null if the mesh is not skinned
+    pub p_joints: FatPtr,
+    /// null if the mesh is not skinned
+    pub p_weights: FatPtr,
+    /// null if the mesh has no tangents
+    pub p_tangents: FatPtr,
+    /// the indices of the simplified levels of detail. empty if the mesh has no lods
+    pub p_lods: Vec<FatPtr>,
     pub index_type: vk::IndexType,
     pub data: Vec<u8>,
 }
@@ -192,11 +272,51 @@ struct GpuMeshInner {
     p_normals: vk::DeviceSize,
     p_uvs: vk::DeviceSize,
     p_indices: vk::DeviceSize,
-    index_count: u32,
+    p_skin: Option<[vk::DeviceSize; 2]>,
+    p_tangents: Option<vk::DeviceSize>,
     index_type: vk::IndexType,
+    /// the index offsets of all levels of detail, starting with the full mesh
+    lod_offsets: Vec<vk::DeviceSize>,
+    /// the index counts of all levels of detail, starting with the full mesh
+    lod_index_counts: Vec<u32>,
+    bounding_sphere: (Vec3, f32),
     buffer: Buffer,
 }
 
+impl MeshPrototype {
+    /// Replaces the normals. The normal of a vertex is the average of the normals of its
+    /// triangles, weighted by their area. Vertices, which are not part of a triangle, point up.
+    pub fn generate_normals(&mut self) {
+        let mut normals = vec![Vec3::init(0.0); self.vertices.len()];
+        for (a, b, c) in self.indices.triangles() {
+            let (Some(&va), Some(&vb), Some(&vc)) = (
+                self.vertices.get(a),
+                self.vertices.get(b),
+                self.vertices.get(c),
+            ) else {
+                continue;
+            };
+
+            // the cross product is twice the area of the triangle
+            let normal = (vb - va).cross(vc - va);
+            normals[a] += normal;
+            normals[b] += normal;
+            normals[c] += normal;
+        }
+
+        self.normals = normals
+            .into_iter()
+            .map(|x| {
+                if x.length_squared() > 0.0 {
+                    x.normalize()
+                } else {
+                    Vec3::up()
+                }
+            })
+            .collect();
+    }
+}
+
 impl TryFrom<CpuMesh> for MeshPrototype {
     type Error = RisError;
 
@@ -221,8 +341,30 @@ impl TryFrom<CpuMesh> for MeshPrototype {
 
         let index_bytes = ris_io::read_at(s, value.p_indices)?;
         let index_stride = Indices::stride_of(value.index_type);
-        ris_error::assert!(index_bytes.len() % index_stride == 0)?;
-        let index_count = index_bytes.len() / index_stride;
+        ris_error::assert!(index_stride == 0 || index_bytes.len() % index_stride == 0)?;
+
+        let joint_bytes = ris_io::read_at(s, value.p_joints)?;
+        let joint_stride = std::mem::size_of::<[u16; 4]>();
+        ris_error::assert!(joint_bytes.len() % joint_stride == 0)?;
+        let joint_count = joint_bytes.len() / joint_stride;
+
+        let weight_bytes = ris_io::read_at(s, value.p_weights)?;
+        let weight_stride = std::mem::size_of::<[f32; 4]>();
+        ris_error::assert!(weight_bytes.len() % weight_stride == 0)?;
+        let weight_count = weight_bytes.len() / weight_stride;
+        ris_error::assert!(joint_count == weight_count)?;
+
+        let tangent_bytes = ris_io::read_at(s, value.p_tangents)?;
+        let tangent_stride = std::mem::size_of::<Vec4>();
+        ris_error::assert!(tangent_bytes.len() % tangent_stride == 0)?;
+        let tangent_count = tangent_bytes.len() / tangent_stride;
+
+        let mut lod_bytes = Vec::with_capacity(value.p_lods.len());
+        for &p_lod in value.p_lods.iter() {
+            let bytes = ris_io::read_at(s, p_lod)?;
+            ris_error::assert!(index_stride == 0 || bytes.len() % index_stride == 0)?;
+            lod_bytes.push(bytes);
+        }
 
         let mut stream = std::io::Cursor::new(vertex_bytes);
         let s = &mut stream;
@@ -248,46 +390,52 @@ impl TryFrom<CpuMesh> for MeshPrototype {
             uvs.push(uv);
         }
 
-        let mut stream = std::io::Cursor::new(index_bytes);
-        let s = &mut stream;
-
-        let indices = match value.index_type {
-            vk::IndexType::UINT16 => {
-                let mut indices = Vec::with_capacity(index_count);
-                for _ in 0..index_count {
-                    let index = ris_io::read_u16(s)?;
-                    indices.push(index);
-                }
+        let indices = read_indices(index_bytes, value.index_type)?;
 
-                Indices::U16(indices)
+        let mut stream = std::io::Cursor::new(joint_bytes);
+        let s = &mut stream;
+        let mut joints = Vec::with_capacity(joint_count);
+        for _ in 0..joint_count {
+            let mut joint = [0; 4];
+            for index in joint.iter_mut() {
+                *index = ris_io::read_u16(s)?;
             }
-            vk::IndexType::UINT32 => {
-                let mut indices = Vec::with_capacity(index_count);
-                for _ in 0..index_count {
-                    let index = ris_io::read_u32(s)?;
-                    indices.push(index);
-                }
+            joints.push(joint);
+        }
 
-                Indices::U32(indices)
+        let mut stream = std::io::Cursor::new(weight_bytes);
+        let s = &mut stream;
+        let mut weights = Vec::with_capacity(weight_count);
+        for _ in 0..weight_count {
+            let mut weight = [0.0; 4];
+            for value in weight.iter_mut() {
+                *value = ris_io::read_f32(s)?;
             }
-            vk::IndexType::UINT8_EXT => {
-                let mut indices = Vec::with_capacity(index_count);
-                for _ in 0..index_count {
-                    let index = ris_io::read_u8(s)?;
-                    indices.push(index);
-                }
+            weights.push(weight);
+        }
 
-                Indices::U8(indices)
-            }
-            vk::IndexType::NONE_KHR => Indices::None,
-            index_type => ris_error::new_result!("unkown index type: {:?}", index_type)?,
-        };
+        let mut stream = std::io::Cursor::new(tangent_bytes);
+        let s = &mut stream;
+        let mut tangents = Vec::with_capacity(tangent_count);
+        for _ in 0..tangent_count {
+            let tangent = ris_io::read_vec4(s)?;
+            tangents.push(tangent);
+        }
+
+        let lods = lod_bytes
+            .into_iter()
+            .map(|x| read_indices(x, value.index_type))
+            .collect::<RisResult<Vec<_>>>()?;
 
         Ok(Self {
             vertices,
             normals,
             uvs,
             indices,
+            joints,
+            weights,
+            tangents,
+            lods,
         })
     }
 }
@@ -299,27 +447,14 @@ impl TryFrom<MeshPrototype> for CpuMesh {
         let len = value.vertices.len();
         ris_error::assert!(value.normals.len() == len)?;
         ris_error::assert!(value.uvs.len() == len)?;
-
-        match &value.indices {
-            Indices::U16(indices) => {
-                for &index in indices.iter() {
-                    let index = usize::from(index);
-                    ris_error::assert!(index < len)?;
-                }
-            }
-            Indices::U32(indices) => {
-                for &index in indices.iter() {
-                    let index = usize::try_from(index)?;
-                    ris_error::assert!(index < len)?;
-                }
-            }
-            Indices::U8(indices) => {
-                for &index in indices.iter() {
-                    let index = usize::from(index);
-                    ris_error::assert!(index < len)?;
-                }
-            }
-            Indices::None => (),
+        ris_error::assert!(value.joints.len() == value.weights.len())?;
+        ris_error::assert!(value.joints.is_empty() || value.joints.len() == len)?;
+        ris_error::assert!(value.tangents.is_empty() || value.tangents.len() == len)?;
+
+        validate_indices(&value.indices, len)?;
+        for lod in value.lods.iter() {
+            ris_error::assert!(lod.index_type() == value.indices.index_type())?;
+            validate_indices(lod, len)?;
         }
 
         let mut cursor = std::io::Cursor::new(Vec::new());
@@ -338,36 +473,60 @@ impl TryFrom<MeshPrototype> for CpuMesh {
             ris_io::write_vec2(s, uv)?;
         }
         let indices_addr = ris_io::seek(s, SeekFrom::Current(0))?;
-        let index_type = match value.indices {
-            Indices::U16(indices) => {
-                for index in indices {
+        let index_type = write_indices(s, value.indices)?;
+        let end = ris_io::seek(s, SeekFrom::Current(0))?;
+
+        let p_vertices = FatPtr::begin_end(vertices_addr, normals_addr)?;
+        let p_normals = FatPtr::begin_end(normals_addr, uv_addr)?;
+        let p_uvs = FatPtr::begin_end(uv_addr, indices_addr)?;
+        let p_indices = FatPtr::begin_end(indices_addr, end)?;
+
+        // the skin is appended after the indices, such that a mesh without skin has the same
+        // layout as before
+        let (p_joints, p_weights) = if value.joints.is_empty() {
+            (FatPtr::null(), FatPtr::null())
+        } else {
+            let joints_addr = end;
+            for joint in value.joints {
+                for index in joint {
                     ris_io::write_u16(s, index)?;
                 }
-
-                vk::IndexType::UINT16
             }
-            Indices::U32(indices) => {
-                for index in indices {
-                    ris_io::write_u32(s, index)?;
+            let weights_addr = ris_io::seek(s, SeekFrom::Current(0))?;
+            for weight in value.weights {
+                for value in weight {
+                    ris_io::write_f32(s, value)?;
                 }
-
-                vk::IndexType::UINT32
             }
-            Indices::U8(indices) => {
-                for index in indices {
-                    ris_io::write_u8(s, index)?;
-                }
+            let skin_end = ris_io::seek(s, SeekFrom::Current(0))?;
 
-                vk::IndexType::UINT8_EXT
+            (
+                FatPtr::begin_end(joints_addr, weights_addr)?,
+                FatPtr::begin_end(weights_addr, skin_end)?,
+            )
+        };
+
+        // like the skin, the tangents are appended
+        let p_tangents = if value.tangents.is_empty() {
+            FatPtr::null()
+        } else {
+            let tangents_addr = ris_io::seek(s, SeekFrom::Current(0))?;
+            for tangent in value.tangents {
+                ris_io::write_vec4(s, tangent)?;
             }
-            Indices::None => vk::IndexType::NONE_KHR,
+            let tangents_end = ris_io::seek(s, SeekFrom::Current(0))?;
+
+            FatPtr::begin_end(tangents_addr, tangents_end)?
         };
-        let end = ris_io::seek(s, SeekFrom::Current(0))?;
 
-        let p_vertices = FatPtr::begin_end(vertices_addr, normals_addr)?;
-        let p_normals = FatPtr::begin_end(normals_addr, uv_addr)?;
-        let p_uvs = FatPtr::begin_end(uv_addr, indices_addr)?;
-        let p_indices = FatPtr::begin_end(indices_addr, end)?;
+        // and so are the lods
+        let mut p_lods = Vec::with_capacity(value.lods.len());
+        for lod in value.lods {
+            let lod_addr = ris_io::seek(s, SeekFrom::Current(0))?;
+            write_indices(s, lod)?;
+            let lod_end = ris_io::seek(s, SeekFrom::Current(0))?;
+            p_lods.push(FatPtr::begin_end(lod_addr, lod_end)?);
+        }
         let data = cursor.into_inner();
 
         Ok(CpuMesh {
@@ -375,12 +534,132 @@ impl TryFrom<MeshPrototype> for CpuMesh {
             p_normals,
             p_uvs,
             p_indices,
+            p_joints,
+            p_weights,
+            p_tangents,
+            p_lods,
             index_type,
             data,
         })
     }
 }
 
+fn validate_indices(indices: &Indices, vertex_count: usize) -> RisResult<()> {
+    for index in indices.usize_iter() {
+        ris_error::assert!(index < vertex_count)?;
+    }
+
+    Ok(())
+}
+
+fn read_indices(bytes: Vec<u8>, index_type: vk::IndexType) -> RisResult<Indices> {
+    let index_count = match Indices::stride_of(index_type) {
+        0 => 0,
+        stride => bytes.len() / stride,
+    };
+
+    let mut stream = std::io::Cursor::new(bytes);
+    let s = &mut stream;
+
+    let indices = match index_type {
+        vk::IndexType::UINT16 => {
+            let mut indices = Vec::with_capacity(index_count);
+            for _ in 0..index_count {
+                let index = ris_io::read_u16(s)?;
+                indices.push(index);
+            }
+
+            Indices::U16(indices)
+        }
+        vk::IndexType::UINT32 => {
+            let mut indices = Vec::with_capacity(index_count);
+            for _ in 0..index_count {
+                let index = ris_io::read_u32(s)?;
+                indices.push(index);
+            }
+
+            Indices::U32(indices)
+        }
+        vk::IndexType::UINT8_EXT => {
+            let mut indices = Vec::with_capacity(index_count);
+            for _ in 0..index_count {
+                let index = ris_io::read_u8(s)?;
+                indices.push(index);
+            }
+
+            Indices::U8(indices)
+        }
+        vk::IndexType::NONE_KHR => Indices::None,
+        index_type => ris_error::new_result!("unkown index type: {:?}", index_type)?,
+    };
+
+    Ok(indices)
+}
+
+fn write_indices(stream: &mut Cursor<Vec<u8>>, indices: Indices) -> RisResult<vk::IndexType> {
+    let s = stream;
+    let index_type = match indices {
+        Indices::U16(indices) => {
+            for index in indices {
+                ris_io::write_u16(s, index)?;
+            }
+
+            vk::IndexType::UINT16
+        }
+        Indices::U32(indices) => {
+            for index in indices {
+                ris_io::write_u32(s, index)?;
+            }
+
+            vk::IndexType::UINT32
+        }
+        Indices::U8(indices) => {
+            for index in indices {
+                ris_io::write_u8(s, index)?;
+            }
+
+            vk::IndexType::UINT8_EXT
+        }
+        Indices::None => vk::IndexType::NONE_KHR,
+    };
+
+    Ok(index_type)
+}
+
+/// A mesh, which covers at least this fraction of the height of the screen, is drawn with full
+/// detail.
+pub const LOD_FULL_DETAIL_COVERAGE: f32 = 0.5;
+
+/// Selects the level of detail for a mesh, which covers `screen_coverage` of the height of the
+/// screen. `index_counts` are the index counts of all levels of detail, starting with the full
+/// mesh. Because the number of covered pixels shrinks with the square of the coverage, the
+/// coarsest level is chosen, which keeps at least the square of the coverage relative to
+/// `LOD_FULL_DETAIL_COVERAGE` of the triangles.
+pub fn select_lod(index_counts: &[u32], screen_coverage: f32) -> usize {
+    let Some(&full_count) = index_counts.first() else {
+        return 0;
+    };
+
+    if full_count == 0 || screen_coverage.is_nan() {
+        return 0;
+    }
+
+    let relative_coverage = screen_coverage / LOD_FULL_DETAIL_COVERAGE;
+    let required_ratio = relative_coverage * relative_coverage;
+
+    let mut lod = 0;
+    for (i, &index_count) in index_counts.iter().enumerate().skip(1) {
+        let ratio = index_count as f32 / full_count as f32;
+        if ratio < required_ratio {
+            break;
+        }
+
+        lod = i;
+    }
+
+    lod
+}
+
 impl GpuMesh {
     pub fn free(&mut self, device: &ash::Device) {
         if let Some(inner) = self.inner.take() {
@@ -428,8 +707,12 @@ impl GpuMesh {
                 p_normals: Default::default(),
                 p_uvs: Default::default(),
                 p_indices: Default::default(),
-                index_count: Default::default(),
+                p_skin: Default::default(),
+                p_tangents: Default::default(),
                 index_type: Default::default(),
+                lod_offsets: Default::default(),
+                lod_index_counts: Default::default(),
+                bounding_sphere: (Vec3::zero(), 0.0),
                 buffer,
             }),
         };
@@ -484,6 +767,16 @@ impl GpuMesh {
         let p_normals = value.p_normals.addr;
         let p_uvs = value.p_uvs.addr;
         let p_indices = value.p_indices.addr;
+        let p_skin = if value.p_joints.is_null() {
+            None
+        } else {
+            Some([value.p_joints.addr, value.p_weights.addr])
+        };
+        let p_tangents = if value.p_tangents.is_null() {
+            None
+        } else {
+            Some(value.p_tangents.addr)
+        };
         let index_size = match value.index_type {
             vk::IndexType::UINT16 => std::mem::size_of::<u16>(),
             vk::IndexType::UINT32 => std::mem::size_of::<u32>(),
@@ -492,16 +785,23 @@ impl GpuMesh {
             index_type => ris_error::new_result!("unknown index type: {:?}", index_type)?,
         };
 
-        let index_count = value.p_indices.len as u32 / index_size as u32;
         let index_type = value.index_type;
+        let p_lods = std::iter::once(&value.p_indices).chain(value.p_lods.iter());
+        let lod_offsets = p_lods.clone().map(|x| x.addr).collect();
+        let lod_index_counts = p_lods.map(|x| x.len as u32 / index_size as u32).collect();
+        let bounding_sphere = bounding_sphere(&value)?;
 
         // assign values
         inner.p_vertices = p_vertices;
         inner.p_normals = p_normals;
         inner.p_uvs = p_uvs;
         inner.p_indices = p_indices;
-        inner.index_count = index_count;
+        inner.p_skin = p_skin;
+        inner.p_tangents = p_tangents;
         inner.index_type = index_type;
+        inner.lod_offsets = lod_offsets;
+        inner.lod_index_counts = lod_index_counts;
+        inner.bounding_sphere = bounding_sphere;
 
         // write to gpu
         let device = &transient_command_args.device.clone();
@@ -545,7 +845,7 @@ impl GpuMesh {
 
     pub fn index_count(&self) -> RisResult<u32> {
         let inner = self.get_inner()?;
-        Ok(inner.index_count)
+        inner.lod_index_counts.first().copied().into_ris_error()
     }
 
     pub fn index_type(&self) -> RisResult<vk::IndexType> {
@@ -553,6 +853,52 @@ impl GpuMesh {
         Ok(inner.index_type)
     }
 
+    pub fn is_skinned(&self) -> RisResult<bool> {
+        let inner = self.get_inner()?;
+        Ok(inner.p_skin.is_some())
+    }
+
+    /// The offsets of the joints and weights, or `None` if the mesh is not skinned. Both live in
+    /// the same buffer as the vertices.
+    pub fn skin_offsets(&self) -> RisResult<Option<[vk::DeviceSize; 2]>> {
+        let inner = self.get_inner()?;
+        Ok(inner.p_skin)
+    }
+
+    /// The offset of the tangents, or `None` if the mesh has no tangents. They live in the same
+    /// buffer as the vertices.
+    pub fn tangent_offset(&self) -> RisResult<Option<vk::DeviceSize>> {
+        let inner = self.get_inner()?;
+        Ok(inner.p_tangents)
+    }
+
+    /// The number of levels of detail, including the full mesh.
+    pub fn lod_count(&self) -> RisResult<usize> {
+        let inner = self.get_inner()?;
+        Ok(inner.lod_index_counts.len())
+    }
+
+    /// The offset and the index count of a level of detail. Level 0 is the full mesh. All levels
+    /// use the same vertices and index type.
+    pub fn lod_indices(&self, lod: usize) -> RisResult<(vk::DeviceSize, u32)> {
+        let inner = self.get_inner()?;
+        let offset = inner.lod_offsets.get(lod).copied().into_ris_error()?;
+        let index_count = inner.lod_index_counts.get(lod).copied().into_ris_error()?;
+        Ok((offset, index_count))
+    }
+
+    /// See `select_lod()`.
+    pub fn select_lod(&self, screen_coverage: f32) -> RisResult<usize> {
+        let inner = self.get_inner()?;
+        Ok(select_lod(&inner.lod_index_counts, screen_coverage))
+    }
+
+    /// The center and radius of a sphere in model space, which contains all vertices.
+    pub fn bounding_sphere(&self) -> RisResult<(Vec3, f32)> {
+        let inner = self.get_inner()?;
+        Ok(inner.bounding_sphere)
+    }
+
     fn get_inner(&self) -> RisResult<&GpuMeshInner> {
         match self.inner.as_ref() {
             Some(inner) => Ok(inner),
@@ -560,3 +906,35 @@ impl GpuMesh {
         }
     }
 }
+
+/// The sphere is centered at the center of the bounding box of the vertices.
+fn bounding_sphere(value: &CpuMesh) -> RisResult<(Vec3, f32)> {
+    let mut stream = Cursor::new(value.data.as_slice());
+    let vertex_bytes = ris_io::read_at(&mut stream, value.p_vertices)?;
+    let vertex_count = vertex_bytes.len() / std::mem::size_of::<Vec3>();
+
+    let mut stream = Cursor::new(vertex_bytes);
+    let mut vertices = Vec::with_capacity(vertex_count);
+    for _ in 0..vertex_count {
+        vertices.push(ris_io::read_vec3(&mut stream)?);
+    }
+
+    let Some(&first) = vertices.first() else {
+        return Ok((Vec3::zero(), 0.0));
+    };
+
+    let mut min = first;
+    let mut max = first;
+    for &vertex in vertices.iter() {
+        min = Vec3::min(min, vertex);
+        max = Vec3::max(max, vertex);
+    }
+
+    let center = 0.5 * (min + max);
+    let radius = vertices
+        .iter()
+        .map(|&x| (x - center).length())
+        .fold(0.0, f32::max);
+
+    Ok((center, radius))
+}
@@ -0,0 +1,164 @@
+use ris_error::prelude::*;
+use ris_math::affine;
+use ris_math::matrix::Mat4;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+
+/// The local transform of a joint, relative to its parent.
+#[derive(Debug, Clone, Copy)]
+pub struct JointTransform {
+    pub translation: Vec3,
+    pub rotation: Quat,
+    pub scale: Vec3,
+}
+
+#[derive(Debug, Clone)]
+pub struct Joint {
+    pub name: String,
+    /// the index of the parent joint. `None` for root joints
+    pub parent: Option<usize>,
+    pub rest: JointTransform,
+    /// transforms a vertex from mesh space to the local space of the joint, at bind time
+    pub inverse_bind_matrix: Mat4,
+}
+
+/// The joints are in the order of the joint indices of the skinned mesh.
+#[derive(Debug, Clone, Default)]
+pub struct Skeleton {
+    pub joints: Vec<Joint>,
+}
+
+/// A local transform for each joint of a skeleton.
+#[derive(Debug, Clone, Default)]
+pub struct Pose {
+    pub joints: Vec<JointTransform>,
+}
+
+impl Default for JointTransform {
+    fn default() -> Self {
+        Self {
+            translation: Vec3::init(0.0),
+            rotation: Quat::identity(),
+            scale: Vec3::init(1.0),
+        }
+    }
+}
+
+impl JointTransform {
+    pub fn to_matrix(self) -> Mat4 {
+        affine::trs(self.translation, self.rotation, self.scale)
+    }
+
+    /// `a` = 0 returns `self`, `a` = 1 returns `other`
+    pub fn blend(self, other: Self, a: f32) -> Self {
+        Self {
+            translation: self.translation + (other.translation - self.translation) * a,
+            rotation: self.rotation.slerp(other.rotation, a),
+            scale: self.scale + (other.scale - self.scale) * a,
+        }
+    }
+}
+
+impl Skeleton {
+    pub fn validate(&self) -> RisResult<()> {
+        let count = self.joints.len();
+        for (i, joint) in self.joints.iter().enumerate() {
+            // walking up more parents than there are joints means there is a cycle
+            let mut parent = joint.parent;
+            let mut steps = 0;
+            while let Some(index) = parent {
+                if index >= count {
+                    return ris_error::new_result!("joint {} has an invalid parent: {}", i, index,);
+                }
+
+                steps += 1;
+                if steps > count {
+                    return ris_error::new_result!("joint {} is part of a cycle", i);
+                }
+
+                parent = self.joints[index].parent;
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn rest_pose(&self) -> Pose {
+        Pose {
+            joints: self.joints.iter().map(|x| x.rest).collect(),
+        }
+    }
+}
+
+impl Pose {
+    /// `a` = 0 returns `self`, `a` = 1 returns `other`
+    pub fn blend(&self, other: &Pose, a: f32) -> RisResult<Pose> {
+        ris_error::assert!(self.joints.len() == other.joints.len())?;
+
+        let joints = self
+            .joints
+            .iter()
+            .zip(other.joints.iter())
+            .map(|(left, right)| left.blend(*right, a))
+            .collect();
+
+        Ok(Pose { joints })
+    }
+
+    /// Blends the poses by their weights. The weights don't need to add up to 1. Returns `rest`,
+    /// if the weights add up to 0.
+    pub fn blend_weighted(rest: Pose, layers: &[(Pose, f32)]) -> RisResult<Pose> {
+        let mut result = rest;
+        let mut accumulated_weight = 0.0;
+
+        for (pose, weight) in layers.iter() {
+            let weight = f32::max(*weight, 0.0);
+            if weight == 0.0 {
+                continue;
+            }
+
+            accumulated_weight += weight;
+            result = result.blend(pose, weight / accumulated_weight)?;
+        }
+
+        Ok(result)
+    }
+
+    /// The matrices that transform a vertex from its bind pose to this pose, one for each joint.
+    pub fn joint_matrices(&self, skeleton: &Skeleton) -> RisResult<Vec<Mat4>> {
+        ris_error::assert!(self.joints.len() == skeleton.joints.len())?;
+        skeleton.validate()?;
+
+        let count = self.joints.len();
+        let mut globals: Vec<Option<Mat4>> = vec![None; count];
+        for i in 0..count {
+            // collect the joints, whose global transform is not known yet, from the bottom up
+            let mut chain = Vec::new();
+            let mut current = Some(i);
+            while let Some(index) = current {
+                if globals[index].is_some() {
+                    break;
+                }
+
+                chain.push(index);
+                current = skeleton.joints[index].parent;
+            }
+
+            for &index in chain.iter().rev() {
+                let local = self.joints[index].to_matrix();
+                let global = match skeleton.joints[index].parent {
+                    Some(parent) => globals[parent].into_ris_error()? * local,
+                    None => local,
+                };
+                globals[index] = Some(global);
+            }
+        }
+
+        let mut joint_matrices = Vec::with_capacity(count);
+        for (global, joint) in globals.into_iter().zip(skeleton.joints.iter()) {
+            joint_matrices.push(global.into_ris_error()? * joint.inverse_bind_matrix);
+        }
+
+        Ok(joint_matrices)
+    }
+}
@@ -0,0 +1,376 @@
+use std::sync::Arc;
+
+use ash::vk;
+
+use ris_error::prelude::*;
+use ris_gpu::buffer::Buffer;
+use ris_gpu::texture::Texture;
+use ris_gpu::texture::TextureMipsCreateInfo;
+use ris_gpu::texture::TextureSampler;
+use ris_gpu::transient_command::TransientCommandArgs;
+
+#[derive(Debug, Clone)]
+pub struct TextureLookupId {
+    index: usize,
+    references: Arc<()>,
+}
+
+impl TextureLookupId {
+    pub fn new(index: usize) -> Self {
+        Self {
+            index,
+            references: Arc::default(),
+        }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn is_unique(&mut self) -> bool {
+        Arc::get_mut(&mut self.references).is_some()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextureFormat {
+    Rgba8,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Filter {
+    Nearest,
+    Linear,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressMode {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SamplerSettings {
+    pub mag_filter: Filter,
+    pub min_filter: Filter,
+    pub mipmap_filter: Filter,
+    pub address_mode: AddressMode,
+}
+
+impl Default for SamplerSettings {
+    fn default() -> Self {
+        Self {
+            mag_filter: Filter::Linear,
+            min_filter: Filter::Linear,
+            mipmap_filter: Filter::Linear,
+            address_mode: AddressMode::Repeat,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuTexture {
+    pub width: usize,
+    pub height: usize,
+    pub format: TextureFormat,
+    pub srgb: bool,
+    pub sampler: SamplerSettings,
+    /// the pixels of every mip level, starting with the full resolution image. every following
+    /// level has half the width and height of the previous one, but at least 1
+    pub mips: Vec<Vec<u8>>,
+}
+
+pub struct GpuTexture {
+    inner: Option<Texture>,
+}
+
+impl TextureFormat {
+    pub fn pixel_width(self) -> usize {
+        match self {
+            TextureFormat::Rgba8 => 4,
+        }
+    }
+
+    pub fn to_vk(self, srgb: bool) -> vk::Format {
+        match (self, srgb) {
+            (TextureFormat::Rgba8, true) => vk::Format::R8G8B8A8_SRGB,
+            (TextureFormat::Rgba8, false) => vk::Format::R8G8B8A8_UNORM,
+        }
+    }
+
+    pub fn to_u8(self) -> u8 {
+        match self {
+            TextureFormat::Rgba8 => 0,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> RisResult<Self> {
+        match value {
+            0 => Ok(TextureFormat::Rgba8),
+            value => ris_error::new_result!("unknown texture format: {}", value),
+        }
+    }
+}
+
+impl Filter {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            Filter::Nearest => 0,
+            Filter::Linear => 1,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> RisResult<Self> {
+        match value {
+            0 => Ok(Filter::Nearest),
+            1 => Ok(Filter::Linear),
+            value => ris_error::new_result!("unknown filter: {}", value),
+        }
+    }
+}
+
+impl AddressMode {
+    pub fn to_u8(self) -> u8 {
+        match self {
+            AddressMode::Repeat => 0,
+            AddressMode::MirroredRepeat => 1,
+            AddressMode::ClampToEdge => 2,
+        }
+    }
+
+    pub fn from_u8(value: u8) -> RisResult<Self> {
+        match value {
+            0 => Ok(AddressMode::Repeat),
+            1 => Ok(AddressMode::MirroredRepeat),
+            2 => Ok(AddressMode::ClampToEdge),
+            value => ris_error::new_result!("unknown address mode: {}", value),
+        }
+    }
+}
+
+impl SamplerSettings {
+    pub fn to_vk(self) -> TextureSampler {
+        let filter = |x| match x {
+            Filter::Nearest => vk::Filter::NEAREST,
+            Filter::Linear => vk::Filter::LINEAR,
+        };
+
+        let mipmap_mode = match self.mipmap_filter {
+            Filter::Nearest => vk::SamplerMipmapMode::NEAREST,
+            Filter::Linear => vk::SamplerMipmapMode::LINEAR,
+        };
+
+        let address_mode = match self.address_mode {
+            AddressMode::Repeat => vk::SamplerAddressMode::REPEAT,
+            AddressMode::MirroredRepeat => vk::SamplerAddressMode::MIRRORED_REPEAT,
+            AddressMode::ClampToEdge => vk::SamplerAddressMode::CLAMP_TO_EDGE,
+        };
+
+        TextureSampler {
+            mag_filter: filter(self.mag_filter),
+            min_filter: filter(self.min_filter),
+            mipmap_mode,
+            address_mode,
+        }
+    }
+}
+
+impl CpuTexture {
+    /// creates a texture with a full mip chain, down to 1x1
+    pub fn with_mips(
+        width: usize,
+        height: usize,
+        srgb: bool,
+        sampler: SamplerSettings,
+        pixels: Vec<u8>,
+    ) -> RisResult<Self> {
+        let mips = generate_mips(width, height, srgb, pixels)?;
+        Ok(Self {
+            width,
+            height,
+            format: TextureFormat::Rgba8,
+            srgb,
+            sampler,
+            mips,
+        })
+    }
+
+    pub fn mip_width(&self, mip_level: usize) -> usize {
+        usize::max(self.width >> mip_level, 1)
+    }
+
+    pub fn mip_height(&self, mip_level: usize) -> usize {
+        usize::max(self.height >> mip_level, 1)
+    }
+
+    pub fn validate(&self) -> RisResult<()> {
+        ris_error::assert!(self.width != 0)?;
+        ris_error::assert!(self.height != 0)?;
+        ris_error::assert!(!self.mips.is_empty())?;
+
+        let max_mip_levels = mip_level_count(self.width, self.height);
+        ris_error::assert!(self.mips.len() <= max_mip_levels)?;
+
+        let pixel_width = self.format.pixel_width();
+        for (i, mip) in self.mips.iter().enumerate() {
+            let expected_len = self.mip_width(i) * self.mip_height(i) * pixel_width;
+            if mip.len() != expected_len {
+                return ris_error::new_result!(
+                    "mip {} has {} bytes, but {} were expected",
+                    i,
+                    mip.len(),
+                    expected_len,
+                );
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// the number of mip levels from the full resolution down to 1x1
+pub fn mip_level_count(width: usize, height: usize) -> usize {
+    let max = usize::max(width, height);
+    if max == 0 {
+        0
+    } else {
+        (usize::BITS - max.leading_zeros()) as usize
+    }
+}
+
+/// downsamples rgba8 pixels by averaging 2x2 blocks. if `srgb` is set, color channels are
+/// averaged in linear space. alpha is always linear.
+pub fn generate_mips(
+    width: usize,
+    height: usize,
+    srgb: bool,
+    pixels: Vec<u8>,
+) -> RisResult<Vec<Vec<u8>>> {
+    let pixel_width = TextureFormat::Rgba8.pixel_width();
+    ris_error::assert!(width != 0)?;
+    ris_error::assert!(height != 0)?;
+    ris_error::assert!(pixels.len() == width * height * pixel_width)?;
+
+    let to_linear = |value: u8| {
+        let value = value as f32 / 255.0;
+        if !srgb {
+            value
+        } else if value <= 0.04045 {
+            value / 12.92
+        } else {
+            ((value + 0.055) / 1.055).powf(2.4)
+        }
+    };
+
+    let from_linear = |value: f32| {
+        let value = if !srgb {
+            value
+        } else if value <= 0.0031308 {
+            value * 12.92
+        } else {
+            1.055 * value.powf(1.0 / 2.4) - 0.055
+        };
+        (value * 255.0).round().clamp(0.0, 255.0) as u8
+    };
+
+    let count = mip_level_count(width, height);
+    let mut mips = Vec::with_capacity(count);
+    mips.push(pixels);
+
+    let mut src_width = width;
+    let mut src_height = height;
+    for _ in 1..count {
+        let dst_width = usize::max(src_width / 2, 1);
+        let dst_height = usize::max(src_height / 2, 1);
+        let src = mips.last().into_ris_error()?;
+        let mut dst = Vec::with_capacity(dst_width * dst_height * pixel_width);
+
+        for y in 0..dst_height {
+            for x in 0..dst_width {
+                // a dimension that is already 1 is not halved, thus the block is clamped
+                let x0 = usize::min(x * 2, src_width - 1);
+                let x1 = usize::min(x * 2 + 1, src_width - 1);
+                let y0 = usize::min(y * 2, src_height - 1);
+                let y1 = usize::min(y * 2 + 1, src_height - 1);
+                let block = [(x0, y0), (x1, y0), (x0, y1), (x1, y1)];
+
+                for channel in 0..pixel_width {
+                    let mut sum = 0.0;
+                    for (bx, by) in block {
+                        let value = src[(by * src_width + bx) * pixel_width + channel];
+                        sum += if channel == 3 {
+                            value as f32 / 255.0
+                        } else {
+                            to_linear(value)
+                        };
+                    }
+
+                    let average = sum / 4.0;
+                    let value = if channel == 3 {
+                        (average * 255.0).round().clamp(0.0, 255.0) as u8
+                    } else {
+                        from_linear(average)
+                    };
+                    dst.push(value);
+                }
+            }
+        }
+
+        mips.push(dst);
+        src_width = dst_width;
+        src_height = dst_height;
+    }
+
+    Ok(mips)
+}
+
+impl GpuTexture {
+    pub fn free(&mut self, device: &ash::Device) {
+        if let Some(texture) = self.inner.take() {
+            unsafe { texture.free(device) };
+        }
+    }
+
+    pub fn from_cpu_texture(
+        transient_command_args: TransientCommandArgs,
+        physical_device_memory_properties: vk::PhysicalDeviceMemoryProperties,
+        physical_device_properties: vk::PhysicalDeviceProperties,
+        value: CpuTexture,
+    ) -> RisResult<Self> {
+        value.validate()?;
+
+        let device = transient_command_args.device.clone();
+        let staging = Buffer::alloc_staging(
+            &device,
+            value.mips[0].len(),
+            physical_device_memory_properties,
+        )?;
+
+        let mips = value.mips.iter().map(|x| x.as_slice()).collect::<Vec<_>>();
+        let result = Texture::alloc_mips(TextureMipsCreateInfo {
+            transient_command_args,
+            staging: &staging,
+            physical_device_memory_properties,
+            physical_device_properties,
+            width: value.width,
+            height: value.height,
+            format: value.format.to_vk(value.srgb),
+            sampler: value.sampler.to_vk(),
+            mips: &mips,
+        });
+
+        unsafe { staging.free(&device) };
+
+        Ok(Self {
+            inner: Some(result?),
+        })
+    }
+
+    pub fn texture(&self) -> RisResult<&Texture> {
+        match self.inner.as_ref() {
+            Some(texture) => Ok(texture),
+            None => ris_error::new_result!("gpu texture was freed"),
+        }
+    }
+}
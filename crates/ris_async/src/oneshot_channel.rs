@@ -24,6 +24,15 @@ pub struct OneshotReceiver<T> {
 
 unsafe impl<T> Sync for OneshotChannel<T> where T: Send {}
 
+impl<T> Drop for OneshotChannel<T> {
+    fn drop(&mut self) {
+        // a value that was sent but never received must still be dropped
+        if *self.ready.get_mut() {
+            unsafe { self.data.get_mut().assume_init_drop() };
+        }
+    }
+}
+
 pub fn oneshot_channel<T>() -> (OneshotSender<T>, OneshotReceiver<T>) {
     let channel = Arc::new(OneshotChannel {
         ready: AtomicBool::new(false),
@@ -43,6 +52,12 @@ impl<T> OneshotSender<T> {
         unsafe { (*self.channel.data.get()).write(value) };
         self.channel.ready.store(true, Ordering::Release);
     }
+
+    /// Whether the receiver was dropped. If so, a sent value would never be received, and the
+    /// work to produce it may be skipped.
+    pub fn is_cancelled(&self) -> bool {
+        Arc::strong_count(&self.channel) == 1
+    }
 }
 
 impl<T> OneshotReceiver<T> {
@@ -367,6 +367,12 @@ impl ThreadPool {
         })
     }
 
+    /// Whether the calling thread belongs to an initialized thread pool, and thus may submit
+    /// and block on futures.
+    pub fn is_worker() -> bool {
+        get_worker().is_some()
+    }
+
     pub fn submit<F: Future + 'static>(future: F) -> JobFuture<F::Output> {
         let Some(worker) = get_worker() else {
             ris_error::throw!("cannot submit future, caller is not a worker");
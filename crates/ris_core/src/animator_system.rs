@@ -0,0 +1,76 @@
+use ris_asset::asset_watcher;
+use ris_asset::asset_watcher::AssetSubscription;
+use ris_asset::lookup::ris_animation_lookup::AnimationLookup;
+use ris_data::ecs::components::animator_component::AnimatorComponent;
+use ris_data::ecs::scene::Scene;
+use ris_data::gameloop::frame::Frame;
+use ris_error::prelude::*;
+
+/// Advances every `AnimatorComponent` once per frame. The scene renderer skins the meshes with
+/// the resulting joint matrices.
+pub struct AnimatorSystem {
+    lookup: AnimationLookup,
+    subscription: AssetSubscription,
+}
+
+impl AnimatorSystem {
+    pub fn new() -> Self {
+        Self {
+            lookup: AnimationLookup::default(),
+            subscription: asset_watcher::subscribe_all(),
+        }
+    }
+
+    pub fn update(&mut self, frame: Frame, scene: &Scene) -> RisResult<()> {
+        let changed_assets = self.subscription.take_changed();
+        if !changed_assets.is_empty() {
+            self.lookup.reload(&changed_assets);
+        }
+
+        let delta = frame.average_seconds();
+
+        let animators = scene.query().components::<AnimatorComponent>()?;
+        for handle in animators {
+            let Ok(ptr) = scene.deref(handle) else {
+                continue;
+            };
+            let mut animator = ptr.borrow_mut();
+
+            let Some(skeleton_id) = animator.skeleton() else {
+                continue;
+            };
+
+            // request everything, such that all assets load in parallel
+            let mut is_loaded = self.lookup.request_skeleton(&skeleton_id);
+            for clip in animator.clips() {
+                is_loaded &= self.lookup.request_clip(&clip.asset_id);
+            }
+
+            if !is_loaded {
+                continue;
+            }
+
+            let skeleton = self.lookup.skeleton(&skeleton_id).into_ris_error()?;
+            let clips = animator
+                .clips()
+                .iter()
+                .map(|x| self.lookup.clip(&x.asset_id))
+                .collect::<Option<Vec<_>>>()
+                .into_ris_error()?;
+
+            if let Err(e) = animator.evaluate(delta, skeleton, &clips) {
+                ris_log::error!("failed to evaluate animator {:?}: {}", handle, e);
+            }
+        }
+
+        self.lookup.free_unused();
+
+        Ok(())
+    }
+}
+
+impl Default for AnimatorSystem {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -3,6 +3,10 @@ use sdl2::event::WindowEvent;
 
 use ris_async::ThreadPool;
 use ris_async::ThreadPoolCreateInfo;
+use ris_data::ecs::components::script_component::DynScriptComponent;
+use ris_data::ecs::decl::DynScriptComponentHandle;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::schedule::ScriptSchedule;
 use ris_data::ecs::script_prelude::*;
 use ris_data::gameloop::gameloop_state::GameloopState;
 use ris_input::general_logic::update_general;
@@ -21,7 +25,7 @@ pub fn run(mut god_object: GodObject) -> RisResult<WantsTo> {
 
     loop {
         ris_debug::profiler::new_frame()?;
-        let frame = frame_calculator.bump_and_create_frame();
+        let frame = frame_calculator.bump_and_create_frame(god_object.state.settings.simulation());
 
         // reset events
         let mut r = ris_debug::new_record!("main loop");
@@ -89,14 +93,34 @@ pub fn run(mut god_object: GodObject) -> RisResult<WantsTo> {
         update_general(&mut god_object.state);
 
         // update scripts
-        ris_debug::add_record!(r, "update scripts")?;
-        for script in god_object.state.scene.script_components.iter() {
-            let mut aref_mut = script.borrow_mut();
-            if aref_mut.is_alive {
-                aref_mut.update(frame, &god_object.state)?;
-            }
+        ris_debug::add_record!(r, "schedule scripts")?;
+        let scene = &god_object.state.scene;
+        let script_components = scene
+            .query()
+            .components::<DynScriptComponent>()?
+            .into_iter()
+            .map(DynScriptComponentHandle::from)
+            .collect::<Vec<_>>();
+        let script_schedule = ScriptSchedule::new(scene, &script_components)?;
+
+        ris_debug::add_record!(r, "fixed update scripts")?;
+        for fixed_frame in frame.fixed_frames() {
+            scene.begin_fixed_update();
+            script_schedule.run_fixed(fixed_frame, &god_object.state)?;
+            apply_scene_commands(scene);
+            scene.end_fixed_update();
         }
 
+        ris_debug::add_record!(r, "update scripts")?;
+        script_schedule.run(frame, &god_object.state)?;
+
+        ris_debug::add_record!(r, "apply scene commands")?;
+        apply_scene_commands(scene);
+
+        // animate
+        ris_debug::add_record!(r, "update animators")?;
+        god_object.animator_system.update(frame, scene)?;
+
         // render
         ris_debug::add_record!(r, "gpu frame")?;
         let gpu_result =
@@ -165,11 +189,17 @@ pub fn run(mut god_object: GodObject) -> RisResult<WantsTo> {
         };
 
         // shutdown
-        for script in god_object.state.scene.script_components.iter() {
-            let mut aref_mut = script.borrow_mut();
-            if aref_mut.is_alive {
-                aref_mut.end(&god_object.state.scene)?;
-            }
+        let scene = &god_object.state.scene;
+        let script_components = scene
+            .query()
+            .include_inactive()
+            .components::<DynScriptComponent>()?;
+        for handle in script_components {
+            let Ok(ptr) = scene.deref(handle) else {
+                continue;
+            };
+
+            ptr.borrow_mut().end(scene)?;
         }
 
         god_object.gpu_frame.wait_idle()?;
@@ -177,3 +207,11 @@ pub fn run(mut god_object: GodObject) -> RisResult<WantsTo> {
         return Ok(wants_to);
     }
 }
+
+/// Applies the commands, that were recorded by scripts. A failing command is logged, but does
+/// not end the game loop.
+pub fn apply_scene_commands(scene: &Scene) {
+    if let Err(e) = scene.apply_commands() {
+        ris_log::error!("failed to apply scene commands: {}", e);
+    }
+}
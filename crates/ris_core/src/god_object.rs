@@ -25,6 +25,7 @@ use ris_gpu::core::VulkanCore;
 use ris_gpu_renderers::ImguiBackend;
 use ris_input::gamepad_logic::GamepadLogic;
 
+use crate::animator_system::AnimatorSystem;
 use crate::gpu_frame::GpuFrame;
 use crate::gpu_frame::Renderer;
 #[cfg(feature = "ui_helper_enabled")]
@@ -38,6 +39,7 @@ pub struct GodObject {
     pub keyboard_util: KeyboardUtil,
     pub gamepad_logic: GamepadLogic,
     pub gpu_frame: GpuFrame,
+    pub animator_system: AnimatorSystem,
     pub god_asset: RisGodAsset,
     pub state: GodState,
 
@@ -178,6 +180,7 @@ impl GodObject {
             keyboard_util,
             gamepad_logic,
             gpu_frame,
+            animator_system: AnimatorSystem::new(),
             god_asset,
             state,
 
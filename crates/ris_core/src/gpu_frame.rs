@@ -4,7 +4,9 @@ use ash::vk;
 use sdl2::video::Window;
 use sdl2_sys::SDL_WindowFlags;
 
+use ris_asset::lookup::ris_material_lookup::MaterialLookup;
 use ris_asset::lookup::ris_mesh_lookup::MeshLookup;
+use ris_asset::lookup::ris_texture_lookup::TextureLookup;
 use ris_asset::RisGodAsset;
 use ris_data::gameloop::frame::Frame;
 use ris_data::gameloop::gameloop_state::GameloopState;
@@ -47,6 +49,13 @@ pub struct RendererIds {
     imgui: RendererId,
 }
 
+/// The lookups of the scene renderer, which are kept when the renderers are recreated.
+pub struct SceneLookups {
+    mesh: MeshLookup,
+    texture: TextureLookup,
+    material: MaterialLookup,
+}
+
 impl Renderer {
     /// # Safety
     ///
@@ -85,7 +94,7 @@ impl Renderer {
     fn alloc_internal(
         core: &VulkanCore,
         god_asset: &RisGodAsset,
-        mesh_lookup: Option<MeshLookup>,
+        lookups: Option<SceneLookups>,
         renderer_ids: Option<RendererIds>,
         #[cfg(feature = "ui_helper_enabled")] imgui_context: &mut imgui::Context,
         frames_in_flight: Option<FramesInFlight>,
@@ -103,8 +112,24 @@ impl Renderer {
             existing_id: None,
         };
 
+        let (mesh_lookup, texture_lookup, material_lookup) = match lookups {
+            Some(SceneLookups {
+                mesh,
+                texture,
+                material,
+            }) => (Some(mesh), Some(texture), Some(material)),
+            None => (None, None, None),
+        };
+
         renderer_registerer.existing_id = renderer_ids.as_ref().map(|x| x.scene);
-        let scene = SceneRenderer::alloc(core, god_asset, mesh_lookup, &mut renderer_registerer)?;
+        let scene = SceneRenderer::alloc(
+            core,
+            god_asset,
+            mesh_lookup,
+            texture_lookup,
+            material_lookup,
+            &mut renderer_registerer,
+        )?;
         renderer_registerer.existing_id = renderer_ids.as_ref().map(|x| x.gizmo_segment);
         let gizmo_segment = GizmoSegmentRenderer::alloc(core, god_asset, &mut renderer_registerer)?;
         renderer_registerer.existing_id = renderer_ids.as_ref().map(|x| x.gizmo_text);
@@ -148,12 +173,22 @@ impl Renderer {
             ..
         } = core;
 
-        let mut mesh_lookup = self.scene.mesh_lookup.take().into_ris_error()?;
-        mesh_lookup.reimport_everything(TransientCommandArgs {
+        let transient_command_args = TransientCommandArgs {
             device: device.clone(),
             queue: *graphics_queue,
             command_pool: *transient_command_pool,
-        });
+        };
+
+        let mut mesh_lookup = self.scene.mesh_lookup.take().into_ris_error()?;
+        mesh_lookup.reimport_everything(transient_command_args.clone());
+        let mut texture_lookup = self.scene.texture_lookup.take().into_ris_error()?;
+        texture_lookup.reimport_everything(transient_command_args);
+        let material_lookup = self.scene.material_lookup.take().into_ris_error()?;
+        let lookups = SceneLookups {
+            mesh: mesh_lookup,
+            texture: texture_lookup,
+            material: material_lookup,
+        };
 
         let renderer_ids = RendererIds {
             scene: self.scene.renderer_id,
@@ -169,7 +204,7 @@ impl Renderer {
         *self = Self::alloc_internal(
             core,
             god_asset,
-            Some(mesh_lookup),
+            Some(lookups),
             Some(renderer_ids),
             #[cfg(feature = "ui_helper_enabled")]
             imgui_context,
@@ -178,6 +213,102 @@ impl Renderer {
 
         Ok(())
     }
+
+    /// Rebuilds the renderers, whose shaders or textures changed on disk. A renderer that fails
+    /// to build, for example because a shader is invalid, is kept as it is.
+    fn rebuild_changed(
+        &mut self,
+        core: &VulkanCore,
+        god_asset: &RisGodAsset,
+        #[cfg(feature = "ui_helper_enabled")] imgui_context: &mut imgui::Context,
+    ) -> RisResult<()> {
+        let device = &core.device;
+
+        if self.scene.take_assets_changed() {
+            let scene = rebuild(core, "scene", self.scene.renderer_id, |x| {
+                SceneRenderer::alloc(core, god_asset, None, None, None, x)
+            })?;
+
+            if let Some(mut scene) = scene {
+                scene.mesh_lookup = self.scene.mesh_lookup.take();
+                scene.texture_lookup = self.scene.texture_lookup.take();
+                scene.material_lookup = self.scene.material_lookup.take();
+                unsafe { self.scene.free(device) };
+                self.scene = scene;
+            }
+        }
+
+        if self.gizmo_segment.take_assets_changed() {
+            let gizmo_segment =
+                rebuild(core, "gizmo segment", self.gizmo_segment.renderer_id, |x| {
+                    GizmoSegmentRenderer::alloc(core, god_asset, x)
+                })?;
+
+            if let Some(gizmo_segment) = gizmo_segment {
+                unsafe { self.gizmo_segment.free(device) };
+                self.gizmo_segment = gizmo_segment;
+            }
+        }
+
+        if self.gizmo_text.take_assets_changed() {
+            let gizmo_text = rebuild(core, "gizmo text", self.gizmo_text.renderer_id, |x| {
+                GizmoTextRenderer::alloc(core, god_asset, x)
+            })?;
+
+            if let Some(gizmo_text) = gizmo_text {
+                unsafe { self.gizmo_text.free(device) };
+                self.gizmo_text = gizmo_text;
+            }
+        }
+
+        #[cfg(feature = "ui_helper_enabled")]
+        if self.imgui.take_assets_changed() {
+            let imgui = rebuild(core, "imgui", self.imgui.renderer_id, |x| {
+                ImguiRenderer::alloc(core, god_asset, imgui_context, x)
+            })?;
+
+            if let Some(imgui) = imgui {
+                unsafe { self.imgui.free(device) };
+                self.imgui = imgui;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Allocates a renderer, that replaces the one with the given id. When the allocation succeeds,
+/// this waits for the device to be idle, such that the caller can free the old renderer.
+fn rebuild<T>(
+    core: &VulkanCore,
+    name: &str,
+    renderer_id: RendererId,
+    alloc: impl FnOnce(&mut RendererRegisterer) -> RisResult<T>,
+) -> RisResult<Option<T>> {
+    ris_log::debug!("rebuilding {} renderer...", name);
+
+    let mut renderer_registerer = RendererRegisterer {
+        info: FrameInFlightCreateInfo {
+            debugger: &core.debugger,
+            suitable_device: &core.suitable_device,
+            device: &core.device,
+            renderer_count: 0,
+            secondary_command_buffer_count: 0,
+        },
+        existing_id: Some(renderer_id),
+    };
+
+    match alloc(&mut renderer_registerer) {
+        Ok(renderer) => {
+            unsafe { core.device.device_wait_idle() }?;
+            ris_log::info!("rebuilt {} renderer!", name);
+            Ok(Some(renderer))
+        }
+        Err(e) => {
+            ris_log::error!("failed to rebuild {} renderer: {}", name, e);
+            Ok(None)
+        }
+    }
 }
 
 pub struct GpuFrame {
@@ -256,7 +387,6 @@ impl GpuFrame {
 
             #[cfg(not(feature = "ui_helper_enabled"))]
             {
-                let _ = frame;
                 GameloopState::WantsToContinue
             }
         };
@@ -277,6 +407,13 @@ impl GpuFrame {
             }
         }
 
+        self.renderer.rebuild_changed(
+            &self.core,
+            god_asset,
+            #[cfg(feature = "ui_helper_enabled")]
+            self.imgui_backend.context(),
+        )?;
+
         let device = self.core.device.clone();
         let graphics_queue = self.core.graphics_queue;
         let present_queue = self.core.present_queue;
@@ -343,6 +480,7 @@ impl GpuFrame {
             window_drawable_size,
             camera: &camera,
             scene: &state.scene,
+            interpolation_alpha: frame.interpolation_alpha(),
             frame_in_flight,
         };
 
@@ -1,3 +1,4 @@
+pub mod animator_system;
 pub mod god_job;
 pub mod god_object;
 pub mod gpu_frame;
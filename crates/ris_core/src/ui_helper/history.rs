@@ -0,0 +1,928 @@
+use std::any::TypeId;
+use std::collections::VecDeque;
+
+use ris_asset::assets::ris_scene;
+use ris_asset_data::asset_id::AssetId;
+use ris_data::ecs::components::script_component::DynScriptComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::handle::ComponentHandle;
+use ris_data::ecs::handle::DynComponentHandle;
+use ris_data::ecs::handle::GenericHandle;
+use ris_data::ecs::id::GameObjectKind;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene_stream::SceneReader;
+use ris_data::ecs::scene_stream::SceneWriter;
+use ris_error::Extensions;
+use ris_error::RisResult;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+
+pub const DEFAULT_CAPACITY: usize = 100;
+
+/// Maps the handle of a game object, that was destroyed by undoing or redoing a command, to the
+/// handle of the game object that replaced it.
+pub type Remap = Vec<(GameObjectHandle, GameObjectHandle)>;
+
+/// A reversible operation of the editor.
+///
+/// Destroyed game objects are restored from a snapshot, which creates new handles. Handles that
+/// are stored outside of the history, for example by components, are not updated and point to the
+/// destroyed game objects.
+#[derive(Debug)]
+pub enum EditorCommand {
+    SetName {
+        game_object: GameObjectHandle,
+        old: String,
+        new: String,
+    },
+    SetActive {
+        game_object: GameObjectHandle,
+        old: bool,
+        new: bool,
+    },
+    SetPosition {
+        game_object: GameObjectHandle,
+        old: Vec3,
+        new: Vec3,
+    },
+    SetRotation {
+        game_object: GameObjectHandle,
+        old: Quat,
+        new: Quat,
+    },
+    SetScale {
+        game_object: GameObjectHandle,
+        old: Vec3,
+        new: Vec3,
+    },
+    SetParent {
+        game_object: GameObjectHandle,
+        old: Placement,
+        new: Placement,
+    },
+    Create {
+        kind: GameObjectKind,
+        parent: Option<GameObjectHandle>,
+        game_object: GameObjectHandle,
+        snapshot: Option<Snapshot>,
+    },
+    Destroy {
+        game_object: GameObjectHandle,
+        snapshot: Option<Snapshot>,
+    },
+    AddComponent {
+        game_object: GameObjectHandle,
+        /// The index of the component factory in the registry.
+        factory: usize,
+        /// The index of the component on the game object.
+        index: usize,
+        snapshot: Option<ComponentSnapshot>,
+    },
+    RemoveComponent {
+        game_object: GameObjectHandle,
+        index: usize,
+        snapshot: Option<ComponentSnapshot>,
+    },
+    AttachScript {
+        game_object: GameObjectHandle,
+        /// The index of the script factory in the registry.
+        factory: usize,
+        index: usize,
+        snapshot: Option<ComponentSnapshot>,
+    },
+    DetachScript {
+        game_object: GameObjectHandle,
+        index: usize,
+        snapshot: Option<ComponentSnapshot>,
+    },
+}
+
+/// Where a game object sits in the hierarchy.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    pub parent: Option<GameObjectHandle>,
+    pub sibling_index: usize,
+}
+
+/// A destroyed game object and its descendants.
+#[derive(Debug)]
+pub struct Snapshot {
+    kind: GameObjectKind,
+    placement: Placement,
+    bytes: Vec<u8>,
+    references: Vec<AssetId>,
+    /// The destroyed game objects, in the order they were written.
+    game_objects: Vec<GameObjectHandle>,
+    /// Game objects outside of the snapshot, that may be referenced by its components.
+    external: Vec<GameObjectHandle>,
+}
+
+/// A removed component or script.
+#[derive(Debug)]
+pub struct ComponentSnapshot {
+    bytes: Vec<u8>,
+    references: Vec<AssetId>,
+    /// Game objects, that may be referenced by the component.
+    external: Vec<GameObjectHandle>,
+}
+
+pub struct HistoryEntry {
+    pub label: String,
+    command: EditorCommand,
+}
+
+/// Undo and redo stacks of editor commands.
+///
+/// Consecutive commands, that change the same value of the same game object, are merged into a
+/// single entry, until the history is sealed. This way dragging a value or typing a name can be
+/// undone in one step.
+pub struct History {
+    capacity: usize,
+    undo_stack: VecDeque<HistoryEntry>,
+    redo_stack: Vec<HistoryEntry>,
+    is_sealed: bool,
+}
+
+impl Default for History {
+    fn default() -> Self {
+        Self::new(DEFAULT_CAPACITY)
+    }
+}
+
+impl History {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            undo_stack: VecDeque::new(),
+            redo_stack: Vec::new(),
+            is_sealed: true,
+        }
+    }
+
+    /// Applies the command and records it.
+    pub fn execute(&mut self, scene: &Scene, mut command: EditorCommand) -> RisResult<Remap> {
+        let label = command.label(scene)?;
+        let remap = command.redo(scene)?;
+        self.apply_remap(&remap);
+        self.redo_stack.clear();
+
+        if !self.is_sealed {
+            if let Some(top) = self.undo_stack.back_mut() {
+                if top.command.merge(&command) {
+                    return Ok(remap);
+                }
+            }
+        }
+
+        self.undo_stack.push_back(HistoryEntry { label, command });
+        while self.undo_stack.len() > self.capacity {
+            self.undo_stack.pop_front();
+        }
+
+        self.is_sealed = false;
+        Ok(remap)
+    }
+
+    /// Prevents the next command from being merged into the last one.
+    pub fn seal(&mut self) {
+        self.is_sealed = true;
+    }
+
+    /// Reverts the last command. Returns `None` if there is nothing to undo.
+    ///
+    /// A command that fails to revert is discarded, such that it does not block the remaining
+    /// history.
+    pub fn undo(&mut self, scene: &Scene) -> RisResult<Option<Remap>> {
+        self.seal();
+
+        let Some(mut entry) = self.undo_stack.pop_back() else {
+            return Ok(None);
+        };
+
+        let remap = entry.command.undo(scene)?;
+        self.redo_stack.push(entry);
+        self.apply_remap(&remap);
+        Ok(Some(remap))
+    }
+
+    /// Reapplies the last undone command. Returns `None` if there is nothing to redo.
+    pub fn redo(&mut self, scene: &Scene) -> RisResult<Option<Remap>> {
+        self.seal();
+
+        let Some(mut entry) = self.redo_stack.pop() else {
+            return Ok(None);
+        };
+
+        let remap = entry.command.redo(scene)?;
+        self.undo_stack.push_back(entry);
+        self.apply_remap(&remap);
+        Ok(Some(remap))
+    }
+
+    pub fn clear(&mut self) {
+        self.undo_stack.clear();
+        self.redo_stack.clear();
+        self.seal();
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo_stack.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo_stack.is_empty()
+    }
+
+    /// Entries that may be undone, from oldest to newest.
+    pub fn undo_entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.undo_stack.iter()
+    }
+
+    /// Entries that may be redone, from next to last.
+    pub fn redo_entries(&self) -> impl Iterator<Item = &HistoryEntry> {
+        self.redo_stack.iter().rev()
+    }
+
+    fn apply_remap(&mut self, remap: &Remap) {
+        for &(from, to) in remap.iter() {
+            let entries = self.undo_stack.iter_mut().chain(self.redo_stack.iter_mut());
+            for entry in entries {
+                entry.command.remap(from, to);
+            }
+        }
+    }
+}
+
+impl EditorCommand {
+    pub fn set_name(
+        scene: &Scene,
+        game_object: GameObjectHandle,
+        value: impl Into<String>,
+    ) -> RisResult<Self> {
+        Ok(Self::SetName {
+            game_object,
+            old: game_object.name(scene)?,
+            new: value.into(),
+        })
+    }
+
+    pub fn set_active(
+        scene: &Scene,
+        game_object: GameObjectHandle,
+        value: bool,
+    ) -> RisResult<Self> {
+        Ok(Self::SetActive {
+            game_object,
+            old: game_object.is_active(scene)?,
+            new: value,
+        })
+    }
+
+    pub fn set_position(
+        scene: &Scene,
+        game_object: GameObjectHandle,
+        value: Vec3,
+    ) -> RisResult<Self> {
+        Ok(Self::SetPosition {
+            game_object,
+            old: game_object.position(scene)?,
+            new: value,
+        })
+    }
+
+    pub fn set_rotation(
+        scene: &Scene,
+        game_object: GameObjectHandle,
+        value: Quat,
+    ) -> RisResult<Self> {
+        Ok(Self::SetRotation {
+            game_object,
+            old: game_object.rotation(scene)?,
+            new: value,
+        })
+    }
+
+    pub fn set_scale(scene: &Scene, game_object: GameObjectHandle, value: Vec3) -> RisResult<Self> {
+        Ok(Self::SetScale {
+            game_object,
+            old: game_object.scale(scene)?,
+            new: value,
+        })
+    }
+
+    pub fn set_parent(
+        scene: &Scene,
+        game_object: GameObjectHandle,
+        parent: Option<GameObjectHandle>,
+        sibling_index: usize,
+    ) -> RisResult<Self> {
+        Ok(Self::SetParent {
+            game_object,
+            old: Placement::of(scene, game_object)?,
+            new: Placement {
+                parent,
+                sibling_index,
+            },
+        })
+    }
+
+    /// Creates a new game object. Its handle is known, after the command was executed.
+    pub fn create(kind: GameObjectKind, parent: Option<GameObjectHandle>) -> Self {
+        Self::Create {
+            kind,
+            parent,
+            game_object: GameObjectHandle::null(),
+            snapshot: None,
+        }
+    }
+
+    pub fn destroy(game_object: GameObjectHandle) -> Self {
+        Self::Destroy {
+            game_object,
+            snapshot: None,
+        }
+    }
+
+    /// Adds a component, that is made by the given component factory. The index of the
+    /// component is known, after the command was executed.
+    pub fn add_component(game_object: GameObjectHandle, factory: usize) -> Self {
+        Self::AddComponent {
+            game_object,
+            factory,
+            index: usize::MAX,
+            snapshot: None,
+        }
+    }
+
+    pub fn remove_component(
+        scene: &Scene,
+        game_object: GameObjectHandle,
+        component: DynComponentHandle,
+    ) -> RisResult<Self> {
+        Ok(Self::RemoveComponent {
+            game_object,
+            index: component_index(scene, game_object, component)?,
+            snapshot: None,
+        })
+    }
+
+    /// Attaches a script, that is made by the given script factory. The index of its component is
+    /// known, after the command was executed.
+    pub fn attach_script(game_object: GameObjectHandle, factory: usize) -> Self {
+        Self::AttachScript {
+            game_object,
+            factory,
+            index: usize::MAX,
+            snapshot: None,
+        }
+    }
+
+    pub fn detach_script(
+        scene: &Scene,
+        game_object: GameObjectHandle,
+        component: DynComponentHandle,
+    ) -> RisResult<Self> {
+        Ok(Self::DetachScript {
+            game_object,
+            index: component_index(scene, game_object, component)?,
+            snapshot: None,
+        })
+    }
+
+    /// The game object this command operates on.
+    pub fn game_object(&self) -> GameObjectHandle {
+        match self {
+            Self::SetName { game_object, .. }
+            | Self::SetActive { game_object, .. }
+            | Self::SetPosition { game_object, .. }
+            | Self::SetRotation { game_object, .. }
+            | Self::SetScale { game_object, .. }
+            | Self::SetParent { game_object, .. }
+            | Self::Create { game_object, .. }
+            | Self::Destroy { game_object, .. }
+            | Self::AddComponent { game_object, .. }
+            | Self::RemoveComponent { game_object, .. }
+            | Self::AttachScript { game_object, .. }
+            | Self::DetachScript { game_object, .. } => *game_object,
+        }
+    }
+
+    fn label(&self, scene: &Scene) -> RisResult<String> {
+        let label = match self {
+            Self::SetName { old, new, .. } => format!("rename \"{}\" to \"{}\"", old, new),
+            Self::SetActive {
+                game_object, new, ..
+            } => {
+                let verb = if *new { "activate" } else { "deactivate" };
+                format!("{} \"{}\"", verb, game_object.name(scene)?)
+            }
+            Self::SetPosition { game_object, .. } => {
+                format!("move \"{}\"", game_object.name(scene)?)
+            }
+            Self::SetRotation { game_object, .. } => {
+                format!("rotate \"{}\"", game_object.name(scene)?)
+            }
+            Self::SetScale { game_object, .. } => {
+                format!("scale \"{}\"", game_object.name(scene)?)
+            }
+            Self::SetParent {
+                game_object, new, ..
+            } => match new.parent {
+                Some(parent) => format!(
+                    "parent \"{}\" to \"{}\"",
+                    game_object.name(scene)?,
+                    parent.name(scene)?,
+                ),
+                None => format!("unparent \"{}\"", game_object.name(scene)?),
+            },
+            Self::Create { parent, .. } => match parent {
+                Some(parent) => format!("create child of \"{}\"", parent.name(scene)?),
+                None => "create game object".to_string(),
+            },
+            Self::Destroy { game_object, .. } => {
+                format!("destroy \"{}\"", game_object.name(scene)?)
+            }
+            Self::AddComponent {
+                game_object,
+                factory,
+                ..
+            } => {
+                let factories = scene.registry.component_factories();
+                let factory = factories.get(*factory).into_ris_error()?;
+                format!(
+                    "add {} to \"{}\"",
+                    factory.component_name(),
+                    game_object.name(scene)?,
+                )
+            }
+            Self::RemoveComponent {
+                game_object, index, ..
+            }
+            | Self::DetachScript {
+                game_object, index, ..
+            } => {
+                let component = component_at(scene, *game_object, *index)?;
+                let verb = match self {
+                    Self::DetachScript { .. } => "detach",
+                    _ => "remove",
+                };
+                format!(
+                    "{} {} from \"{}\"",
+                    verb,
+                    component_name(scene, component)?,
+                    game_object.name(scene)?,
+                )
+            }
+            Self::AttachScript {
+                game_object,
+                factory,
+                ..
+            } => {
+                let factories = scene.registry.script_factories();
+                let factory = factories.get(*factory).into_ris_error()?;
+                format!(
+                    "attach {} to \"{}\"",
+                    factory.script_name(),
+                    game_object.name(scene)?,
+                )
+            }
+        };
+
+        Ok(label)
+    }
+
+    fn redo(&mut self, scene: &Scene) -> RisResult<Remap> {
+        match self {
+            Self::SetName {
+                game_object, new, ..
+            } => game_object.set_name(scene, new.as_str())?,
+            Self::SetActive {
+                game_object, new, ..
+            } => game_object.set_active(scene, *new)?,
+            Self::SetPosition {
+                game_object, new, ..
+            } => game_object.set_position(scene, *new)?,
+            Self::SetRotation {
+                game_object, new, ..
+            } => game_object.set_rotation(scene, *new)?,
+            Self::SetScale {
+                game_object, new, ..
+            } => game_object.set_scale(scene, *new)?,
+            Self::SetParent {
+                game_object, new, ..
+            } => new.apply(scene, *game_object)?,
+            Self::Create {
+                kind,
+                parent,
+                game_object,
+                snapshot,
+            } => match snapshot.take() {
+                Some(snapshot) => return snapshot.restore(scene),
+                None => {
+                    let created = GameObjectHandle::new_with_kind(scene, *kind)?;
+                    if parent.is_some() {
+                        created.set_parent(scene, *parent, usize::MAX)?;
+                    }
+                    *game_object = created;
+                }
+            },
+            Self::Destroy {
+                game_object,
+                snapshot,
+            } => *snapshot = Some(Snapshot::take(scene, *game_object)?),
+            Self::AddComponent {
+                game_object,
+                factory,
+                index,
+                snapshot,
+            } => {
+                let component = match snapshot.take() {
+                    Some(snapshot) => snapshot.restore(scene, *game_object)?,
+                    None => {
+                        let factories = scene.registry.component_factories();
+                        let factory = factories.get(*factory).into_ris_error()?;
+                        factory.make(scene, *game_object)?
+                    }
+                };
+                *index = place_component(scene, *game_object, component, *index)?;
+            }
+            Self::AttachScript {
+                game_object,
+                factory,
+                index,
+                snapshot,
+            } => {
+                let component = match snapshot.take() {
+                    Some(snapshot) => snapshot.restore(scene, *game_object)?,
+                    None => {
+                        let factories = scene.registry.script_factories();
+                        let factory = factories.get(*factory).into_ris_error()?;
+                        factory
+                            .make_and_attach(scene, *game_object)?
+                            .to_dyn_component()
+                    }
+                };
+                *index = place_component(scene, *game_object, component, *index)?;
+            }
+            Self::RemoveComponent {
+                game_object,
+                index,
+                snapshot,
+            }
+            | Self::DetachScript {
+                game_object,
+                index,
+                snapshot,
+            } => *snapshot = Some(ComponentSnapshot::take(scene, *game_object, *index)?),
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn undo(&mut self, scene: &Scene) -> RisResult<Remap> {
+        match self {
+            Self::SetName {
+                game_object, old, ..
+            } => game_object.set_name(scene, old.as_str())?,
+            Self::SetActive {
+                game_object, old, ..
+            } => game_object.set_active(scene, *old)?,
+            Self::SetPosition {
+                game_object, old, ..
+            } => game_object.set_position(scene, *old)?,
+            Self::SetRotation {
+                game_object, old, ..
+            } => game_object.set_rotation(scene, *old)?,
+            Self::SetScale {
+                game_object, old, ..
+            } => game_object.set_scale(scene, *old)?,
+            Self::SetParent {
+                game_object, old, ..
+            } => old.apply(scene, *game_object)?,
+            Self::Create {
+                game_object,
+                snapshot,
+                ..
+            } => *snapshot = Some(Snapshot::take(scene, *game_object)?),
+            Self::Destroy { snapshot, .. } => {
+                let snapshot = snapshot.take().into_ris_error()?;
+                return snapshot.restore(scene);
+            }
+            Self::AddComponent {
+                game_object,
+                index,
+                snapshot,
+                ..
+            }
+            | Self::AttachScript {
+                game_object,
+                index,
+                snapshot,
+                ..
+            } => *snapshot = Some(ComponentSnapshot::take(scene, *game_object, *index)?),
+            Self::RemoveComponent {
+                game_object,
+                index,
+                snapshot,
+            }
+            | Self::DetachScript {
+                game_object,
+                index,
+                snapshot,
+            } => {
+                let snapshot = snapshot.take().into_ris_error()?;
+                let component = snapshot.restore(scene, *game_object)?;
+                place_component(scene, *game_object, component, *index)?;
+            }
+        }
+
+        Ok(Vec::new())
+    }
+
+    fn merge(&mut self, other: &Self) -> bool {
+        if self.game_object() != other.game_object() {
+            return false;
+        }
+
+        match (self, other) {
+            (Self::SetName { new, .. }, Self::SetName { new: other, .. }) => {
+                new.clone_from(other);
+            }
+            (Self::SetPosition { new, .. }, Self::SetPosition { new: other, .. }) => *new = *other,
+            (Self::SetRotation { new, .. }, Self::SetRotation { new: other, .. }) => *new = *other,
+            (Self::SetScale { new, .. }, Self::SetScale { new: other, .. }) => *new = *other,
+            _ => return false,
+        }
+
+        true
+    }
+
+    fn remap(&mut self, from: GameObjectHandle, to: GameObjectHandle) {
+        let replace = |handle: &mut GameObjectHandle| {
+            if *handle == from {
+                *handle = to;
+            }
+        };
+        let replace_parent = |parent: &mut Option<GameObjectHandle>| {
+            if *parent == Some(from) {
+                *parent = Some(to);
+            }
+        };
+
+        match self {
+            Self::SetName { game_object, .. }
+            | Self::SetActive { game_object, .. }
+            | Self::SetPosition { game_object, .. }
+            | Self::SetRotation { game_object, .. }
+            | Self::SetScale { game_object, .. } => replace(game_object),
+            Self::SetParent {
+                game_object,
+                old,
+                new,
+            } => {
+                replace(game_object);
+                replace_parent(&mut old.parent);
+                replace_parent(&mut new.parent);
+            }
+            Self::Create {
+                parent,
+                game_object,
+                snapshot,
+                ..
+            } => {
+                replace_parent(parent);
+                replace(game_object);
+                if let Some(snapshot) = snapshot {
+                    snapshot.remap(from, to);
+                }
+            }
+            Self::Destroy {
+                game_object,
+                snapshot,
+            } => {
+                replace(game_object);
+                if let Some(snapshot) = snapshot {
+                    snapshot.remap(from, to);
+                }
+            }
+            Self::AddComponent {
+                game_object,
+                snapshot,
+                ..
+            }
+            | Self::RemoveComponent {
+                game_object,
+                snapshot,
+                ..
+            }
+            | Self::AttachScript {
+                game_object,
+                snapshot,
+                ..
+            }
+            | Self::DetachScript {
+                game_object,
+                snapshot,
+                ..
+            } => {
+                replace(game_object);
+                if let Some(snapshot) = snapshot {
+                    snapshot.remap(from, to);
+                }
+            }
+        }
+    }
+}
+
+impl Placement {
+    pub fn of(scene: &Scene, game_object: GameObjectHandle) -> RisResult<Self> {
+        Ok(Self {
+            parent: game_object.parent(scene)?,
+            sibling_index: game_object.sibling_index(scene)?,
+        })
+    }
+
+    fn apply(self, scene: &Scene, game_object: GameObjectHandle) -> RisResult<()> {
+        game_object.set_parent(scene, self.parent, self.sibling_index)?;
+        Ok(())
+    }
+}
+
+impl Snapshot {
+    /// Serializes and destroys the game object and its descendants.
+    fn take(scene: &Scene, root: GameObjectHandle) -> RisResult<Self> {
+        let scene_kind = root.0.scene_id().kind;
+        let kind = GameObjectKind::try_from(scene_kind)?;
+        let placement = Placement::of(scene, root)?;
+
+        let game_objects = root.subtree(scene)?;
+
+        let external = scene
+            .query()
+            .in_kind(kind)
+            .include_inactive()
+            .game_objects()?
+            .into_iter()
+            .filter(|x| !game_objects.contains(x))
+            .collect::<Vec<_>>();
+
+        let mut stream = SceneWriter::new_with_kind(scene_kind, scene);
+        let mut lookup = ris_scene::write_game_objects(&mut stream, &game_objects)?;
+        lookup.extend(external.iter().map(|x| x.0.scene_id().index));
+        let (bytes, references) = stream.resolve(lookup)?;
+
+        root.destroy(scene);
+
+        Ok(Self {
+            kind,
+            placement,
+            bytes,
+            references,
+            game_objects,
+            external,
+        })
+    }
+
+    /// Recreates the game objects and returns their new handles.
+    fn restore(self, scene: &Scene) -> RisResult<Remap> {
+        let Self {
+            kind,
+            placement,
+            bytes,
+            references,
+            game_objects,
+            external,
+        } = self;
+
+        let mut stream = SceneReader::new(scene, bytes, references);
+        let pending = ris_scene::read_game_objects(&mut stream, || {
+            let game_object = GameObjectHandle::new_with_kind(scene, kind)?;
+            Ok(game_object)
+        })?;
+
+        let restored = stream.lookup.clone();
+        let root = *restored.first().into_ris_error()?;
+        placement.apply(scene, root)?;
+
+        stream.lookup.extend(external);
+        ris_scene::read_components(&mut stream, pending)?;
+
+        let remap = game_objects.into_iter().zip(restored).collect();
+        Ok(remap)
+    }
+
+    fn remap(&mut self, from: GameObjectHandle, to: GameObjectHandle) {
+        if self.placement.parent == Some(from) {
+            self.placement.parent = Some(to);
+        }
+
+        for handle in self.external.iter_mut() {
+            if *handle == from {
+                *handle = to;
+            }
+        }
+    }
+}
+
+impl ComponentSnapshot {
+    /// Serializes and destroys the component at the given index of the game object.
+    fn take(scene: &Scene, game_object: GameObjectHandle, index: usize) -> RisResult<Self> {
+        let component = component_at(scene, game_object, index)?;
+        let scene_kind = game_object.0.scene_id().kind;
+        let kind = GameObjectKind::try_from(scene_kind)?;
+
+        let external = scene
+            .query()
+            .in_kind(kind)
+            .include_inactive()
+            .game_objects()?;
+
+        let mut stream = SceneWriter::new_with_kind(scene_kind, scene);
+        ris_scene::write_component(&mut stream, component)?;
+        let lookup = external.iter().map(|x| x.0.scene_id().index).collect();
+        let (bytes, references) = stream.resolve(lookup)?;
+
+        game_object.remove_and_destroy_component(scene, component);
+
+        Ok(Self {
+            bytes,
+            references,
+            external,
+        })
+    }
+
+    /// Recreates the component at the end of the components of the game object and returns its
+    /// new handle.
+    fn restore(
+        self,
+        scene: &Scene,
+        game_object: GameObjectHandle,
+    ) -> RisResult<DynComponentHandle> {
+        let Self {
+            bytes,
+            references,
+            external,
+        } = self;
+
+        let mut stream = SceneReader::new(scene, bytes, references);
+        stream.lookup = external;
+        ris_scene::read_component(&mut stream, game_object)
+    }
+
+    fn remap(&mut self, from: GameObjectHandle, to: GameObjectHandle) {
+        for handle in self.external.iter_mut() {
+            if *handle == from {
+                *handle = to;
+            }
+        }
+    }
+}
+
+fn component_at(
+    scene: &Scene,
+    game_object: GameObjectHandle,
+    index: usize,
+) -> RisResult<DynComponentHandle> {
+    let components = game_object.components(scene)?;
+    let component = components.get(index).into_ris_error()?;
+    Ok(*component)
+}
+
+fn component_index(
+    scene: &Scene,
+    game_object: GameObjectHandle,
+    component: DynComponentHandle,
+) -> RisResult<usize> {
+    let components = game_object.components(scene)?;
+    let index = components.iter().position(|&x| x == component);
+    index.into_ris_error()
+}
+
+/// Moves the component to the given index and returns the index it ended up at.
+fn place_component(
+    scene: &Scene,
+    game_object: GameObjectHandle,
+    component: DynComponentHandle,
+    index: usize,
+) -> RisResult<usize> {
+    game_object.set_component_index(scene, component, index)?;
+    component_index(scene, game_object, component)
+}
+
+fn component_name(scene: &Scene, component: DynComponentHandle) -> RisResult<String> {
+    if component.type_id() == TypeId::of::<DynScriptComponent>() {
+        let handle = GenericHandle::<DynScriptComponent>::from_dyn(component.into())?;
+        let ptr = scene.deref(handle)?;
+        let name = ptr.borrow().type_name().into_ris_error()?;
+        return Ok(name.to_string());
+    }
+
+    let name = scene
+        .registry
+        .component_factories()
+        .iter()
+        .find(|x| x.component_id() == component.type_id())
+        .map(|x| x.component_name().to_string())
+        .into_ris_error()?;
+    Ok(name)
+}
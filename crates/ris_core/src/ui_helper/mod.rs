@@ -10,27 +10,40 @@ use imgui::WindowFlags;
 use imgui::WindowFocusedFlags;
 use sdl2::keyboard::Scancode;
 
+use ris_asset::asset_watcher;
+use ris_asset::asset_watcher::AssetSubscription;
+use ris_asset::assets::ris_scene;
+use ris_asset::assets::ris_scene_text;
+use ris_asset::LoadPriority;
 use ris_asset_data::asset_id::AssetId;
 use ris_async::JobFuture;
 use ris_async::ThreadPool;
+use ris_data::ecs::scene::Scene;
 use ris_data::gameloop::frame::Frame;
 use ris_data::gameloop::gameloop_state::GameloopState;
 use ris_data::god_state::GodState;
 use ris_data::info::app_info::AppInfo;
 use ris_data::ris_yaml::RisYaml;
 use ris_error::RisResult;
+use ris_input::keyboard_logic;
 use ris_ptr::ArefCell;
 use ris_ptr::StrongPtr;
 use ris_ptr::WeakPtr;
 
+pub mod history;
 pub mod modules;
 pub mod selection;
 
+use history::EditorCommand;
+use history::History;
+use history::Remap;
+use selection::Selection;
 use selection::Selector;
 
 use modules::asset_browser::AssetBrowser;
 use modules::gizmo::GizmoModule;
 use modules::hierarchy::HierarchyModule;
+use modules::history::HistoryModule;
 use modules::inspector::InspectorModule;
 use modules::log::LogModule;
 use modules::metrics::MetricsModule;
@@ -82,6 +95,7 @@ fn builders() -> RisResult<Vec<UiHelperModuleBuilder>> {
         AssetBrowser,
         GizmoModule,
         HierarchyModule,
+        HistoryModule,
         InspectorModule,
         LogModule,
         MetricsModule,
@@ -118,6 +132,7 @@ fn builders() -> RisResult<Vec<UiHelperModuleBuilder>> {
 pub struct SharedState {
     app_info: AppInfo,
     selector: Selector,
+    history: History,
     loaded_chunks: Vec<Option<AssetId>>,
     drag_drop_payload: Option<Box<dyn std::any::Any>>,
 }
@@ -127,11 +142,46 @@ impl SharedState {
         StrongPtr::new(ArefCell::new(Self {
             app_info,
             selector: Selector::default(),
+            history: History::default(),
             loaded_chunks: Vec::new(),
             drag_drop_payload: None,
         }))
     }
 
+    /// Applies the command and records it in the history, such that it may be undone.
+    pub fn execute(&mut self, scene: &Scene, command: EditorCommand) -> RisResult<()> {
+        let remap = self.history.execute(scene, command)?;
+        self.remap_selection(&remap);
+        Ok(())
+    }
+
+    pub fn undo(&mut self, scene: &Scene) -> RisResult<()> {
+        if let Some(remap) = self.history.undo(scene)? {
+            self.remap_selection(&remap);
+        }
+
+        Ok(())
+    }
+
+    pub fn redo(&mut self, scene: &Scene) -> RisResult<()> {
+        if let Some(remap) = self.history.redo(scene)? {
+            self.remap_selection(&remap);
+        }
+
+        Ok(())
+    }
+
+    fn remap_selection(&mut self, remap: &Remap) {
+        let Some(Selection::GameObject(selected)) = self.selector.get_selection() else {
+            return;
+        };
+
+        if let Some((_, to)) = remap.iter().find(|(from, _)| *from == selected) {
+            let selection = Some(Selection::GameObject(*to));
+            self.selector.set_selection(selection);
+        }
+    }
+
     pub fn chunk(&mut self, index: usize) -> Option<AssetId> {
         self.reserve_chunks(index);
         self.loaded_chunks[index].clone()
@@ -218,6 +268,7 @@ pub struct UiHelper {
     show_ui: bool,
     show_demo: bool,
     reimport_asset_future: Option<JobFuture<()>>,
+    scene_subscription: AssetSubscription,
     crash_timestamp: Instant,
     restart_timestamp: Instant,
     close_window_timestamp: Instant,
@@ -280,6 +331,7 @@ impl UiHelper {
                     show_ui: true,
                     show_demo: false,
                     reimport_asset_future: None,
+                    scene_subscription: asset_watcher::subscribe_all(),
                     crash_timestamp: now,
                     restart_timestamp: now,
                     close_window_timestamp: now,
@@ -390,6 +442,7 @@ impl UiHelper {
             show_ui: true,
             show_demo: false,
             reimport_asset_future: None,
+            scene_subscription: asset_watcher::subscribe_all(),
             crash_timestamp: now,
             restart_timestamp: now,
             close_window_timestamp: now,
@@ -399,6 +452,12 @@ impl UiHelper {
     pub fn draw(&mut self, mut data: UiHelperDrawData) -> RisResult<GameloopState> {
         self.shared_state.borrow_mut().selector.update();
 
+        // edits that span multiple frames, like dragging a value, end when no item is active
+        // anymore. until then, they are merged into a single history entry
+        if !data.ui.is_any_item_active() {
+            self.shared_state.borrow_mut().history.seal();
+        }
+
         let window_flags = WindowFlags::MENU_BAR
             | WindowFlags::NO_DOCKING
             | WindowFlags::NO_TITLE_BAR
@@ -503,6 +562,21 @@ impl UiHelper {
             self.close_window_timestamp = Instant::now();
         }
 
+        // text inputs handle undo and redo themselves
+        if !data.ui.io().want_text_input {
+            let keyboard = &data.state.input.keyboard;
+            let undo_requested = keyboard_logic::ctrl_shortcut_is_down(keyboard, Scancode::Z);
+            let redo_requested = keyboard_logic::ctrl_shortcut_is_down(keyboard, Scancode::Y);
+
+            if undo_requested {
+                self.undo(&data.state.scene);
+            }
+
+            if redo_requested {
+                self.redo(&data.state.scene);
+            }
+        }
+
         if self.show_demo {
             data.ui.show_demo_window(&mut self.show_demo);
         }
@@ -511,12 +585,53 @@ impl UiHelper {
             future.wait();
         }
 
+        self.reload_changed_scenes(&data.state.scene);
+
         match result {
             Some(result) => result,
             None => Ok(GameloopState::WantsToContinue),
         }
     }
 
+    /// Reloads the chunks, whose scene file changed on disk. A file that matches its chunk, like
+    /// after saving it, is not reloaded. A file that fails to load, like a half saved one, is
+    /// logged and the old chunk is kept.
+    fn reload_changed_scenes(&mut self, scene: &Scene) {
+        let changed = self.scene_subscription.take_changed();
+        if changed.is_empty() {
+            return;
+        }
+
+        let mut shared_state = self.shared_state.borrow_mut();
+        for chunk_index in 0..shared_state.loaded_chunks.len() {
+            let Some(id) = shared_state.loaded_chunks[chunk_index].clone() else {
+                continue;
+            };
+
+            if !changed.contains(&id) {
+                continue;
+            }
+
+            let reserved = match reload_chunk(scene, chunk_index, &id) {
+                Ok(Some(reserved)) => reserved,
+                Ok(None) => continue,
+                Err(e) => {
+                    ris_log::error!("failed to reload {:?}, keeping the old scene: {}", id, e);
+                    continue;
+                }
+            };
+
+            scene.clear_chunk(chunk_index);
+            shared_state.set_chunk(chunk_index, None);
+            shared_state.set_chunk(reserved, Some(id.clone()));
+
+            // the history may reference game objects of the cleared chunk
+            shared_state.history.clear();
+
+            ris_log::info!("reloaded {:?} into chunk {}", id, reserved);
+        }
+    }
+
     fn menu_callback(&mut self, data: &mut UiHelperDrawData) -> RisResult<GameloopState> {
         if let Some(_menu_bar) = data.ui.begin_menu_bar() {
             if let Some(_menu) = data.ui.begin_menu("start") {
@@ -543,6 +658,31 @@ impl UiHelper {
                 }
             }
 
+            if let Some(_menu) = data.ui.begin_menu("edit") {
+                let (can_undo, can_redo) = {
+                    let history = &self.shared_state.borrow().history;
+                    (history.can_undo(), history.can_redo())
+                };
+
+                if data
+                    .ui
+                    .menu_item_config("undo (Ctrl+Z)")
+                    .enabled(can_undo)
+                    .build()
+                {
+                    self.undo(&data.state.scene);
+                }
+
+                if data
+                    .ui
+                    .menu_item_config("redo (Ctrl+Y)")
+                    .enabled(can_redo)
+                    .build()
+                {
+                    self.redo(&data.state.scene);
+                }
+            }
+
             if let Some(_menu) = data.ui.begin_menu("debug") {
                 if data.ui.menu_item("reimport assets (F5)") {
                     reimport_assets(&mut self.reimport_asset_future)?;
@@ -624,6 +764,18 @@ impl UiHelper {
         Ok(GameloopState::WantsToContinue)
     }
 
+    fn undo(&mut self, scene: &Scene) {
+        if let Err(e) = self.shared_state.borrow_mut().undo(scene) {
+            ris_log::error!("failed to undo: {}", e);
+        }
+    }
+
+    fn redo(&mut self, scene: &Scene) {
+        if let Err(e) = self.shared_state.borrow_mut().redo(scene) {
+            ris_log::error!("failed to redo: {}", e);
+        }
+    }
+
     fn window_callback(&mut self, window_index: usize, data: &mut UiHelperDrawData) {
         let UiHelperDrawData { ui, .. } = data;
 
@@ -700,3 +852,30 @@ fn reimport_assets(import_asset_future: &mut Option<JobFuture<()>>) -> RisResult
 
     Ok(())
 }
+
+/// Loads the changed scene file into a free chunk, such that the chunk it replaces stays intact
+/// until the file is known to be valid. Returns `None` if the file matches the chunk.
+fn reload_chunk(scene: &Scene, chunk_index: usize, id: &AssetId) -> RisResult<Option<usize>> {
+    let is_text = id.has_extension(ris_scene_text::EXTENSION);
+    let bytes = ris_asset::load_raw_async_with_priority(id.clone(), LoadPriority::High).wait()?;
+    let current = if is_text {
+        ris_scene_text::serialize(scene, chunk_index)?.into_bytes()
+    } else {
+        ris_scene::serialize(scene, chunk_index)?
+    };
+
+    if bytes == current {
+        return Ok(None);
+    }
+
+    let reserved = if is_text {
+        ris_scene_text::deserialize(scene, std::str::from_utf8(&bytes)?)?
+    } else {
+        ris_scene::deserialize(scene, &bytes)?
+    };
+
+    match reserved {
+        Some(reserved) => Ok(Some(reserved)),
+        None => ris_error::new_result!("no free chunk to load the scene into"),
+    }
+}
@@ -1,6 +1,8 @@
 use std::ffi::CString;
 
+use ris_asset::assets::ris_prefab;
 use ris_asset::assets::ris_scene;
+use ris_asset::assets::ris_scene_text;
 use ris_asset_data::asset_id::AssetId;
 use ris_data::ecs::decl::GameObjectHandle;
 use ris_data::ecs::id::GameObjectKind;
@@ -9,6 +11,7 @@ use ris_data::god_state::GodState;
 use ris_error::RisResult;
 
 use crate::inspector_util;
+use crate::ui_helper::history::EditorCommand;
 use crate::ui_helper::selection::Selection;
 use crate::ui_helper::IUiHelperModule;
 use crate::ui_helper::SharedStateWeakPtr;
@@ -65,14 +68,22 @@ impl IUiHelperModule for HierarchyModule {
 
             if ui.button("clear") {
                 scene.clear_chunk(chunk_index);
-                self.shared_state.borrow_mut().set_chunk(chunk_index, None);
+                let mut aref_mut = self.shared_state.borrow_mut();
+                aref_mut.set_chunk(chunk_index, None);
+
+                // the history may reference game objects of the cleared chunk
+                aref_mut.history.clear();
             }
 
             ui.same_line();
             if ui.button("save") {
                 if let Some(AssetId::Path(path)) = chunk.clone() {
                     ris_log::debug!("saving scene... chunk: {} path: {}", chunk_index, path,);
-                    let bytes = ris_scene::serialize(scene, chunk_index)?;
+                    let bytes = if path.ends_with(ris_scene_text::EXTENSION) {
+                        ris_scene_text::serialize(scene, chunk_index)?.into_bytes()
+                    } else {
+                        ris_scene::serialize(scene, chunk_index)?
+                    };
 
                     let asset_path = self.shared_state.borrow().app_info.asset_path()?;
                     let path = asset_path.join(path);
@@ -100,32 +111,32 @@ impl IUiHelperModule for HierarchyModule {
 
         let child_token = ui.child_window("hierarchy child window").begin();
         if child_token.is_some() {
-            let alive = chunk.iter().filter(|x| x.borrow().is_alive).count();
+            let alive = scene
+                .query()
+                .in_kind(kind)
+                .include_inactive()
+                .game_objects()?
+                .len();
             ui.label_text("game objects", format!("{}/{}", alive, chunk.len()));
 
             if unsafe { imgui::sys::igBeginPopupContextWindow(std::ptr::null(), 1) } {
                 if ui.menu_item("new") {
-                    GameObjectHandle::new_with_kind(scene, kind)?;
+                    let command = EditorCommand::create(kind, None);
+                    self.shared_state.borrow_mut().execute(scene, command)?;
                 }
 
                 unsafe { imgui::sys::igEndPopup() }
             }
 
-            let handles = chunk
-                .iter()
-                .filter(|x| {
-                    let handle: GameObjectHandle = x.borrow().handle.into();
-                    let parent_handle = handle.parent(scene).unwrap_or(None);
-                    let is_root = parent_handle.is_none();
-                    let is_alive = x.borrow().is_alive;
-
-                    is_alive && is_root
-                })
-                .map(|x| x.borrow().handle)
-                .collect::<Vec<_>>();
+            let handles = scene
+                .query()
+                .in_kind(kind)
+                .include_inactive()
+                .roots_only()
+                .game_objects()?;
 
             for handle in handles {
-                self.draw_node(handle.into(), data)?;
+                self.draw_node(handle, data)?;
             }
         }
 
@@ -180,7 +191,8 @@ impl HierarchyModule {
             {
                 let _disabled_token = ui.begin_disabled(handle.parent(scene)?.is_none());
                 if ui.menu_item("unparent") {
-                    handle.set_parent(scene, None, usize::MAX)?;
+                    let command = EditorCommand::set_parent(scene, handle, None, usize::MAX)?;
+                    self.shared_state.borrow_mut().execute(scene, command)?;
                 }
             }
 
@@ -194,13 +206,24 @@ impl HierarchyModule {
                 if !is_game_object {
                     return ris_error::new_result!("handle id was not a gameobject");
                 }
-                let child = GameObjectHandle::new_with_kind(scene, kind.try_into()?)?;
-                child.set_parent(scene, Some(handle), usize::MAX)?;
+                let command = EditorCommand::create(kind.try_into()?, Some(handle));
+                self.shared_state.borrow_mut().execute(scene, command)?;
                 ris_log::debug!("parent: {:?}", handle);
             }
 
+            if ui.menu_item("save as prefab") {
+                let bytes = ris_prefab::serialize(scene, handle)?;
+
+                let asset_path = self.shared_state.borrow().app_info.asset_path()?;
+                let path = asset_path.join(format!("{}.{}", name, ris_prefab::EXTENSION));
+                ris_log::debug!("saving prefab... path: {:?}", path);
+                let mut file = std::fs::File::create(path)?;
+                ris_io::write(&mut file, &bytes)?;
+            }
+
             if ui.menu_item("destroy") {
-                handle.destroy(scene);
+                let command = EditorCommand::destroy(handle);
+                self.shared_state.borrow_mut().execute(scene, command)?;
             }
 
             unsafe { imgui::sys::igEndPopup() };
@@ -228,7 +251,9 @@ impl HierarchyModule {
             if let Some(dragged_handle) = payload {
                 ris_log::info!("accepted drag");
 
-                if let Err(e) = dragged_handle.set_parent(scene, Some(handle), 0) {
+                let result = EditorCommand::set_parent(scene, dragged_handle, Some(handle), 0)
+                    .and_then(|command| aref_mut.execute(scene, command));
+                if let Err(e) = result {
                     ris_log::error!("failed to drag: {}", e);
                 }
             }
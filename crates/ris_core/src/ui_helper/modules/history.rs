@@ -0,0 +1,125 @@
+use ris_data::god_state::GodState;
+use ris_error::RisResult;
+
+use crate::ui_helper::IUiHelperModule;
+use crate::ui_helper::SharedStateWeakPtr;
+use crate::ui_helper::UiHelperDrawData;
+
+pub struct HistoryModule {
+    shared_state: SharedStateWeakPtr,
+}
+
+impl IUiHelperModule for HistoryModule {
+    fn name() -> &'static str {
+        "history"
+    }
+
+    fn build(shared_state: SharedStateWeakPtr) -> Box<dyn IUiHelperModule> {
+        Box::new(Self { shared_state })
+    }
+
+    fn draw(&mut self, data: &mut UiHelperDrawData) -> RisResult<()> {
+        let UiHelperDrawData {
+            ui,
+            state: GodState { scene, .. },
+            ..
+        } = data;
+
+        let (can_undo, can_redo) = {
+            let history = &self.shared_state.borrow().history;
+            (history.can_undo(), history.can_redo())
+        };
+
+        {
+            let _disabled_token = ui.begin_disabled(!can_undo);
+            if ui.button("undo") {
+                self.shared_state.borrow_mut().undo(scene)?;
+            }
+        }
+
+        ui.same_line();
+        {
+            let _disabled_token = ui.begin_disabled(!can_redo);
+            if ui.button("redo") {
+                self.shared_state.borrow_mut().redo(scene)?;
+            }
+        }
+
+        ui.same_line();
+        if ui.button("clear") {
+            self.shared_state.borrow_mut().history.clear();
+        }
+
+        ui.separator();
+
+        // the entries are listed from oldest to newest. the selected entry is the last one that
+        // was applied, clicking another one undoes or redoes everything in between
+        let (undo_labels, redo_labels) = {
+            let history = &self.shared_state.borrow().history;
+            let undo_labels = history
+                .undo_entries()
+                .map(|x| x.label.clone())
+                .collect::<Vec<_>>();
+            let redo_labels = history
+                .redo_entries()
+                .map(|x| x.label.clone())
+                .collect::<Vec<_>>();
+            (undo_labels, redo_labels)
+        };
+
+        let current = undo_labels.len();
+        let mut target = None;
+
+        let child_token = ui.child_window("history child window").begin();
+        if child_token.is_some() {
+            if ui
+                .selectable_config("<start>")
+                .selected(current == 0)
+                .build()
+            {
+                target = Some(0);
+            }
+
+            for (i, label) in undo_labels.iter().enumerate() {
+                let position = i + 1;
+                let selected = position == current;
+                if ui
+                    .selectable_config(format!("{}##history_{}", label, position))
+                    .selected(selected)
+                    .build()
+                {
+                    target = Some(position);
+                }
+            }
+
+            for (i, label) in redo_labels.iter().enumerate() {
+                let position = current + i + 1;
+                let color_token = ui.push_style_color(
+                    imgui::StyleColor::Text,
+                    ui.style_color(imgui::StyleColor::TextDisabled),
+                );
+                let clicked = ui
+                    .selectable_config(format!("{}##history_{}", label, position))
+                    .build();
+                color_token.pop();
+
+                if clicked {
+                    target = Some(position);
+                }
+            }
+        }
+
+        if let Some(target) = target {
+            let mut shared_state = self.shared_state.borrow_mut();
+            for _ in target..current {
+                shared_state.undo(scene)?;
+            }
+
+            for _ in current..target {
+                shared_state.redo(scene)?;
+            }
+        }
+
+        Ok(())
+    }
+}
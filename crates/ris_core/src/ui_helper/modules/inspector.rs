@@ -4,12 +4,16 @@ use std::sync::Arc;
 
 use imgui::Ui;
 
+use ris_asset::LoadPriority;
 use ris_asset_data::asset_id::AssetId;
 use ris_async::SpinLock;
 use ris_async::ThreadPool;
+use ris_data::ecs::components::material_component::MaterialComponent;
 use ris_data::ecs::components::mesh_component::MeshComponent;
 use ris_data::ecs::components::script_component::DynScriptComponent;
 use ris_data::ecs::components::script_component::ScriptInspectData;
+use ris_data::ecs::id::ComponentInspectData;
+use ris_data::ecs::scene::Scene;
 use ris_error::Extensions;
 use ris_error::RisResult;
 use ris_math::affine;
@@ -17,6 +21,7 @@ use ris_math::quaternion::Quat;
 use ris_math::vector::Vec3;
 
 use crate::inspector_util;
+use crate::ui_helper::history::EditorCommand;
 use crate::ui_helper::selection::Selection;
 use crate::ui_helper::IUiHelperModule;
 use crate::ui_helper::SharedStateWeakPtr;
@@ -77,12 +82,15 @@ impl IUiHelperModule for InspectorModule {
 
                 let mut name = game_object.name(&data.state.scene)?;
                 if data.ui.input_text("name", &mut name).build() {
-                    game_object.set_name(&data.state.scene, name)?;
+                    let command = EditorCommand::set_name(&data.state.scene, game_object, name)?;
+                    self.execute(&data.state.scene, command)?;
                 }
 
                 let mut is_active = game_object.is_active(&data.state.scene)?;
                 if data.ui.checkbox("is active", &mut is_active) {
-                    game_object.set_active(&data.state.scene, is_active)?;
+                    let command =
+                        EditorCommand::set_active(&data.state.scene, game_object, is_active)?;
+                    self.execute(&data.state.scene, command)?;
                 }
 
                 {
@@ -99,7 +107,9 @@ impl IUiHelperModule for InspectorModule {
 
                 let changed = inspector_util::drag_vec3("position", &mut position)?;
                 if changed {
-                    game_object.set_position(&data.state.scene, position)?;
+                    let command =
+                        EditorCommand::set_position(&data.state.scene, game_object, position)?;
+                    self.execute(&data.state.scene, command)?;
                 }
 
                 let format = CString::new("%.3f")?;
@@ -205,7 +215,8 @@ impl IUiHelperModule for InspectorModule {
                         Quat(x, y, z, w).normalize()
                     };
 
-                    game_object.set_rotation(&data.state.scene, q)?;
+                    let command = EditorCommand::set_rotation(&data.state.scene, game_object, q)?;
+                    self.execute(&data.state.scene, command)?;
                 }
 
                 data.ui.same_line();
@@ -324,7 +335,9 @@ impl IUiHelperModule for InspectorModule {
                     }
 
                     if let Some(rotation) = rotation {
-                        game_object.set_rotation(&data.state.scene, rotation)?;
+                        let command =
+                            EditorCommand::set_rotation(&data.state.scene, game_object, rotation)?;
+                        self.execute(&data.state.scene, command)?;
                         self.cache_rotation_axes(rotation);
                     }
                 }
@@ -332,7 +345,8 @@ impl IUiHelperModule for InspectorModule {
                 let mut scale = game_object.scale(&data.state.scene)?;
                 let changed = inspector_util::drag_vec3("scale", &mut scale)?;
                 if changed {
-                    game_object.set_scale(&data.state.scene, scale)?;
+                    let command = EditorCommand::set_scale(&data.state.scene, game_object, scale)?;
+                    self.execute(&data.state.scene, command)?;
                 }
 
                 let model = game_object.model(&data.state.scene)?;
@@ -362,7 +376,12 @@ impl IUiHelperModule for InspectorModule {
                     let delete_requested;
 
                     if component.type_id() == TypeId::of::<MeshComponent>() {
-                        let ptr = data.state.scene.mesh_renderer_components[index].to_weak();
+                        let ptr = data
+                            .state
+                            .scene
+                            .component_pool::<MeshComponent>()?
+                            .get(index)
+                            .into_ris_error()?;
                         let mut aref_mut = ptr.borrow_mut();
 
                         let header =
@@ -381,6 +400,37 @@ impl IUiHelperModule for InspectorModule {
                                 aref_mut.set_asset_id(asset_id);
                             }
 
+                            let lookup_id_string = match aref_mut.lookup_id() {
+                                Some(lookup_id) => lookup_id.index().to_string(),
+                                None => "none".to_string(),
+                            };
+                            data.ui.label_text("lookup id", lookup_id_string);
+                        }
+                    } else if component.type_id() == TypeId::of::<MaterialComponent>() {
+                        let ptr = data
+                            .state
+                            .scene
+                            .component_pool::<MaterialComponent>()?
+                            .get(index)
+                            .into_ris_error()?;
+                        let mut aref_mut = ptr.borrow_mut();
+
+                        let header =
+                            ComponentHeader::draw(data.ui, format!("material##{:?}", component));
+                        delete_requested = header.delete_requested;
+                        if header.is_open {
+                            let mut asset_id = aref_mut.asset_id();
+                            let changed = inspector_util::asset_field(
+                                "asset",
+                                self.shared_state.clone(),
+                                &mut asset_id,
+                                Some(ris_asset::assets::ris_material::EXTENSION),
+                            )?;
+
+                            if changed {
+                                aref_mut.set_asset_id(asset_id);
+                            }
+
                             let lookup_id_string = match aref_mut.lookup_id() {
                                 Some(lookup_id) => lookup_id.index().to_string(),
                                 None => "none".to_string(),
@@ -388,7 +438,12 @@ impl IUiHelperModule for InspectorModule {
                             data.ui.label_text("lookup id", lookup_id_string);
                         }
                     } else if component.type_id() == TypeId::of::<DynScriptComponent>() {
-                        let ptr = data.state.scene.script_components[index].to_weak();
+                        let ptr = data
+                            .state
+                            .scene
+                            .component_pool::<DynScriptComponent>()?
+                            .get(index)
+                            .into_ris_error()?;
                         let mut aref_mut = ptr.borrow_mut();
                         let script_name = aref_mut.type_name().into_ris_error()?;
 
@@ -412,15 +467,44 @@ impl IUiHelperModule for InspectorModule {
                             script.inspect(script_inspect_data)?;
                         }
                     } else {
+                        let component_name = data
+                            .state
+                            .scene
+                            .registry
+                            .component_factories()
+                            .iter()
+                            .find(|x| x.component_id() == component.type_id())
+                            .map(|x| x.component_name().to_string())
+                            .unwrap_or_else(|| format!("{:?}", component.type_id()));
+
                         let header = ComponentHeader::draw(
                             data.ui,
-                            format!("{:?}##{:?}", component.type_id(), component),
+                            format!("{}##{:?}", component_name, component),
                         );
                         delete_requested = header.delete_requested;
+                        if header.is_open {
+                            let component_inspect_data = ComponentInspectData {
+                                id: format!("{:?}", component),
+                                ui: data.ui,
+                                frame: data.frame,
+                                state: data.state,
+                            };
+
+                            data.state.scene.deref_mut_component(component, |x| {
+                                x.inspect(component_inspect_data)
+                            })??;
+                        }
                     }
 
                     if delete_requested {
-                        game_object.remove_and_destroy_component(&data.state.scene, component);
+                        let scene = &data.state.scene;
+                        let is_script = component.type_id() == TypeId::of::<DynScriptComponent>();
+                        let command = if is_script {
+                            EditorCommand::detach_script(scene, game_object, component)?
+                        } else {
+                            EditorCommand::remove_component(scene, game_object, component)?
+                        };
+                        self.execute(scene, command)?;
                     }
                 }
 
@@ -438,12 +522,13 @@ impl IUiHelperModule for InspectorModule {
 
                     // the first component is the DynScriptComponent, which may not be added
                     // manually
-                    for factory in data
+                    for (i, factory) in data
                         .state
                         .scene
                         .registry
                         .component_factories()
                         .iter()
+                        .enumerate()
                         .skip(1)
                     {
                         let name = factory.component_name();
@@ -455,13 +540,15 @@ impl IUiHelperModule for InspectorModule {
                         }
 
                         if data.ui.menu_item(name) {
-                            factory.make(&data.state.scene, game_object)?;
+                            let command = EditorCommand::add_component(game_object, i);
+                            self.execute(&data.state.scene, command)?;
                         }
                     }
 
                     data.ui.separator();
 
-                    for factory in data.state.scene.registry.script_factories() {
+                    let script_factories = data.state.scene.registry.script_factories();
+                    for (i, factory) in script_factories.iter().enumerate() {
                         let name = factory.script_name();
                         if !name
                             .to_lowercase()
@@ -471,7 +558,8 @@ impl IUiHelperModule for InspectorModule {
                         }
 
                         if data.ui.menu_item(name) {
-                            factory.make_and_attach(&data.state.scene, game_object)?;
+                            let command = EditorCommand::attach_script(game_object, i);
+                            self.execute(&data.state.scene, command)?;
                         }
                     }
                 }
@@ -491,7 +579,9 @@ impl IUiHelperModule for InspectorModule {
                         let lock = original_lock.clone();
                         let id = id.clone();
                         let future = ThreadPool::submit(async move {
-                            let data = ris_asset::load_raw_async(id).wait();
+                            let data =
+                                ris_asset::load_raw_async_with_priority(id, LoadPriority::High)
+                                    .wait();
                             *lock.lock() = Some(data);
                         });
                         future.ignore();
@@ -527,10 +617,35 @@ impl IUiHelperModule for InspectorModule {
                     if let Some(chunk_index) = reserved {
                         self.shared_state
                             .borrow_mut()
-                            .set_chunk(chunk_index, Some(id));
+                            .set_chunk(chunk_index, Some(id.clone()));
                         ris_log::info!("loaded scene into chunk {}", chunk_index);
                     }
                 }
+
+                if path_string.ends_with(ris_asset::assets::ris_scene_text::EXTENSION)
+                    && data.ui.button("load")
+                {
+                    let text = std::str::from_utf8(&self.loaded_asset)?;
+                    let reserved =
+                        ris_asset::assets::ris_scene_text::deserialize(&data.state.scene, text)?;
+                    if let Some(chunk_index) = reserved {
+                        self.shared_state
+                            .borrow_mut()
+                            .set_chunk(chunk_index, Some(id));
+                        ris_log::info!("loaded text scene into chunk {}", chunk_index);
+                    }
+                }
+
+                if path_string.ends_with(ris_asset::assets::ris_prefab::EXTENSION)
+                    && data.ui.button("instantiate")
+                {
+                    let root = ris_asset::assets::ris_prefab::instantiate(
+                        &data.state.scene,
+                        &self.loaded_asset,
+                        Default::default(),
+                    )?;
+                    ris_log::info!("instantiated prefab {:?}", root);
+                }
             }
         }
 
@@ -539,6 +654,10 @@ impl IUiHelperModule for InspectorModule {
 }
 
 impl InspectorModule {
+    fn execute(&self, scene: &Scene, command: EditorCommand) -> RisResult<()> {
+        self.shared_state.borrow_mut().execute(scene, command)
+    }
+
     fn cache_rotation_axes(&mut self, q: Quat) {
         self.cached_xyz = Vec3(q.x(), q.y(), q.z());
         self.cached_xyw = Vec3(q.x(), q.y(), q.w());
@@ -46,6 +46,13 @@ impl IUiHelperModule for MetricsModule {
 
         ui.label_text("frame", format!("{}", frame.number()));
 
+        let fixed_frames = frame.fixed_frames();
+        ui.label_text("fixed steps", format!("{}", fixed_frames.len()));
+        ui.label_text(
+            "interpolation alpha",
+            format!("{:.2}", frame.interpolation_alpha()),
+        );
+
         let now = Instant::now();
         self.plot_frames.push((now, *frame));
         self.average_frames.push(*frame);
@@ -1,6 +1,7 @@
 pub mod asset_browser;
 pub mod gizmo;
 pub mod hierarchy;
+pub mod history;
 pub mod inspector;
 pub mod log;
 pub mod metrics;
@@ -51,6 +51,23 @@ impl IUiHelperModule for SettingsModule {
             }
         }
 
+        if ui.collapsing_header("simulation", imgui::TreeNodeFlags::empty()) {
+            let mut fixed_rate = settings.simulation().fixed_rate();
+            let mut max_fixed_steps = settings.simulation().max_fixed_steps();
+
+            if ui.slider("fixed rate", 1, 240, &mut fixed_rate) {
+                settings.simulation_mut().set_fixed_rate(fixed_rate);
+                self.saved = false;
+            }
+
+            if ui.slider("max fixed steps", 1, 20, &mut max_fixed_steps) {
+                settings
+                    .simulation_mut()
+                    .set_max_fixed_steps(max_fixed_steps);
+                self.saved = false;
+            }
+        }
+
         let mut header_flags = imgui::TreeNodeFlags::empty();
         header_flags.set(imgui::TreeNodeFlags::DEFAULT_OPEN, true);
         header_flags.set(imgui::TreeNodeFlags::BULLET, !self.saved);
@@ -0,0 +1,242 @@
+use ris_async::SpinLock;
+use ris_error::RisResult;
+
+use super::components::script_component::Script;
+use super::decl::GameObjectHandle;
+use super::error::EcsError;
+use super::error::EcsResult;
+use super::handle::DynComponentHandle;
+use super::id::Component;
+use super::id::GameObjectKind;
+use super::scene::Scene;
+
+type Command = Box<dyn FnOnce(&Scene, &mut CommandContext) -> RisResult<()> + Send>;
+
+/// A game object, that is created when the commands are applied.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SpawnedGameObject {
+    generation: usize,
+    index: usize,
+}
+
+/// The game object a command operates on. Either one that exists already, or one that is spawned
+/// by a previous command in the same queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandTarget {
+    GameObject(GameObjectHandle),
+    Spawned(SpawnedGameObject),
+}
+
+/// Records structural changes to a scene, to apply them later at a sync point.
+///
+/// Creating or destroying game objects and adding or removing components directly borrows the
+/// scene storage, which conflicts with scripts that are updated at the same time. Recording the
+/// changes instead is safe from any thread. The engine applies them after every fixed update step
+/// and after the update, in the order they were recorded.
+///
+/// Commands that are recorded while the queue is applied, for example by a script that is ended
+/// when its game object is destroyed, are applied at the next sync point.
+#[derive(Default)]
+pub struct SceneCommands {
+    queue: SpinLock<CommandQueue>,
+}
+
+#[derive(Default)]
+struct CommandQueue {
+    generation: usize,
+    spawned: usize,
+    commands: Vec<Command>,
+}
+
+struct CommandContext {
+    generation: usize,
+    spawned: Vec<GameObjectHandle>,
+}
+
+impl From<GameObjectHandle> for CommandTarget {
+    fn from(value: GameObjectHandle) -> Self {
+        Self::GameObject(value)
+    }
+}
+
+impl From<SpawnedGameObject> for CommandTarget {
+    fn from(value: SpawnedGameObject) -> Self {
+        Self::Spawned(value)
+    }
+}
+
+impl SceneCommands {
+    pub fn len(&self) -> usize {
+        self.queue.lock().commands.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Spawns a dynamic game object.
+    pub fn spawn(&self) -> SpawnedGameObject {
+        self.spawn_with_kind(GameObjectKind::Dynamic)
+    }
+
+    pub fn spawn_with_kind(&self, kind: GameObjectKind) -> SpawnedGameObject {
+        let mut queue = self.queue.lock();
+
+        let spawned = SpawnedGameObject {
+            generation: queue.generation,
+            index: queue.spawned,
+        };
+        queue.spawned += 1;
+
+        queue.commands.push(Box::new(move |scene, context| {
+            let result = GameObjectHandle::new_with_kind(scene, kind);
+
+            // keep the indices of later spawns intact, even if this one failed
+            let handle = result.as_ref().copied().unwrap_or(GameObjectHandle::null());
+            context.spawned.push(handle);
+
+            result?;
+            Ok(())
+        }));
+
+        spawned
+    }
+
+    pub fn destroy(&self, target: impl Into<CommandTarget>) {
+        let target = target.into();
+        self.push(move |scene, context| {
+            let game_object = context.resolve(target)?;
+            game_object.destroy(scene);
+            Ok(())
+        });
+    }
+
+    pub fn set_parent(
+        &self,
+        target: impl Into<CommandTarget>,
+        parent: Option<CommandTarget>,
+        sibling_index: usize,
+    ) {
+        let target = target.into();
+        self.push(move |scene, context| {
+            let game_object = context.resolve(target)?;
+            let parent = match parent {
+                Some(parent) => Some(context.resolve(parent)?),
+                None => None,
+            };
+
+            game_object.set_parent(scene, parent, sibling_index)?;
+            Ok(())
+        });
+    }
+
+    pub fn add_component<T: Component + Default + 'static>(
+        &self,
+        target: impl Into<CommandTarget>,
+    ) {
+        let target = target.into();
+        self.push(move |scene, context| {
+            let game_object = context.resolve(target)?;
+            game_object.add_component::<T>(scene)?;
+            Ok(())
+        });
+    }
+
+    pub fn add_script<T: Script + Default + 'static>(&self, target: impl Into<CommandTarget>) {
+        let target = target.into();
+        self.push(move |scene, context| {
+            let game_object = context.resolve(target)?;
+            game_object.add_script::<T>(scene)?;
+            Ok(())
+        });
+    }
+
+    pub fn remove_component(
+        &self,
+        target: impl Into<CommandTarget>,
+        component: DynComponentHandle,
+    ) {
+        let target = target.into();
+        self.push(move |scene, context| {
+            let game_object = context.resolve(target)?;
+            game_object.remove_and_destroy_component(scene, component);
+            Ok(())
+        });
+    }
+
+    /// Calls `callback` with the target, when the commands are applied. Useful to initialize
+    /// spawned game objects and their components.
+    pub fn with<F>(&self, target: impl Into<CommandTarget>, callback: F)
+    where
+        F: FnOnce(&Scene, GameObjectHandle) -> RisResult<()> + Send + 'static,
+    {
+        let target = target.into();
+        self.push(move |scene, context| {
+            let game_object = context.resolve(target)?;
+            callback(scene, game_object)
+        });
+    }
+
+    fn push<F>(&self, command: F)
+    where
+        F: FnOnce(&Scene, &mut CommandContext) -> RisResult<()> + Send + 'static,
+    {
+        self.queue.lock().commands.push(Box::new(command));
+    }
+
+    fn take(&self) -> (usize, Vec<Command>) {
+        let mut queue = self.queue.lock();
+        let generation = queue.generation;
+        let commands = std::mem::take(&mut queue.commands);
+        queue.generation = queue.generation.wrapping_add(1);
+        queue.spawned = 0;
+        (generation, commands)
+    }
+}
+
+impl CommandContext {
+    fn resolve(&self, target: CommandTarget) -> EcsResult<GameObjectHandle> {
+        match target {
+            CommandTarget::GameObject(handle) => Ok(handle),
+            CommandTarget::Spawned(spawned) => {
+                if spawned.generation != self.generation {
+                    return Err(EcsError::InvalidOperation(
+                        "spawned game object belongs to commands that were already applied"
+                            .to_string(),
+                    ));
+                }
+
+                self.spawned
+                    .get(spawned.index)
+                    .copied()
+                    .ok_or(EcsError::OutOfBounds)
+            }
+        }
+    }
+}
+
+impl Scene {
+    /// Applies all recorded commands in order. A failing command does not prevent the remaining
+    /// ones from being applied, the first error is returned after all commands ran. Returns the
+    /// game objects that were spawned, in the order they were spawned.
+    ///
+    /// Must not be called while scripts are updated.
+    pub fn apply_commands(&self) -> RisResult<Vec<GameObjectHandle>> {
+        let (generation, commands) = self.commands.take();
+
+        let mut context = CommandContext {
+            generation,
+            spawned: Vec::new(),
+        };
+
+        let mut result = Ok(());
+        for command in commands {
+            let command_result = command(self, &mut context);
+            if result.is_ok() {
+                result = command_result;
+            }
+        }
+
+        result.map(|()| context.spawned)
+    }
+}
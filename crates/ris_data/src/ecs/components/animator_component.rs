@@ -0,0 +1,205 @@
+use ris_asset_data::animation::AnimationClip;
+use ris_asset_data::skeleton::Pose;
+use ris_asset_data::skeleton::Skeleton;
+use ris_asset_data::AssetId;
+use ris_error::prelude::*;
+use ris_math::matrix::Mat4;
+
+use crate::ecs::decl::GameObjectHandle;
+use crate::ecs::id::Component;
+use crate::ecs::id::ComponentInspectData;
+use crate::ecs::scene::Scene;
+use crate::ecs::scene_stream::SceneReader;
+use crate::ecs::scene_stream::SceneWriter;
+
+/// A clip, that is played by an `AnimatorComponent`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AnimatorClip {
+    pub asset_id: AssetId,
+    /// clips are blended by their weights. a clip with weight 0 does not contribute to the pose
+    pub weight: f32,
+    /// playback speed. 1 is realtime, negative values play backwards
+    pub speed: f32,
+    /// when false, the clip stops at its end
+    pub looping: bool,
+    /// the current playback position in seconds. not serialized
+    pub time: f32,
+}
+
+/// Plays and blends animation clips on a skeleton, and produces the joint matrices to skin the
+/// `MeshComponent` on the same game object.
+#[derive(Debug, Default)]
+pub struct AnimatorComponent {
+    game_object: GameObjectHandle,
+    skeleton: Option<AssetId>,
+    clips: Vec<AnimatorClip>,
+    joint_matrices: Vec<Mat4>,
+}
+
+impl AnimatorClip {
+    pub fn new(asset_id: AssetId) -> Self {
+        Self {
+            asset_id,
+            weight: 1.0,
+            speed: 1.0,
+            looping: true,
+            time: 0.0,
+        }
+    }
+}
+
+impl Component for AnimatorComponent {
+    fn destroy(&mut self, _scene: &Scene) {}
+
+    fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+
+    fn game_object_mut(&mut self) -> &mut GameObjectHandle {
+        &mut self.game_object
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        match self.skeleton.as_ref() {
+            Some(skeleton) => {
+                stream.write_field("has_skeleton", true)?;
+                stream.write_field("skeleton", skeleton.clone())?;
+            }
+            None => {
+                stream.write_field("has_skeleton", false)?;
+            }
+        }
+
+        stream.write_field("clip_count", self.clips.len())?;
+        for clip in self.clips.iter() {
+            stream.write_field("clip", clip.asset_id.clone())?;
+            stream.write_field("weight", clip.weight)?;
+            stream.write_field("speed", clip.speed)?;
+            stream.write_field("looping", clip.looping)?;
+        }
+
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        let has_skeleton = ris_io::read_bool(stream)?;
+        if has_skeleton {
+            self.skeleton = Some(stream.read_asset_id()?);
+        } else {
+            self.skeleton = None;
+        }
+
+        let clip_count = ris_io::read_uint(stream)?;
+        self.clips.clear();
+        for _ in 0..clip_count {
+            let asset_id = stream.read_asset_id()?;
+            let weight = ris_io::read_f32(stream)?;
+            let speed = ris_io::read_f32(stream)?;
+            let looping = ris_io::read_bool(stream)?;
+
+            self.clips.push(AnimatorClip {
+                asset_id,
+                weight,
+                speed,
+                looping,
+                time: 0.0,
+            });
+        }
+
+        self.joint_matrices.clear();
+        Ok(())
+    }
+
+    fn inspect(&mut self, data: ComponentInspectData) -> RisResult<()> {
+        let skeleton = match self.skeleton.as_ref() {
+            Some(skeleton) => format!("{:?}", skeleton),
+            None => "none".to_string(),
+        };
+        data.ui
+            .label_text(format!("skeleton##{}", data.id), skeleton);
+
+        for (i, clip) in self.clips.iter_mut().enumerate() {
+            data.ui.separator();
+            data.ui.label_text(
+                format!("clip##{}-{}", data.id, i),
+                format!("{:?}", clip.asset_id),
+            );
+            data.ui.slider(
+                format!("weight##{}-{}", data.id, i),
+                0.0,
+                1.0,
+                &mut clip.weight,
+            );
+            data.ui.slider(
+                format!("speed##{}-{}", data.id, i),
+                -2.0,
+                2.0,
+                &mut clip.speed,
+            );
+            data.ui
+                .checkbox(format!("looping##{}-{}", data.id, i), &mut clip.looping);
+            data.ui.label_text(
+                format!("time##{}-{}", data.id, i),
+                format!("{:.3}", clip.time),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl AnimatorComponent {
+    pub fn skeleton(&self) -> Option<AssetId> {
+        self.skeleton.clone()
+    }
+
+    pub fn set_skeleton(&mut self, value: Option<AssetId>) {
+        self.skeleton = value;
+        self.joint_matrices.clear();
+    }
+
+    pub fn clips(&self) -> &[AnimatorClip] {
+        &self.clips
+    }
+
+    pub fn clips_mut(&mut self) -> &mut Vec<AnimatorClip> {
+        &mut self.clips
+    }
+
+    /// The joint matrices of the last call to `evaluate()`. Empty if it was not called yet.
+    pub fn joint_matrices(&self) -> &[Mat4] {
+        &self.joint_matrices
+    }
+
+    /// Advances the clips by `delta` seconds, blends them and computes the joint matrices.
+    /// `clips` must contain the loaded clips, in the same order as `self.clips()`.
+    pub fn evaluate(
+        &mut self,
+        delta: f32,
+        skeleton: &Skeleton,
+        clips: &[&AnimationClip],
+    ) -> RisResult<&[Mat4]> {
+        ris_error::assert!(clips.len() == self.clips.len())?;
+
+        let mut layers = Vec::with_capacity(clips.len());
+        for (state, &clip) in self.clips.iter_mut().zip(clips.iter()) {
+            let time = state.time + delta * state.speed;
+            state.time = if state.looping && clip.duration > 0.0 {
+                time.rem_euclid(clip.duration)
+            } else {
+                time.clamp(0.0, clip.duration)
+            };
+
+            if state.weight <= 0.0 {
+                continue;
+            }
+
+            let pose = clip.sample(skeleton, state.time)?;
+            layers.push((pose, state.weight));
+        }
+
+        let pose = Pose::blend_weighted(skeleton.rest_pose(), &layers)?;
+        self.joint_matrices = pose.joint_matrices(skeleton)?;
+        Ok(&self.joint_matrices)
+    }
+}
@@ -0,0 +1,101 @@
+use ris_asset_data::material::MaterialLookupId;
+use ris_asset_data::AssetId;
+use ris_error::prelude::*;
+
+use crate::ecs::decl::GameObjectHandle;
+use crate::ecs::id::Component;
+use crate::ecs::id::ComponentInspectData;
+use crate::ecs::scene::Scene;
+use crate::ecs::scene_stream::SceneReader;
+use crate::ecs::scene_stream::SceneWriter;
+
+/// Describes the surface of the `MeshComponent` on the same game object.
+#[derive(Debug, Default)]
+pub struct MaterialComponent {
+    game_object: GameObjectHandle,
+    previous_asset_id: Option<AssetId>,
+    current_asset_id: Option<AssetId>,
+    lookup_id: Option<MaterialLookupId>,
+}
+
+impl MaterialComponent {
+    pub fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+}
+
+impl Component for MaterialComponent {
+    fn destroy(&mut self, _scene: &Scene) {
+        self.lookup_id.take();
+    }
+
+    fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+
+    fn game_object_mut(&mut self) -> &mut GameObjectHandle {
+        &mut self.game_object
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        match self.current_asset_id.as_ref() {
+            Some(asset_id) => {
+                stream.write_field("has_asset", true)?;
+                stream.write_field("asset", asset_id.clone())?;
+            }
+            None => {
+                stream.write_field("has_asset", false)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        let has_asset = ris_io::read_bool(stream)?;
+        if has_asset {
+            self.current_asset_id = Some(stream.read_asset_id()?);
+        } else {
+            self.current_asset_id = None;
+        }
+
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ComponentInspectData) -> RisResult<()> {
+        // the inspector module draws material components itself, because picking an asset
+        // requires the asset browser
+        Ok(())
+    }
+}
+
+impl MaterialComponent {
+    pub fn poll_asset_id_to_allocate(&mut self) -> Option<AssetId> {
+        let changed = self.current_asset_id != self.previous_asset_id;
+        if !changed {
+            return None;
+        }
+
+        self.previous_asset_id = self.current_asset_id.clone();
+        self.current_asset_id.clone()
+    }
+
+    pub fn asset_id(&self) -> Option<AssetId> {
+        self.current_asset_id.clone()
+    }
+
+    pub fn set_asset_id(&mut self, value: Option<AssetId>) {
+        if value.is_none() {
+            self.lookup_id = None;
+        }
+        self.current_asset_id = value;
+    }
+
+    pub fn lookup_id(&self) -> Option<&MaterialLookupId> {
+        self.lookup_id.as_ref()
+    }
+
+    pub fn set_lookup_id(&mut self, value: MaterialLookupId) {
+        self.lookup_id = Some(value);
+    }
+}
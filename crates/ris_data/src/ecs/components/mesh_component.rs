@@ -4,6 +4,7 @@ use ris_error::prelude::*;
 
 use crate::ecs::decl::GameObjectHandle;
 use crate::ecs::id::Component;
+use crate::ecs::id::ComponentInspectData;
 use crate::ecs::scene::Scene;
 use crate::ecs::scene_stream::SceneReader;
 use crate::ecs::scene_stream::SceneWriter;
@@ -40,13 +41,13 @@ impl Component for MeshComponent {
     fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
         match self.current_asset_id.as_ref() {
             Some(asset_id) => {
-                ris_io::write_bool(stream, true)?;
-                stream.write_asset_id(asset_id.clone())?;
+                stream.write_field("has_asset", true)?;
+                stream.write_field("asset", asset_id.clone())?;
             }
             None => {
                 let asset_id = AssetId::Path(ERROR_MESH_PATH.to_string());
-                ris_io::write_bool(stream, false)?;
-                stream.write_asset_id(asset_id)?;
+                stream.write_field("has_asset", false)?;
+                stream.write_field("asset", asset_id)?;
             }
         }
 
@@ -65,6 +66,12 @@ impl Component for MeshComponent {
 
         Ok(())
     }
+
+    fn inspect(&mut self, _data: ComponentInspectData) -> RisResult<()> {
+        // the inspector module draws mesh components itself, because picking an asset requires
+        // the asset browser
+        Ok(())
+    }
 }
 
 impl MeshComponent {
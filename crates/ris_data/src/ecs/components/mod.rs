@@ -1,2 +1,4 @@
+pub mod animator_component;
+pub mod material_component;
 pub mod mesh_component;
 pub mod script_component;
@@ -16,10 +16,13 @@ use crate::ecs::error::EcsError;
 use crate::ecs::error::EcsResult;
 use crate::ecs::handle::ComponentHandle;
 use crate::ecs::id::Component;
+use crate::ecs::id::ComponentInspectData;
 use crate::ecs::id::EcsInstance;
 use crate::ecs::scene::Scene;
 use crate::ecs::scene_stream::SceneReader;
 use crate::ecs::scene_stream::SceneWriter;
+use crate::ecs::schedule::ScriptAccess;
+use crate::gameloop::fixed_frame::FixedFrame;
 use crate::gameloop::frame::Frame;
 use crate::god_state::GodState;
 
@@ -34,6 +37,12 @@ pub struct ScriptUpdateData<'a> {
     pub state: &'a GodState,
 }
 
+pub struct ScriptFixedUpdateData<'a> {
+    pub game_object: GameObjectHandle,
+    pub fixed_frame: FixedFrame,
+    pub state: &'a GodState,
+}
+
 pub struct ScriptInspectData<'a> {
     pub id: String,
     pub ui: &'a Ui,
@@ -49,6 +58,19 @@ pub trait Script: Debug + Send + Sync {
     fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()>;
     fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()>;
     fn inspect(&mut self, data: ScriptInspectData) -> RisResult<()>;
+
+    /// Called zero or more times per frame, before `update`, such that it runs at the fixed rate
+    /// configured in the simulation settings. Prefer this over `update` for anything, that must
+    /// not depend on the frame rate.
+    fn fixed_update(&mut self, _data: ScriptFixedUpdateData) -> RisResult<()> {
+        Ok(())
+    }
+
+    /// Declares what `update` and `fixed_update` touch, such that scripts can be updated in
+    /// parallel. Scripts which do not override this are updated exclusively.
+    fn access(&self, _game_object: GameObjectHandle) -> ScriptAccess {
+        ScriptAccess::exclusive()
+    }
 }
 
 #[derive(Debug)]
@@ -140,6 +162,7 @@ impl Component for DynScriptComponent {
 
                 let mut script = factory.make();
                 script.boxed.deserialize(stream)?;
+                stream.apply_script_overrides(self.game_object, script.id, script.boxed.as_mut())?;
                 let data = ScriptStartEndData {
                     game_object: self.game_object(),
                     scene: stream.scene,
@@ -151,6 +174,28 @@ impl Component for DynScriptComponent {
             },
         }
     }
+
+    fn inspect(&mut self, data: ComponentInspectData) -> RisResult<()> {
+        let ComponentInspectData {
+            id,
+            ui,
+            frame,
+            state,
+        } = data;
+
+        let data = ScriptInspectData {
+            id,
+            ui,
+            game_object: self.game_object,
+            frame,
+            state,
+        };
+
+        match self.script_mut() {
+            Some(script) => script.inspect(data),
+            None => Ok(()),
+        }
+    }
 }
 
 impl DynScriptComponent {
@@ -177,6 +222,13 @@ impl DynScriptComponent {
         self.script.as_mut().map(|x| &mut x.boxed)
     }
 
+    pub fn access(&self) -> ScriptAccess {
+        match self.script.as_ref() {
+            Some(script) => script.boxed.access(self.game_object),
+            None => ScriptAccess::exclusive(),
+        }
+    }
+
     pub fn update(&mut self, frame: Frame, state: &GodState) -> RisResult<()> {
         let data = ScriptUpdateData {
             game_object: self.game_object,
@@ -195,6 +247,24 @@ impl DynScriptComponent {
         }
     }
 
+    pub fn fixed_update(&mut self, fixed_frame: FixedFrame, state: &GodState) -> RisResult<()> {
+        let data = ScriptFixedUpdateData {
+            game_object: self.game_object,
+            fixed_frame,
+            state,
+        };
+
+        match self.script_mut() {
+            Some(script) => script.fixed_update(data),
+            None => {
+                ris_log::error!(
+                    "script was none. make sure to start the script before calling fixed update"
+                );
+                Ok(())
+            }
+        }
+    }
+
     pub fn end(&mut self, scene: &Scene) -> RisResult<()> {
         let data = ScriptStartEndData {
             game_object: self.game_object,
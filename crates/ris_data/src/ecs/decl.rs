@@ -1,21 +1,17 @@
-use std::any::TypeId;
 use std::marker::PhantomData;
 
+use super::components::animator_component::AnimatorComponent;
+use super::components::material_component::MaterialComponent;
 use super::components::mesh_component::MeshComponent;
 use super::components::script_component::DynScriptComponent;
 use super::components::script_component::Script;
 use super::game_object::GameObject;
-use super::handle::ComponentHandle;
-use super::handle::DynComponentHandle;
-use super::handle::DynHandle;
-use super::handle::GenericHandle;
-use super::handle::Handle;
-use super::id::EcsObject;
-use super::scene::Scene;
-
-declare::object!(GameObjectHandle, GameObject);
-declare::component!(MeshComponentHandle, MeshComponent);
-declare::component!(DynScriptComponentHandle, DynScriptComponent);
+
+crate::declare_object!(GameObjectHandle, GameObject);
+crate::declare_component!(MeshComponentHandle, MeshComponent);
+crate::declare_component!(DynScriptComponentHandle, DynScriptComponent);
+crate::declare_component!(MaterialComponentHandle, MaterialComponent);
+crate::declare_component!(AnimatorComponentHandle, AnimatorComponent);
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct ScriptComponentHandle<T: Script> {
@@ -31,80 +27,80 @@ impl<T: Script> Clone for ScriptComponentHandle<T> {
 
 impl<T: Script> Copy for ScriptComponentHandle<T> {}
 
-mod declare {
-    macro_rules! object {
-        (
-            $handle_name:ident,
-            $handle_type:ident $(,)?
-        ) => {
-            #[derive(Debug, Clone, Copy, PartialEq, Eq)]
-            pub struct $handle_name(pub GenericHandle<$handle_type>);
-
-            impl std::ops::Deref for $handle_name {
-                type Target = GenericHandle<$handle_type>;
-                fn deref(&self) -> &Self::Target {
-                    &self.0
-                }
+/// Declares a handle for an ecs object and implements `EcsObject` for it.
+#[macro_export]
+macro_rules! declare_object {
+    (
+        $handle_name:ident,
+        $handle_type:ident $(,)?
+    ) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        pub struct $handle_name(pub $crate::ecs::handle::GenericHandle<$handle_type>);
+
+        impl std::ops::Deref for $handle_name {
+            type Target = $crate::ecs::handle::GenericHandle<$handle_type>;
+            fn deref(&self) -> &Self::Target {
+                &self.0
             }
+        }
 
-            impl std::ops::DerefMut for $handle_name {
-                fn deref_mut(&mut self) -> &mut Self::Target {
-                    &mut self.0
-                }
+        impl std::ops::DerefMut for $handle_name {
+            fn deref_mut(&mut self) -> &mut Self::Target {
+                &mut self.0
             }
+        }
 
-            impl Handle for $handle_name {
-                fn type_id() -> TypeId {
-                    TypeId::of::<$handle_type>()
-                }
-
-                fn to_dyn(self) -> DynHandle {
-                    self.0.into()
-                }
+        impl $crate::ecs::handle::Handle for $handle_name {
+            fn type_id() -> std::any::TypeId {
+                std::any::TypeId::of::<$handle_type>()
             }
 
-            impl From<GenericHandle<$handle_type>> for $handle_name {
-                fn from(value: GenericHandle<$handle_type>) -> Self {
-                    Self(value)
-                }
+            fn to_dyn(self) -> $crate::ecs::handle::DynHandle {
+                self.0.into()
             }
+        }
 
-            impl From<$handle_name> for GenericHandle<$handle_type> {
-                fn from(value: $handle_name) -> Self {
-                    value.0
-                }
+        impl From<$crate::ecs::handle::GenericHandle<$handle_type>> for $handle_name {
+            fn from(value: $crate::ecs::handle::GenericHandle<$handle_type>) -> Self {
+                Self(value)
             }
+        }
 
-            impl EcsObject for $handle_type {}
+        impl From<$handle_name> for $crate::ecs::handle::GenericHandle<$handle_type> {
+            fn from(value: $handle_name) -> Self {
+                value.0
+            }
+        }
 
-            impl $handle_name {
-                pub fn null() -> Self {
-                    let handle = GenericHandle::null();
-                    Self(handle)
-                }
+        impl $crate::ecs::id::EcsObject for $handle_type {}
 
-                pub fn is_alive(self, scene: &Scene) -> bool {
-                    self.0.is_alive(scene)
-                }
+        impl $handle_name {
+            pub fn null() -> Self {
+                let handle = $crate::ecs::handle::GenericHandle::null();
+                Self(handle)
             }
-        };
-    }
 
-    macro_rules! component {
-        (
-            $handle_name:ident,
-            $handle_type:ident $(,)?
-        ) => {
-            declare::object!($handle_name, $handle_type);
-
-            impl ComponentHandle for $handle_name {
-                fn to_dyn_component(self) -> DynComponentHandle {
-                    self.0.into()
-                }
+            pub fn is_alive(self, scene: &$crate::ecs::scene::Scene) -> bool {
+                self.0.is_alive(scene)
             }
-        };
-    }
+        }
+    };
+}
 
-    pub(crate) use component;
-    pub(crate) use object;
+/// Declares a handle for a component. The component must still be registered in the `Registry`,
+/// before it can be added to a game object.
+#[macro_export]
+macro_rules! declare_component {
+    (
+        $handle_name:ident,
+        $handle_type:ident $(,)?
+    ) => {
+        $crate::declare_object!($handle_name, $handle_type);
+
+        impl $crate::ecs::handle::ComponentHandle for $handle_name {
+            fn to_dyn_component(self) -> $crate::ecs::handle::DynComponentHandle {
+                self.0.into()
+            }
+        }
+    };
 }
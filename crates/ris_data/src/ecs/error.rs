@@ -4,9 +4,10 @@ pub enum EcsError {
     InvalidOperation(String),
     IsNull,
     ObjectIsDestroyed,
-    //OutOfBounds,
+    OutOfBounds,
     OutOfMemory,
     TypeDoesNotMatchSceneKind,
+    ComponentIsNotRegistered,
 }
 
 impl std::fmt::Display for EcsError {
@@ -16,9 +17,10 @@ impl std::fmt::Display for EcsError {
             EcsError::InvalidOperation(reason) => write!(f, "invalid operation: {}", reason),
             EcsError::IsNull => write!(f, "is null"),
             EcsError::ObjectIsDestroyed => write!(f, "object is destroyed"),
-            //EcsError::OutOfBounds => write!(f, "operation was out of bounds"),
+            EcsError::OutOfBounds => write!(f, "operation was out of bounds"),
             EcsError::OutOfMemory => write!(f, "out of memory"),
             EcsError::TypeDoesNotMatchSceneKind => write!(f, "type does not match the scene kind"),
+            EcsError::ComponentIsNotRegistered => write!(f, "component is not registered"),
         }
     }
 }
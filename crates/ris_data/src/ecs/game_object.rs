@@ -47,6 +47,13 @@ pub struct GameObject {
     scale: Vec3,
     components: Vec<DynComponentHandle>,
 
+    // the local values before they were changed during the fixed update `previous_step`. the
+    // values are only blended, if no other fixed update happened since
+    previous_position: Vec3,
+    previous_rotation: Quat,
+    previous_scale: Vec3,
+    previous_step: Option<u64>,
+
     // hierarchy
     parent: Option<GameObjectHandle>,
     children: Vec<GameObjectHandle>,
@@ -61,12 +68,38 @@ impl Default for GameObject {
             rotation: Quat::identity(),
             scale: Vec3::init(1.0),
             components: Vec::new(),
+            previous_position: Vec3::init(0.0),
+            previous_rotation: Quat::identity(),
+            previous_scale: Vec3::init(1.0),
+            previous_step: None,
             parent: None,
             children: Vec::new(),
         }
     }
 }
 
+impl GameObject {
+    /// Remembers the local values, the first time they change during a fixed update, such that
+    /// they can be blended with the values after it. Changes outside of a fixed update are not
+    /// blended.
+    fn store_previous_transform(&mut self, scene: &Scene) {
+        if !scene.is_in_fixed_update() {
+            self.previous_step = None;
+            return;
+        }
+
+        let step = scene.fixed_update_step();
+        if self.previous_step == Some(step) {
+            return;
+        }
+
+        self.previous_position = self.position;
+        self.previous_rotation = self.rotation;
+        self.previous_scale = self.scale;
+        self.previous_step = Some(step);
+    }
+}
+
 impl Default for GameObjectHandle {
     fn default() -> Self {
         Self::null()
@@ -144,6 +177,7 @@ impl GameObjectHandle {
     pub fn set_position(self, scene: &Scene, value: Vec3) -> EcsResult<()> {
         let ptr = scene.deref(self.into())?;
         let mut aref_mut = ptr.borrow_mut();
+        aref_mut.store_previous_transform(scene);
         aref_mut.position = value;
         Ok(())
     }
@@ -156,6 +190,7 @@ impl GameObjectHandle {
     pub fn set_rotation(self, scene: &Scene, value: Quat) -> EcsResult<()> {
         let ptr = scene.deref(self.into())?;
         let mut aref_mut = ptr.borrow_mut();
+        aref_mut.store_previous_transform(scene);
         aref_mut.rotation = value;
         Ok(())
     }
@@ -168,6 +203,7 @@ impl GameObjectHandle {
     pub fn set_scale(self, scene: &Scene, value: Vec3) -> EcsResult<()> {
         let ptr = scene.deref(self.into())?;
         let mut aref_mut = ptr.borrow_mut();
+        aref_mut.store_previous_transform(scene);
         aref_mut.scale = value;
         Ok(())
     }
@@ -281,6 +317,30 @@ impl GameObjectHandle {
         Ok(components)
     }
 
+    /// Moves the component to the given index of the components of this game object. Indices past
+    /// the end move it to the end.
+    pub fn set_component_index(
+        self,
+        scene: &Scene,
+        component: DynComponentHandle,
+        index: usize,
+    ) -> EcsResult<()> {
+        let ptr = scene.deref(self.into())?;
+        let mut aref_mut = ptr.borrow_mut();
+        let position = aref_mut
+            .components
+            .iter()
+            .position(|&x| x == component)
+            .ok_or_else(|| {
+                EcsError::InvalidOperation("component is not attached to game object".to_string())
+            })?;
+
+        aref_mut.components.remove(position);
+        let index = index.clamp(0, aref_mut.components.len());
+        aref_mut.components.insert(index, component);
+        Ok(())
+    }
+
     pub fn remove_and_destroy_component(self, scene: &Scene, component: DynComponentHandle) {
         let Ok(ptr) = scene.deref(self.into()) else {
             return;
@@ -332,6 +392,37 @@ impl GameObjectHandle {
         Ok(model)
     }
 
+    /// Like `model()`, but blends the transforms before and after the last fixed update by
+    /// `alpha`. Game objects that were moved outside of a fixed update are not blended.
+    pub fn interpolated_model(self, scene: &Scene, alpha: f32) -> EcsResult<Mat4> {
+        let mut model = Mat4::init(1.0);
+        let mut option = Some(self);
+        while let Some(handle) = option {
+            let ptr = scene.deref(handle.into())?;
+            let aref = ptr.borrow();
+
+            let trs = if aref
+                .previous_step
+                .is_some_and(|x| x == scene.fixed_update_step())
+            {
+                let a = Vec3::init(alpha);
+                let position = Vec3::mix(aref.previous_position, aref.position, a);
+                let rotation = aref.previous_rotation.slerp(aref.rotation, alpha);
+                let scale = Vec3::mix(aref.previous_scale, aref.scale, a);
+                affine::trs(position, rotation, scale)
+            } else {
+                affine::trs(aref.position, aref.rotation, aref.scale)
+            };
+
+            model = trs * model;
+
+            drop(aref);
+            option = handle.parent(scene)?;
+        }
+
+        Ok(model)
+    }
+
     pub fn parent(self, scene: &Scene) -> EcsResult<Option<GameObjectHandle>> {
         let ptr = scene.deref(self.into())?;
         let mut aref_mut = ptr.borrow_mut();
@@ -424,6 +515,18 @@ impl GameObjectHandle {
         Ok(children)
     }
 
+    /// The game object and all its descendants, depth first. Parents precede their children.
+    pub fn subtree(self, scene: &Scene) -> EcsResult<Vec<GameObjectHandle>> {
+        let mut subtree = Vec::new();
+        let mut stack = vec![self];
+        while let Some(game_object) = stack.pop() {
+            subtree.push(game_object);
+            stack.extend(game_object.children(scene)?.into_iter().rev());
+        }
+
+        Ok(subtree)
+    }
+
     pub fn sibling_index(self, scene: &Scene) -> EcsResult<usize> {
         let Some(parent) = self.parent(scene)? else {
             return Ok(0);
@@ -2,8 +2,6 @@ use std::any::TypeId;
 use std::fmt::Debug;
 use std::marker::PhantomData;
 
-use super::components::mesh_component::MeshComponent;
-use super::components::script_component::DynScriptComponent;
 use super::decl::GameObjectHandle;
 use super::error::EcsError;
 use super::error::EcsResult;
@@ -74,8 +72,8 @@ impl DynHandle {
             SceneKind::StaticGameObjct { chunk: _ } if type_id == TypeId::of::<GameObject>() => {
                 true
             }
-            SceneKind::Component if type_id == TypeId::of::<MeshComponent>() => true,
-            SceneKind::Component if type_id == TypeId::of::<DynScriptComponent>() => true,
+            // whether the component is actually registered is checked when the handle is deref'ed
+            SceneKind::Component if type_id != TypeId::of::<GameObject>() => true,
             _ => false,
         };
 
@@ -1,5 +1,7 @@
 use std::fmt::Debug;
 
+use imgui::Ui;
+
 use ris_error::RisResult;
 use ris_ptr::ArefCell;
 use ris_ptr::StrongPtr;
@@ -11,6 +13,8 @@ use super::handle::GenericHandle;
 use super::scene::Scene;
 use super::scene_stream::SceneReader;
 use super::scene_stream::SceneWriter;
+use crate::gameloop::frame::Frame;
+use crate::god_state::GodState;
 
 //
 // ids
@@ -65,12 +69,20 @@ impl TryFrom<SceneKind> for GameObjectKind {
 
 pub trait EcsObject: Debug {}
 
+pub struct ComponentInspectData<'a> {
+    pub id: String,
+    pub ui: &'a Ui,
+    pub frame: Frame,
+    pub state: &'a GodState,
+}
+
 pub trait Component: EcsObject {
     fn destroy(&mut self, scene: &Scene);
     fn game_object(&self) -> GameObjectHandle;
     fn game_object_mut(&mut self) -> &mut GameObjectHandle;
     fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()>;
     fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()>;
+    fn inspect(&mut self, data: ComponentInspectData) -> RisResult<()>;
 }
 
 pub struct EcsInstance<T: EcsObject> {
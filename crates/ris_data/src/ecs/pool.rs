@@ -0,0 +1,209 @@
+use std::any::Any;
+use std::any::TypeId;
+
+use ris_ptr::ArefCell;
+use ris_ptr::StrongPtr;
+
+use super::error::EcsError;
+use super::error::EcsResult;
+use super::handle::GenericHandle;
+use super::id::Component;
+use super::id::EcsInstance;
+use super::id::EcsObject;
+use super::id::EcsPtr;
+use super::id::EcsWeakPtr;
+use super::id::SceneId;
+use super::id::SceneKind;
+
+/// Growable storage for ecs objects.
+///
+/// Objects are stored in pages of a fixed size. When every slot is in use, a new page is
+/// appended. The list of pages reallocates as it grows, but each page is a boxed slice, whose
+/// contents keep their address until the pool is dropped. Additionally every slot is boxed in its
+/// own `EcsPtr`, thus growing the pool never invalidates previously handed out pointers.
+///
+/// A slot keeps its handle when its object is destroyed. Reusing a slot bumps the generation of
+/// the handle, such that stale handles fail to deref.
+pub struct EcsPool<T: EcsObject> {
+    kind: SceneKind,
+    page_size: usize,
+    pages: ArefCell<Vec<Box<[EcsPtr<T>]>>>,
+}
+
+pub struct EcsPoolIter<'a, T: EcsObject> {
+    pool: &'a EcsPool<T>,
+    index: usize,
+}
+
+impl<T: EcsObject + Default + 'static> EcsPool<T> {
+    pub fn new(kind: SceneKind, page_size: usize) -> EcsResult<Self> {
+        if page_size == 0 {
+            return Err(EcsError::InvalidOperation(
+                "page size must be greater than 0".to_string(),
+            ));
+        }
+
+        Ok(Self {
+            kind,
+            page_size,
+            pages: ArefCell::new(Vec::new()),
+        })
+    }
+
+    /// Allocates pages until the pool has at least `capacity` slots.
+    pub fn reserve(&self, capacity: usize) -> EcsResult<()> {
+        while self.len() < capacity {
+            self.grow()?;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the first slot that is not alive. Grows the pool if there is none.
+    pub fn next_free(&self) -> EcsResult<EcsWeakPtr<T>> {
+        let free = self.iter().find(|x| !x.borrow().is_alive);
+        if let Some(ptr) = free {
+            return Ok(ptr);
+        }
+
+        let index = self.len();
+        self.grow()?;
+        self.get(index).ok_or(EcsError::OutOfBounds)
+    }
+
+    fn grow(&self) -> EcsResult<()> {
+        let offset = self.len();
+
+        let mut page = Vec::with_capacity(self.page_size);
+        for i in 0..self.page_size {
+            let id = SceneId {
+                kind: self.kind,
+                index: offset + i,
+            };
+            let handle = GenericHandle::new(id, 0)?;
+            let instance = EcsInstance::new(handle);
+            let ptr = StrongPtr::new(ArefCell::new(instance));
+            page.push(ptr);
+        }
+
+        self.pages.borrow_mut().push(page.into_boxed_slice());
+
+        Ok(())
+    }
+}
+
+impl<T: EcsObject> EcsPool<T> {
+    pub fn kind(&self) -> SceneKind {
+        self.kind
+    }
+
+    pub fn page_size(&self) -> usize {
+        self.page_size
+    }
+
+    pub fn page_count(&self) -> usize {
+        self.pages.borrow().len()
+    }
+
+    /// The number of slots, alive or not.
+    pub fn len(&self) -> usize {
+        self.page_count() * self.page_size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn get(&self, index: usize) -> Option<EcsWeakPtr<T>> {
+        let page = index / self.page_size;
+        let slot = index % self.page_size;
+
+        let pages = self.pages.borrow();
+        pages.get(page).map(|x| x[slot].to_weak())
+    }
+
+    /// Iterates over all slots, alive or not.
+    ///
+    /// The pool is only borrowed while advancing the iterator. Creating objects while iterating
+    /// is allowed, slots that are added by growing the pool will be visited as well.
+    pub fn iter(&self) -> EcsPoolIter<'_, T> {
+        EcsPoolIter {
+            pool: self,
+            index: 0,
+        }
+    }
+}
+
+impl<T: EcsObject> Iterator for EcsPoolIter<'_, T> {
+    type Item = EcsWeakPtr<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let item = self.pool.get(self.index)?;
+        self.index += 1;
+        Some(item)
+    }
+}
+
+impl<'a, T: EcsObject> IntoIterator for &'a EcsPool<T> {
+    type Item = EcsWeakPtr<T>;
+    type IntoIter = EcsPoolIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+/// Type erased component pool. The scene owns one per component factory in the registry.
+pub trait IComponentPool: Send + Sync {
+    fn component_id(&self) -> TypeId;
+    fn as_any(&self) -> &dyn Any;
+    fn mark_as_destroyed(&self, index: usize) -> EcsResult<()>;
+    fn with_component(
+        &self,
+        index: usize,
+        callback: &mut dyn FnMut(&dyn Component),
+    ) -> EcsResult<()>;
+    fn with_component_mut(
+        &self,
+        index: usize,
+        callback: &mut dyn FnMut(&mut dyn Component),
+    ) -> EcsResult<()>;
+}
+
+impl<T: Component + Default + Send + Sync + 'static> IComponentPool for EcsPool<T> {
+    fn component_id(&self) -> TypeId {
+        TypeId::of::<T>()
+    }
+
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn mark_as_destroyed(&self, index: usize) -> EcsResult<()> {
+        let ptr = self.get(index).ok_or(EcsError::OutOfBounds)?;
+        ptr.borrow_mut().is_alive = false;
+        Ok(())
+    }
+
+    fn with_component(
+        &self,
+        index: usize,
+        callback: &mut dyn FnMut(&dyn Component),
+    ) -> EcsResult<()> {
+        let ptr = self.get(index).ok_or(EcsError::OutOfBounds)?;
+        let aref = ptr.borrow();
+        callback(&aref.value);
+        Ok(())
+    }
+
+    fn with_component_mut(
+        &self,
+        index: usize,
+        callback: &mut dyn FnMut(&mut dyn Component),
+    ) -> EcsResult<()> {
+        let ptr = self.get(index).ok_or(EcsError::OutOfBounds)?;
+        let mut aref_mut = ptr.borrow_mut();
+        callback(&mut aref_mut.value);
+        Ok(())
+    }
+}
@@ -0,0 +1,207 @@
+use std::any::TypeId;
+
+use super::components::script_component::DynScriptComponent;
+use super::components::script_component::Script;
+use super::decl::DynScriptComponentHandle;
+use super::decl::GameObjectHandle;
+use super::decl::ScriptComponentHandle;
+use super::error::EcsError;
+use super::error::EcsResult;
+use super::handle::GenericHandle;
+use super::id::Component;
+use super::id::GameObjectKind;
+use super::scene::Scene;
+
+/// Finds game objects and components in a scene.
+///
+/// By default, a query only yields alive game objects that are active in the hierarchy. Filters
+/// are combined, a game object must pass all of them to be yielded.
+///
+/// Queries collect their results, instead of borrowing the scene while iterating. Thus it is safe
+/// to modify the scene while looping over the result.
+pub struct Query<'a> {
+    scene: &'a Scene,
+    components: Vec<TypeId>,
+    scripts: Vec<TypeId>,
+    kind: Option<GameObjectKind>,
+    include_inactive: bool,
+    roots_only: bool,
+}
+
+impl Scene {
+    pub fn query(&self) -> Query<'_> {
+        Query {
+            scene: self,
+            components: Vec::new(),
+            scripts: Vec::new(),
+            kind: None,
+            include_inactive: false,
+            roots_only: false,
+        }
+    }
+}
+
+impl<'a> Query<'a> {
+    /// Only yield game objects that have a component of type `T`.
+    pub fn with<T: Component + 'static>(mut self) -> Self {
+        self.components.push(TypeId::of::<T>());
+        self
+    }
+
+    /// Only yield game objects that have a script of type `T`.
+    pub fn with_script<T: Script + 'static>(mut self) -> Self {
+        self.scripts.push(TypeId::of::<T>());
+        self
+    }
+
+    /// Only yield game objects that are stored in the dynamic or the given static chunk.
+    pub fn in_kind(mut self, kind: GameObjectKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Also yield game objects that are not active in the hierarchy.
+    pub fn include_inactive(mut self) -> Self {
+        self.include_inactive = true;
+        self
+    }
+
+    /// Only yield game objects without a parent.
+    pub fn roots_only(mut self) -> Self {
+        self.roots_only = true;
+        self
+    }
+
+    pub fn game_objects(self) -> EcsResult<Vec<GameObjectHandle>> {
+        let mut chunks = Vec::with_capacity(1 + self.scene.static_chunks.len());
+        match self.kind {
+            Some(GameObjectKind::Dynamic) => chunks.push(&self.scene.dynamic_game_objects),
+            Some(GameObjectKind::Static { chunk }) => {
+                let chunk = self
+                    .scene
+                    .static_chunks
+                    .get(chunk)
+                    .ok_or(EcsError::OutOfBounds)?;
+                chunks.push(&chunk.game_objects)
+            }
+            None => {
+                chunks.push(&self.scene.dynamic_game_objects);
+                for chunk in self.scene.static_chunks.iter() {
+                    chunks.push(&chunk.game_objects);
+                }
+            }
+        }
+
+        let mut result = Vec::new();
+        for chunk in chunks {
+            for ptr in chunk.iter() {
+                let aref = ptr.borrow();
+                if !aref.is_alive {
+                    continue;
+                }
+
+                let game_object = GameObjectHandle::from(aref.handle);
+                drop(aref);
+
+                if self.matches(game_object)? {
+                    result.push(game_object);
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Yields every component of type `T`, whose game object passes the filters.
+    pub fn components<T: Component + 'static>(self) -> EcsResult<Vec<GenericHandle<T>>> {
+        let pool = self.scene.component_pool::<T>()?;
+
+        let mut result = Vec::new();
+        for ptr in pool.iter() {
+            let aref = ptr.borrow();
+            if !aref.is_alive {
+                continue;
+            }
+
+            let handle = aref.handle;
+            let game_object = aref.value.game_object();
+            drop(aref);
+
+            if self.matches(game_object)? {
+                result.push(handle);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Yields every script of type `T`, whose game object passes the filters.
+    pub fn scripts<T: Script + 'static>(self) -> EcsResult<Vec<ScriptComponentHandle<T>>> {
+        let scene = self.scene;
+
+        let result = self
+            .with_script::<T>()
+            .components::<DynScriptComponent>()?
+            .into_iter()
+            .filter_map(|x| {
+                let handle = DynScriptComponentHandle::from(x);
+                ScriptComponentHandle::<T>::try_from(handle, scene).ok()
+            })
+            .collect();
+
+        Ok(result)
+    }
+
+    fn matches(&self, game_object: GameObjectHandle) -> EcsResult<bool> {
+        let scene = self.scene;
+
+        if !game_object.is_alive(scene) {
+            return Ok(false);
+        }
+
+        if let Some(kind) = self.kind {
+            let actual_kind = GameObjectKind::try_from(game_object.scene_id().kind)?;
+            if actual_kind != kind {
+                return Ok(false);
+            }
+        }
+
+        if self.roots_only && game_object.parent(scene)?.is_some() {
+            return Ok(false);
+        }
+
+        if !self.include_inactive && !game_object.is_active_in_hierarchy(scene)? {
+            return Ok(false);
+        }
+
+        if self.components.is_empty() && self.scripts.is_empty() {
+            return Ok(true);
+        }
+
+        let components = game_object.components(scene)?;
+
+        for &component_id in self.components.iter() {
+            if !components.iter().any(|x| x.type_id() == component_id) {
+                return Ok(false);
+            }
+        }
+
+        for &script_id in self.scripts.iter() {
+            let has_script = components
+                .iter()
+                .filter_map(|&x| GenericHandle::<DynScriptComponent>::from_dyn(x.into()).ok())
+                .any(|x| {
+                    scene
+                        .deref(x)
+                        .map(|ptr| ptr.borrow().type_id() == Some(script_id))
+                        .unwrap_or(false)
+                });
+
+            if !has_script {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+}
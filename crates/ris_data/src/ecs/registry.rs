@@ -5,14 +5,20 @@ use std::marker::PhantomData;
 use ris_error::RisResult;
 use ris_ptr::SyncUnsafeCell;
 
+use super::components::animator_component::AnimatorComponent;
+use super::components::material_component::MaterialComponent;
 use super::components::mesh_component::MeshComponent;
 use super::components::script_component::DynScript;
 use super::components::script_component::DynScriptComponent;
 use super::components::script_component::Script;
 use super::decl::DynScriptComponentHandle;
 use super::decl::GameObjectHandle;
+use super::error::EcsResult;
 use super::handle::DynComponentHandle;
 use super::id::Component;
+use super::id::SceneKind;
+use super::pool::EcsPool;
+use super::pool::IComponentPool;
 use super::scene::Scene;
 
 static REGISTRY: SyncUnsafeCell<Option<Registry>> = SyncUnsafeCell::new(None);
@@ -27,6 +33,7 @@ pub trait IComponentFactory: Debug + Send + Sync {
     fn component_id(&self) -> TypeId;
     fn component_name(&self) -> &str;
     fn make(&self, scene: &Scene, game_object: GameObjectHandle) -> RisResult<DynComponentHandle>;
+    fn make_pool(&self, page_size: usize) -> EcsResult<Box<dyn IComponentPool>>;
 }
 
 pub trait IScriptFactory: Debug + Send + Sync {
@@ -51,7 +58,8 @@ pub struct ScriptFactory<T: Script + Default> {
 }
 
 impl Registry {
-    fn component<T: Component>() -> RisResult<Box<ComponentFactory<T>>> {
+    pub fn component<T: Component + Default + Send + Sync + 'static>(
+    ) -> RisResult<Box<ComponentFactory<T>>> {
         let factory = ComponentFactory {
             boo: PhantomData::<T>,
         };
@@ -67,11 +75,43 @@ impl Registry {
         Ok(Box::new(factory))
     }
 
-    pub fn new(scripts: Vec<Box<dyn IScriptFactory>>) -> RisResult<Self> {
-        let components: Vec<Box<dyn IComponentFactory>> = vec![
+    /// Built in components are registered automatically, thus `components` only needs to contain
+    /// user defined ones.
+    ///
+    /// Serialized scenes refer to components and scripts by the order they are registered in.
+    /// Reordering them breaks existing scenes.
+    pub fn new(
+        components: Vec<Box<dyn IComponentFactory>>,
+        scripts: Vec<Box<dyn IScriptFactory>>,
+    ) -> RisResult<Self> {
+        let mut all_components: Vec<Box<dyn IComponentFactory>> = vec![
             Self::component::<DynScriptComponent>()?,
             Self::component::<MeshComponent>()?,
+            Self::component::<MaterialComponent>()?,
+            Self::component::<AnimatorComponent>()?,
         ];
+        all_components.extend(components);
+        let components = all_components;
+
+        // assert that all components have unique ids
+        for (i, left) in components.iter().enumerate() {
+            for (j, right) in components.iter().enumerate().skip(1 + i) {
+                let left_id = left.component_id();
+                let right_id = right.component_id();
+
+                if left_id == right_id {
+                    return ris_error::new_result!(
+                        "component id collision detected!\n[{}]: {:?} -> {:?}\n[{}]: {:?} -> {:?}\n",
+                        i,
+                        left,
+                        left_id,
+                        j,
+                        right,
+                        right_id,
+                    );
+                }
+            }
+        }
 
         // assert that all scripts have unique ids
         for (i, left) in scripts.iter().enumerate() {
@@ -122,6 +162,11 @@ impl<T: Component + Default + Send + Sync + 'static> IComponentFactory for Compo
         let handle = game_object.add_component::<T>(scene)?;
         Ok(handle.into())
     }
+
+    fn make_pool(&self, page_size: usize) -> EcsResult<Box<dyn IComponentPool>> {
+        let pool = EcsPool::<T>::new(SceneKind::Component, page_size)?;
+        Ok(Box::new(pool))
+    }
 }
 
 impl<T: Script + Default + 'static> IScriptFactory for ScriptFactory<T> {
@@ -157,8 +202,11 @@ impl<T: Script + Default + 'static> IScriptFactory for ScriptFactory<T> {
 ///     - being called while a reference per `get()` exists
 ///
 /// i recommend you call it once before ever calling `get()` and then never again
-pub unsafe fn init(scripts: Vec<Box<dyn IScriptFactory>>) -> RisResult<()> {
-    let new_registry = Registry::new(scripts)?;
+pub unsafe fn init(
+    components: Vec<Box<dyn IComponentFactory>>,
+    scripts: Vec<Box<dyn IScriptFactory>>,
+) -> RisResult<()> {
+    let new_registry = Registry::new(components, scripts)?;
     *REGISTRY.get() = Some(new_registry);
 
     Ok(())
@@ -2,10 +2,8 @@ use std::any::TypeId;
 use std::sync::Arc;
 
 use ris_ptr::ArefCell;
-use ris_ptr::StrongPtr;
 
-use super::components::mesh_component::MeshComponent;
-use super::components::script_component::DynScriptComponent;
+use super::commands::SceneCommands;
 use super::decl::GameObjectHandle;
 use super::error::EcsError;
 use super::error::EcsResult;
@@ -14,30 +12,30 @@ use super::handle::DynComponentHandle;
 use super::handle::DynHandle;
 use super::handle::GenericHandle;
 use super::id::Component;
-use super::id::EcsInstance;
 use super::id::EcsObject;
-use super::id::EcsPtr;
 use super::id::EcsWeakPtr;
 use super::id::SceneId;
 use super::id::SceneKind;
+use super::pool::EcsPool;
+use super::pool::IComponentPool;
 use super::registry::Registry;
 
-const DEFAULT_DYNAMIC_GAME_OBJECTS: usize = 1024;
+const DEFAULT_DYNAMIC_GAME_OBJECTS_PER_PAGE: usize = 1024;
 const DEFAULT_STATIC_CHUNKS: usize = 8;
-const DEFAULT_GAME_OBJECTS_PER_STATIC_CHUNK: usize = 1024;
-const DEFAULT_MESH_RENDERER_COMPONENTS: usize = 1024;
-const DEFAULT_SCRIPT_COMPONENTS: usize = 1024;
+const DEFAULT_STATIC_GAME_OBJECTS_PER_PAGE: usize = 1024;
+const DEFAULT_COMPONENTS_PER_PAGE: usize = 1024;
 
+/// Storage grows on demand, one page at a time. Pages are allocated lazily, the values below only
+/// determine how many objects are allocated at once.
 #[derive(Debug)]
 pub struct SceneCreateInfo {
     // game objects
-    pub dynamic_game_objects: usize,
+    pub dynamic_game_objects_per_page: usize,
     pub static_chunks: usize,
-    pub game_objects_per_static_chunk: usize,
+    pub static_game_objects_per_page: usize,
 
     // components
-    pub mesh_renderer_components: usize,
-    pub script_components: usize,
+    pub components_per_page: usize,
 
     // other
     pub registry: Option<Arc<Registry>>,
@@ -45,30 +43,31 @@ pub struct SceneCreateInfo {
 
 pub struct StaticChunk {
     is_reserved: ArefCell<bool>,
-    pub game_objects: Vec<EcsPtr<GameObject>>,
+    pub game_objects: EcsPool<GameObject>,
 }
 
 pub struct Scene {
     // game objects
-    pub dynamic_game_objects: Vec<EcsPtr<GameObject>>,
+    pub dynamic_game_objects: EcsPool<GameObject>,
     pub static_chunks: Vec<StaticChunk>,
 
-    // components
-    pub mesh_renderer_components: Vec<EcsPtr<MeshComponent>>,
-    pub script_components: Vec<EcsPtr<DynScriptComponent>>,
+    // components, one pool per component factory in the registry
+    pub components: Vec<Box<dyn IComponentPool>>,
 
     // other
     pub registry: Arc<Registry>,
+    pub commands: SceneCommands,
+    is_in_fixed_update: ArefCell<bool>,
+    fixed_update_step: ArefCell<u64>,
 }
 
 impl Default for SceneCreateInfo {
     fn default() -> Self {
         Self {
-            dynamic_game_objects: DEFAULT_DYNAMIC_GAME_OBJECTS,
+            dynamic_game_objects_per_page: DEFAULT_DYNAMIC_GAME_OBJECTS_PER_PAGE,
             static_chunks: DEFAULT_STATIC_CHUNKS,
-            game_objects_per_static_chunk: DEFAULT_GAME_OBJECTS_PER_STATIC_CHUNK,
-            mesh_renderer_components: DEFAULT_MESH_RENDERER_COMPONENTS,
-            script_components: DEFAULT_SCRIPT_COMPONENTS,
+            static_game_objects_per_page: DEFAULT_STATIC_GAME_OBJECTS_PER_PAGE,
+            components_per_page: DEFAULT_COMPONENTS_PER_PAGE,
             registry: None,
         }
     }
@@ -77,23 +76,19 @@ impl Default for SceneCreateInfo {
 impl SceneCreateInfo {
     pub fn empty() -> Self {
         Self {
-            dynamic_game_objects: 0,
+            dynamic_game_objects_per_page: 1,
             static_chunks: 0,
-            game_objects_per_static_chunk: 0,
-            mesh_renderer_components: 0,
-            script_components: 0,
+            static_game_objects_per_page: 1,
+            components_per_page: 1,
             registry: None,
         }
     }
 
     pub fn with_single_static_chunk(registry: Arc<Registry>) -> Self {
         Self {
-            dynamic_game_objects: 0,
             static_chunks: 1,
-            game_objects_per_static_chunk: DEFAULT_GAME_OBJECTS_PER_STATIC_CHUNK,
-            mesh_renderer_components: DEFAULT_MESH_RENDERER_COMPONENTS,
-            script_components: DEFAULT_SCRIPT_COMPONENTS,
             registry: Some(registry),
+            ..Default::default()
         }
     }
 }
@@ -104,13 +99,15 @@ impl Scene {
             return Err(EcsError::InvalidOperation("registry was none".to_string()));
         };
 
-        let dynamic_game_objects =
-            create_chunk(SceneKind::DynamicGameObject, info.dynamic_game_objects)?;
+        let dynamic_game_objects = EcsPool::new(
+            SceneKind::DynamicGameObject,
+            info.dynamic_game_objects_per_page,
+        )?;
 
         let mut static_chunks = Vec::with_capacity(info.static_chunks);
         for i in 0..info.static_chunks {
             let kind = SceneKind::StaticGameObjct { chunk: i };
-            let game_objects = create_chunk(kind, info.game_objects_per_static_chunk)?;
+            let game_objects = EcsPool::new(kind, info.static_game_objects_per_page)?;
             let chunk = StaticChunk {
                 is_reserved: ArefCell::new(false),
                 game_objects,
@@ -118,19 +115,45 @@ impl Scene {
             static_chunks.push(chunk);
         }
 
-        let mesh_renderer_components =
-            create_chunk(SceneKind::Component, info.mesh_renderer_components)?;
-        let script_components = create_chunk(SceneKind::Component, info.script_components)?;
+        let mut components = Vec::new();
+        for factory in registry.component_factories() {
+            let pool = factory.make_pool(info.components_per_page)?;
+            components.push(pool);
+        }
 
         Ok(Self {
             dynamic_game_objects,
             static_chunks,
-            mesh_renderer_components,
-            script_components,
+            components,
             registry,
+            commands: SceneCommands::default(),
+            is_in_fixed_update: ArefCell::new(false),
+            fixed_update_step: ArefCell::new(0),
         })
     }
 
+    /// Starts a new fixed update. Game objects store their transform when it is first set during
+    /// it, such that the renderer can blend it with the transform after the fixed update.
+    /// Transforms that are set until `end_fixed_update()` are blended, transforms that are set
+    /// outside are not.
+    pub fn begin_fixed_update(&self) {
+        *self.fixed_update_step.borrow_mut() += 1;
+        *self.is_in_fixed_update.borrow_mut() = true;
+    }
+
+    pub fn end_fixed_update(&self) {
+        *self.is_in_fixed_update.borrow_mut() = false;
+    }
+
+    pub fn is_in_fixed_update(&self) -> bool {
+        *self.is_in_fixed_update.borrow()
+    }
+
+    /// Counts the calls to `begin_fixed_update()`.
+    pub fn fixed_update_step(&self) -> u64 {
+        *self.fixed_update_step.borrow()
+    }
+
     pub fn reserve_chunk(&self) -> Option<usize> {
         let position = self
             .static_chunks
@@ -171,14 +194,16 @@ impl Scene {
     ) -> EcsResult<EcsWeakPtr<T>> {
         let chunk = self.find_chunk(handle.scene_id().kind)?;
         let index = handle.scene_id().index;
-        let ptr = &chunk[index];
+        let ptr = chunk.get(index).ok_or(EcsError::OutOfBounds)?;
         let aref = ptr.borrow();
 
         let is_alive = aref.is_alive;
         let generation_matches = aref.handle.generation() == handle.generation();
 
+        drop(aref);
+
         if is_alive && generation_matches {
-            Ok(ptr.to_weak())
+            Ok(ptr)
         } else {
             Err(EcsError::ObjectIsDestroyed)
         }
@@ -189,12 +214,8 @@ impl Scene {
         kind: SceneKind,
     ) -> EcsResult<EcsWeakPtr<T>> {
         let chunk = self.find_chunk(kind)?;
+        let ptr = chunk.next_free()?;
 
-        let Some(position) = chunk.iter().position(|x| !x.borrow().is_alive) else {
-            return Err(EcsError::OutOfMemory);
-        };
-
-        let ptr = &chunk[position];
         let old_handle = ptr.borrow().handle;
         let new_generation = old_handle.generation().wrapping_add(1);
         let new_handle = GenericHandle::new(old_handle.scene_id(), new_generation)?;
@@ -205,7 +226,7 @@ impl Scene {
         aref_mut.value = T::default();
         drop(aref_mut);
 
-        Ok(ptr.to_weak())
+        Ok(ptr)
     }
 
     pub fn mark_as_destroyed(&self, handle: DynHandle) -> EcsResult<()> {
@@ -214,13 +235,11 @@ impl Scene {
 
         if type_id == TypeId::of::<GameObject>() {
             let chunk = self.find_chunk::<GameObject>(kind)?;
-            chunk[index].borrow_mut().is_alive = false;
-        } else if type_id == TypeId::of::<MeshComponent>() {
-            let chunk = self.find_chunk::<MeshComponent>(kind)?;
-            chunk[index].borrow_mut().is_alive = false;
-        } else if type_id == TypeId::of::<DynScriptComponent>() {
-            let chunk = self.find_chunk::<DynScriptComponent>(kind)?;
-            chunk[index].borrow_mut().is_alive = false;
+            let ptr = chunk.get(index).ok_or(EcsError::OutOfBounds)?;
+            ptr.borrow_mut().is_alive = false;
+        } else if kind == SceneKind::Component {
+            let pool = self.find_component_pool(type_id)?;
+            pool.mark_as_destroyed(index)?;
         } else {
             return Err(EcsError::InvalidCast);
         }
@@ -240,17 +259,17 @@ impl Scene {
             return Err(EcsError::InvalidCast);
         }
 
-        let retval = if type_id == TypeId::of::<MeshComponent>() {
-            let aref = self.mesh_renderer_components[index].borrow();
-            callback(&aref.value)
-        } else if type_id == TypeId::of::<DynScriptComponent>() {
-            let aref = self.script_components[index].borrow();
-            callback(&aref.value)
-        } else {
-            return Err(EcsError::InvalidCast);
-        };
+        let pool = self.find_component_pool(type_id)?;
 
-        Ok(retval)
+        let mut callback = Some(callback);
+        let mut retval = None;
+        pool.with_component(index, &mut |component| {
+            retval = callback.take().map(|callback| callback(component));
+        })?;
+
+        retval.ok_or(EcsError::InvalidOperation(
+            "callback was not called".to_string(),
+        ))
     }
 
     pub fn deref_mut_component<T>(
@@ -265,20 +284,36 @@ impl Scene {
             return Err(EcsError::InvalidCast);
         }
 
-        let retval = if type_id == TypeId::of::<MeshComponent>() {
-            let mut aref = self.mesh_renderer_components[index].borrow_mut();
-            callback(&mut aref.value)
-        } else if type_id == TypeId::of::<DynScriptComponent>() {
-            let mut aref = self.script_components[index].borrow_mut();
-            callback(&mut aref.value)
-        } else {
-            return Err(EcsError::InvalidCast);
-        };
+        let pool = self.find_component_pool(type_id)?;
 
-        Ok(retval)
+        let mut callback = Some(callback);
+        let mut retval = None;
+        pool.with_component_mut(index, &mut |component| {
+            retval = callback.take().map(|callback| callback(component));
+        })?;
+
+        retval.ok_or(EcsError::InvalidOperation(
+            "callback was not called".to_string(),
+        ))
+    }
+
+    /// Returns the storage of a registered component type.
+    pub fn component_pool<T: Component + 'static>(&self) -> EcsResult<&EcsPool<T>> {
+        let pool = self.find_component_pool(TypeId::of::<T>())?;
+        pool.as_any()
+            .downcast_ref::<EcsPool<T>>()
+            .ok_or(EcsError::InvalidCast)
     }
 
-    fn find_chunk<T: EcsObject + 'static>(&self, kind: SceneKind) -> EcsResult<&[EcsPtr<T>]> {
+    fn find_component_pool(&self, type_id: TypeId) -> EcsResult<&dyn IComponentPool> {
+        self.components
+            .iter()
+            .find(|x| x.component_id() == type_id)
+            .map(|x| x.as_ref())
+            .ok_or(EcsError::ComponentIsNotRegistered)
+    }
+
+    fn find_chunk<T: EcsObject + 'static>(&self, kind: SceneKind) -> EcsResult<&EcsPool<T>> {
         match kind {
             SceneKind::Null => Err(EcsError::IsNull),
             SceneKind::DynamicGameObject => cast_chunk(&self.dynamic_game_objects),
@@ -286,45 +321,25 @@ impl Scene {
                 cast_chunk(&self.static_chunks[chunk].game_objects)
             }
             SceneKind::Component => {
-                let type_id = TypeId::of::<T>();
-                if type_id == TypeId::of::<MeshComponent>() {
-                    cast_chunk(&self.mesh_renderer_components)
-                } else if type_id == TypeId::of::<DynScriptComponent>() {
-                    cast_chunk(&self.script_components)
-                } else {
-                    Err(EcsError::TypeDoesNotMatchSceneKind)
-                }
+                let pool = self.find_component_pool(TypeId::of::<T>())?;
+                pool.as_any()
+                    .downcast_ref::<EcsPool<T>>()
+                    .ok_or(EcsError::TypeDoesNotMatchSceneKind)
             }
             SceneKind::Other => Err(EcsError::TypeDoesNotMatchSceneKind),
         }
     }
 }
 
-fn create_chunk<T: EcsObject + Default + 'static>(
-    kind: SceneKind,
-    capacity: usize,
-) -> EcsResult<Vec<EcsPtr<T>>> {
-    let mut result = Vec::with_capacity(capacity);
-    for i in 0..capacity {
-        let id = SceneId { kind, index: i };
-        let handle = GenericHandle::new(id, 0)?;
-        let instance = EcsInstance::new(handle);
-        let ptr = StrongPtr::new(ArefCell::new(instance));
-        result.push(ptr);
-    }
-
-    Ok(result)
-}
-
 fn cast_chunk<T: EcsObject + 'static, U: EcsObject + 'static>(
-    chunk: &[EcsPtr<T>],
-) -> EcsResult<&[EcsPtr<U>]> {
+    chunk: &EcsPool<T>,
+) -> EcsResult<&EcsPool<U>> {
     if TypeId::of::<T>() != TypeId::of::<U>() {
         return Err(EcsError::InvalidCast);
     }
 
     // transmute is safe, because T is equal to U
-    let result = unsafe { std::mem::transmute::<&[EcsPtr<T>], &[EcsPtr<U>]>(chunk) };
+    let result = unsafe { std::mem::transmute::<&EcsPool<T>, &EcsPool<U>>(chunk) };
 
     Ok(result)
 }
@@ -1,3 +1,4 @@
+use std::any::TypeId;
 use std::io::Cursor;
 use std::io::Read;
 use std::io::Seek;
@@ -8,35 +9,78 @@ use ris_asset_data::asset_id::AssetId;
 use ris_error::Extensions;
 use ris_error::RisResult;
 use ris_io::FatPtr;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
 
+use crate::ecs::components::script_component::Script;
 use crate::ecs::decl::GameObjectHandle;
 use crate::ecs::id::SceneKind;
 use crate::ecs::scene::Scene;
 
+type ScriptOverrideCallback = Box<dyn FnMut(&mut dyn Script) -> RisResult<()>>;
+
 pub struct SceneWriter<'a> {
     stream: Cursor<Vec<u8>>,
-    chunk: usize,
+    kind: SceneKind,
     pub scene: &'a Scene,
     placeholders: Vec<FatPtr>,
     assets_ids: Vec<AssetId>,
+    fields: Vec<(FatPtr, SceneField)>,
 }
 
 pub struct SceneReader<'a> {
     stream: Cursor<Vec<u8>>,
-    chunk: usize,
     pub scene: &'a Scene,
-    pub lookup: Vec<usize>,
+    /// Maps the serialized index of a game object to the deserialized game object.
+    pub lookup: Vec<GameObjectHandle>,
+    pub script_overrides: Vec<ScriptOverride>,
     assets_ids: Vec<AssetId>,
 }
 
+/// A value, that is written by `SceneWriter::write_field`. Each is serialized like the `ris_io`
+/// function of the same type.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SceneValue {
+    Bool(bool),
+    I32(i32),
+    Uint(usize),
+    F32(f32),
+    String(String),
+    Vec3(Vec3),
+    Quat(Quat),
+    GameObject(GameObjectHandle),
+    Asset(AssetId),
+}
+
+/// A value, that was written to a scene stream. Game object references and asset ids are fields
+/// too, even if they were written without a name, in which case the name is empty.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SceneField {
+    pub name: String,
+    pub value: SceneValue,
+}
+
+/// Modifies a script after it was deserialized, but before it is started.
+pub struct ScriptOverride {
+    pub game_object: GameObjectHandle,
+    script_id: TypeId,
+    callback: ScriptOverrideCallback,
+}
+
 impl<'a> SceneWriter<'a> {
     pub fn new(chunk: usize, scene: &'a Scene) -> Self {
+        Self::new_with_kind(SceneKind::StaticGameObjct { chunk }, scene)
+    }
+
+    /// Game objects may only reference game objects of the given kind.
+    pub fn new_with_kind(kind: SceneKind, scene: &'a Scene) -> Self {
         Self {
             stream: Cursor::new(Vec::new()),
-            chunk,
+            kind,
             scene,
             placeholders: Vec::new(),
             assets_ids: Vec::new(),
+            fields: Vec::new(),
         }
     }
 
@@ -60,17 +104,67 @@ impl<'a> SceneWriter<'a> {
         Ok((bytes, asset_ids))
     }
 
-    pub fn write_game_object(&mut self, game_object: GameObjectHandle) -> RisResult<FatPtr> {
-        let scene_id = game_object.0.scene_id();
-        let SceneKind::StaticGameObjct { chunk } = scene_id.kind else {
-            return ris_error::new_result!(
-                "can only serialize static game objects. kind was: {:?}",
-                scene_id.kind
-            );
+    /// Where the game object references written so far are located in the stream.
+    pub fn game_object_references(&self) -> &[FatPtr] {
+        &self.placeholders
+    }
+
+    /// The fields written so far, and where they are located in the stream. Bytes that are not
+    /// covered by any field were written by `ris_io` directly.
+    pub fn fields(&self) -> &[(FatPtr, SceneField)] {
+        &self.fields
+    }
+
+    /// Writes a named value. The binary format is the same as if the value was written by
+    /// `ris_io` directly, but text formats use the name and the type of the field to make the
+    /// value human-readable.
+    pub fn write_field(
+        &mut self,
+        name: impl Into<String>,
+        value: impl Into<SceneValue>,
+    ) -> RisResult<FatPtr> {
+        let value = value.into();
+        let begin = ris_io::seek(self, SeekFrom::Current(0))?;
+
+        match &value {
+            SceneValue::Bool(value) => ris_io::write_bool(self, *value)?,
+            SceneValue::I32(value) => ris_io::write_i32(self, *value)?,
+            SceneValue::Uint(value) => ris_io::write_uint(self, *value)?,
+            SceneValue::F32(value) => ris_io::write_f32(self, *value)?,
+            SceneValue::String(value) => ris_io::write_string(self, value)?,
+            SceneValue::Vec3(value) => ris_io::write_vec3(self, *value)?,
+            SceneValue::Quat(value) => ris_io::write_quat(self, *value)?,
+            SceneValue::GameObject(value) => self.write_game_object_unrecorded(*value)?,
+            SceneValue::Asset(value) => self.write_asset_id_unrecorded(value.clone())?,
         };
 
-        if self.chunk != chunk {
-            return ris_error::new_result!("during serialization, a chunk may only reference gameobjects in the same chunk. expected: {} actual: {}", self.chunk, chunk);
+        let end = ris_io::seek(self, SeekFrom::Current(0))?;
+        let ptr = FatPtr::begin_end(begin, end)?;
+        self.record(ptr, name.into(), value);
+
+        Ok(ptr)
+    }
+
+    pub fn write_game_object(&mut self, game_object: GameObjectHandle) -> RisResult<FatPtr> {
+        let ptr = self.write_game_object_unrecorded(game_object)?;
+        self.record(ptr, String::new(), SceneValue::GameObject(game_object));
+        Ok(ptr)
+    }
+
+    pub fn write_asset_id(&mut self, asset_id: AssetId) -> RisResult<FatPtr> {
+        let ptr = self.write_asset_id_unrecorded(asset_id.clone())?;
+        self.record(ptr, String::new(), SceneValue::Asset(asset_id));
+        Ok(ptr)
+    }
+
+    fn record(&mut self, ptr: FatPtr, name: String, value: SceneValue) {
+        self.fields.push((ptr, SceneField { name, value }));
+    }
+
+    fn write_game_object_unrecorded(&mut self, game_object: GameObjectHandle) -> RisResult<FatPtr> {
+        let scene_id = game_object.0.scene_id();
+        if scene_id.kind != self.kind {
+            return ris_error::new_result!("during serialization, game objects may only reference game objects of the same kind. expected: {:?} actual: {:?}", self.kind, scene_id.kind);
         }
 
         let fat_ptr = ris_io::write_uint(self, scene_id.index)?;
@@ -79,7 +173,7 @@ impl<'a> SceneWriter<'a> {
         Ok(fat_ptr)
     }
 
-    pub fn write_asset_id(&mut self, asset_id: AssetId) -> RisResult<FatPtr> {
+    fn write_asset_id_unrecorded(&mut self, asset_id: AssetId) -> RisResult<FatPtr> {
         let position = self.assets_ids.iter().position(|x| *x == asset_id);
         let to_write = match position {
             Some(position) => position,
@@ -96,26 +190,20 @@ impl<'a> SceneWriter<'a> {
 }
 
 impl<'a> SceneReader<'a> {
-    pub fn new(chunk: usize, scene: &'a Scene, data: Vec<u8>, assets_ids: Vec<AssetId>) -> Self {
+    pub fn new(scene: &'a Scene, data: Vec<u8>, assets_ids: Vec<AssetId>) -> Self {
         Self {
             stream: Cursor::new(data),
-            chunk,
             scene,
             lookup: Vec::new(),
+            script_overrides: Vec::new(),
             assets_ids,
         }
     }
 
     pub fn read_game_object(&mut self) -> RisResult<GameObjectHandle> {
         let index = ris_io::read_uint(self)?;
-        let scene_index = self.lookup.get(index).into_ris_error()?;
-        let game_object: GameObjectHandle = self.scene.static_chunks[self.chunk].game_objects
-            [*scene_index]
-            .borrow()
-            .handle
-            .into();
-
-        Ok(game_object)
+        let game_object = self.lookup.get(index).into_ris_error()?;
+        Ok(*game_object)
     }
 
     pub fn read_asset_id(&mut self) -> RisResult<AssetId> {
@@ -123,6 +211,104 @@ impl<'a> SceneReader<'a> {
         let asset_id = self.assets_ids.get(index).into_ris_error()?;
         Ok(asset_id.clone())
     }
+
+    pub fn apply_script_overrides(
+        &mut self,
+        game_object: GameObjectHandle,
+        script_id: TypeId,
+        script: &mut dyn Script,
+    ) -> RisResult<()> {
+        for script_override in self.script_overrides.iter_mut() {
+            if script_override.game_object == game_object && script_override.script_id == script_id
+            {
+                (script_override.callback)(script)?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ScriptOverride {
+    pub fn new<T: Script + 'static>(
+        game_object: GameObjectHandle,
+        mut callback: impl FnMut(&mut T) -> RisResult<()> + 'static,
+    ) -> Self {
+        let callback = move |script: &mut dyn Script| {
+            let dyn_ptr = script as *mut dyn Script;
+            let t_ptr = dyn_ptr as *mut T;
+
+            // this is safe, because the reader only calls this for scripts of type T
+            let reference = unsafe { t_ptr.as_mut() }.into_ris_error()?;
+            callback(reference)
+        };
+
+        Self {
+            game_object,
+            script_id: TypeId::of::<T>(),
+            callback: Box::new(callback),
+        }
+    }
+}
+
+impl From<bool> for SceneValue {
+    fn from(value: bool) -> Self {
+        Self::Bool(value)
+    }
+}
+
+impl From<i32> for SceneValue {
+    fn from(value: i32) -> Self {
+        Self::I32(value)
+    }
+}
+
+impl From<usize> for SceneValue {
+    fn from(value: usize) -> Self {
+        Self::Uint(value)
+    }
+}
+
+impl From<f32> for SceneValue {
+    fn from(value: f32) -> Self {
+        Self::F32(value)
+    }
+}
+
+impl From<String> for SceneValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<&str> for SceneValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<Vec3> for SceneValue {
+    fn from(value: Vec3) -> Self {
+        Self::Vec3(value)
+    }
+}
+
+impl From<Quat> for SceneValue {
+    fn from(value: Quat) -> Self {
+        Self::Quat(value)
+    }
+}
+
+impl From<GameObjectHandle> for SceneValue {
+    fn from(value: GameObjectHandle) -> Self {
+        Self::GameObject(value)
+    }
+}
+
+impl From<AssetId> for SceneValue {
+    fn from(value: AssetId) -> Self {
+        Self::Asset(value)
+    }
 }
 
 impl Seek for SceneWriter<'_> {
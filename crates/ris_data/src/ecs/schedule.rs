@@ -0,0 +1,288 @@
+use std::any::TypeId;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use ris_async::ThreadPool;
+use ris_error::RisResult;
+
+use super::components::script_component::DynScriptComponent;
+use super::components::script_component::Script;
+use super::decl::DynScriptComponentHandle;
+use super::decl::GameObjectHandle;
+use super::error::EcsResult;
+use super::handle::ComponentHandle;
+use super::handle::DynComponentHandle;
+use super::handle::GenericHandle;
+use super::id::Component;
+use super::id::EcsWeakPtr;
+use super::scene::Scene;
+use crate::gameloop::fixed_frame::FixedFrame;
+use crate::gameloop::frame::Frame;
+use crate::god_state::GodState;
+
+/// Something a script may touch during `update`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptResource {
+    /// Everything, including the god state. Creating, destroying or reparenting game objects and
+    /// adding or removing components directly requires this, because it may grow the scene.
+    /// Recording these changes in `SceneCommands` does not.
+    All,
+    /// A single game object, including its transform, name, activity and list of components.
+    GameObject(GameObjectHandle),
+    /// A single component. Scripts are components too.
+    Component(DynComponentHandle),
+    /// Every component of the given type.
+    Components(TypeId),
+    /// Every script of the given type.
+    Scripts(TypeId),
+}
+
+/// Declares what a script reads and writes during `update`.
+///
+/// Scripts of a lower phase are updated before scripts of a higher phase. Within a phase, scripts
+/// without conflicting access may be updated in parallel. Conflicting scripts are updated in the
+/// order they are stored in the scene.
+///
+/// A script always has write access to itself, it does not need to declare that.
+#[derive(Debug, Default, Clone)]
+pub struct ScriptAccess {
+    pub phase: usize,
+    pub reads: Vec<ScriptResource>,
+    pub writes: Vec<ScriptResource>,
+}
+
+/// Batches of scripts, whose updates do not conflict with each other.
+///
+/// Conflicts are resolved when the schedule is built, thus running it never borrows the same
+/// `ArefCell` mutably on two threads at once, as long as the scripts stick to what they declared.
+#[derive(Debug, Default)]
+pub struct ScriptSchedule {
+    batches: Vec<Vec<DynScriptComponentHandle>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Key {
+    All,
+    GameObject(GameObjectHandle),
+    Component {
+        handle: DynComponentHandle,
+        script_id: Option<TypeId>,
+    },
+    Components(TypeId),
+    Scripts(TypeId),
+}
+
+struct Job {
+    handle: DynScriptComponentHandle,
+    // (key, is_write)
+    keys: Vec<(Key, bool)>,
+}
+
+impl ScriptResource {
+    pub fn components<T: Component + 'static>() -> Self {
+        Self::Components(TypeId::of::<T>())
+    }
+
+    pub fn scripts<T: Script + 'static>() -> Self {
+        Self::Scripts(TypeId::of::<T>())
+    }
+}
+
+impl ScriptAccess {
+    /// Conflicts with every other script. This is the default for scripts, which do not declare
+    /// their access.
+    pub fn exclusive() -> Self {
+        Self {
+            phase: 0,
+            reads: Vec::new(),
+            writes: vec![ScriptResource::All],
+        }
+    }
+}
+
+impl ScriptSchedule {
+    /// Builds a schedule for the given scripts. Scripts which are not alive are skipped.
+    pub fn new(scene: &Scene, scripts: &[DynScriptComponentHandle]) -> EcsResult<Self> {
+        let mut phases = BTreeMap::<usize, Vec<Job>>::new();
+
+        for &handle in scripts.iter() {
+            let Ok(ptr) = scene.deref(handle.into()) else {
+                continue;
+            };
+
+            let aref = ptr.borrow();
+            let access = aref.access();
+            let own_key = Key::Component {
+                handle: handle.to_dyn_component(),
+                script_id: aref.type_id(),
+            };
+            drop(aref);
+
+            let mut keys = Vec::with_capacity(1 + access.reads.len() + access.writes.len());
+            keys.push((own_key, true));
+            for &resource in access.reads.iter() {
+                keys.push((resolve(scene, resource)?, false));
+            }
+            for &resource in access.writes.iter() {
+                keys.push((resolve(scene, resource)?, true));
+            }
+
+            phases
+                .entry(access.phase)
+                .or_default()
+                .push(Job { handle, keys });
+        }
+
+        let mut batches = Vec::new();
+        for jobs in phases.into_values() {
+            let offset = batches.len();
+
+            // a job must run after every earlier job it conflicts with, thus it is placed in the
+            // first batch after the last conflicting one
+            let mut assigned = Vec::<usize>::with_capacity(jobs.len());
+            for (i, job) in jobs.iter().enumerate() {
+                let mut batch = 0;
+                for (j, other) in jobs.iter().enumerate().take(i) {
+                    if conflicts(job, other) {
+                        batch = usize::max(batch, assigned[j] + 1);
+                    }
+                }
+
+                assigned.push(batch);
+
+                let index = offset + batch;
+                if batches.len() <= index {
+                    batches.push(Vec::new());
+                }
+                batches[index].push(job.handle);
+            }
+        }
+
+        Ok(Self { batches })
+    }
+
+    pub fn batches(&self) -> &[Vec<DynScriptComponentHandle>] {
+        &self.batches
+    }
+
+    /// Updates all scheduled scripts. Batches with more than one script are submitted to the
+    /// thread pool and joined before the next batch starts. Must be called on a worker thread.
+    pub fn run(&self, frame: Frame, state: &GodState) -> RisResult<()> {
+        self.run_batches(state, move |ptr, state| {
+            ptr.borrow_mut().update(frame, state)
+        })
+    }
+
+    /// Like `run`, but calls `fixed_update` instead.
+    pub fn run_fixed(&self, fixed_frame: FixedFrame, state: &GodState) -> RisResult<()> {
+        self.run_batches(state, move |ptr, state| {
+            ptr.borrow_mut().fixed_update(fixed_frame, state)
+        })
+    }
+
+    fn run_batches<F>(&self, state: &GodState, callback: F) -> RisResult<()>
+    where
+        F: Fn(EcsWeakPtr<DynScriptComponent>, &GodState) -> RisResult<()> + Copy + 'static,
+    {
+        let mut shared_state = None;
+
+        for batch in self.batches.iter() {
+            if let [handle] = batch.as_slice() {
+                call(*handle, state, callback)?;
+                continue;
+            }
+
+            let shared_state = shared_state.get_or_insert_with(|| Arc::new(state.clone()));
+
+            let mut futures = Vec::with_capacity(batch.len());
+            for &handle in batch.iter() {
+                let state = shared_state.clone();
+                let future = ThreadPool::submit(async move { call(handle, &state, callback) });
+                futures.push(future);
+            }
+
+            // wait for every job before handling errors, such that no update outlives its batch
+            let mut result = Ok(());
+            for future in futures {
+                let job_result = future.wait();
+                if result.is_ok() {
+                    result = job_result;
+                }
+            }
+
+            result?;
+        }
+
+        Ok(())
+    }
+}
+
+fn call<F>(handle: DynScriptComponentHandle, state: &GodState, callback: F) -> RisResult<()>
+where
+    F: Fn(EcsWeakPtr<DynScriptComponent>, &GodState) -> RisResult<()>,
+{
+    // a previous script may have destroyed this one
+    let Ok(ptr) = state.scene.deref(handle.into()) else {
+        return Ok(());
+    };
+
+    callback(ptr, state)
+}
+
+fn resolve(scene: &Scene, resource: ScriptResource) -> EcsResult<Key> {
+    let key = match resource {
+        ScriptResource::All => Key::All,
+        ScriptResource::GameObject(handle) => Key::GameObject(handle),
+        ScriptResource::Component(handle) => {
+            let script_id = if handle.type_id() == TypeId::of::<DynScriptComponent>() {
+                let handle = GenericHandle::<DynScriptComponent>::from_dyn(handle.into())?;
+                // a destroyed script cannot be updated, thus it conflicts with nothing
+                scene
+                    .deref(handle)
+                    .ok()
+                    .and_then(|ptr| ptr.borrow().type_id())
+            } else {
+                None
+            };
+
+            Key::Component { handle, script_id }
+        }
+        ScriptResource::Components(type_id) => Key::Components(type_id),
+        ScriptResource::Scripts(type_id) => Key::Scripts(type_id),
+    };
+
+    Ok(key)
+}
+
+fn conflicts(left: &Job, right: &Job) -> bool {
+    for &(left_key, left_writes) in left.keys.iter() {
+        for &(right_key, right_writes) in right.keys.iter() {
+            if (left_writes || right_writes) && overlaps(left_key, right_key) {
+                return true;
+            }
+        }
+    }
+
+    false
+}
+
+fn overlaps(left: Key, right: Key) -> bool {
+    match (left, right) {
+        (Key::All, _) | (_, Key::All) => true,
+        (Key::GameObject(left), Key::GameObject(right)) => left == right,
+        (Key::Component { handle: left, .. }, Key::Component { handle: right, .. }) => {
+            left == right
+        }
+        (Key::Components(type_id), Key::Component { handle, .. })
+        | (Key::Component { handle, .. }, Key::Components(type_id)) => handle.type_id() == type_id,
+        (Key::Scripts(type_id), Key::Component { script_id, .. })
+        | (Key::Component { script_id, .. }, Key::Scripts(type_id)) => script_id == Some(type_id),
+        (Key::Components(left), Key::Components(right)) => left == right,
+        (Key::Scripts(left), Key::Scripts(right)) => left == right,
+        (Key::Components(type_id), Key::Scripts(_))
+        | (Key::Scripts(_), Key::Components(type_id)) => {
+            type_id == TypeId::of::<DynScriptComponent>()
+        }
+        _ => false,
+    }
+}
@@ -0,0 +1,135 @@
+use std::time::Duration;
+
+use crate::settings::simulation_settings::SimulationSettings;
+
+#[derive(Copy, Clone)]
+pub struct FixedFrame {
+    number: usize,
+    step: Duration,
+}
+
+/// The fixed frames, that must be simulated during a single variable frame.
+#[derive(Copy, Clone, Default)]
+pub struct FixedFrames {
+    first: usize,
+    count: usize,
+    step: Duration,
+    alpha: f32,
+}
+
+pub struct FixedFramesIter {
+    frames: FixedFrames,
+    index: usize,
+}
+
+/// Accumulates the duration of variable frames and turns it into fixed frames.
+#[derive(Default)]
+pub struct FixedFrameAccumulator {
+    number: usize,
+    accumulator: Duration,
+}
+
+impl FixedFrameAccumulator {
+    pub fn bump_and_create_fixed_frames(
+        &mut self,
+        delta: Duration,
+        settings: &SimulationSettings,
+    ) -> FixedFrames {
+        let step = settings.fixed_step();
+        let max_steps = settings.max_fixed_steps();
+
+        self.accumulator += delta;
+
+        let mut count = 0;
+        while self.accumulator >= step && count < max_steps {
+            self.accumulator -= step;
+            count += 1;
+        }
+
+        // drop what could not be simulated, to avoid a spiral of death
+        while self.accumulator >= step {
+            self.accumulator -= step;
+        }
+
+        let first = self.number;
+        self.number = self.number.wrapping_add(count);
+
+        let alpha = self.accumulator.as_secs_f32() / step.as_secs_f32();
+
+        FixedFrames {
+            first,
+            count,
+            step,
+            alpha: alpha.clamp(0.0, 1.0),
+        }
+    }
+}
+
+impl FixedFrames {
+    pub fn len(&self) -> usize {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn step_duration(&self) -> Duration {
+        self.step
+    }
+
+    /// How far the simulation has advanced from the last fixed frame towards the next one, in the
+    /// range of 0 to 1. Renderers may use it to interpolate between the previous and the current
+    /// simulation state.
+    pub fn alpha(&self) -> f32 {
+        self.alpha
+    }
+
+    pub fn iter(&self) -> FixedFramesIter {
+        FixedFramesIter {
+            frames: *self,
+            index: 0,
+        }
+    }
+}
+
+impl Iterator for FixedFramesIter {
+    type Item = FixedFrame;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.index >= self.frames.count {
+            return None;
+        }
+
+        let number = self.frames.first.wrapping_add(self.index);
+        self.index += 1;
+
+        Some(FixedFrame {
+            number,
+            step: self.frames.step,
+        })
+    }
+}
+
+impl IntoIterator for FixedFrames {
+    type Item = FixedFrame;
+    type IntoIter = FixedFramesIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl FixedFrame {
+    pub fn number(&self) -> usize {
+        self.number
+    }
+
+    pub fn step_duration(&self) -> Duration {
+        self.step
+    }
+
+    pub fn step_seconds(&self) -> f32 {
+        self.step_duration().as_secs_f32()
+    }
+}
@@ -1,6 +1,11 @@
 use std::time::Duration;
 use std::time::Instant;
 
+use crate::settings::simulation_settings::SimulationSettings;
+
+use super::fixed_frame::FixedFrameAccumulator;
+use super::fixed_frame::FixedFrames;
+
 const FRAME_COUNT: usize = 4;
 const MAX_DURATION: Duration = Duration::from_millis((1000. / 2.) as u64); // 2 fps
 const IDEAL_DURATION: Duration = Duration::from_millis((1000. / 60.) as u64); // 60 fps
@@ -8,6 +13,7 @@ const IDEAL_DURATION: Duration = Duration::from_millis((1000. / 60.) as u64); //
 pub struct FrameCalculator {
     number: usize,
     instants: [Instant; FRAME_COUNT],
+    fixed: FixedFrameAccumulator,
 }
 
 #[derive(Copy, Clone)]
@@ -15,6 +21,7 @@ pub struct Frame {
     number: usize,
     previous: Duration,
     average: Duration,
+    fixed: FixedFrames,
 }
 
 impl Default for FrameCalculator {
@@ -22,6 +29,7 @@ impl Default for FrameCalculator {
         Self {
             number: 0,
             instants: [Instant::now(); FRAME_COUNT],
+            fixed: FixedFrameAccumulator::default(),
         }
     }
 }
@@ -35,7 +43,7 @@ impl std::fmt::Display for Frame {
 }
 
 impl FrameCalculator {
-    pub fn bump_and_create_frame(&mut self) -> Frame {
+    pub fn bump_and_create_frame(&mut self, settings: &SimulationSettings) -> Frame {
         let current_index = self.number % FRAME_COUNT;
         let prev_index = self.number.wrapping_sub(1) % FRAME_COUNT;
 
@@ -82,11 +90,14 @@ impl FrameCalculator {
             IDEAL_DURATION
         };
 
+        let fixed = self.fixed.bump_and_create_fixed_frames(previous, settings);
+
         let number = self.number;
         Frame {
             number,
             previous,
             average,
+            fixed,
         }
     }
 }
@@ -119,4 +130,13 @@ impl Frame {
     pub fn average_fps(&self) -> usize {
         (1. / self.average_seconds()) as usize
     }
+
+    pub fn fixed_frames(&self) -> FixedFrames {
+        self.fixed
+    }
+
+    /// See `FixedFrames::alpha`.
+    pub fn interpolation_alpha(&self) -> f32 {
+        self.fixed.alpha()
+    }
 }
@@ -1,2 +1,3 @@
+pub mod fixed_frame;
 pub mod frame;
 pub mod gameloop_state;
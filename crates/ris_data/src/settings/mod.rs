@@ -2,5 +2,6 @@ pub mod job_settings;
 pub mod serializer;
 #[allow(clippy::module_inception)]
 pub mod settings;
+pub mod simulation_settings;
 
 pub use settings::Settings;
@@ -4,6 +4,7 @@ use crate::ris_yaml::RisYamlEntry;
 
 use super::job_settings::JobSettings;
 use super::serializer::SerializeError;
+use super::simulation_settings::SimulationSettings;
 
 #[derive(Default, Clone)]
 pub struct Settings {
@@ -11,6 +12,7 @@ pub struct Settings {
     save_requested: bool,
 
     job: JobSettings,
+    simulation: SimulationSettings,
 }
 
 impl Settings {
@@ -20,11 +22,12 @@ impl Settings {
             save_requested: false,
 
             job: JobSettings::new(app_info),
+            simulation: SimulationSettings::default(),
         }
     }
 
     pub fn changed(&self) -> bool {
-        self.changed || self.job.changed()
+        self.changed || self.job.changed() || self.simulation.changed()
     }
 
     pub fn reset(&mut self) {
@@ -34,6 +37,7 @@ impl Settings {
         }
 
         self.job.reset();
+        self.simulation.reset();
     }
 
     pub fn save_requested(&self) -> bool {
@@ -53,8 +57,17 @@ impl Settings {
         &mut self.job
     }
 
+    pub fn simulation(&self) -> &SimulationSettings {
+        &self.simulation
+    }
+
+    pub fn simulation_mut(&mut self) -> &mut SimulationSettings {
+        &mut self.simulation
+    }
+
     pub fn serialize(&self, yaml: &mut RisYaml) {
         self.job.serialize(yaml);
+        self.simulation.serialize(yaml);
         // add more serializers here...
     }
 
@@ -67,6 +80,14 @@ impl Settings {
             return Err(e);
         }
 
+        let Err(e) = self.simulation.deserialize(entry) else {
+            return Ok(());
+        };
+
+        if e == SerializeError::ParseFailed {
+            return Err(e);
+        }
+
         // add more deserializers here...
 
         Ok(())
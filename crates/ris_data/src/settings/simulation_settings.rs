@@ -0,0 +1,96 @@
+use std::time::Duration;
+
+use crate::ris_yaml::RisYaml;
+use crate::ris_yaml::RisYamlEntry;
+
+use super::serializer::SerializeError;
+use crate::settings::serializer::SettingsSerializer;
+
+const KEY_FIXED_RATE: &str = "simulation.fixed_rate";
+const KEY_MAX_FIXED_STEPS: &str = "simulation.max_fixed_steps";
+
+pub const DEFAULT_FIXED_RATE: usize = 50;
+pub const DEFAULT_MAX_FIXED_STEPS: usize = 5;
+
+#[derive(Clone)]
+pub struct SimulationSettings {
+    changed: bool,
+
+    fixed_rate: usize,
+    max_fixed_steps: usize,
+}
+
+impl Default for SimulationSettings {
+    fn default() -> Self {
+        Self {
+            changed: false,
+            fixed_rate: DEFAULT_FIXED_RATE,
+            max_fixed_steps: DEFAULT_MAX_FIXED_STEPS,
+        }
+    }
+}
+
+impl SimulationSettings {
+    pub fn changed(&self) -> bool {
+        self.changed
+    }
+
+    pub fn reset(&mut self) {
+        self.changed = false;
+    }
+
+    /// Fixed steps per second.
+    pub fn fixed_rate(&self) -> usize {
+        self.fixed_rate
+    }
+
+    pub fn set_fixed_rate(&mut self, value: usize) {
+        self.changed = true;
+        self.fixed_rate = usize::max(value, 1);
+    }
+
+    /// Upper limit of fixed steps per frame. Time that would require more steps is dropped, such
+    /// that a slow frame does not cause even slower frames.
+    pub fn max_fixed_steps(&self) -> usize {
+        self.max_fixed_steps
+    }
+
+    pub fn set_max_fixed_steps(&mut self, value: usize) {
+        self.changed = true;
+        self.max_fixed_steps = usize::max(value, 1);
+    }
+
+    pub fn fixed_step(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / usize::max(self.fixed_rate, 1) as f64)
+    }
+
+    pub fn serialize(&self, yaml: &mut RisYaml) {
+        yaml.add_entry(None, Some("simulation"));
+        yaml.add_entry(Some((KEY_FIXED_RATE, &self.fixed_rate.to_string())), None);
+        yaml.add_entry(
+            Some((KEY_MAX_FIXED_STEPS, &self.max_fixed_steps.to_string())),
+            None,
+        );
+        yaml.add_entry(None, None);
+    }
+
+    pub fn deserialize(&mut self, entry: &RisYamlEntry) -> Result<(), SerializeError> {
+        let Some((key, value)) = &entry.key_value else {
+            return Err(SerializeError::EntryWasEmpty);
+        };
+
+        match key.as_str() {
+            KEY_FIXED_RATE => {
+                let parsed = SettingsSerializer::parse(value)?;
+                self.set_fixed_rate(parsed);
+            }
+            KEY_MAX_FIXED_STEPS => {
+                let parsed = SettingsSerializer::parse(value)?;
+                self.set_max_fixed_steps(parsed);
+            }
+            _ => return Err(SerializeError::UnkownKey),
+        }
+
+        Ok(())
+    }
+}
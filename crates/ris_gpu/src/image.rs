@@ -12,6 +12,7 @@ pub struct Image {
     pub memory: vk::DeviceMemory,
     width: usize,
     height: usize,
+    mip_levels: usize,
     format: vk::Format,
     layout: vk::ImageLayout,
 }
@@ -20,6 +21,9 @@ pub struct ImageCreateInfo {
     pub device: ash::Device,
     pub width: usize,
     pub height: usize,
+    /// must be at least 1. level 0 is the full resolution image, every following level has
+    /// half the width and height of the previous one
+    pub mip_levels: usize,
     pub format: vk::Format,
     pub usage: vk::ImageUsageFlags,
     pub physical_device_memory_properties: vk::PhysicalDeviceMemoryProperties,
@@ -46,11 +50,14 @@ impl Image {
             device,
             width,
             height,
+            mip_levels,
             format,
             usage,
             physical_device_memory_properties,
         } = info;
 
+        ris_error::assert!(mip_levels != 0)?;
+
         let layout = vk::ImageLayout::UNDEFINED;
 
         let image_create_info = vk::ImageCreateInfo {
@@ -64,7 +71,7 @@ impl Image {
                 height: height as u32,
                 depth: 1,
             },
-            mip_levels: 1,
+            mip_levels: mip_levels as u32,
             array_layers: 1,
             samples: vk::SampleCountFlags::TYPE_1,
             tiling: vk::ImageTiling::OPTIMAL,
@@ -100,6 +107,7 @@ impl Image {
             memory,
             width,
             height,
+            mip_levels,
             format,
             layout,
         })
@@ -110,6 +118,7 @@ impl Image {
         image: vk::Image,
         format: vk::Format,
         aspect_mask: vk::ImageAspectFlags,
+        mip_levels: usize,
     ) -> RisResult<vk::ImageView> {
         let image_view_create_info = vk::ImageViewCreateInfo {
             s_type: vk::StructureType::IMAGE_VIEW_CREATE_INFO,
@@ -127,7 +136,7 @@ impl Image {
             subresource_range: vk::ImageSubresourceRange {
                 aspect_mask,
                 base_mip_level: 0,
-                level_count: 1,
+                level_count: mip_levels as u32,
                 base_array_layer: 0,
                 layer_count: 1,
             },
@@ -146,9 +155,26 @@ impl Image {
         self.height
     }
 
+    pub fn mip_levels(&self) -> usize {
+        self.mip_levels
+    }
+
+    pub fn mip_width(&self, mip_level: usize) -> usize {
+        usize::max(self.width >> mip_level, 1)
+    }
+
+    pub fn mip_height(&self, mip_level: usize) -> usize {
+        usize::max(self.height >> mip_level, 1)
+    }
+
     pub fn size(&self) -> usize {
+        self.mip_size(0)
+    }
+
+    pub fn mip_size(&self, mip_level: usize) -> usize {
         let pixel_width = match self.format {
             vk::Format::R8G8B8A8_SRGB => 4,
+            vk::Format::R8G8B8A8_UNORM => 4,
             vk::Format::R8G8B8A8_UINT => 4,
             format => ris_error::throw!(
                 "todo: pixel width for format {:?} is not yet implemented",
@@ -156,7 +182,7 @@ impl Image {
             ),
         };
 
-        self.width() * self.height() * pixel_width
+        self.mip_width(mip_level) * self.mip_height(mip_level) * pixel_width
     }
 
     pub fn layout(&self) -> vk::ImageLayout {
@@ -241,7 +267,7 @@ impl Image {
             subresource_range: vk::ImageSubresourceRange {
                 aspect_mask,
                 base_mip_level: 0,
-                level_count: 1,
+                level_count: self.mip_levels as u32,
                 base_array_layer: 0,
                 layer_count: 1,
             },
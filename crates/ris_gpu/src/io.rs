@@ -189,6 +189,13 @@ pub fn read_from_buffer<T: Copy>(args: Args<Buffer, impl AsMut<[T]>>) -> RisResu
 }
 
 pub fn write_to_image<T: Copy>(args: Args<Image, impl AsRef<[T]>>) -> RisResult<()> {
+    write_to_image_mip(args, 0)
+}
+
+pub fn write_to_image_mip<T: Copy>(
+    args: Args<Image, impl AsRef<[T]>>,
+    mip_level: usize,
+) -> RisResult<()> {
     let Args {
         transient_command_args,
         values: src,
@@ -200,7 +207,8 @@ pub fn write_to_image<T: Copy>(args: Args<Image, impl AsRef<[T]>>) -> RisResult<
     let src = src.as_ref();
     let src_size = std::mem::size_of_val(src);
 
-    ris_error::assert!(src_size == dst.size())?;
+    ris_error::assert!(mip_level < dst.mip_levels())?;
+    ris_error::assert!(src_size == dst.mip_size(mip_level))?;
     ris_error::assert!(src_size <= staging.size())?;
 
     let device = transient_command_args.device.clone();
@@ -224,14 +232,14 @@ pub fn write_to_image<T: Copy>(args: Args<Image, impl AsRef<[T]>>) -> RisResult<
                 buffer_image_height: 0,
                 image_subresource: vk::ImageSubresourceLayers {
                     aspect_mask: vk::ImageAspectFlags::COLOR,
-                    mip_level: 0,
+                    mip_level: mip_level as u32,
                     base_array_layer: 0,
                     layer_count: 1,
                 },
                 image_offset: vk::Offset3D { x: 0, y: 0, z: 0 },
                 image_extent: vk::Extent3D {
-                    width: dst.width() as u32,
-                    height: dst.height() as u32,
+                    width: dst.mip_width(mip_level) as u32,
+                    height: dst.mip_height(mip_level) as u32,
                     depth: 1,
                 },
             }],
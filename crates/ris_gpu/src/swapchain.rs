@@ -203,12 +203,14 @@ impl Swapchain {
                 viewport_image,
                 format.format,
                 vk::ImageAspectFlags::COLOR,
+                1,
             )?;
 
             let mut depth_image = Image::alloc(ImageCreateInfo {
                 device: device.clone(),
                 width: extent.width as usize,
                 height: extent.height as usize,
+                mip_levels: 1,
                 format: depth_format,
                 usage: vk::ImageUsageFlags::DEPTH_STENCIL_ATTACHMENT,
                 physical_device_memory_properties,
@@ -219,6 +221,7 @@ impl Swapchain {
                 depth_image.image,
                 depth_format,
                 vk::ImageAspectFlags::DEPTH,
+                1,
             )?;
 
             let fence = unsafe { device.create_fence(&fence_create_info, None) }?;
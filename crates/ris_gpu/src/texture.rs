@@ -27,6 +27,39 @@ pub struct TextureCreateInfo<'a> {
     pub pixels: &'a [u8],
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct TextureSampler {
+    pub mag_filter: vk::Filter,
+    pub min_filter: vk::Filter,
+    pub mipmap_mode: vk::SamplerMipmapMode,
+    pub address_mode: vk::SamplerAddressMode,
+}
+
+impl Default for TextureSampler {
+    fn default() -> Self {
+        Self {
+            mag_filter: vk::Filter::LINEAR,
+            min_filter: vk::Filter::LINEAR,
+            mipmap_mode: vk::SamplerMipmapMode::NEAREST,
+            address_mode: vk::SamplerAddressMode::REPEAT,
+        }
+    }
+}
+
+pub struct TextureMipsCreateInfo<'a> {
+    pub transient_command_args: TransientCommandArgs,
+    /// must be big enough to hold the first mip level
+    pub staging: &'a Buffer,
+    pub physical_device_memory_properties: vk::PhysicalDeviceMemoryProperties,
+    pub physical_device_properties: vk::PhysicalDeviceProperties,
+    pub width: usize,
+    pub height: usize,
+    pub format: vk::Format,
+    pub sampler: TextureSampler,
+    /// the pixels of every mip level, starting with the full resolution image
+    pub mips: &'a [&'a [u8]],
+}
+
 impl Texture {
     /// # Safety
     ///
@@ -51,15 +84,50 @@ impl Texture {
             pixels,
         } = info;
 
+        Self::alloc_mips(TextureMipsCreateInfo {
+            transient_command_args,
+            staging,
+            physical_device_memory_properties,
+            physical_device_properties,
+            width,
+            height,
+            format,
+            sampler: TextureSampler {
+                mag_filter: filter,
+                min_filter: filter,
+                ..Default::default()
+            },
+            mips: &[pixels],
+        })
+    }
+
+    pub fn alloc_mips(info: TextureMipsCreateInfo) -> RisResult<Self> {
+        let TextureMipsCreateInfo {
+            transient_command_args,
+            staging,
+            physical_device_memory_properties,
+            physical_device_properties,
+            width,
+            height,
+            format,
+            sampler,
+            mips,
+        } = info;
+
         ris_error::assert!(width != 0)?;
         ris_error::assert!(height != 0)?;
+        ris_error::assert!(!mips.is_empty())?;
 
         // https://github.com/Rismosch/ris_engine/issues/209
         let pixel_width = 4;
 
-        let actual_len = pixels.len();
-        let expected_len = width * height * pixel_width;
-        ris_error::assert!(actual_len == expected_len)?;
+        for (i, pixels) in mips.iter().enumerate() {
+            let mip_width = usize::max(width >> i, 1);
+            let mip_height = usize::max(height >> i, 1);
+            let actual_len = pixels.len();
+            let expected_len = mip_width * mip_height * pixel_width;
+            ris_error::assert!(actual_len == expected_len)?;
+        }
 
         let device = transient_command_args.device.clone();
         let tcas = transient_command_args.clone();
@@ -76,6 +144,7 @@ impl Texture {
             device: device.clone(),
             width,
             height,
+            mip_levels: mips.len(),
             format,
             usage: vk::ImageUsageFlags::TRANSFER_DST | vk::ImageUsageFlags::SAMPLED,
             physical_device_memory_properties,
@@ -89,13 +158,18 @@ impl Texture {
 
         unsafe {
             device.reset_fences(&[fence])?;
+        }
 
-            super::io::write_to_image(super::io::Args {
-                transient_command_args: tcas.clone(),
-                values: pixels,
-                gpu_object: &image,
-                staging,
-            })?;
+        for (i, pixels) in mips.iter().enumerate() {
+            super::io::write_to_image_mip(
+                super::io::Args {
+                    transient_command_args: tcas.clone(),
+                    values: pixels,
+                    gpu_object: &image,
+                    staging,
+                },
+                i,
+            )?;
         }
 
         image.transition_layout(TransitionLayoutInfo {
@@ -114,6 +188,7 @@ impl Texture {
             image.image,
             format,
             vk::ImageAspectFlags::COLOR,
+            mips.len(),
         )?;
 
         // create sampler
@@ -121,19 +196,19 @@ impl Texture {
             s_type: vk::StructureType::SAMPLER_CREATE_INFO,
             p_next: std::ptr::null(),
             flags: vk::SamplerCreateFlags::empty(),
-            mag_filter: filter,
-            min_filter: filter,
-            mipmap_mode: vk::SamplerMipmapMode::NEAREST,
-            address_mode_u: vk::SamplerAddressMode::REPEAT,
-            address_mode_v: vk::SamplerAddressMode::REPEAT,
-            address_mode_w: vk::SamplerAddressMode::REPEAT,
+            mag_filter: sampler.mag_filter,
+            min_filter: sampler.min_filter,
+            mipmap_mode: sampler.mipmap_mode,
+            address_mode_u: sampler.address_mode,
+            address_mode_v: sampler.address_mode,
+            address_mode_w: sampler.address_mode,
             mip_lod_bias: 0.0,
             anisotropy_enable: vk::TRUE,
             max_anisotropy: physical_device_properties.limits.max_sampler_anisotropy,
             compare_enable: vk::FALSE,
             compare_op: vk::CompareOp::ALWAYS,
             min_lod: 0.0,
-            max_lod: 0.0,
+            max_lod: (mips.len() - 1) as f32,
             border_color: vk::BorderColor::INT_OPAQUE_BLACK,
             unnormalized_coordinates: vk::FALSE,
         };
@@ -1,5 +1,8 @@
 use ash::vk;
 
+use ris_asset::asset_watcher;
+use ris_asset::asset_watcher::AssetSubscription;
+use ris_asset::LoadPriority;
 use ris_asset::RisGodAsset;
 use ris_debug::gizmo::GizmoSegmentVertex;
 use ris_error::Extensions;
@@ -52,6 +55,7 @@ pub struct GizmoSegmentRenderer {
     pipeline_layout: vk::PipelineLayout,
     pub renderer_id: RendererId,
     frames: Vec<GizmoSegmentFrame>,
+    asset_subscription: AssetSubscription,
 }
 
 pub struct GizmoSegmentRendererArgs<'a> {
@@ -150,9 +154,21 @@ impl GizmoSegmentRenderer {
         let descriptor_sets =
             unsafe { device.allocate_descriptor_sets(&descriptor_set_allocate_info) }?;
 
+        // subscribe before loading, such that no change is missed
+        let asset_subscription = asset_watcher::subscribe(vec![
+            god_asset.gizmo_segment_vert_spv.clone(),
+            god_asset.gizmo_segment_frag_spv.clone(),
+        ]);
+
         // shaders
-        let vs_future = ris_asset::load_raw_async(god_asset.gizmo_segment_vert_spv.clone());
-        let fs_future = ris_asset::load_raw_async(god_asset.gizmo_segment_frag_spv.clone());
+        let vs_future = ris_asset::load_raw_async_with_priority(
+            god_asset.gizmo_segment_vert_spv.clone(),
+            LoadPriority::High,
+        );
+        let fs_future = ris_asset::load_raw_async_with_priority(
+            god_asset.gizmo_segment_frag_spv.clone(),
+            LoadPriority::High,
+        );
 
         let vs_bytes = vs_future.wait()?;
         let fs_bytes = fs_future.wait()?;
@@ -490,9 +506,17 @@ impl GizmoSegmentRenderer {
             pipeline_layout,
             renderer_id,
             frames,
+            asset_subscription,
         })
     }
 
+    /// Whether a shader or texture this renderer was built from changed since the last call. If
+    /// so, the renderer must be rebuilt to use it. Consumes the changes, such that a rebuild that
+    /// failed is not retried until the assets change again.
+    pub fn take_assets_changed(&self) -> bool {
+        !self.asset_subscription.take_changed().is_empty()
+    }
+
     pub fn draw(&mut self, args: GizmoSegmentRendererArgs) -> RisResult<Option<vk::CommandBuffer>> {
         let GizmoSegmentRendererArgs {
             core,
@@ -1,6 +1,9 @@
 use ash::vk;
 
+use ris_asset::asset_watcher;
+use ris_asset::asset_watcher::AssetSubscription;
 use ris_asset::codecs::qoi;
+use ris_asset::LoadPriority;
 use ris_asset::RisGodAsset;
 use ris_debug::gizmo::GizmoTextVertex;
 use ris_error::Extensions;
@@ -59,6 +62,7 @@ pub struct GizmoTextRenderer {
     pub renderer_id: RendererId,
     frames: Vec<GizmoTextFrame>,
     font_texture: Texture,
+    asset_subscription: AssetSubscription,
 }
 
 pub struct GizmoTextRendererArgs<'a> {
@@ -194,10 +198,27 @@ impl GizmoTextRenderer {
         let descriptor_sets =
             unsafe { device.allocate_descriptor_sets(&descriptor_set_allocate_info) }?;
 
+        // subscribe before loading, such that no change is missed
+        let asset_subscription = asset_watcher::subscribe(vec![
+            god_asset.gizmo_text_vert_spv.clone(),
+            god_asset.gizmo_text_geom_spv.clone(),
+            god_asset.gizmo_text_frag_spv.clone(),
+            god_asset.debug_font_texture.clone(),
+        ]);
+
         // shaders
-        let vs_future = ris_asset::load_raw_async(god_asset.gizmo_text_vert_spv.clone());
-        let gs_future = ris_asset::load_raw_async(god_asset.gizmo_text_geom_spv.clone());
-        let fs_future = ris_asset::load_raw_async(god_asset.gizmo_text_frag_spv.clone());
+        let vs_future = ris_asset::load_raw_async_with_priority(
+            god_asset.gizmo_text_vert_spv.clone(),
+            LoadPriority::High,
+        );
+        let gs_future = ris_asset::load_raw_async_with_priority(
+            god_asset.gizmo_text_geom_spv.clone(),
+            LoadPriority::High,
+        );
+        let fs_future = ris_asset::load_raw_async_with_priority(
+            god_asset.gizmo_text_frag_spv.clone(),
+            LoadPriority::High,
+        );
 
         let vs_bytes = vs_future.wait()?;
         let gs_bytes = gs_future.wait()?;
@@ -510,7 +531,10 @@ impl GizmoTextRenderer {
         unsafe { device.destroy_shader_module(fs_module, None) };
 
         // texture
-        let font_future = ris_asset::load_raw_async(god_asset.debug_font_texture.clone());
+        let font_future = ris_asset::load_raw_async_with_priority(
+            god_asset.debug_font_texture.clone(),
+            LoadPriority::High,
+        );
         let font_data = font_future.wait()?;
         let (pixels, desc) = qoi::decode(&font_data, None)?;
 
@@ -581,9 +605,17 @@ impl GizmoTextRenderer {
             renderer_id,
             frames,
             font_texture,
+            asset_subscription,
         })
     }
 
+    /// Whether a shader or texture this renderer was built from changed since the last call. If
+    /// so, the renderer must be rebuilt to use it. Consumes the changes, such that a rebuild that
+    /// failed is not retried until the assets change again.
+    pub fn take_assets_changed(&self) -> bool {
+        !self.asset_subscription.take_changed().is_empty()
+    }
+
     pub fn draw(&mut self, args: GizmoTextRendererArgs) -> RisResult<Option<vk::CommandBuffer>> {
         let GizmoTextRendererArgs {
             core,
@@ -10,6 +10,9 @@ use imgui::DrawData;
 use imgui::TextureId;
 use imgui::Textures;
 
+use ris_asset::asset_watcher;
+use ris_asset::asset_watcher::AssetSubscription;
+use ris_asset::LoadPriority;
 use ris_asset::RisGodAsset;
 use ris_error::prelude::*;
 use ris_gpu::buffer::Buffer;
@@ -51,6 +54,7 @@ pub struct ImguiRenderer {
     frames: Vec<ImguiFrame>,
     font_texture: Texture,
     textures: Textures<vk::DescriptorSet>,
+    asset_subscription: AssetSubscription,
 }
 
 pub struct ImguiRendererArgs<'a> {
@@ -98,9 +102,21 @@ impl ImguiRenderer {
             ..
         } = core;
 
+        // subscribe before loading, such that no change is missed
+        let asset_subscription = asset_watcher::subscribe(vec![
+            god_asset.imgui_vert_spv.clone(),
+            god_asset.imgui_frag_spv.clone(),
+        ]);
+
         // shaders
-        let vs_asset_future = ris_asset::load_raw_async(god_asset.imgui_vert_spv.clone());
-        let fs_asset_future = ris_asset::load_raw_async(god_asset.imgui_frag_spv.clone());
+        let vs_asset_future = ris_asset::load_raw_async_with_priority(
+            god_asset.imgui_vert_spv.clone(),
+            LoadPriority::High,
+        );
+        let fs_asset_future = ris_asset::load_raw_async_with_priority(
+            god_asset.imgui_frag_spv.clone(),
+            LoadPriority::High,
+        );
 
         let vs_bytes = vs_asset_future.wait()?;
         let fs_bytes = fs_asset_future.wait()?;
@@ -539,9 +555,17 @@ impl ImguiRenderer {
             frames,
             font_texture,
             textures: Textures::new(),
+            asset_subscription,
         })
     }
 
+    /// Whether a shader or texture this renderer was built from changed since the last call. If
+    /// so, the renderer must be rebuilt to use it. Consumes the changes, such that a rebuild that
+    /// failed is not retried until the assets change again.
+    pub fn take_assets_changed(&self) -> bool {
+        !self.asset_subscription.take_changed().is_empty()
+    }
+
     pub fn draw(&mut self, args: ImguiRendererArgs) -> RisResult<Option<vk::CommandBuffer>> {
         let ImguiRendererArgs {
             core,
@@ -1,8 +1,21 @@
 use ash::vk;
 
-use ris_asset::codecs::qoi;
+use ris_asset::asset_watcher;
+use ris_asset::asset_watcher::AssetSubscription;
+use ris_asset::lookup::ris_material_lookup::MaterialLookup;
 use ris_asset::lookup::ris_mesh_lookup::MeshLookup;
+use ris_asset::lookup::ris_texture_lookup::TextureLookup;
+use ris_asset::LoadPriority;
 use ris_asset::RisGodAsset;
+use ris_asset_data::material::AlphaMode;
+use ris_asset_data::material::MaterialParams;
+use ris_asset_data::material::MaterialTextures;
+use ris_asset_data::texture::TextureLookupId;
+use ris_asset_data::AssetId;
+use ris_data::ecs::components::animator_component::AnimatorComponent;
+use ris_data::ecs::components::material_component::MaterialComponent;
+use ris_data::ecs::components::mesh_component::MeshComponent;
+use ris_data::ecs::game_object::GetFrom;
 use ris_data::ecs::scene::Scene;
 use ris_error::Extensions;
 use ris_error::RisResult;
@@ -17,12 +30,23 @@ use ris_gpu::texture::Texture;
 use ris_gpu::texture::TextureCreateInfo;
 use ris_gpu::transient_command::TransientCommandArgs;
 use ris_math::camera::Camera;
+use ris_math::matrix::Mat3;
 use ris_math::matrix::Mat4;
+use ris_math::vector::Vec3;
+use ris_math::vector::Vec4;
 
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
 pub struct PushConstants {
     pub model: Mat4,
+    pub base_color: Vec4,
+    /// rgb: emissive, a: alpha cutoff
+    pub emissive: Vec4,
+    /// r: metallic, g: roughness, b: normal scale, a: occlusion strength
+    pub surface: Vec4,
+    /// x: the index of the first joint matrix of a skinned mesh, y: how many joint matrices it
+    /// has. zw: unused
+    pub skin: [u32; 4],
 }
 
 /// the actual push constants size can be checked using
@@ -48,10 +72,26 @@ pub struct UniformBufferObject {
     pub proj: Mat4,
 }
 
+/// The material descriptor sets are allocated anew every frame. If a frame draws more materials
+/// than this, the remaining meshes are drawn with the default material.
+const MATERIAL_DESCRIPTOR_SETS_PER_FRAME: usize = 256;
+
+/// The normal slot samples a flat normal, every other slot samples white.
+const NORMAL_TEXTURE_SLOT: usize = 2;
+
+/// The joint matrices of all skinned meshes of a frame share one buffer. If it is full, the
+/// remaining skinned meshes are drawn in their bind pose.
+const JOINT_MATRICES_PER_FRAME: usize = 4096;
+
 pub struct SceneFrame {
     descriptor: Buffer,
     descriptor_mapped_memory: *mut UniformBufferObject,
     descriptor_set: vk::DescriptorSet,
+    joint_matrices: Buffer,
+    joint_matrices_mapped_memory: *mut Mat4,
+    material_descriptor_pool: vk::DescriptorPool,
+    /// the sets allocated this frame, by the index of their `MaterialLookupId`
+    material_descriptor_sets: Vec<(usize, vk::DescriptorSet)>,
 }
 
 impl SceneFrame {
@@ -61,19 +101,34 @@ impl SceneFrame {
     /// - This object must not be used after it was freed
     pub unsafe fn free(&mut self, device: &ash::Device) {
         self.descriptor.free(device);
+        self.joint_matrices.free(device);
+        device.destroy_descriptor_pool(self.material_descriptor_pool, None);
     }
 }
 
 pub struct SceneRenderer {
     descriptor_set_layout: vk::DescriptorSetLayout,
+    material_descriptor_set_layout: vk::DescriptorSetLayout,
     descriptor_pool: vk::DescriptorPool,
     render_pass: vk::RenderPass,
     pipeline: vk::Pipeline,
+    skinned_pipeline: vk::Pipeline,
     pipeline_layout: vk::PipelineLayout,
     pub renderer_id: RendererId,
     frames: Vec<SceneFrame>,
-    texture: Texture,
+    texture_asset_id: AssetId,
+    texture_id: Option<TextureLookupId>,
+    /// bound until the texture is loaded, and for missing material textures
+    fallback_texture: Texture,
+    /// bound for missing normal textures
+    fallback_normal_texture: Texture,
+    warned_material_descriptor_sets_exhausted: bool,
+    warned_joint_matrices_exhausted: bool,
+    asset_subscription: AssetSubscription,
+    lookup_subscription: AssetSubscription,
     pub mesh_lookup: Option<MeshLookup>,
+    pub texture_lookup: Option<TextureLookup>,
+    pub material_lookup: Option<MaterialLookup>,
 }
 
 pub struct SceneRendererArgs<'a> {
@@ -82,6 +137,9 @@ pub struct SceneRendererArgs<'a> {
     pub window_drawable_size: (u32, u32),
     pub camera: &'a Camera,
     pub scene: &'a Scene,
+    /// blends the transforms before and after the last fixed update. see
+    /// `Frame::interpolation_alpha()`
+    pub interpolation_alpha: f32,
     pub frame_in_flight: &'a FrameInFlight,
 }
 
@@ -97,21 +155,30 @@ impl SceneRenderer {
 
         device.destroy_descriptor_pool(self.descriptor_pool, None);
         device.destroy_descriptor_set_layout(self.descriptor_set_layout, None);
+        device.destroy_descriptor_set_layout(self.material_descriptor_set_layout, None);
 
         device.destroy_pipeline(self.pipeline, None);
+        device.destroy_pipeline(self.skinned_pipeline, None);
         device.destroy_pipeline_layout(self.pipeline_layout, None);
         device.destroy_render_pass(self.render_pass, None);
 
-        self.texture.free(device);
+        self.fallback_texture.free(device);
+        self.fallback_normal_texture.free(device);
+        self.material_lookup.take();
         if let Some(mut mesh_lookup) = self.mesh_lookup.take() {
             mesh_lookup.free(device);
         }
+        if let Some(mut texture_lookup) = self.texture_lookup.take() {
+            texture_lookup.free(device);
+        }
     }
 
     pub fn alloc(
         core: &VulkanCore,
         god_asset: &RisGodAsset,
         mesh_lookup: Option<MeshLookup>,
+        texture_lookup: Option<TextureLookup>,
+        material_lookup: Option<MaterialLookup>,
         renderer_registerer: &mut RendererRegisterer,
     ) -> RisResult<Self> {
         ris_log::info!("building scene renderer...");
@@ -132,40 +199,40 @@ impl SceneRenderer {
         let physical_device_properties =
             unsafe { instance.get_physical_device_properties(suitable_device.physical_device) };
 
-        // texture
-        let texture_asset_id = god_asset.texture.clone();
-        let content = ris_asset::load_raw_async(texture_asset_id.clone()).wait()?;
-        let (pixels, desc) = qoi::decode(&content, None)?;
-
-        let pixels_rgba = match desc.channels {
-            qoi::Channels::RGB => ris_asset::util::add_alpha_channel(&pixels)?,
-            qoi::Channels::RGBA => pixels,
+        // subscribe before loading, such that no change is missed. the texture is reloaded by
+        // the texture lookup
+        let asset_subscription = asset_watcher::subscribe(vec![
+            god_asset.default_vert_spv.clone(),
+            god_asset.default_frag_spv.clone(),
+            god_asset.default_skinned_vert_spv.clone(),
+        ]);
+
+        // fallback textures
+        let transient_command_args = TransientCommandArgs {
+            device: device.clone(),
+            queue: *graphics_queue,
+            command_pool: *transient_command_pool,
         };
 
-        let staging =
-            Buffer::alloc_staging(device, pixels_rgba.len(), physical_device_memory_properties)?;
-
-        let texture = Texture::alloc(TextureCreateInfo {
-            transient_command_args: TransientCommandArgs {
-                device: device.clone(),
-                queue: *graphics_queue,
-                command_pool: *transient_command_pool,
-            },
-            staging: &staging,
+        let fallback_texture = alloc_fallback_texture(
+            transient_command_args.clone(),
             physical_device_memory_properties,
             physical_device_properties,
-            width: desc.width as usize,
-            height: desc.height as usize,
-            format: vk::Format::R8G8B8A8_SRGB,
-            filter: vk::Filter::LINEAR,
-            pixels: &pixels_rgba,
-        })?;
+            vk::Format::R8G8B8A8_SRGB,
+            [u8::MAX; 4],
+        )?;
 
-        unsafe { staging.free(device) };
+        let fallback_normal_texture = alloc_fallback_texture(
+            transient_command_args,
+            physical_device_memory_properties,
+            physical_device_properties,
+            vk::Format::R8G8B8A8_UNORM,
+            [128, 128, u8::MAX, u8::MAX],
+        )?;
 
         // push constants
         let push_constant_range = [vk::PushConstantRange {
-            stage_flags: vk::ShaderStageFlags::VERTEX,
+            stage_flags: vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
             offset: 0,
             size: std::mem::size_of::<PushConstants>() as u32,
         }];
@@ -176,14 +243,14 @@ impl SceneRenderer {
                 binding: 0,
                 descriptor_type: vk::DescriptorType::UNIFORM_BUFFER,
                 descriptor_count: 1,
-                stage_flags: vk::ShaderStageFlags::VERTEX,
+                stage_flags: vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
                 p_immutable_samplers: std::ptr::null(),
             },
             vk::DescriptorSetLayoutBinding {
                 binding: 1,
-                descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+                descriptor_type: vk::DescriptorType::STORAGE_BUFFER,
                 descriptor_count: 1,
-                stage_flags: vk::ShaderStageFlags::FRAGMENT,
+                stage_flags: vk::ShaderStageFlags::VERTEX,
                 p_immutable_samplers: std::ptr::null(),
             },
         ];
@@ -200,13 +267,33 @@ impl SceneRenderer {
             device.create_descriptor_set_layout(&descriptor_set_layout_create_info, None)
         }?;
 
+        let material_descriptor_set_layout_bindings = [vk::DescriptorSetLayoutBinding {
+            binding: 0,
+            descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+            descriptor_count: MaterialTextures::SLOT_COUNT as u32,
+            stage_flags: vk::ShaderStageFlags::FRAGMENT,
+            p_immutable_samplers: std::ptr::null(),
+        }];
+
+        let material_descriptor_set_layout_create_info = vk::DescriptorSetLayoutCreateInfo {
+            s_type: vk::StructureType::DESCRIPTOR_SET_LAYOUT_CREATE_INFO,
+            p_next: std::ptr::null(),
+            flags: vk::DescriptorSetLayoutCreateFlags::empty(),
+            binding_count: material_descriptor_set_layout_bindings.len() as u32,
+            p_bindings: material_descriptor_set_layout_bindings.as_ptr(),
+        };
+
+        let material_descriptor_set_layout = unsafe {
+            device.create_descriptor_set_layout(&material_descriptor_set_layout_create_info, None)
+        }?;
+
         let descriptor_pool_sizes = [
             vk::DescriptorPoolSize {
                 ty: vk::DescriptorType::UNIFORM_BUFFER,
                 descriptor_count: FRAMES_IN_FLIGHT as u32,
             },
             vk::DescriptorPoolSize {
-                ty: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+                ty: vk::DescriptorType::STORAGE_BUFFER,
                 descriptor_count: FRAMES_IN_FLIGHT as u32,
             },
         ];
@@ -242,14 +329,27 @@ impl SceneRenderer {
         ris_error::assert!(descriptor_sets.len() == total_descriptor_set_count)?;
 
         // shaders
-        let vs_asset_future = ris_asset::load_raw_async(god_asset.default_vert_spv.clone());
-        let fs_asset_future = ris_asset::load_raw_async(god_asset.default_frag_spv.clone());
+        let vs_asset_future = ris_asset::load_raw_async_with_priority(
+            god_asset.default_vert_spv.clone(),
+            LoadPriority::High,
+        );
+        let fs_asset_future = ris_asset::load_raw_async_with_priority(
+            god_asset.default_frag_spv.clone(),
+            LoadPriority::High,
+        );
+
+        let skinned_vs_asset_future = ris_asset::load_raw_async_with_priority(
+            god_asset.default_skinned_vert_spv.clone(),
+            LoadPriority::High,
+        );
 
         let vs_bytes = vs_asset_future.wait()?;
         let fs_bytes = fs_asset_future.wait()?;
+        let skinned_vs_bytes = skinned_vs_asset_future.wait()?;
 
         let vs_module = ris_gpu::shader::create_module(device, &vs_bytes)?;
         let fs_module = ris_gpu::shader::create_module(device, &fs_bytes)?;
+        let skinned_vs_module = ris_gpu::shader::create_module(device, &skinned_vs_bytes)?;
         let entry = ris_gpu::shader::ENTRY.as_ptr();
 
         let shader_stages = [
@@ -273,6 +373,14 @@ impl SceneRenderer {
             },
         ];
 
+        let skinned_shader_stages = [
+            vk::PipelineShaderStageCreateInfo {
+                module: skinned_vs_module,
+                ..shader_stages[0]
+            },
+            shader_stages[1],
+        ];
+
         // pipeline
         let vertex_binding_descriptions = ris_asset_data::mesh::VERTEX_BINDING_DESCRIPTIONS;
         let vertex_attribute_descriptions = ris_asset_data::mesh::VERTEX_ATTRIBUTE_DESCRIPTIONS;
@@ -287,6 +395,19 @@ impl SceneRenderer {
             p_vertex_attribute_descriptions: vertex_attribute_descriptions.as_ptr(),
         }];
 
+        let skinned_vertex_binding_descriptions =
+            ris_asset_data::mesh::SKINNED_VERTEX_BINDING_DESCRIPTIONS;
+        let skinned_vertex_attribute_descriptions =
+            ris_asset_data::mesh::SKINNED_VERTEX_ATTRIBUTE_DESCRIPTIONS;
+
+        let skinned_vertex_input_state = [vk::PipelineVertexInputStateCreateInfo {
+            vertex_binding_description_count: skinned_vertex_binding_descriptions.len() as u32,
+            p_vertex_binding_descriptions: skinned_vertex_binding_descriptions.as_ptr(),
+            vertex_attribute_description_count: skinned_vertex_attribute_descriptions.len() as u32,
+            p_vertex_attribute_descriptions: skinned_vertex_attribute_descriptions.as_ptr(),
+            ..vertex_input_state[0]
+        }];
+
         let input_assembly_state = [vk::PipelineInputAssemblyStateCreateInfo {
             s_type: vk::StructureType::PIPELINE_INPUT_ASSEMBLY_STATE_CREATE_INFO,
             p_next: std::ptr::null(),
@@ -404,7 +525,7 @@ impl SceneRenderer {
         }];
 
         // pipeline layout
-        let descriptor_set_layouts = [descriptor_set_layout];
+        let descriptor_set_layouts = [descriptor_set_layout, material_descriptor_set_layout];
 
         let pipeline_layout_create_info = vk::PipelineLayoutCreateInfo {
             s_type: vk::StructureType::PIPELINE_LAYOUT_CREATE_INFO,
@@ -519,6 +640,13 @@ impl SceneRenderer {
             base_pipeline_index: -1,
         }];
 
+        let skinned_graphics_pipeline_create_info = [vk::GraphicsPipelineCreateInfo {
+            stage_count: skinned_shader_stages.len() as u32,
+            p_stages: skinned_shader_stages.as_ptr(),
+            p_vertex_input_state: skinned_vertex_input_state.as_ptr(),
+            ..graphics_pipeline_create_info[0]
+        }];
+
         let graphics_pipelines = unsafe {
             device.create_graphics_pipelines(
                 vk::PipelineCache::null(),
@@ -529,8 +657,22 @@ impl SceneRenderer {
         .map_err(|e| e.1)?;
         let pipeline = graphics_pipelines.into_iter().next().into_ris_error()?;
 
+        let skinned_graphics_pipelines = unsafe {
+            device.create_graphics_pipelines(
+                vk::PipelineCache::null(),
+                &skinned_graphics_pipeline_create_info,
+                None,
+            )
+        }
+        .map_err(|e| e.1)?;
+        let skinned_pipeline = skinned_graphics_pipelines
+            .into_iter()
+            .next()
+            .into_ris_error()?;
+
         unsafe { device.destroy_shader_module(vs_module, None) };
         unsafe { device.destroy_shader_module(fs_module, None) };
+        unsafe { device.destroy_shader_module(skinned_vs_module, None) };
 
         // frames
         let renderer_id = renderer_registerer.register(0)?;
@@ -555,10 +697,50 @@ impl SceneRenderer {
                 )
             }? as *mut UniformBufferObject;
 
+            let joint_matrices = Buffer::alloc(
+                device,
+                JOINT_MATRICES_PER_FRAME * std::mem::size_of::<Mat4>(),
+                vk::BufferUsageFlags::STORAGE_BUFFER,
+                vk::MemoryPropertyFlags::HOST_VISIBLE,
+                physical_device_memory_properties,
+            )?;
+
+            let joint_matrices_mapped_memory = unsafe {
+                device.map_memory(
+                    joint_matrices.memory,
+                    0,
+                    vk::WHOLE_SIZE,
+                    vk::MemoryMapFlags::empty(),
+                )
+            }? as *mut Mat4;
+
+            let material_descriptor_pool_sizes = [vk::DescriptorPoolSize {
+                ty: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+                descriptor_count: (MATERIAL_DESCRIPTOR_SETS_PER_FRAME
+                    * MaterialTextures::SLOT_COUNT) as u32,
+            }];
+
+            let material_descriptor_pool_create_info = vk::DescriptorPoolCreateInfo {
+                s_type: vk::StructureType::DESCRIPTOR_POOL_CREATE_INFO,
+                p_next: std::ptr::null(),
+                flags: vk::DescriptorPoolCreateFlags::empty(),
+                max_sets: MATERIAL_DESCRIPTOR_SETS_PER_FRAME as u32,
+                pool_size_count: material_descriptor_pool_sizes.len() as u32,
+                p_pool_sizes: material_descriptor_pool_sizes.as_ptr(),
+            };
+
+            let material_descriptor_pool = unsafe {
+                device.create_descriptor_pool(&material_descriptor_pool_create_info, None)
+            }?;
+
             let frame = SceneFrame {
                 descriptor,
                 descriptor_mapped_memory,
                 descriptor_set,
+                joint_matrices,
+                joint_matrices_mapped_memory,
+                material_descriptor_pool,
+                material_descriptor_sets: Vec::new(),
             };
             frames.push(frame);
         }
@@ -569,19 +751,47 @@ impl SceneRenderer {
             None => Some(MeshLookup::default()),
         };
 
+        let texture_lookup = match texture_lookup {
+            Some(texture_lookup) => Some(texture_lookup),
+            None => Some(TextureLookup::default()),
+        };
+
+        let material_lookup = match material_lookup {
+            Some(material_lookup) => Some(material_lookup),
+            None => Some(MaterialLookup::default()),
+        };
+
         Ok(Self {
             descriptor_set_layout,
+            material_descriptor_set_layout,
             descriptor_pool,
             render_pass,
             pipeline,
+            skinned_pipeline,
             pipeline_layout,
             renderer_id,
             frames,
-            texture,
+            texture_asset_id: god_asset.texture.clone(),
+            texture_id: None,
+            fallback_texture,
+            fallback_normal_texture,
+            warned_material_descriptor_sets_exhausted: false,
+            warned_joint_matrices_exhausted: false,
+            asset_subscription,
+            lookup_subscription: asset_watcher::subscribe_all(),
             mesh_lookup,
+            texture_lookup,
+            material_lookup,
         })
     }
 
+    /// Whether a shader or texture this renderer was built from changed since the last call. If
+    /// so, the renderer must be rebuilt to use it. Consumes the changes, such that a rebuild that
+    /// failed is not retried until the assets change again.
+    pub fn take_assets_changed(&self) -> bool {
+        !self.asset_subscription.take_changed().is_empty()
+    }
+
     pub fn draw(&mut self, args: SceneRendererArgs) -> RisResult<vk::CommandBuffer> {
         let SceneRendererArgs {
             core,
@@ -589,6 +799,7 @@ impl SceneRenderer {
             window_drawable_size,
             camera,
             scene,
+            interpolation_alpha,
             frame_in_flight,
         } = args;
 
@@ -605,6 +816,8 @@ impl SceneRenderer {
         let physical_device_memory_properties = unsafe {
             instance.get_physical_device_memory_properties(suitable_device.physical_device)
         };
+        let physical_device_properties =
+            unsafe { instance.get_physical_device_properties(suitable_device.physical_device) };
 
         let SwapchainEntry {
             viewport_image_view,
@@ -616,12 +829,82 @@ impl SceneRenderer {
             descriptor,
             descriptor_mapped_memory,
             descriptor_set,
+            joint_matrices,
+            joint_matrices_mapped_memory,
+            material_descriptor_pool,
+            material_descriptor_sets,
         } = &mut self.frames[frame_in_flight.index];
 
         let mesh_lookup = self.mesh_lookup.as_mut().into_ris_error()?;
+        let texture_lookup = self.texture_lookup.as_mut().into_ris_error()?;
+        let material_lookup = self.material_lookup.as_mut().into_ris_error()?;
 
-        // clean up
+        // clean up. materials first, such that their textures are freed in the same frame
         mesh_lookup.free_unused_meshes(device)?;
+        material_lookup.free_unused_materials();
+        texture_lookup.free_unused_textures(device)?;
+
+        // hot reload
+        let changed_assets = self.lookup_subscription.take_changed();
+        if !changed_assets.is_empty() {
+            mesh_lookup.reload(device, &changed_assets)?;
+            material_lookup.reload(&changed_assets);
+            texture_lookup.reload(device, &changed_assets)?;
+        }
+
+        // the previous use of this frame has finished, thus its material sets can be reused
+        unsafe {
+            device.reset_descriptor_pool(
+                *material_descriptor_pool,
+                vk::DescriptorPoolResetFlags::empty(),
+            )
+        }?;
+        material_descriptor_sets.clear();
+
+        let transient_command_args = TransientCommandArgs {
+            device: device.clone(),
+            queue: *graphics_queue,
+            command_pool: *transient_command_pool,
+        };
+
+        let fallback_image_info = image_info(&self.fallback_texture);
+        let fallback_normal_image_info = image_info(&self.fallback_normal_texture);
+        let mut fallback_image_infos = [fallback_image_info; MaterialTextures::SLOT_COUNT];
+        fallback_image_infos[NORMAL_TEXTURE_SLOT] = fallback_normal_image_info;
+
+        // texture
+        let texture_id = self
+            .texture_id
+            .get_or_insert_with(|| texture_lookup.alloc(self.texture_asset_id.clone()));
+        let gpu_texture = unsafe {
+            texture_lookup.get(
+                transient_command_args.clone(),
+                physical_device_memory_properties,
+                physical_device_properties,
+                texture_id,
+            )
+        };
+        let texture_image_info = match gpu_texture {
+            Some(gpu_texture) => image_info(gpu_texture.texture()?),
+            None => fallback_image_info,
+        };
+
+        // meshes without a material, or whose material is still loading, are drawn with the
+        // default material. it uses the texture of the god asset as base color
+        let mut default_image_infos = fallback_image_infos;
+        default_image_infos[0] = texture_image_info;
+        let default_material_descriptor_set = unsafe {
+            alloc_material_descriptor_set(
+                device,
+                *material_descriptor_pool,
+                self.material_descriptor_set_layout,
+                &default_image_infos,
+            )
+        }?;
+        let default_material_params = MaterialParams {
+            metallic: 0.0,
+            ..Default::default()
+        };
 
         // command buffer
         let command_buffer = frame_in_flight.primary_command_buffer(self.renderer_id);
@@ -734,10 +1017,10 @@ impl SceneRenderer {
                 range: std::mem::size_of::<UniformBufferObject>() as vk::DeviceSize,
             }];
 
-            let descriptor_image_info = [vk::DescriptorImageInfo {
-                sampler: self.texture.sampler,
-                image_view: self.texture.view,
-                image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+            let joint_matrices_buffer_info = [vk::DescriptorBufferInfo {
+                buffer: joint_matrices.buffer,
+                offset: 0,
+                range: vk::WHOLE_SIZE,
             }];
 
             let write_descriptor_sets = [
@@ -759,10 +1042,10 @@ impl SceneRenderer {
                     dst_set: *descriptor_set,
                     dst_binding: 1,
                     dst_array_element: 0,
-                    descriptor_count: descriptor_image_info.len() as u32,
-                    descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
-                    p_image_info: descriptor_image_info.as_ptr(),
-                    p_buffer_info: std::ptr::null(),
+                    descriptor_count: joint_matrices_buffer_info.len() as u32,
+                    descriptor_type: vk::DescriptorType::STORAGE_BUFFER,
+                    p_image_info: std::ptr::null(),
+                    p_buffer_info: joint_matrices_buffer_info.as_ptr(),
                     p_texel_buffer_view: std::ptr::null(),
                 },
             ];
@@ -778,11 +1061,15 @@ impl SceneRenderer {
                 &[],
             );
 
-            for mesh_renderer_component in scene.mesh_renderer_components.iter() {
-                let mut aref_mut = mesh_renderer_component.borrow_mut();
-                if !aref_mut.is_alive {
+            let mut bound_pipeline = self.pipeline;
+            let mut joint_matrix_count = 0;
+
+            let mesh_renderer_components = scene.query().components::<MeshComponent>()?;
+            for handle in mesh_renderer_components {
+                let Ok(ptr) = scene.deref(handle) else {
                     continue;
-                }
+                };
+                let mut aref_mut = ptr.borrow_mut();
 
                 if let Some(to_allocate) = aref_mut.poll_asset_id_to_allocate() {
                     let lookup_id = mesh_lookup.alloc(to_allocate);
@@ -806,15 +1093,170 @@ impl SceneRenderer {
                 };
 
                 let game_object = aref_mut.game_object();
-                if game_object.is_active_in_hierarchy(scene) != Ok(true) {
+                let Ok(model) = game_object.interpolated_model(scene, interpolation_alpha) else {
                     continue;
+                };
+
+                // pick the level of detail by the size of the bounding sphere on screen
+                let (center, radius) = mesh.bounding_sphere()?;
+                let world_center = model * Vec4(center.0, center.1, center.2, 1.0);
+                let world_center = Vec3(world_center.0, world_center.1, world_center.2);
+                let scale = Mat3::from(model);
+                let max_scale = f32::max(
+                    scale.0.length(),
+                    f32::max(scale.1.length(), scale.2.length()),
+                );
+                let screen_coverage = camera.screen_coverage(world_center, radius * max_scale);
+                let lod = mesh.select_lod(screen_coverage)?;
+                let (index_offset, index_count) = mesh.lod_indices(lod)?;
+
+                // skinning. the joint matrices are in the space of the mesh, but the animator may
+                // sit on a parent
+                let mut skin = None;
+                if let Some(skin_offsets) = mesh.skin_offsets()? {
+                    let animator_handle = game_object
+                        .get_component::<AnimatorComponent>(scene, GetFrom::ThisAndParents)
+                        .ok()
+                        .flatten();
+                    if let Some(Ok(ptr)) = animator_handle.map(|x| scene.deref(x)) {
+                        let animator = ptr.borrow();
+                        let matrices = animator.joint_matrices();
+                        let fits = joint_matrix_count + matrices.len() <= JOINT_MATRICES_PER_FRAME;
+
+                        if matrices.is_empty() {
+                            // not evaluated yet, draw the bind pose
+                        } else if fits {
+                            ris_gpu::io::write_to_mapped_memory(
+                                device,
+                                matrices,
+                                joint_matrices.memory,
+                                joint_matrices_mapped_memory.add(joint_matrix_count),
+                            )?;
+
+                            skin = Some((joint_matrix_count, matrices.len(), skin_offsets));
+                            joint_matrix_count += matrices.len();
+                        } else if !self.warned_joint_matrices_exhausted {
+                            self.warned_joint_matrices_exhausted = true;
+                            ris_log::warning!(
+                                "more than {} joint matrices in one frame, drawing the bind pose",
+                                JOINT_MATRICES_PER_FRAME,
+                            );
+                        }
+                    }
                 }
 
-                let Ok(model) = game_object.model(scene) else {
-                    continue;
+                let pipeline = match skin {
+                    Some(_) => self.skinned_pipeline,
+                    None => self.pipeline,
+                };
+
+                if pipeline != bound_pipeline {
+                    device.cmd_bind_pipeline(
+                        command_buffer,
+                        vk::PipelineBindPoint::GRAPHICS,
+                        pipeline,
+                    );
+                    bound_pipeline = pipeline;
+                }
+
+                // material
+                let mut material = None;
+                let material_handle = game_object
+                    .get_component::<MaterialComponent>(scene, GetFrom::This)
+                    .ok()
+                    .flatten();
+                if let Some(Ok(ptr)) = material_handle.map(|x| scene.deref(x)) {
+                    let mut material_component = ptr.borrow_mut();
+
+                    if let Some(to_allocate) = material_component.poll_asset_id_to_allocate() {
+                        let lookup_id = material_lookup.alloc(to_allocate);
+                        material_component.set_lookup_id(lookup_id);
+                    }
+
+                    if let Some(lookup_id) = material_component.lookup_id() {
+                        material = material_lookup
+                            .get(texture_lookup, lookup_id)
+                            .map(|x| (lookup_id.index(), x));
+                    }
+                }
+
+                let (params, material_descriptor_set) = match material {
+                    Some((index, gpu_material)) => {
+                        let cached = material_descriptor_sets
+                            .iter()
+                            .find(|(x, _)| *x == index)
+                            .map(|(_, x)| *x);
+
+                        let material_descriptor_set = match cached {
+                            Some(material_descriptor_set) => material_descriptor_set,
+                            None => {
+                                let mut image_infos = fallback_image_infos;
+                                for (i, texture_id) in gpu_material.textures.iter().enumerate() {
+                                    let Some(texture_id) = texture_id else {
+                                        continue;
+                                    };
+
+                                    let gpu_texture = texture_lookup.get(
+                                        transient_command_args.clone(),
+                                        physical_device_memory_properties,
+                                        physical_device_properties,
+                                        texture_id,
+                                    );
+
+                                    if let Some(gpu_texture) = gpu_texture {
+                                        image_infos[i] = image_info(gpu_texture.texture()?);
+                                    }
+                                }
+
+                                let result = alloc_material_descriptor_set(
+                                    device,
+                                    *material_descriptor_pool,
+                                    self.material_descriptor_set_layout,
+                                    &image_infos,
+                                );
+
+                                match result {
+                                    Ok(material_descriptor_set) => {
+                                        material_descriptor_sets
+                                            .push((index, material_descriptor_set));
+                                        material_descriptor_set
+                                    }
+                                    Err(vk::Result::ERROR_OUT_OF_POOL_MEMORY)
+                                    | Err(vk::Result::ERROR_FRAGMENTED_POOL) => {
+                                        if !self.warned_material_descriptor_sets_exhausted {
+                                            self.warned_material_descriptor_sets_exhausted = true;
+                                            ris_log::warning!(
+                                                "more than {} materials in one frame, using the default material",
+                                                MATERIAL_DESCRIPTOR_SETS_PER_FRAME,
+                                            );
+                                        }
+
+                                        default_material_descriptor_set
+                                    }
+                                    Err(e) => return Err(e.into()),
+                                }
+                            }
+                        };
+
+                        (gpu_material.params, material_descriptor_set)
+                    }
+                    None => (default_material_params, default_material_descriptor_set),
                 };
 
-                let push_constants = PushConstants { model };
+                device.cmd_bind_descriptor_sets(
+                    command_buffer,
+                    vk::PipelineBindPoint::GRAPHICS,
+                    self.pipeline_layout,
+                    1,
+                    &[material_descriptor_set],
+                    &[],
+                );
+
+                let mut push_constants = PushConstants::new(model, params);
+                if let Some((first_joint_matrix, joint_matrix_count, _)) = skin {
+                    push_constants.skin[0] = first_joint_matrix as u32;
+                    push_constants.skin[1] = joint_matrix_count as u32;
+                }
 
                 let push_constants_ptr = &push_constants as *const PushConstants as *const u8;
                 let size = std::mem::size_of::<PushConstants>();
@@ -823,26 +1265,30 @@ impl SceneRenderer {
                 device.cmd_push_constants(
                     command_buffer,
                     self.pipeline_layout,
-                    vk::ShaderStageFlags::VERTEX,
+                    vk::ShaderStageFlags::VERTEX | vk::ShaderStageFlags::FRAGMENT,
                     0,
                     push_constants_bytes,
                 );
 
-                device.cmd_bind_vertex_buffers(
-                    command_buffer,
-                    0,
-                    &mesh.vertex_buffers()?,
-                    &mesh.vertex_offsets()?,
-                );
+                let mut vertex_buffers = mesh.vertex_buffers()?;
+                let mut vertex_offsets = mesh.vertex_offsets()?;
+                if let Some((_, _, [joints_offset, weights_offset])) = skin {
+                    // the joints and weights live in the same buffer as the vertices
+                    let buffer = vertex_buffers[0];
+                    vertex_buffers.extend([buffer, buffer]);
+                    vertex_offsets.extend([joints_offset, weights_offset]);
+                }
+
+                device.cmd_bind_vertex_buffers(command_buffer, 0, &vertex_buffers, &vertex_offsets);
 
                 device.cmd_bind_index_buffer(
                     command_buffer,
                     mesh.index_buffer()?,
-                    mesh.index_offset()?,
+                    index_offset,
                     mesh.index_type()?,
                 );
 
-                device.cmd_draw_indexed(command_buffer, mesh.index_count()?, 1, 0, 0, 0);
+                device.cmd_draw_indexed(command_buffer, index_count, 1, 0, 0, 0);
             }
 
             device.cmd_end_render_pass(command_buffer);
@@ -852,3 +1298,106 @@ impl SceneRenderer {
         Ok(command_buffer)
     }
 }
+
+impl PushConstants {
+    fn new(model: Mat4, params: MaterialParams) -> Self {
+        let [r, g, b, a] = params.base_color;
+        let [er, eg, eb] = params.emissive;
+
+        // blending is not supported yet. blended materials are drawn opaque
+        let alpha_cutoff = match params.alpha_mode {
+            AlphaMode::Mask => params.alpha_cutoff,
+            AlphaMode::Opaque | AlphaMode::Blend => 0.0,
+        };
+
+        Self {
+            model,
+            base_color: Vec4(r, g, b, a),
+            emissive: Vec4(er, eg, eb, alpha_cutoff),
+            surface: Vec4(
+                params.metallic,
+                params.roughness,
+                params.normal_scale,
+                params.occlusion_strength,
+            ),
+            skin: [0; 4],
+        }
+    }
+}
+
+fn image_info(texture: &Texture) -> vk::DescriptorImageInfo {
+    vk::DescriptorImageInfo {
+        sampler: texture.sampler,
+        image_view: texture.view,
+        image_layout: vk::ImageLayout::SHADER_READ_ONLY_OPTIMAL,
+    }
+}
+
+fn alloc_fallback_texture(
+    transient_command_args: TransientCommandArgs,
+    physical_device_memory_properties: vk::PhysicalDeviceMemoryProperties,
+    physical_device_properties: vk::PhysicalDeviceProperties,
+    format: vk::Format,
+    pixel: [u8; 4],
+) -> RisResult<Texture> {
+    let device = transient_command_args.device.clone();
+    let staging = Buffer::alloc_staging(&device, pixel.len(), physical_device_memory_properties)?;
+
+    let texture = Texture::alloc(TextureCreateInfo {
+        transient_command_args,
+        staging: &staging,
+        physical_device_memory_properties,
+        physical_device_properties,
+        width: 1,
+        height: 1,
+        format,
+        filter: vk::Filter::NEAREST,
+        pixels: &pixel,
+    });
+
+    unsafe { staging.free(&device) };
+
+    texture
+}
+
+/// # Safety
+///
+/// The image infos must stay valid as long as the returned set is bound.
+unsafe fn alloc_material_descriptor_set(
+    device: &ash::Device,
+    descriptor_pool: vk::DescriptorPool,
+    descriptor_set_layout: vk::DescriptorSetLayout,
+    image_infos: &[vk::DescriptorImageInfo; MaterialTextures::SLOT_COUNT],
+) -> Result<vk::DescriptorSet, vk::Result> {
+    let descriptor_set_layouts = [descriptor_set_layout];
+    let descriptor_set_allocate_info = vk::DescriptorSetAllocateInfo {
+        s_type: vk::StructureType::DESCRIPTOR_SET_ALLOCATE_INFO,
+        p_next: std::ptr::null(),
+        descriptor_pool,
+        descriptor_set_count: descriptor_set_layouts.len() as u32,
+        p_set_layouts: descriptor_set_layouts.as_ptr(),
+    };
+
+    let descriptor_set = device
+        .allocate_descriptor_sets(&descriptor_set_allocate_info)?
+        .into_iter()
+        .next()
+        .ok_or(vk::Result::ERROR_UNKNOWN)?;
+
+    let write_descriptor_sets = [vk::WriteDescriptorSet {
+        s_type: vk::StructureType::WRITE_DESCRIPTOR_SET,
+        p_next: std::ptr::null(),
+        dst_set: descriptor_set,
+        dst_binding: 0,
+        dst_array_element: 0,
+        descriptor_count: image_infos.len() as u32,
+        descriptor_type: vk::DescriptorType::COMBINED_IMAGE_SAMPLER,
+        p_image_info: image_infos.as_ptr(),
+        p_buffer_info: std::ptr::null(),
+        p_texel_buffer_view: std::ptr::null(),
+    }];
+
+    device.update_descriptor_sets(&write_descriptor_sets, &[]);
+
+    Ok(descriptor_set)
+}
@@ -1,4 +1,7 @@
-use sdl2::{event::Event, keyboard::Scancode};
+use sdl2::{
+    event::Event,
+    keyboard::{Mod, Scancode},
+};
 
 use ris_data::input::keyboard_data::KeyboardData;
 
@@ -40,6 +43,15 @@ pub fn post_events(
     keyboard_data.mod_state = mod_state;
 }
 
+/// Whether `scancode` was pressed this frame, while either control key is held.
+pub fn ctrl_shortcut_is_down(keyboard_data: &KeyboardData, scancode: Scancode) -> bool {
+    let ctrl_is_hold = keyboard_data
+        .mod_state
+        .intersects(Mod::LCTRLMOD | Mod::RCTRLMOD);
+
+    ctrl_is_hold && keyboard_data.keys.is_down(scancode)
+}
+
 // sdl2::keyboard::KeyboardState::scancodes() is broken due to
 // an invalid internal transmute. thus we implement a custom
 // iterator ourselves
@@ -1,6 +1,7 @@
 pub mod fallback_file;
 pub mod io;
 pub mod io_math;
+pub mod mapped_file;
 pub mod path;
 pub mod util;
 
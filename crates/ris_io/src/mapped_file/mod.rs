@@ -0,0 +1,52 @@
+use std::fs::File;
+use std::ops::Deref;
+use std::path::Path;
+
+use ris_error::RisResult;
+
+cfg_if::cfg_if! {
+    if #[cfg(target_os = "windows")] {
+        mod windows;
+        use windows as os;
+    } else if #[cfg(unix)] {
+        mod unix;
+        use unix as os;
+    } else {
+        mod unknown;
+        use unknown as os;
+    }
+}
+
+/// A read only file, that is mapped into memory. Slices of it can be read concurrently, without
+/// copying them first. On platforms that don't support mapping, the whole file is read instead.
+///
+/// The file must not be modified while it is mapped.
+pub struct MappedFile {
+    mapping: os::Mapping,
+}
+
+impl MappedFile {
+    pub fn open(path: impl AsRef<Path>) -> RisResult<Self> {
+        let file = File::open(path)?;
+        let len = file.metadata()?.len();
+        let len = usize::try_from(len)
+            .map_err(|_| ris_error::new!("file is too large to be mapped: {} bytes", len))?;
+
+        let mapping = os::Mapping::new(&file, len)?;
+        Ok(Self { mapping })
+    }
+}
+
+impl Deref for MappedFile {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        self.mapping.as_slice()
+    }
+}
+
+impl AsRef<[u8]> for MappedFile {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
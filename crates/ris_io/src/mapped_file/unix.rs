@@ -0,0 +1,65 @@
+extern crate libc;
+
+use std::fs::File;
+use std::io::Error;
+use std::io::Result;
+use std::os::unix::io::AsRawFd;
+
+pub struct Mapping {
+    ptr: *mut libc::c_void,
+    len: usize,
+}
+
+// the mapping is read only, thus it may be shared between threads
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        let result = unsafe { libc::munmap(self.ptr, self.len) };
+        if result != 0 {
+            ris_log::error!("munmap failed: {}", Error::last_os_error());
+        }
+    }
+}
+
+impl Mapping {
+    pub fn new(file: &File, len: usize) -> Result<Self> {
+        // mapping 0 bytes is an error
+        if len == 0 {
+            return Ok(Self {
+                ptr: std::ptr::null_mut(),
+                len,
+            });
+        }
+
+        let ptr = unsafe {
+            libc::mmap(
+                std::ptr::null_mut(),
+                len,
+                libc::PROT_READ,
+                libc::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        };
+
+        if ptr == libc::MAP_FAILED {
+            return Err(Error::last_os_error());
+        }
+
+        Ok(Self { ptr, len })
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
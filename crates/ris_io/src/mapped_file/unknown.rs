@@ -0,0 +1,21 @@
+use std::fs::File;
+use std::io::Read;
+use std::io::Result;
+
+/// The current os is not supported, thus the whole file is read into memory.
+pub struct Mapping {
+    bytes: Vec<u8>,
+}
+
+impl Mapping {
+    pub fn new(file: &File, len: usize) -> Result<Self> {
+        let mut bytes = Vec::with_capacity(len);
+        let mut file = file;
+        file.read_to_end(&mut bytes)?;
+        Ok(Self { bytes })
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+}
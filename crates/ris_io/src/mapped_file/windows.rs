@@ -0,0 +1,99 @@
+use std::ffi::c_void;
+use std::fs::File;
+use std::io::Error;
+use std::io::Result;
+use std::os::windows::io::AsRawHandle;
+
+const PAGE_READONLY: u32 = 0x02;
+const FILE_MAP_READ: u32 = 0x04;
+
+extern "system" {
+    fn CreateFileMappingW(
+        file: *mut c_void,
+        attributes: *mut c_void,
+        protect: u32,
+        maximum_size_high: u32,
+        maximum_size_low: u32,
+        name: *const u16,
+    ) -> *mut c_void;
+    fn MapViewOfFile(
+        mapping: *mut c_void,
+        desired_access: u32,
+        offset_high: u32,
+        offset_low: u32,
+        bytes_to_map: usize,
+    ) -> *mut c_void;
+    fn UnmapViewOfFile(base_address: *const c_void) -> i32;
+    fn CloseHandle(handle: *mut c_void) -> i32;
+}
+
+pub struct Mapping {
+    handle: *mut c_void,
+    ptr: *mut c_void,
+    len: usize,
+}
+
+// the mapping is read only, thus it may be shared between threads
+unsafe impl Send for Mapping {}
+unsafe impl Sync for Mapping {}
+
+impl Drop for Mapping {
+    fn drop(&mut self) {
+        if self.len == 0 {
+            return;
+        }
+
+        if unsafe { UnmapViewOfFile(self.ptr) } == 0 {
+            ris_log::error!("UnmapViewOfFile failed: {}", Error::last_os_error());
+        }
+
+        if unsafe { CloseHandle(self.handle) } == 0 {
+            ris_log::error!("CloseHandle failed: {}", Error::last_os_error());
+        }
+    }
+}
+
+impl Mapping {
+    pub fn new(file: &File, len: usize) -> Result<Self> {
+        // mapping 0 bytes is an error
+        if len == 0 {
+            return Ok(Self {
+                handle: std::ptr::null_mut(),
+                ptr: std::ptr::null_mut(),
+                len,
+            });
+        }
+
+        let handle = unsafe {
+            CreateFileMappingW(
+                file.as_raw_handle(),
+                std::ptr::null_mut(),
+                PAGE_READONLY,
+                0,
+                0,
+                std::ptr::null(),
+            )
+        };
+
+        if handle.is_null() {
+            return Err(Error::last_os_error());
+        }
+
+        let ptr = unsafe { MapViewOfFile(handle, FILE_MAP_READ, 0, 0, len) };
+        if ptr.is_null() {
+            let error = Error::last_os_error();
+            unsafe { CloseHandle(handle) };
+            return Err(error);
+        }
+
+        Ok(Self { handle, ptr, len })
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        if self.len == 0 {
+            return &[];
+        }
+
+        unsafe { std::slice::from_raw_parts(self.ptr as *const u8, self.len) }
+    }
+}
@@ -70,4 +70,18 @@ impl Camera {
 
         mat
     }
+
+    /// The fraction of the height of the screen, which a sphere covers. 1 means that the sphere
+    /// fills the height of the screen. Returns infinity if the camera is inside the sphere.
+    pub fn screen_coverage(&self, center: Vec3, radius: f32) -> f32 {
+        let distance_squared = (center - self.position).length_squared();
+        let radius_squared = radius * radius;
+        if distance_squared <= radius_squared {
+            return f32::INFINITY;
+        }
+
+        // tangent of the angle between the center and the silhouette of the sphere
+        let tan_angle = radius / f32::sqrt(distance_squared - radius_squared);
+        tan_angle / f32::tan(self.fovy * 0.5)
+    }
 }
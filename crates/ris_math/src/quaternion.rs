@@ -221,6 +221,44 @@ impl Quat {
         q_.length()
     }
 
+    pub fn dot(self, other: Self) -> f32 {
+        Vec4::from(self).dot(Vec4::from(other))
+    }
+
+    /// spherical linear interpolation along the shortest path. `a` = 0 returns `self`, `a` = 1
+    /// returns `other`
+    pub fn slerp(self, other: Self, a: f32) -> Self {
+        let mut other = other;
+        let mut cos_theta = self.dot(other);
+
+        // q and -q describe the same rotation. take the shorter way
+        if cos_theta < 0.0 {
+            other = Quat(-other.0, -other.1, -other.2, -other.3);
+            cos_theta = -cos_theta;
+        }
+
+        // the quaternions are nearly parallel, fall back to a normalized lerp to avoid
+        // dividing by sin(theta) close to 0
+        let (s0, s1) = if cos_theta > 1.0 - 1e-6 {
+            (1.0 - a, a)
+        } else {
+            let theta = f32::acos(cos_theta);
+            let sin_theta = f32::sin(theta);
+            (
+                f32::sin((1.0 - a) * theta) / sin_theta,
+                f32::sin(a * theta) / sin_theta,
+            )
+        };
+
+        Quat(
+            s0 * self.0 + s1 * other.0,
+            s0 * self.1 + s1 * other.1,
+            s0 * self.2 + s1 * other.2,
+            s0 * self.3 + s1 * other.3,
+        )
+        .normalize()
+    }
+
     // 3d functions
     pub fn rotate(self, p: Vec3) -> Vec3 {
         let r = self;
@@ -1,8 +1,11 @@
 use std::path::Path;
+use std::sync::Arc;
 
 use ris_asset::asset_compiler;
 use ris_asset::asset_compiler::CompileOptions;
 use ris_asset::asset_importer;
+use ris_asset::asset_loader_compiled;
+use ris_asset::mesh_processing::mesh_processor;
 use ris_core::log_appenders::console_appender::ConsoleAppender;
 use ris_error::Extensions;
 use ris_error::RisResult;
@@ -17,9 +20,11 @@ const LOG_LEVEL: LogLevel = LogLevel::Trace;
 
 pub const COMPILE: &str = "compile";
 pub const DECOMPILE: &str = "decompile";
+pub const PATCH: &str = "patch";
 pub const CLEAN: &str = "clean";
 pub const IMPORT: &str = "import";
 pub const REIMPORT: &str = "reimport";
+pub const PROCESS_MESHES: &str = "process_meshes";
 
 pub struct Asset;
 
@@ -29,7 +34,7 @@ impl ICommand for Asset {
     }
 
     fn args(&self) -> String {
-        "<command>".to_string()
+        format!("<command> | {} [<old>] [<new>] [<target>]", PATCH)
     }
 
     fn explanation(&self, level: ExplanationLevel) -> String {
@@ -48,14 +53,21 @@ impl ICommand for Asset {
                 explanation.push_str(&format!("{}\n", DECOMPILE));
                 explanation.push_str("Decompiles the ris_assets file.\n");
                 explanation.push('\n');
+                explanation.push_str(&format!("{}\n", PATCH));
+                explanation.push_str("Compiles a patch, which contains only the assets that changed or were added from <old> to <new>. Both may be directories or compiled files. Patches next to a compiled file are applied before diffing. <old> defaults to the ris_assets file, <new> to the assets in use and <target> to the next patch of <old>, like `ris_assets.patch1`. The engine applies these patches in order when it loads the ris_assets file.\n");
+                explanation.push('\n');
                 explanation.push_str(&format!("{}\n", CLEAN));
                 explanation.push_str("Cleans the imported assets.\n");
                 explanation.push('\n');
                 explanation.push_str(&format!("{}\n", IMPORT));
-                explanation.push_str("Recursively imports ALL source files. Then, it copies imported files, which are marked by corresponding meta files, to the assets in use.\n");
+                explanation.push_str("Recursively imports all source files that changed since the last import and deletes the imported files of removed source files. Then, it copies imported files, which are marked by corresponding meta files, to the assets in use.\n");
                 explanation.push('\n');
                 explanation.push_str(&format!("{}\n", REIMPORT));
-                explanation.push_str("Runs clean and then import.");
+                explanation
+                    .push_str("Runs clean and then import. This imports ALL source files.\n");
+                explanation.push('\n');
+                explanation.push_str(&format!("{}\n", PROCESS_MESHES));
+                explanation.push_str("Processes all meshes in use, as configured by their sidecar meta files, and overwrites them. This generates the levels of detail of meshes, which have no source file. Imported meshes are processed during import already.");
                 explanation
             }
         }
@@ -71,11 +83,13 @@ impl ICommand for Asset {
         let console_appender = Box::new(ConsoleAppender);
         let appenders: Vec<Box<dyn IAppender + Send>> = vec![console_appender];
         let _log_guard = ris_log::log::init(LOG_LEVEL, appenders);
+        let _thread_pool_guard = util::init_thread_pool()?;
 
         match command.as_str() {
             COMPILE => {
                 let compile_options = CompileOptions {
                     include_original_paths: false,
+                    registry: Some(Arc::new(crate::scripts::registry()?)),
                 };
                 asset_compiler::compile(
                     asset_compiler::DEFAULT_ASSET_DIRECTORY,
@@ -87,6 +101,38 @@ impl ICommand for Asset {
                 asset_compiler::DEFAULT_COMPILED_FILE,
                 asset_compiler::DEFAULT_DECOMPILED_DIRECTORY,
             ),
+            PATCH => {
+                let old = args
+                    .get(4)
+                    .map(|x| x.as_str())
+                    .unwrap_or(asset_compiler::DEFAULT_COMPILED_FILE);
+                let new = args
+                    .get(5)
+                    .map(|x| x.as_str())
+                    .unwrap_or(asset_compiler::DEFAULT_ASSET_DIRECTORY);
+                let target = match args.get(6) {
+                    Some(target) => target.clone(),
+                    None => {
+                        let old_path = Path::new(old);
+                        if !old_path.is_file() {
+                            return util::command_error(
+                                "<target> is required, when <old> is not a compiled file",
+                                self,
+                            );
+                        }
+
+                        let patch_count = asset_loader_compiled::find_patches(old_path).len();
+                        let target = asset_loader_compiled::patch_path(old_path, patch_count + 1);
+                        target.to_str().into_ris_error()?.to_string()
+                    }
+                };
+
+                let compile_options = CompileOptions {
+                    include_original_paths: false,
+                    registry: Some(Arc::new(crate::scripts::registry()?)),
+                };
+                asset_compiler::compile_patch(old, new, &target, compile_options)
+            }
             CLEAN => asset_importer::clean(asset_importer::DEFAULT_IMPORT_DIRECTORY),
             IMPORT => asset_importer::import_all(
                 asset_importer::DEFAULT_SOURCE_DIRECTORY,
@@ -103,6 +149,12 @@ impl ICommand for Asset {
                     None,
                 )
             }
+            PROCESS_MESHES => {
+                let changed =
+                    mesh_processor::process_directory(asset_importer::DEFAULT_IN_USE_DIRECTORY)?;
+                ris_log::info!("processed {} meshes", changed.len());
+                Ok(())
+            }
             _ => ris_error::new_result!("unkown arg: {}", command),
         }
     }
@@ -1,6 +1,7 @@
 use std::io::Read;
 use std::io::Write;
 use std::path::Path;
+use std::sync::Arc;
 
 use ris_error::RisResult;
 
@@ -178,22 +179,27 @@ impl ICommand for Build {
             file.write_all(new_content)?;
         }
 
-        eprintln!("importing assets...");
-        ris_asset::asset_importer::import_all(
-            ris_asset::asset_importer::DEFAULT_SOURCE_DIRECTORY,
-            ris_asset::asset_importer::DEFAULT_IMPORT_DIRECTORY,
-            ris_asset::asset_importer::DEFAULT_IN_USE_DIRECTORY,
-            None,
-        )?;
-        eprintln!("compiling assets...");
-        let compile_options = ris_asset::asset_compiler::CompileOptions {
-            include_original_paths: !is_release,
-        };
-        ris_asset::asset_compiler::compile(
-            ris_asset::asset_compiler::DEFAULT_ASSET_DIRECTORY,
-            ris_asset::asset_compiler::DEFAULT_COMPILED_FILE,
-            compile_options,
-        )?;
+        {
+            let _thread_pool_guard = util::init_thread_pool()?;
+
+            eprintln!("importing assets...");
+            ris_asset::asset_importer::import_all(
+                ris_asset::asset_importer::DEFAULT_SOURCE_DIRECTORY,
+                ris_asset::asset_importer::DEFAULT_IMPORT_DIRECTORY,
+                ris_asset::asset_importer::DEFAULT_IN_USE_DIRECTORY,
+                None,
+            )?;
+            eprintln!("compiling assets...");
+            let compile_options = ris_asset::asset_compiler::CompileOptions {
+                include_original_paths: !is_release,
+                registry: Some(Arc::new(crate::scripts::registry()?)),
+            };
+            ris_asset::asset_compiler::compile(
+                ris_asset::asset_compiler::DEFAULT_ASSET_DIRECTORY,
+                ris_asset::asset_compiler::DEFAULT_COMPILED_FILE,
+                compile_options,
+            )?;
+        }
 
         eprintln!("compiling workspace...");
 
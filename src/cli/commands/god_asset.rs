@@ -101,6 +101,7 @@ impl ICommand for GodAsset {
                 let mut_ref = match field.trim().to_lowercase().as_str() {
                     "default_vert_spv" => &mut god_asset.default_vert_spv,
                     "default_frag_spv" => &mut god_asset.default_frag_spv,
+                    "default_skinned_vert_spv" => &mut god_asset.default_skinned_vert_spv,
                     "terrain_vert_spv" => &mut god_asset.terrain_vert_spv,
                     "terrain_frag_spv" => &mut god_asset.terrain_frag_spv,
                     "imgui_vert_spv" => &mut god_asset.imgui_vert_spv,
@@ -142,6 +143,10 @@ fn print_god_asset(god_asset: &RisGodAsset) {
     println!("RisGodAsset {{");
     println!("    default_vert_spv: {:?},", god_asset.default_vert_spv);
     println!("    default_frag_spv: {:?},", god_asset.default_frag_spv);
+    println!(
+        "    default_skinned_vert_spv: {:?},",
+        god_asset.default_skinned_vert_spv
+    );
     println!("    terrain_vert_spv: {:?},", god_asset.terrain_vert_spv);
     println!("    terrain_frag_spv: {:?},", god_asset.terrain_frag_spv);
     println!("    imgui_vert_spv: {:?},", god_asset.imgui_vert_spv);
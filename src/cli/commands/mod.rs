@@ -4,6 +4,7 @@ pub mod doc;
 pub mod god_asset;
 pub mod pipeline;
 pub mod profiler_html;
+pub mod scene;
 
 use std::path::Path;
 
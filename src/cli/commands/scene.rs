@@ -0,0 +1,118 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use ris_asset::scene_diff;
+use ris_asset::scene_diff::MergeOutcome;
+use ris_error::Extensions;
+use ris_error::RisResult;
+
+use super::ExplanationLevel;
+use super::ICommand;
+use super::util;
+
+pub const DIFF: &str = "diff";
+pub const MERGE: &str = "merge";
+
+pub struct Scene;
+
+impl ICommand for Scene {
+    fn name(&self) -> String {
+        "scene".to_string()
+    }
+
+    fn args(&self) -> String {
+        format!(
+            "{} <old> <new> | {} <base> <ours> <theirs> <output>",
+            DIFF, MERGE
+        )
+    }
+
+    fn explanation(&self, level: ExplanationLevel) -> String {
+        match level {
+            ExplanationLevel::Short => String::from("Diff or merge ris_scene files."),
+            ExplanationLevel::Detailed => {
+                let mut explanation = String::new();
+                explanation.push_str(&self.explanation(ExplanationLevel::Short));
+                explanation.push('\n');
+                explanation.push('\n');
+                explanation.push_str("commands:\n");
+                explanation.push('\n');
+                explanation.push_str(&format!("{}\n", DIFF));
+                explanation.push_str("Prints the game objects that were added, removed or moved, and the properties and components that changed. Game objects are identified by their path in the hierarchy.\n");
+                explanation.push('\n');
+                explanation.push_str(&format!("{}\n", MERGE));
+                explanation.push_str("Three-way merges <ours> and <theirs>, which both derive from <base>, and writes the result to <output>. When both sides changed the same thing, the conflicts are printed and nothing is written.\n");
+                explanation.push('\n');
+                explanation.push_str("to use it as git merge driver, add `*.ris_scene merge=ris_scene` to .gitattributes and run:\n");
+                explanation.push_str(&format!(
+                    "git config merge.ris_scene.driver \"{} cli scene merge %O %A %B %A\"",
+                    env!("CARGO_PKG_NAME"),
+                ));
+                explanation
+            }
+        }
+    }
+
+    fn run(&self, args: Vec<String>, _target_dir: &Path) -> RisResult<()> {
+        if args.len() < 4 {
+            return util::command_error("no args provided", self);
+        }
+
+        let command = args.get(3).into_ris_error()?.to_lowercase();
+        let registry = Arc::new(crate::scripts::registry()?);
+
+        match command.as_str() {
+            DIFF => {
+                if args.len() < 6 {
+                    return util::command_error("diff requires <old> and <new>", self);
+                }
+
+                let old = read(&args[4])?;
+                let new = read(&args[5])?;
+
+                let changes = scene_diff::diff(registry, &old, &new)?;
+                for change in changes.iter() {
+                    println!("{}", change);
+                }
+
+                eprintln!("{} changes", changes.len());
+                Ok(())
+            }
+            MERGE => {
+                if args.len() < 8 {
+                    return util::command_error(
+                        "merge requires <base>, <ours>, <theirs> and <output>",
+                        self,
+                    );
+                }
+
+                let base = read(&args[4])?;
+                let ours = read(&args[5])?;
+                let theirs = read(&args[6])?;
+                let output = &args[7];
+
+                match scene_diff::merge(registry, &base, &ours, &theirs)? {
+                    MergeOutcome::Merged(bytes) => {
+                        eprintln!("writing... \"{}\"", output);
+                        std::fs::write(output, bytes)?;
+                        Ok(())
+                    }
+                    MergeOutcome::Conflicted(conflicts) => {
+                        for conflict in conflicts.iter() {
+                            println!("{}", conflict);
+                        }
+
+                        ris_error::new_result!("merge has {} conflicts", conflicts.len())
+                    }
+                }
+            }
+            _ => ris_error::new_result!("unkown arg: {}", command),
+        }
+    }
+}
+
+fn read(path: &str) -> RisResult<Vec<u8>> {
+    eprintln!("reading... \"{}\"", path);
+    let bytes = std::fs::read(path)?;
+    Ok(bytes)
+}
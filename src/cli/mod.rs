@@ -43,6 +43,7 @@ pub fn run<T: AsRef<str>>(args: impl IntoIterator<Item = T>) -> RisResult<()> {
         Box::new(commands::god_asset::GodAsset),
         Box::new(commands::pipeline::Pipeline),
         Box::new(commands::profiler_html::ProfilerHtml),
+        Box::new(commands::scene::Scene),
     ];
 
     // check if no arguments provided
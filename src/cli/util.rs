@@ -1,6 +1,9 @@
 use std::path::Path;
 use std::path::PathBuf;
 
+use ris_async::ThreadPool;
+use ris_async::ThreadPoolCreateInfo;
+use ris_async::ThreadPoolGuard;
 use ris_error::Extensions;
 use ris_error::RisResult;
 
@@ -38,3 +41,20 @@ pub fn get_root_dir() -> RisResult<PathBuf> {
 
     Ok(root_dir)
 }
+
+/// Makes the calling thread a worker of a new thread pool, such that assets can be imported and
+/// compiled in parallel.
+pub fn init_thread_pool() -> RisResult<ThreadPoolGuard> {
+    let cpu_count = std::thread::available_parallelism()
+        .map(|x| x.get())
+        .unwrap_or(1);
+    let create_info = ThreadPoolCreateInfo {
+        buffer_capacity: ris_async::DEFAULT_BUFFER_CAPACITY,
+        cpu_count,
+        threads: cpu_count,
+        set_affinity: false,
+        use_parking: true,
+    };
+
+    ThreadPool::init(create_info)
+}
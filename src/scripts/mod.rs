@@ -8,9 +8,10 @@ use ris_error::RisResult;
 use ris_math::vector::Vec3;
 
 pub fn registry() -> RisResult<Registry> {
-    Registry::new(vec![
-        Registry::script::<test_rotation::TestRotationScript>()?
-    ])
+    Registry::new(
+        Vec::new(),
+        vec![Registry::script::<test_rotation::TestRotationScript>()?],
+    )
 }
 
 pub fn setup_flycam(god_object: &GodObject) -> RisResult<()> {
@@ -1,5 +1,6 @@
 use std::f32::consts::PI;
 
+use ris_data::ecs::decl::GameObjectHandle;
 use ris_data::ecs::script_prelude::*;
 use ris_math::quaternion::Quat;
 use ris_math::vector::Vec3;
@@ -45,6 +46,13 @@ impl Script for TestRotationScript {
         Ok(())
     }
 
+    fn access(&self, game_object: GameObjectHandle) -> ScriptAccess {
+        ScriptAccess {
+            writes: vec![ScriptResource::GameObject(game_object)],
+            ..Default::default()
+        }
+    }
+
     fn inspect(&mut self, data: ScriptInspectData) -> RisResult<()> {
         let ScriptInspectData { id, .. } = data;
 
@@ -59,8 +67,8 @@ impl Script for TestRotationScript {
     }
 
     fn serialize(&mut self, f: &mut SceneWriter) -> RisResult<()> {
-        ris_io::write_vec3(f, self.rotation_axis)?;
-        ris_io::write_f32(f, self.rotation_speed)?;
+        f.write_field("rotation_axis", self.rotation_axis)?;
+        f.write_field("rotation_speed", self.rotation_speed)?;
         Ok(())
     }
 
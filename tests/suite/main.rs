@@ -1,5 +1,6 @@
 pub mod ris_asset;
 pub mod ris_async;
+pub mod ris_core;
 pub mod ris_data;
 pub mod ris_debug;
 pub mod ris_input;
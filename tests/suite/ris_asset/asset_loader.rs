@@ -0,0 +1,191 @@
+use std::io::Cursor;
+use std::io::Read;
+use std::io::Seek;
+use std::io::SeekFrom;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::sync::Mutex;
+
+use ris_asset::LoadPriority;
+use ris_asset::asset_archive;
+use ris_asset::asset_archive::ArchiveHeader;
+use ris_asset::asset_archive::Codec;
+use ris_asset::asset_compiler;
+use ris_asset::asset_compiler::CompileOptions;
+use ris_asset::asset_loader;
+use ris_asset::asset_loader_compiled::AssetLoaderCompiled;
+use ris_asset::assets::ris_god_asset;
+use ris_asset_data::asset_id::AssetId;
+use ris_async::ThreadPool;
+use ris_async::ThreadPoolCreateInfo;
+use ris_rng::rng::Rng;
+use ris_rng::rng::Seed;
+
+#[test]
+fn should_load_by_priority_and_skip_cancelled_requests() {
+    let dir = ris_util::prep_test_dir!();
+    std::fs::write(dir.join(ris_god_asset::PATH), b"god asset").unwrap();
+    for name in ["low", "normal", "high", "cancelled"] {
+        std::fs::write(dir.join(name), name).unwrap();
+    }
+
+    // a single worker runs the queued jobs only when the test waits
+    let _thread_pool_guard = ThreadPool::init(ThreadPoolCreateInfo {
+        buffer_capacity: 16,
+        cpu_count: 1,
+        threads: 1,
+        set_affinity: false,
+        use_parking: false,
+    })
+    .unwrap();
+    let asset_loader_guard = asset_loader::init_from_path(&dir).unwrap();
+    assert_eq!(
+        asset_loader_guard.god_asset_id,
+        AssetId::Path(ris_god_asset::PATH.to_string()),
+    );
+
+    let order = Arc::new(Mutex::new(Vec::new()));
+    let load = |name: &str, priority: LoadPriority| {
+        let order = order.clone();
+        let id = AssetId::Path(name.to_string());
+        ris_asset::load_async_with_priority(id, priority, move |bytes| {
+            let name = String::from_utf8(bytes).unwrap();
+            order.lock().unwrap().push(name.clone());
+            Ok(name)
+        })
+    };
+
+    let low = load("low", LoadPriority::Low);
+    let normal = load("normal", LoadPriority::Normal);
+    let cancelled = load("cancelled", LoadPriority::High);
+    let high = load("high", LoadPriority::High);
+    drop(cancelled);
+
+    assert_eq!(low.wait().unwrap(), "low");
+    assert_eq!(normal.wait().unwrap(), "normal");
+    assert_eq!(high.wait().unwrap(), "high");
+    assert_eq!(*order.lock().unwrap(), ["high", "normal", "low"]);
+
+    let missing = load("missing", LoadPriority::Normal);
+    assert!(missing.wait().is_err());
+
+    drop(asset_loader_guard);
+    let not_initialized = load("low", LoadPriority::Normal);
+    assert!(not_initialized.wait().is_err());
+}
+
+fn compile(dir: &Path, files: &[(&str, &[u8])]) -> PathBuf {
+    let source = dir.join("source");
+    for (path, bytes) in files {
+        let path = source.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    let target = dir.join("ris_assets");
+    let options = CompileOptions {
+        include_original_paths: true,
+        registry: None,
+    };
+    asset_compiler::compile(source.to_str().unwrap(), target.to_str().unwrap(), options).unwrap();
+
+    target
+}
+
+#[test]
+fn should_slice_and_stream_compiled_assets() {
+    // noise does not compress, thus it is stored as is
+    let mut rng = Rng::new(Seed::new());
+    let large = (0..100_000)
+        .map(|_| rng.next_u32() as u8)
+        .collect::<Vec<_>>();
+    let repeated = vec![42u8; 100_000];
+    let path = compile(
+        &ris_util::prep_test_dir!(),
+        &[
+            ("a_god_asset", b"the god asset....."),
+            ("b_large", &large),
+            ("c_repeated", &repeated),
+        ],
+    );
+
+    let compiled = std::fs::read(&path).unwrap();
+    assert!(compiled.len() < 2 * 100_000, "{}", compiled.len());
+
+    let loader = AssetLoaderCompiled::new(&path).unwrap();
+    assert_eq!(&*loader.slice(0).unwrap(), b"the god asset.....");
+    assert_eq!(loader.load(1).unwrap(), large);
+    assert_eq!(&*loader.slice(2).unwrap(), &repeated[..]);
+    assert_eq!(loader.load(2).unwrap(), repeated);
+    assert!(loader.slice(3).is_err());
+
+    // slices can be read from multiple threads at once
+    let handles = (0..4)
+        .map(|_| {
+            let slice = loader.slice(1).unwrap();
+            std::thread::spawn(move || slice.iter().map(|&x| x as u64).sum::<u64>())
+        })
+        .collect::<Vec<_>>();
+    let expected = large.iter().map(|&x| x as u64).sum::<u64>();
+    for handle in handles {
+        assert_eq!(handle.join().unwrap(), expected);
+    }
+
+    let mut stream = loader.stream(1).unwrap();
+    let mut chunk = [0; 1000];
+    stream.seek(SeekFrom::Start(50_000)).unwrap();
+    stream.read_exact(&mut chunk).unwrap();
+    assert_eq!(chunk, large[50_000..51_000]);
+    let mut rest = Vec::new();
+    stream.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, large[51_000..]);
+}
+
+#[test]
+fn should_report_corrupt_compiled_assets() {
+    let path = compile(
+        &ris_util::prep_test_dir!(),
+        &[
+            ("a_god_asset", b"the god asset....."),
+            ("b_textures/raw", b"raw bytes, that are not compressed"),
+            ("c_repeated", &[7u8; 1000]),
+        ],
+    );
+    let compiled = std::fs::read(&path).unwrap();
+    let header = ArchiveHeader::read(&mut Cursor::new(&compiled)).unwrap();
+    assert_eq!(header.entries[1].codec, Codec::None);
+    assert_eq!(header.entries[2].codec, Codec::Deflate);
+
+    for (id, name) in [(1, "b_textures/raw"), (2, "c_repeated")] {
+        let mut corrupt = compiled.clone();
+        corrupt[header.entries[id].p_content.addr as usize] ^= 0xFF;
+        std::fs::write(&path, corrupt).unwrap();
+
+        let loader = AssetLoaderCompiled::new(&path).unwrap();
+        assert_eq!(&*loader.slice(0).unwrap(), b"the god asset.....");
+        let error = loader.slice(id).err().unwrap().to_string();
+        assert!(error.contains(name), "{}", error);
+        assert!(error.contains("corrupt"), "{}", error);
+        assert!(loader.load(id).is_err());
+        assert!(loader.stream(id).is_err());
+    }
+
+    // the last asset is said to be there, but the file ends before it
+    let p_last = header.entries[2].p_content;
+    std::fs::write(&path, &compiled[..(p_last.end() - 1) as usize]).unwrap();
+    assert!(AssetLoaderCompiled::new(&path).is_err());
+
+    // archives of an other version must be recompiled
+    let mut other_version = compiled.clone();
+    other_version[16] = 0;
+    std::fs::write(&path, other_version).unwrap();
+    let error = AssetLoaderCompiled::new(&path).err().unwrap().to_string();
+    assert!(error.contains("version"), "{}", error);
+}
+
+#[test]
+fn should_compute_crc32() {
+    assert_eq!(asset_archive::crc32(b""), 0);
+    assert_eq!(asset_archive::crc32(b"123456789"), 0xCBF43926);
+}
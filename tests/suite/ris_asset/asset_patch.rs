@@ -0,0 +1,216 @@
+use std::io::Cursor;
+use std::path::Path;
+
+use ris_asset::asset_archive::ArchiveHeader;
+use ris_asset::asset_compiler;
+use ris_asset::asset_compiler::CompileOptions;
+use ris_asset::asset_loader_compiled;
+use ris_asset::asset_loader_compiled::AssetLoaderCompiled;
+
+fn write_dir(dir: &Path, files: &[(&str, &str)]) {
+    for (path, content) in files {
+        let path = dir.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+}
+
+fn options() -> CompileOptions {
+    CompileOptions {
+        include_original_paths: true,
+        registry: None,
+    }
+}
+
+fn path_str(path: &Path) -> &str {
+    path.to_str().unwrap()
+}
+
+fn entry_ids(path: &Path) -> (usize, Vec<usize>) {
+    let bytes = std::fs::read(path).unwrap();
+    let header = ArchiveHeader::read(&mut Cursor::new(bytes)).unwrap();
+    let ids = header.entries.iter().map(|x| x.id).collect();
+    (header.asset_count, ids)
+}
+
+fn assert_loads(loader: &AssetLoaderCompiled, expected: &[(&str, &str)]) {
+    assert_eq!(loader.asset_count(), expected.len());
+    for (id, (name, content)) in expected.iter().enumerate() {
+        assert_eq!(loader.load(id).unwrap(), content.as_bytes(), "{}", name);
+        assert_eq!(loader.original_name(id), Some(*name));
+    }
+}
+
+#[test]
+fn should_apply_patches_in_order() {
+    let dir = ris_util::prep_test_dir!();
+    let v1 = [
+        ("a.txt", "first version of a"),
+        ("b.txt", "first version of b"),
+        ("c.txt", "first version of c"),
+    ];
+    let v2 = [
+        ("a.txt", "second version of a"),
+        ("b.txt", "first version of b"),
+        ("c.txt", "first version of c"),
+        ("d.txt", "first version of d"),
+    ];
+    let v3 = [
+        ("a.txt", "second version of a"),
+        ("b.txt", "third version of b"),
+        ("c.txt", "first version of c"),
+    ];
+    write_dir(&dir.join("v1"), &v1);
+    write_dir(&dir.join("v2"), &v2);
+    write_dir(&dir.join("v3"), &v3);
+
+    let base = dir.join("ris_assets");
+    asset_compiler::compile(path_str(&dir.join("v1")), path_str(&base), options()).unwrap();
+    assert!(asset_loader_compiled::find_patches(&base).is_empty());
+
+    // diff two directories
+    let patch1 = asset_loader_compiled::patch_path(&base, 1);
+    assert_eq!(patch1, dir.join("ris_assets.patch1"));
+    asset_compiler::compile_patch(
+        path_str(&dir.join("v1")),
+        path_str(&dir.join("v2")),
+        path_str(&patch1),
+        options(),
+    )
+    .unwrap();
+    assert_eq!(entry_ids(&patch1), (4, vec![0, 3]));
+
+    // diff the patched file against a directory
+    let patch2 = asset_loader_compiled::patch_path(&base, 2);
+    asset_compiler::compile_patch(
+        path_str(&base),
+        path_str(&dir.join("v3")),
+        path_str(&patch2),
+        options(),
+    )
+    .unwrap();
+    assert_eq!(entry_ids(&patch2), (3, vec![1]));
+
+    let patches = asset_loader_compiled::find_patches(&base);
+    assert_eq!(patches, [patch1.clone(), patch2]);
+
+    let loader = AssetLoaderCompiled::new(&base).unwrap();
+    assert_loads(&loader, &v1);
+
+    let loader = AssetLoaderCompiled::with_patches(&base, &patches[..1]).unwrap();
+    assert_loads(&loader, &v2);
+
+    let loader = AssetLoaderCompiled::with_patches(&base, &patches).unwrap();
+    assert_loads(&loader, &v3);
+    assert!(loader.load(3).is_err());
+
+    // no temporary files are left behind
+    let mut entries = std::fs::read_dir(&dir)
+        .unwrap()
+        .map(|x| x.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    entries.sort();
+    assert_eq!(
+        entries,
+        [
+            "ris_assets",
+            "ris_assets.patch1",
+            "ris_assets.patch2",
+            "v1",
+            "v2",
+            "v3"
+        ],
+    );
+}
+
+#[test]
+fn should_diff_compiled_files() {
+    let dir = ris_util::prep_test_dir!();
+    let old = [
+        ("a.txt", "first version of a"),
+        ("b.txt", "first version of b"),
+    ];
+    let new = [
+        ("a.txt", "first version of a"),
+        ("b.txt", "second version of b"),
+    ];
+    write_dir(&dir.join("old"), &old);
+    write_dir(&dir.join("new"), &new);
+
+    let old_compiled = dir.join("old_compiled");
+    let new_compiled = dir.join("new_compiled");
+    let patch = dir.join("patch");
+    asset_compiler::compile(
+        path_str(&dir.join("old")),
+        path_str(&old_compiled),
+        options(),
+    )
+    .unwrap();
+    asset_compiler::compile(
+        path_str(&dir.join("new")),
+        path_str(&new_compiled),
+        options(),
+    )
+    .unwrap();
+    asset_compiler::compile_patch(
+        path_str(&old_compiled),
+        path_str(&new_compiled),
+        path_str(&patch),
+        options(),
+    )
+    .unwrap();
+    assert_eq!(entry_ids(&patch), (2, vec![1]));
+
+    let loader =
+        AssetLoaderCompiled::with_patches(&old_compiled, std::slice::from_ref(&patch)).unwrap();
+    assert_loads(&loader, &new);
+    drop(loader);
+
+    // the same files don't need a patch
+    asset_compiler::compile_patch(
+        path_str(&new_compiled),
+        path_str(&dir.join("new")),
+        path_str(&patch),
+        options(),
+    )
+    .unwrap();
+    assert_eq!(entry_ids(&patch), (2, vec![]));
+}
+
+#[test]
+fn should_keep_the_ids_of_unchanged_assets() {
+    let dir = ris_util::prep_test_dir!();
+    let v1 = [
+        ("b.txt", "first version of b"),
+        ("c.txt", "first version of c"),
+        ("d.txt", "first version of d"),
+    ];
+    let v2 = [
+        ("a.txt", "first version of a"),
+        ("b.txt", "first version of b"),
+        ("d.txt", "second version of d"),
+    ];
+    write_dir(&dir.join("v1"), &v1);
+    write_dir(&dir.join("v2"), &v2);
+
+    let base = dir.join("ris_assets");
+    asset_compiler::compile(path_str(&dir.join("v1")), path_str(&base), options()).unwrap();
+
+    // a.txt sorts before the others, but is appended. c.txt is removed, but d.txt keeps its id
+    let patch = asset_loader_compiled::patch_path(&base, 1);
+    asset_compiler::compile_patch(
+        path_str(&base),
+        path_str(&dir.join("v2")),
+        path_str(&patch),
+        options(),
+    )
+    .unwrap();
+    assert_eq!(entry_ids(&patch), (4, vec![2, 3]));
+
+    let loader = AssetLoaderCompiled::with_patches(&base, std::slice::from_ref(&patch)).unwrap();
+    assert_eq!(loader.original_name(0), Some("b.txt"));
+    assert_eq!(loader.original_name(2), Some("d.txt"));
+    assert_eq!(loader.load(2).unwrap(), b"second version of d");
+    assert_eq!(loader.original_name(3), Some("a.txt"));
+    assert_eq!(loader.load(3).unwrap(), b"first version of a");
+}
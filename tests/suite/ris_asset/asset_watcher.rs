@@ -0,0 +1,102 @@
+use std::path::Path;
+use std::time::Duration;
+use std::time::SystemTime;
+
+use ris_asset::asset_watcher;
+use ris_asset::asset_watcher::AssetWatcher;
+use ris_asset_data::asset_id::AssetId;
+
+fn write(path: impl AsRef<Path>, bytes: &[u8]) {
+    let path = path.as_ref();
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, bytes).unwrap();
+}
+
+fn ids(values: &[&str]) -> Vec<AssetId> {
+    values
+        .iter()
+        .map(|x| AssetId::Path(x.to_string()))
+        .collect()
+}
+
+#[test]
+fn should_report_changed_files_once_they_are_stable() {
+    let dir = ris_util::prep_test_dir!();
+    write(dir.join("shaders/default.vert.spv"), b"vert");
+    write(dir.join("textures/debug.qoi"), b"qoi");
+
+    let mut watcher = AssetWatcher::new(&dir).unwrap();
+    assert!(watcher.poll().unwrap().is_empty());
+
+    write(dir.join("shaders/default.vert.spv"), b"changed vert");
+    write(dir.join("meshes/new.ris_mesh"), b"mesh");
+
+    // the first poll only sees that something is being written
+    assert!(watcher.poll().unwrap().is_empty());
+    assert_eq!(
+        watcher.poll().unwrap(),
+        ids(&["meshes/new.ris_mesh", "shaders/default.vert.spv"]),
+    );
+    assert!(watcher.poll().unwrap().is_empty());
+
+    std::fs::remove_file(dir.join("textures/debug.qoi")).unwrap();
+    assert!(watcher.poll().unwrap().is_empty());
+    assert!(watcher.poll().unwrap().is_empty());
+}
+
+#[test]
+fn should_not_report_files_with_the_same_content() {
+    let dir = ris_util::prep_test_dir!();
+    let path = dir.join("shaders/default.frag.spv");
+    write(&path, b"frag");
+
+    let mut watcher = AssetWatcher::new(&dir).unwrap();
+
+    // copying a file, like the importer does, touches it without changing its content
+    write(&path, b"frag");
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(60))
+        .unwrap();
+
+    assert!(watcher.poll().unwrap().is_empty());
+    assert!(watcher.poll().unwrap().is_empty());
+
+    write(&path, b"FRAG");
+    let file = std::fs::File::options().write(true).open(&path).unwrap();
+    file.set_modified(SystemTime::now() + Duration::from_secs(120))
+        .unwrap();
+
+    assert!(watcher.poll().unwrap().is_empty());
+    assert_eq!(watcher.poll().unwrap(), ids(&["shaders/default.frag.spv"]));
+}
+
+#[test]
+fn should_notify_subscriptions() {
+    let [vert, frag, mesh] = [
+        "asset_watcher_test/notify.vert.spv",
+        "asset_watcher_test/notify.frag.spv",
+        "asset_watcher_test/notify.ris_mesh",
+    ]
+    .map(|x| AssetId::Path(x.to_string()));
+
+    let shaders = asset_watcher::subscribe(vec![vert.clone(), frag.clone()]);
+    let everything = asset_watcher::subscribe_all();
+    let dropped = asset_watcher::subscribe(vec![mesh.clone()]);
+    drop(dropped);
+
+    asset_watcher::notify(std::slice::from_ref(&mesh));
+    assert!(!shaders.has_changed());
+
+    asset_watcher::notify(&[frag.clone(), vert.clone()]);
+    asset_watcher::notify(std::slice::from_ref(&frag));
+    assert!(shaders.has_changed());
+    assert!(shaders.has_changed());
+    assert_eq!(shaders.take_changed(), vec![frag.clone(), vert.clone()]);
+    assert!(!shaders.has_changed());
+
+    // other tests may notify as well, because subscriptions are global
+    let changed = everything.take_changed();
+    for id in [&vert, &frag, &mesh] {
+        assert!(changed.contains(id), "{:?}", id);
+    }
+}
@@ -0,0 +1,368 @@
+use std::f32::consts::PI;
+use std::path::Path;
+use std::sync::Arc;
+
+use ris_asset::asset_importer;
+use ris_asset::assets::ris_animation;
+use ris_asset::assets::ris_mesh;
+use ris_asset::assets::ris_prefab;
+use ris_asset::assets::ris_prefab::PrefabOverrides;
+use ris_asset::assets::ris_skeleton;
+use ris_asset_data::AssetId;
+use ris_asset_data::animation::Interpolation;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_data::ecs::components::animator_component::AnimatorComponent;
+use ris_data::ecs::components::material_component::MaterialComponent;
+use ris_data::ecs::components::mesh_component::MeshComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::game_object::GetFrom;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+use ris_math::vector::Vec4;
+use ris_util::assert_vec3_feq;
+
+pub fn write_glb(path: impl AsRef<Path>, json: &str, bin: &[u8]) {
+    let mut json = json.as_bytes().to_vec();
+    while !json.len().is_multiple_of(4) {
+        json.push(b' ');
+    }
+    let mut bin = bin.to_vec();
+    while !bin.len().is_multiple_of(4) {
+        bin.push(0);
+    }
+
+    let length = 12 + 8 + json.len() + 8 + bin.len();
+    let mut bytes = Vec::with_capacity(length);
+    bytes.extend_from_slice(&0x46546C67u32.to_le_bytes());
+    bytes.extend_from_slice(&2u32.to_le_bytes());
+    bytes.extend_from_slice(&(length as u32).to_le_bytes());
+    bytes.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&0x4E4F534Au32.to_le_bytes());
+    bytes.extend_from_slice(&json);
+    bytes.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    bytes.extend_from_slice(&0x004E4942u32.to_le_bytes());
+    bytes.extend_from_slice(&bin);
+
+    let path = path.as_ref();
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(path, bytes).unwrap();
+}
+
+/// A single triangle, with positions, normals, uvs and u16 indices.
+fn triangle_bin() -> Vec<u8> {
+    let positions = [0.0f32, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 1.0, 0.0];
+    let normals = [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0];
+    let uvs = [0.0f32, 0.0, 1.0, 0.0, 0.0, 1.0];
+    let indices = [0u16, 1, 2];
+
+    let mut bin = Vec::new();
+    for value in positions.iter().chain(normals.iter()).chain(uvs.iter()) {
+        bin.extend_from_slice(&value.to_le_bytes());
+    }
+    for value in indices {
+        bin.extend_from_slice(&value.to_le_bytes());
+    }
+    bin
+}
+
+const TRIANGLE_JSON: &str = r#"
+    "buffers": [{ "byteLength": 102 }],
+    "bufferViews": [
+        { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+        { "buffer": 0, "byteOffset": 36, "byteLength": 36 },
+        { "buffer": 0, "byteOffset": 72, "byteLength": 24 },
+        { "buffer": 0, "byteOffset": 96, "byteLength": 6 }
+    ],
+    "accessors": [
+        { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+        { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3" },
+        { "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC2" },
+        { "bufferView": 3, "componentType": 5123, "count": 3, "type": "SCALAR" }
+    ],
+    "materials": [{ "name": "skin" }],
+"#;
+
+fn child(scene: &Scene, game_object: GameObjectHandle, index: usize) -> GameObjectHandle {
+    game_object.children(scene).unwrap()[index]
+}
+
+fn mesh_asset_id(scene: &Scene, game_object: GameObjectHandle) -> Option<AssetId> {
+    let handle = game_object
+        .get_component::<MeshComponent>(scene, GetFrom::This)
+        .unwrap()?;
+    let ptr = scene.deref(handle).unwrap();
+    let aref = ptr.borrow();
+    aref.asset_id()
+}
+
+fn material_asset_id(scene: &Scene, game_object: GameObjectHandle) -> Option<AssetId> {
+    let handle = game_object
+        .get_component::<MaterialComponent>(scene, GetFrom::This)
+        .unwrap()?;
+    let ptr = scene.deref(handle).unwrap();
+    let aref = ptr.borrow();
+    aref.asset_id()
+}
+
+#[test]
+fn should_import_node_hierarchy_as_prefab() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+
+    // body (trs)
+    // |- arm (matrix, mesh with one primitive)
+    // |- legs (mesh with two primitives)
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            {}
+            "meshes": [
+                {{ "name": "arm", "primitives": [
+                    {{ "attributes": {{ "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 }}, "indices": 3, "material": 0 }}
+                ] }},
+                {{ "name": "legs", "primitives": [
+                    {{ "attributes": {{ "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 }}, "indices": 3 }},
+                    {{ "attributes": {{ "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2 }}, "indices": 3, "material": 0 }}
+                ] }}
+            ],
+            "nodes": [
+                {{ "name": "body", "children": [1, 2], "translation": [1.0, 2.0, 3.0], "rotation": [0.0, 0.70710677, 0.0, 0.70710677] }},
+                {{ "name": "arm", "mesh": 0, "matrix": [2.0, 0.0, 0.0, 0.0, 0.0, 3.0, 0.0, 0.0, 0.0, 0.0, 4.0, 0.0, 0.0, 0.0, 5.0, 1.0] }},
+                {{ "mesh": 1 }}
+            ],
+            "scenes": [{{ "name": "main", "nodes": [0] }}],
+            "scene": 0
+        }}"#,
+        TRIANGLE_JSON,
+    );
+    write_glb(
+        source.join("models").join("robot.glb"),
+        &json,
+        &triangle_bin(),
+    );
+
+    asset_importer::import_changed(source.to_str().unwrap(), imported.to_str().unwrap(), None)
+        .unwrap();
+    let bytes = std::fs::read(imported.join("models").join("robot-main-000.ris_prefab"));
+
+    let registry = Registry::new(Vec::new(), Vec::new()).unwrap();
+    let scene = Scene::new(SceneCreateInfo {
+        registry: Some(Arc::new(registry)),
+        ..SceneCreateInfo::empty()
+    })
+    .unwrap();
+    let root =
+        ris_prefab::instantiate(&scene, &bytes.unwrap(), PrefabOverrides::default()).unwrap();
+
+    assert_eq!(root.name(&scene).unwrap(), "main");
+    let body = child(&scene, root, 0);
+    let arm = child(&scene, body, 0);
+    let legs = child(&scene, body, 1);
+    assert_eq!(body.name(&scene).unwrap(), "body");
+    assert_eq!(arm.name(&scene).unwrap(), "arm");
+    assert_eq!(legs.name(&scene).unwrap(), "node-002");
+    assert_eq!(legs.children(&scene).unwrap().len(), 2);
+
+    // gltf is y up, ris_engine is z up
+    let correction = Quat::angle_axis(0.5 * PI, Vec3::right());
+    assert_vec3_feq!(body.position(&scene).unwrap(), Vec3(1.0, -3.0, 2.0));
+    assert_vec3_feq!(
+        body.rotation(&scene).unwrap().rotate(Vec3::forward()),
+        correction.rotate(Quat::angle_axis(0.5 * PI, Vec3::up()).rotate(Vec3::forward())),
+    );
+    assert_vec3_feq!(arm.position(&scene).unwrap(), Vec3(0.0, -5.0, 0.0));
+    assert_vec3_feq!(arm.scale(&scene).unwrap(), Vec3(2.0, 4.0, 3.0));
+
+    let path = |x: &str| Some(AssetId::Path(x.to_string()));
+    assert_eq!(
+        mesh_asset_id(&scene, arm),
+        path("models/robot-arm-000-000.ris_mesh")
+    );
+    assert_eq!(
+        material_asset_id(&scene, arm),
+        path("models/robot-skin-000.ris_material")
+    );
+    assert_eq!(mesh_asset_id(&scene, legs), None);
+
+    let primitive_0 = child(&scene, legs, 0);
+    let primitive_1 = child(&scene, legs, 1);
+    assert_eq!(primitive_0.name(&scene).unwrap(), "primitive-000");
+    assert_eq!(
+        mesh_asset_id(&scene, primitive_0),
+        path("models/robot-legs-001-000.ris_mesh")
+    );
+    assert_eq!(material_asset_id(&scene, primitive_0), None);
+    assert_eq!(
+        mesh_asset_id(&scene, primitive_1),
+        path("models/robot-legs-001-001.ris_mesh")
+    );
+    assert_eq!(
+        material_asset_id(&scene, primitive_1),
+        path("models/robot-skin-000.ris_material")
+    );
+}
+
+#[test]
+fn should_import_skin_and_animation() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+
+    let mut bin = triangle_bin();
+    bin.resize(104, 0);
+    // joints, u8
+    for _ in 0..3 {
+        bin.extend_from_slice(&[0, 1, 0, 0]);
+    }
+    // weights, not normalized
+    for _ in 0..3 {
+        for value in [1.0f32, 3.0, 0.0, 0.0] {
+            bin.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    // inverse bind matrices. the knee is 1 unit above the hip
+    let identity = [
+        1.0f32, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 1.0,
+    ];
+    let mut knee = identity;
+    knee[13] = -1.0;
+    for value in identity.iter().chain(knee.iter()) {
+        bin.extend_from_slice(&value.to_le_bytes());
+    }
+    // times and translations of the knee
+    for value in [0.0f32, 1.0, 0.0, 1.0, 0.0, 0.0, 2.0, 0.0] {
+        bin.extend_from_slice(&value.to_le_bytes());
+    }
+    assert_eq!(bin.len(), 324);
+
+    // armature
+    // |- hip (joint 0)
+    //    |- knee (joint 1)
+    // body (skinned mesh)
+    let json = r#"{
+        "asset": { "version": "2.0" },
+        "buffers": [{ "byteLength": 324 }],
+        "bufferViews": [
+            { "buffer": 0, "byteOffset": 0, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 36, "byteLength": 36 },
+            { "buffer": 0, "byteOffset": 72, "byteLength": 24 },
+            { "buffer": 0, "byteOffset": 96, "byteLength": 6 },
+            { "buffer": 0, "byteOffset": 104, "byteLength": 12 },
+            { "buffer": 0, "byteOffset": 116, "byteLength": 48 },
+            { "buffer": 0, "byteOffset": 164, "byteLength": 128 },
+            { "buffer": 0, "byteOffset": 292, "byteLength": 8 },
+            { "buffer": 0, "byteOffset": 300, "byteLength": 24 }
+        ],
+        "accessors": [
+            { "bufferView": 0, "componentType": 5126, "count": 3, "type": "VEC3", "min": [0.0, 0.0, 0.0], "max": [1.0, 1.0, 0.0] },
+            { "bufferView": 1, "componentType": 5126, "count": 3, "type": "VEC3" },
+            { "bufferView": 2, "componentType": 5126, "count": 3, "type": "VEC2" },
+            { "bufferView": 3, "componentType": 5123, "count": 3, "type": "SCALAR" },
+            { "bufferView": 4, "componentType": 5121, "count": 3, "type": "VEC4" },
+            { "bufferView": 5, "componentType": 5126, "count": 3, "type": "VEC4" },
+            { "bufferView": 6, "componentType": 5126, "count": 2, "type": "MAT4" },
+            { "bufferView": 7, "componentType": 5126, "count": 2, "type": "SCALAR", "min": [0.0], "max": [1.0] },
+            { "bufferView": 8, "componentType": 5126, "count": 2, "type": "VEC3" }
+        ],
+        "meshes": [
+            { "primitives": [
+                { "attributes": { "POSITION": 0, "NORMAL": 1, "TEXCOORD_0": 2, "JOINTS_0": 4, "WEIGHTS_0": 5 }, "indices": 3 }
+            ] }
+        ],
+        "skins": [{ "name": "rig", "inverseBindMatrices": 6, "joints": [1, 2] }],
+        "animations": [{
+            "name": "walk",
+            "channels": [
+                { "sampler": 0, "target": { "node": 2, "path": "translation" } },
+                { "sampler": 0, "target": { "node": 0, "path": "translation" } }
+            ],
+            "samplers": [{ "input": 7, "output": 8 }]
+        }],
+        "nodes": [
+            { "name": "armature", "children": [1] },
+            { "name": "hip", "children": [2] },
+            { "name": "knee", "translation": [0.0, 1.0, 0.0] },
+            { "name": "body", "mesh": 0, "skin": 0 }
+        ],
+        "scenes": [{ "name": "main", "nodes": [0, 3] }],
+        "scene": 0
+    }"#;
+    write_glb(source.join("models").join("robot.glb"), json, &bin);
+
+    asset_importer::import_changed(source.to_str().unwrap(), imported.to_str().unwrap(), None)
+        .unwrap();
+    let models = imported.join("models");
+    let mesh_bytes = std::fs::read(models.join("robot-none-000-000.ris_mesh"));
+    let skeleton_bytes = std::fs::read(models.join("robot-rig-000.ris_skeleton"));
+    let animation_bytes = std::fs::read(models.join("robot-walk-000.ris_animation"));
+    let prefab_bytes = std::fs::read(models.join("robot-main-000.ris_prefab"));
+
+    // mesh
+    let cpu_mesh = ris_mesh::deserialize(&mesh_bytes.unwrap()).unwrap();
+    let mesh = MeshPrototype::try_from(cpu_mesh).unwrap();
+    assert_eq!(mesh.joints, vec![[0, 1, 0, 0]; 3]);
+    assert_eq!(mesh.weights, vec![[0.25, 0.75, 0.0, 0.0]; 3]);
+
+    // skeleton. the armature is not a joint, thus the hip is a root
+    let skeleton = ris_skeleton::deserialize(&skeleton_bytes.unwrap()).unwrap();
+    assert_eq!(skeleton.joints.len(), 2);
+    assert_eq!(skeleton.joints[0].name, "hip");
+    assert_eq!(skeleton.joints[0].parent, None);
+    assert_eq!(skeleton.joints[1].name, "knee");
+    assert_eq!(skeleton.joints[1].parent, Some(0));
+    assert_vec3_feq!(skeleton.joints[1].rest.translation, Vec3(0.0, 0.0, 1.0));
+
+    // in the rest pose, the vertices stay where they are
+    let matrices = skeleton.rest_pose().joint_matrices(&skeleton).unwrap();
+    for matrix in matrices {
+        let moved = matrix * Vec4(1.0, 2.0, 3.0, 1.0);
+        assert_vec3_feq!(Vec3(moved.0, moved.1, moved.2), Vec3(1.0, 2.0, 3.0));
+    }
+
+    // animation. the channel of the armature is dropped
+    let (clip, skeleton_id) = ris_animation::deserialize(&animation_bytes.unwrap()).unwrap();
+    assert_eq!(
+        skeleton_id,
+        AssetId::Path("models/robot-rig-000.ris_skeleton".to_string())
+    );
+    assert_eq!(clip.name, "walk");
+    assert_eq!(clip.duration, 1.0);
+    assert_eq!(clip.channels.len(), 1);
+    let channel = &clip.channels[0];
+    assert_eq!(channel.joint, 1);
+    assert_eq!(channel.interpolation, Interpolation::Linear);
+    let sampled = channel.sample(0.5);
+    assert_vec3_feq!(
+        Vec3(sampled[0], sampled[1], sampled[2]),
+        Vec3(0.0, 0.0, 1.5),
+    );
+
+    // prefab
+    let registry = Registry::new(Vec::new(), Vec::new()).unwrap();
+    let scene = Scene::new(SceneCreateInfo {
+        registry: Some(Arc::new(registry)),
+        ..SceneCreateInfo::empty()
+    })
+    .unwrap();
+    let root = ris_prefab::instantiate(&scene, &prefab_bytes.unwrap(), PrefabOverrides::default())
+        .unwrap();
+    let body = child(&scene, root, 1);
+    assert_eq!(body.name(&scene).unwrap(), "body");
+
+    let handle = body
+        .get_component::<AnimatorComponent>(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap();
+    let ptr = scene.deref(handle).unwrap();
+    let animator = ptr.borrow();
+    assert_eq!(animator.skeleton(), Some(skeleton_id));
+    assert_eq!(animator.clips().len(), 1);
+    assert_eq!(
+        animator.clips()[0].asset_id,
+        AssetId::Path("models/robot-walk-000.ris_animation".to_string())
+    );
+}
@@ -0,0 +1,127 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use ris_asset::asset_importer;
+use ris_asset::asset_importer::ImportReport;
+use ris_asset::assets::ris_mesh;
+use ris_asset::codecs::base64;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_math::vector::Vec3;
+use ris_util::assert_vec3_feq;
+
+fn f32_bytes(values: &[f32]) -> Vec<u8> {
+    values.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn import(source: &Path, imported: &Path) -> ImportReport {
+    asset_importer::import_changed(source.to_str().unwrap(), imported.to_str().unwrap(), None)
+        .unwrap()
+}
+
+#[test]
+fn should_encode_and_decode_base64() {
+    assert_eq!(base64::encode(b""), "");
+    assert_eq!(base64::encode(b"f"), "Zg==");
+    assert_eq!(base64::encode(b"fo"), "Zm8=");
+    assert_eq!(base64::encode(b"foobar"), "Zm9vYmFy");
+
+    assert_eq!(base64::decode("Zg==").unwrap(), b"f");
+    assert_eq!(base64::decode("Zm8").unwrap(), b"fo");
+    assert_eq!(base64::decode("Zm9vYmFy").unwrap(), b"foobar");
+    assert!(base64::decode("Zm9v!").is_err());
+    assert!(base64::decode("Z").is_err());
+
+    let bytes = (0..=255).collect::<Vec<u8>>();
+    assert_eq!(base64::decode(&base64::encode(&bytes)).unwrap(), bytes);
+}
+
+#[test]
+fn should_import_gltf_with_external_and_embedded_buffers() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+    std::fs::create_dir_all(source.join("models")).unwrap();
+
+    // external buffer: interleaved positions and uvs
+    let mut vertices = Vec::new();
+    for position in [[0.0, 0.0], [1.0, 0.0], [0.0, 1.0], [1.0, 1.0]] {
+        vertices.extend(f32_bytes(&[position[0], position[1], 0.0, 0.5, 0.25]));
+    }
+    std::fs::write(source.join("models").join("quad data.bin"), &vertices).unwrap();
+
+    // embedded buffer: u8 indices, sparse indices and sparse values, which move vertex 3
+    let mut embedded = vec![0u8, 1, 2, 3, 3, 0, 0, 0];
+    embedded.extend(f32_bytes(&[2.0, 2.0, 0.0]));
+    let data_uri = format!(
+        "data:application/octet-stream;base64,{}",
+        base64::encode(&embedded)
+    );
+
+    // a triangle strip without normals
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [
+                {{ "uri": "quad%20data.bin", "byteLength": 80 }},
+                {{ "uri": "{}", "byteLength": 20 }}
+            ],
+            "bufferViews": [
+                {{ "buffer": 0, "byteOffset": 0, "byteLength": 80, "byteStride": 20 }},
+                {{ "buffer": 1, "byteOffset": 0, "byteLength": 4 }},
+                {{ "buffer": 1, "byteOffset": 4, "byteLength": 1 }},
+                {{ "buffer": 1, "byteOffset": 8, "byteLength": 12 }}
+            ],
+            "accessors": [
+                {{ "bufferView": 0, "componentType": 5126, "count": 4, "type": "VEC3", "min": [0.0, 0.0, 0.0], "max": [2.0, 2.0, 0.0],
+                   "sparse": {{ "count": 1, "indices": {{ "bufferView": 2, "componentType": 5121 }}, "values": {{ "bufferView": 3 }} }} }},
+                {{ "bufferView": 0, "byteOffset": 12, "componentType": 5126, "count": 4, "type": "VEC2" }},
+                {{ "bufferView": 1, "componentType": 5121, "count": 4, "type": "SCALAR" }}
+            ],
+            "meshes": [
+                {{ "primitives": [
+                    {{ "attributes": {{ "POSITION": 0, "TEXCOORD_0": 1 }}, "indices": 2, "mode": 5 }},
+                    {{ "attributes": {{ "POSITION": 0 }}, "mode": 1 }}
+                ] }}
+            ]
+        }}"#,
+        data_uri,
+    );
+    std::fs::write(source.join("models").join("quad.gltf"), json).unwrap();
+
+    let first = import(&source, &imported);
+    let mesh_bytes = std::fs::read(imported.join("models").join("quad-none-000-000.ris_mesh"));
+    let lines_exist = imported
+        .join("models")
+        .join("quad-none-000-001.ris_mesh")
+        .exists();
+
+    // the import depends on the external buffer
+    let second = import(&source, &imported);
+    vertices[0] = 1;
+    std::fs::write(source.join("models").join("quad data.bin"), &vertices).unwrap();
+    let third = import(&source, &imported);
+
+    let gltf = PathBuf::from("models/quad.gltf");
+    assert!(first.imported.contains(&gltf));
+    assert!(!second.imported.contains(&gltf));
+    assert!(third.imported.contains(&gltf));
+    assert!(!lines_exist);
+
+    let cpu_mesh = ris_mesh::deserialize(&mesh_bytes.unwrap()).unwrap();
+    let mesh = MeshPrototype::try_from(cpu_mesh).unwrap();
+
+    // the strip is converted to a list, the u8 indices are widened to u16
+    let Indices::U16(indices) = &mesh.indices else {
+        panic!("expected u16 indices, but was {:?}", mesh.indices);
+    };
+    assert_eq!(indices, &[0, 1, 2, 1, 3, 2]);
+
+    // gltf is y up, ris_engine is z up
+    assert_vec3_feq!(mesh.vertices[1], Vec3(1.0, 0.0, 0.0));
+    assert_vec3_feq!(mesh.vertices[3], Vec3(2.0, 0.0, 2.0));
+    assert_eq!((mesh.uvs[2].0, mesh.uvs[2].1), (0.5, 0.25));
+    for &normal in mesh.normals.iter() {
+        assert_vec3_feq!(normal, Vec3(0.0, -1.0, 0.0));
+    }
+}
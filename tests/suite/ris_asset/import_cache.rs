@@ -0,0 +1,202 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use ris_asset::asset_importer;
+use ris_asset::import_cache;
+use ris_asset::import_cache::CacheEntry;
+use ris_asset::import_cache::ImportCache;
+use ris_asset::importer::glsl_to_spirv_importer;
+
+fn write_png(path: impl AsRef<Path>, color: [u8; 3]) {
+    let path = path.as_ref();
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let file = std::fs::File::create(path).unwrap();
+    let mut encoder = png::Encoder::new(file, 2, 2);
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    writer.write_image_data(&color.repeat(4)).unwrap();
+}
+
+fn import(dir: &Path) -> asset_importer::ImportReport {
+    asset_importer::import_changed(
+        dir.join("source").to_str().unwrap(),
+        dir.join("imported").to_str().unwrap(),
+        None,
+    )
+    .unwrap()
+}
+
+fn paths(values: &[&str]) -> Vec<PathBuf> {
+    values.iter().map(PathBuf::from).collect()
+}
+
+#[test]
+fn should_serialize_and_deserialize_cache() {
+    let mut cache = ImportCache::default();
+    cache.insert(
+        "shaders/lit.glsl",
+        CacheEntry {
+            hash: 0x0123456789abcdef,
+            importer: "glsl".to_string(),
+            outputs: paths(&["shaders/lit.vert.spv", "shaders/lit.frag.spv"]),
+            dependencies: vec![(PathBuf::from("shaders/util/light.glsl"), 42)],
+        },
+    );
+    cache.insert(
+        "textures/grass.png",
+        CacheEntry {
+            hash: 7,
+            importer: "png".to_string(),
+            outputs: paths(&["textures/grass.qoi"]),
+            dependencies: Vec::new(),
+        },
+    );
+
+    let text = cache.serialize().unwrap();
+    let deserialized = ImportCache::deserialize(&text).unwrap();
+
+    assert_eq!(deserialized, cache);
+    assert!(text.contains("source: shaders/lit.glsl\n"));
+    assert!(text.contains("dependency: 000000000000002a shaders/util/light.glsl\n"));
+}
+
+#[test]
+fn should_not_deserialize_invalid_cache() {
+    assert!(ImportCache::deserialize("hash: 0000000000000001").is_err());
+    assert!(ImportCache::deserialize("source: a.png\nhash: not a hash").is_err());
+    assert!(ImportCache::deserialize("source: a.png\nfoo: bar").is_err());
+}
+
+#[test]
+fn should_hash_content() {
+    assert_eq!(import_cache::hash(b""), 0xcbf29ce484222325);
+    assert_eq!(import_cache::hash(b"a"), 0xaf63dc4c8601ec8c);
+    assert_ne!(import_cache::hash(b"ab"), import_cache::hash(b"ba"));
+}
+
+#[test]
+fn should_only_import_changed_files() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+    write_png(source.join("textures").join("grass.png"), [0, 255, 0]);
+    write_png(source.join("textures").join("stone.png"), [128, 128, 128]);
+    std::fs::write(source.join("readme.txt"), "not an asset").unwrap();
+
+    let first = import(&dir);
+    assert_eq!(
+        first.imported,
+        paths(&["textures/grass.png", "textures/stone.png"])
+    );
+    assert!(first.skipped.is_empty());
+
+    let second = import(&dir);
+    assert!(second.imported.is_empty());
+    assert_eq!(second.skipped.len(), 2);
+
+    write_png(source.join("textures").join("grass.png"), [0, 200, 0]);
+    std::fs::remove_file(imported.join("textures").join("stone.qoi")).unwrap();
+    let third = import(&dir);
+    assert_eq!(
+        third.imported,
+        paths(&["textures/grass.png", "textures/stone.png"])
+    );
+
+    let cache = ImportCache::load(imported.join(import_cache::IMPORT_CACHE_FILE));
+    let entry = cache.get("textures/grass.png").unwrap().clone();
+
+    assert_eq!(entry.importer, "png");
+    assert_eq!(
+        entry.outputs,
+        paths(&["textures/grass.qoi", "textures/grass.ris_texture"])
+    );
+}
+
+#[test]
+fn should_find_nested_includes() {
+    let dir = ris_util::prep_test_dir!();
+    let shaders = dir.join("source").join("shaders");
+    std::fs::create_dir_all(shaders.join("util")).unwrap();
+    std::fs::write(
+        shaders.join("lit.glsl"),
+        "#ris_glsl 450 vertex fragment\n#include util/light.glsl\n#include util/math.glsl\n",
+    )
+    .unwrap();
+    std::fs::write(
+        shaders.join("util").join("light.glsl"),
+        "#ris_glsl header\n#include util/math.glsl\n",
+    )
+    .unwrap();
+    std::fs::write(
+        shaders.join("util").join("math.glsl"),
+        "#ris_glsl header\n#include util/light.glsl\n",
+    )
+    .unwrap();
+
+    let lit = glsl_to_spirv_importer::includes(shaders.join("lit.glsl")).unwrap();
+    let header = glsl_to_spirv_importer::includes(shaders.join("util").join("light.glsl")).unwrap();
+
+    assert_eq!(
+        lit,
+        vec![
+            shaders.join("util/light.glsl"),
+            shaders.join("util/math.glsl"),
+        ]
+    );
+    assert!(header.is_empty());
+}
+
+#[test]
+fn should_import_again_when_dependency_changes() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+    write_png(source.join("a.png"), [255, 0, 0]);
+    std::fs::write(source.join("palette.txt"), "red").unwrap();
+
+    import(&dir);
+
+    // png has no dependencies, add one by hand to test the cache
+    let cache_path = imported.join(import_cache::IMPORT_CACHE_FILE);
+    let mut cache = ImportCache::load(&cache_path);
+    let mut entry = cache.get("a.png").unwrap().clone();
+    let palette_hash = import_cache::hash(b"red");
+    entry
+        .dependencies
+        .push((PathBuf::from("palette.txt"), palette_hash));
+    cache.insert("a.png", entry);
+    cache.save(&cache_path).unwrap();
+
+    let unchanged = import(&dir);
+    std::fs::write(source.join("palette.txt"), "blue").unwrap();
+    let changed = import(&dir);
+
+    assert_eq!(unchanged.skipped, paths(&["a.png"]));
+    assert_eq!(changed.imported, paths(&["a.png"]));
+}
+
+#[test]
+fn should_remove_outputs_of_deleted_sources() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+    write_png(source.join("a.png"), [255, 0, 0]);
+    write_png(source.join("b.png"), [0, 0, 255]);
+
+    import(&dir);
+    assert!(imported.join("a.qoi").exists());
+
+    std::fs::remove_file(source.join("a.png")).unwrap();
+    let report = import(&dir);
+    let a_exists = imported.join("a.qoi").exists();
+    let b_exists = imported.join("b.qoi").exists();
+    let cache = ImportCache::load(imported.join(import_cache::IMPORT_CACHE_FILE));
+
+    assert_eq!(report.removed, paths(&["a.png"]));
+    assert_eq!(report.skipped, paths(&["b.png"]));
+    assert!(!a_exists);
+    assert!(b_exists);
+    assert_eq!(cache.len(), 1);
+    assert!(cache.get("a.png").is_none());
+}
@@ -0,0 +1,308 @@
+use std::collections::HashMap;
+
+use ris_asset::assets::ris_mesh;
+use ris_asset::mesh_processing::mesh_processor;
+use ris_asset::mesh_processing::mesh_processor::MeshProcessorSettings;
+use ris_asset::mesh_processing::simplify;
+use ris_asset_data::mesh;
+use ris_asset_data::mesh::CpuMesh;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_math::vector::Vec2;
+use ris_math::vector::Vec3;
+
+fn prototype(vertices: Vec<Vec3>, indices: Vec<u32>) -> MeshPrototype {
+    let vertex_count = vertices.len();
+    let mut mesh = MeshPrototype {
+        vertices,
+        normals: Vec::new(),
+        uvs: vec![Vec2::init(0.0); vertex_count],
+        indices: Indices::U32(indices),
+        joints: Vec::new(),
+        weights: Vec::new(),
+        tangents: Vec::new(),
+        lods: Vec::new(),
+    };
+    mesh.generate_normals();
+    mesh
+}
+
+/// A unit sphere, made by subdividing an octahedron. Every position has a single vertex.
+fn sphere(subdivisions: usize) -> MeshPrototype {
+    let mut vertices = vec![
+        Vec3::right(),
+        Vec3::left(),
+        Vec3::forward(),
+        Vec3::backward(),
+        Vec3::up(),
+        Vec3::down(),
+    ];
+    let mut triangles = vec![
+        [0, 2, 4],
+        [2, 1, 4],
+        [1, 3, 4],
+        [3, 0, 4],
+        [2, 0, 5],
+        [1, 2, 5],
+        [3, 1, 5],
+        [0, 3, 5],
+    ];
+
+    for _ in 0..subdivisions {
+        let mut midpoints = HashMap::new();
+        let mut midpoint = |a: u32, b: u32, vertices: &mut Vec<Vec3>| {
+            *midpoints
+                .entry((u32::min(a, b), u32::max(a, b)))
+                .or_insert_with(|| {
+                    let v = (vertices[a as usize] + vertices[b as usize]).normalize();
+                    vertices.push(v);
+                    vertices.len() as u32 - 1
+                })
+        };
+
+        let mut subdivided = Vec::with_capacity(triangles.len() * 4);
+        for [a, b, c] in triangles {
+            let ab = midpoint(a, b, &mut vertices);
+            let bc = midpoint(b, c, &mut vertices);
+            let ca = midpoint(c, a, &mut vertices);
+            subdivided.push([a, ab, ca]);
+            subdivided.push([ab, b, bc]);
+            subdivided.push([ca, bc, c]);
+            subdivided.push([ab, bc, ca]);
+        }
+        triangles = subdivided;
+    }
+
+    prototype(vertices, triangles.into_iter().flatten().collect())
+}
+
+/// A flat square grid of `size` by `size` quads in the xy plane, facing up.
+fn grid(size: u32) -> MeshPrototype {
+    let mut vertices = Vec::new();
+    for y in 0..=size {
+        for x in 0..=size {
+            vertices.push(Vec3(x as f32, y as f32, 0.0));
+        }
+    }
+
+    let row = size + 1;
+    let mut indices = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            let i = y * row + x;
+            indices.extend([i, i + 1, i + row + 1]);
+            indices.extend([i, i + row + 1, i + row]);
+        }
+    }
+
+    prototype(vertices, indices)
+}
+
+/// How many triangles use each edge, regardless of its direction.
+fn edge_counts(indices: &[u32]) -> HashMap<(u32, u32), usize> {
+    let mut counts = HashMap::new();
+    for triangle in indices.chunks_exact(3) {
+        for (a, b) in [
+            (triangle[0], triangle[1]),
+            (triangle[1], triangle[2]),
+            (triangle[2], triangle[0]),
+        ] {
+            *counts.entry((u32::min(a, b), u32::max(a, b))).or_default() += 1;
+        }
+    }
+    counts
+}
+
+#[test]
+fn should_simplify_closed_mesh() {
+    let sphere = sphere(4);
+    let indices = mesh_processor::triangle_list(&sphere).unwrap();
+    let target = indices.len() / 4;
+
+    let simplified = simplify::simplify(&sphere, &indices, target).unwrap();
+
+    assert!(simplified.len() <= target, "{}", simplified.len());
+    assert!(simplified.len() > target / 2, "{}", simplified.len());
+
+    // still closed and manifold
+    for (edge, count) in edge_counts(&simplified) {
+        assert_eq!(count, 2, "edge {:?}", edge);
+    }
+
+    // no triangle is flipped or degenerated
+    for triangle in simplified.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| sphere.vertices[triangle[i] as usize]);
+        let normal = (b - a).cross(c - a);
+        let center = (a + b + c) / 3.0;
+        assert!(normal.length() > 0.0);
+        assert!(normal.dot(center) > 0.0);
+        assert!(center.length() > 0.85, "{}", center.length());
+    }
+}
+
+#[test]
+fn should_keep_borders_when_simplifying() {
+    let grid = grid(16);
+    let indices = mesh_processor::triangle_list(&grid).unwrap();
+
+    let simplified = simplify::simplify(&grid, &indices, 0).unwrap();
+    assert!(simplified.len() < indices.len() / 8, "{}", simplified.len());
+
+    // the flat square is still covered exactly once
+    let mut area = 0.0;
+    for triangle in simplified.chunks_exact(3) {
+        let [a, b, c] = [0, 1, 2].map(|i| grid.vertices[triangle[i] as usize]);
+        let signed_area = 0.5 * (b - a).cross(c - a).z();
+        assert!(signed_area > 0.0);
+        area += signed_area;
+    }
+    assert!(f32::abs(area - 256.0) < 0.001, "{}", area);
+}
+
+#[test]
+fn should_collapse_seams_along_themselves() {
+    // the column at x = 4 is a uv seam. the vertices right of it get their own copies
+    let size = 8;
+    let row = size + 1;
+    let mut grid = grid(size);
+    let mut seam = Vec::new();
+    for y in 0..=size {
+        let left = (y * row + 4) as usize;
+        let right = grid.vertices.len() as u32;
+        grid.vertices.push(grid.vertices[left]);
+        grid.normals.push(grid.normals[left]);
+        grid.uvs.push(Vec2(1.0, 0.0));
+        seam.push((left as u32, right));
+    }
+
+    let Indices::U32(indices) = &mut grid.indices else {
+        panic!("expected u32 indices");
+    };
+    for triangle in indices.chunks_exact_mut(3) {
+        let is_right = triangle.iter().any(|&x| x % row > 4);
+        if !is_right {
+            continue;
+        }
+
+        for index in triangle.iter_mut() {
+            if let Some(&(_, right)) = seam.iter().find(|(left, _)| left == index) {
+                *index = right;
+            }
+        }
+    }
+    let indices = indices.clone();
+
+    let simplified = simplify::simplify(&grid, &indices, 0).unwrap();
+    assert!(simplified.len() < indices.len() / 4, "{}", simplified.len());
+
+    // the seam slides along itself, thus the sides keep their own vertices
+    let is_left = |i: u32| i < 81 && grid.vertices[i as usize].x() <= 4.0;
+    let is_right = |i: u32| i >= 81 || grid.vertices[i as usize].x() > 4.0;
+    let mut area = 0.0;
+    for triangle in simplified.chunks_exact(3) {
+        assert!(
+            triangle.iter().all(|&x| is_left(x)) || triangle.iter().all(|&x| is_right(x)),
+            "{:?}",
+            triangle,
+        );
+
+        let [a, b, c] = [0, 1, 2].map(|i| grid.vertices[triangle[i] as usize]);
+        let signed_area = 0.5 * (b - a).cross(c - a).z();
+        assert!(signed_area > 0.0);
+        area += signed_area;
+    }
+    assert!(f32::abs(area - 64.0) < 0.001, "{}", area);
+
+    // where the seam meets the border, it can't move
+    for &(left, right) in [seam[0], seam[size as usize]].iter() {
+        assert!(simplified.contains(&left), "{}", left);
+        assert!(simplified.contains(&right), "{}", right);
+    }
+}
+
+#[test]
+fn should_generate_lod_chain() {
+    let settings = MeshProcessorSettings {
+        lod_count: 3,
+        lod_ratio: 0.5,
+        ..Default::default()
+    };
+    let processed = mesh_processor::process(sphere(4), &settings).unwrap();
+
+    let Indices::U16(indices) = &processed.indices else {
+        panic!("expected u16 indices, but was {:?}", processed.indices);
+    };
+    assert_eq!(processed.lods.len(), 3);
+    let mut previous_count = indices.len();
+    for lod in processed.lods.iter() {
+        let Indices::U16(lod) = lod else {
+            panic!("expected u16 indices, but was {:?}", lod);
+        };
+        assert!(lod.len() <= previous_count / 2, "{}", lod.len());
+        assert!(
+            lod.iter()
+                .all(|&x| usize::from(x) < processed.vertices.len())
+        );
+        previous_count = lod.len();
+    }
+
+    let lod_counts = processed
+        .lods
+        .iter()
+        .map(|x| x.usize_iter().count())
+        .collect::<Vec<_>>();
+    let bytes = ris_mesh::serialize(&CpuMesh::try_from(processed).unwrap()).unwrap();
+    assert_eq!(bytes[..16], ris_mesh::LOD_MAGIC);
+
+    let deserialized = MeshPrototype::try_from(ris_mesh::deserialize(&bytes).unwrap()).unwrap();
+    let deserialized_counts = deserialized
+        .lods
+        .iter()
+        .map(|x| x.usize_iter().count())
+        .collect::<Vec<_>>();
+    assert_eq!(deserialized_counts, lod_counts);
+    assert_eq!(deserialized.tangents.len(), deserialized.vertices.len());
+}
+
+#[test]
+fn should_stop_lod_chain_when_mesh_cannot_be_simplified() {
+    let tetrahedron = prototype(
+        vec![
+            Vec3(1.0, 1.0, 1.0),
+            Vec3(1.0, -1.0, -1.0),
+            Vec3(-1.0, 1.0, -1.0),
+            Vec3(-1.0, -1.0, 1.0),
+        ],
+        vec![0, 1, 2, 0, 3, 1, 0, 2, 3, 1, 3, 2],
+    );
+
+    let processed = mesh_processor::process(tetrahedron, &Default::default()).unwrap();
+    assert!(processed.lods.is_empty());
+}
+
+#[test]
+fn should_deserialize_lod_settings() {
+    let settings = MeshProcessorSettings::deserialize("lod_count: 5\nlod_ratio: 0.25\n").unwrap();
+    assert_eq!(settings.lod_count, 5);
+    assert_eq!(settings.lod_ratio, 0.25);
+
+    assert!(MeshProcessorSettings::deserialize("lod_count: -1").is_err());
+    assert!(MeshProcessorSettings::deserialize("lod_ratio: 1").is_err());
+    assert!(MeshProcessorSettings::deserialize("lod_ratio: 0").is_err());
+}
+
+#[test]
+fn should_select_lod() {
+    let index_counts = [1200, 600, 300, 150];
+    let full = mesh::LOD_FULL_DETAIL_COVERAGE;
+
+    assert_eq!(mesh::select_lod(&index_counts, f32::INFINITY), 0);
+    assert_eq!(mesh::select_lod(&index_counts, full), 0);
+    assert_eq!(mesh::select_lod(&index_counts, full * 0.7), 1);
+    assert_eq!(mesh::select_lod(&index_counts, full * 0.5), 2);
+    assert_eq!(mesh::select_lod(&index_counts, full * 0.3), 3);
+    assert_eq!(mesh::select_lod(&index_counts, 0.0), 3);
+    assert_eq!(mesh::select_lod(&index_counts, f32::NAN), 0);
+    assert_eq!(mesh::select_lod(&index_counts[..1], 0.0), 0);
+    assert_eq!(mesh::select_lod(&[], 0.0), 0);
+}
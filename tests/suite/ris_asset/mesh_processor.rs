@@ -0,0 +1,425 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+
+use ris_asset::asset_importer;
+use ris_asset::assets::ris_mesh;
+use ris_asset::mesh_processing::mesh_processor;
+use ris_asset::mesh_processing::mesh_processor::MeshProcessorSettings;
+use ris_asset::mesh_processing::normals;
+use ris_asset::mesh_processing::tangents;
+use ris_asset::mesh_processing::vertex_cache;
+use ris_asset::mesh_processing::weld;
+use ris_asset_data::mesh::CpuMesh;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_math::vector::Vec2;
+use ris_math::vector::Vec3;
+use ris_math::vector::Vec4;
+use ris_util::assert_vec3_feq;
+
+fn mesh(vertices: Vec<Vec3>, uvs: Vec<Vec2>, indices: Vec<u32>) -> MeshPrototype {
+    let vertex_count = vertices.len();
+    let mut mesh = MeshPrototype {
+        vertices,
+        normals: Vec::new(),
+        uvs,
+        indices: Indices::U32(indices),
+        joints: Vec::new(),
+        weights: Vec::new(),
+        tangents: Vec::new(),
+        lods: Vec::new(),
+    };
+    mesh.generate_normals();
+    assert_eq!(mesh.normals.len(), vertex_count);
+    mesh
+}
+
+/// A unit cube, where every triangle has its own vertices.
+fn unshared_cube() -> MeshPrototype {
+    let corners = |i: usize| {
+        Vec3(
+            (i & 1) as f32 * 2.0 - 1.0,
+            ((i >> 1) & 1) as f32 * 2.0 - 1.0,
+            ((i >> 2) & 1) as f32 * 2.0 - 1.0,
+        )
+    };
+
+    // counter clockwise, seen from outside
+    let faces = [
+        [0, 2, 3, 1],
+        [4, 5, 7, 6],
+        [0, 1, 5, 4],
+        [2, 6, 7, 3],
+        [0, 4, 6, 2],
+        [1, 3, 7, 5],
+    ];
+
+    let mut vertices = Vec::new();
+    let mut uvs = Vec::new();
+    let face_uvs = [
+        Vec2(0.0, 0.0),
+        Vec2(1.0, 0.0),
+        Vec2(1.0, 1.0),
+        Vec2(0.0, 1.0),
+    ];
+    for face in faces.iter() {
+        for triangle in [[0, 1, 2], [0, 2, 3]] {
+            for corner in triangle {
+                vertices.push(corners(face[corner]));
+                uvs.push(face_uvs[corner]);
+            }
+        }
+    }
+
+    let indices = (0..vertices.len() as u32).collect();
+    mesh(vertices, uvs, indices)
+}
+
+/// The triangles by the positions of their corners, such that they can be compared
+/// independently of the order of the vertices.
+fn triangle_positions(mesh: &MeshPrototype) -> HashSet<Vec<[u32; 3]>> {
+    mesh.indices
+        .triangles()
+        .map(|(a, b, c)| {
+            [a, b, c]
+                .iter()
+                .map(|&i| {
+                    let v = mesh.vertices[i];
+                    [v.0.to_bits(), v.1.to_bits(), v.2.to_bits()]
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[test]
+fn should_deserialize_settings() {
+    let settings = MeshProcessorSettings::deserialize(
+        "# only some keys\n\
+        weld: false\n\
+        weld_epsilon: 0.01 # in meters\n",
+    )
+    .unwrap();
+
+    let expected = MeshProcessorSettings {
+        weld: false,
+        weld_epsilon: 0.01,
+        ..Default::default()
+    };
+    assert_eq!(settings, expected);
+
+    let text = settings.serialize().unwrap();
+    assert_eq!(MeshProcessorSettings::deserialize(text).unwrap(), settings);
+
+    assert!(MeshProcessorSettings::deserialize("weld: yes").is_err());
+    assert!(MeshProcessorSettings::deserialize("unknown: true").is_err());
+    assert!(MeshProcessorSettings::deserialize("weld_epsilon: -1").is_err());
+}
+
+#[test]
+fn should_weld_duplicate_vertices() {
+    let mut cube = unshared_cube();
+    assert_eq!(cube.vertices.len(), 36);
+    let before = triangle_positions(&cube);
+
+    // each face has its own normal, thus only vertices of the same face are welded
+    weld::weld(&mut cube, 0.0).unwrap();
+    assert_eq!(cube.vertices.len(), 24);
+    assert_eq!(triangle_positions(&cube), before);
+
+    // with the same normal and uv everywhere, only the corners remain
+    let mut cube = unshared_cube();
+    cube.normals = vec![Vec3::up(); 36];
+    cube.uvs = vec![Vec2::init(0.0); 36];
+    weld::weld(&mut cube, 0.0).unwrap();
+    assert_eq!(cube.vertices.len(), 8);
+}
+
+#[test]
+fn should_weld_within_epsilon() {
+    let vertices = vec![
+        Vec3(0.0, 0.0, 0.0),
+        Vec3(1.0, 0.0, 0.0),
+        Vec3(0.0, 1.0, 0.0),
+        Vec3(1.0001, 0.0, 0.0),
+        Vec3(1.0, 1.0, 0.0),
+        Vec3(0.0, 1.0, 0.0),
+        // not used by any triangle
+        Vec3(5.0, 5.0, 5.0),
+    ];
+    let uvs = vec![Vec2::init(0.0); vertices.len()];
+    let indices = vec![0, 1, 2, 3, 4, 5];
+
+    let mut exact = mesh(vertices.clone(), uvs.clone(), indices.clone());
+    exact.normals = vec![Vec3::up(); vertices.len()];
+    weld::weld(&mut exact, 0.0).unwrap();
+    assert_eq!(exact.vertices.len(), 5);
+
+    let mut welded = mesh(vertices.clone(), uvs, indices);
+    welded.normals = vec![Vec3::up(); vertices.len()];
+    weld::weld(&mut welded, 0.001).unwrap();
+    assert_eq!(welded.vertices.len(), 4);
+    let Indices::U32(indices) = &welded.indices else {
+        panic!("expected u32 indices");
+    };
+    assert_eq!(indices, &[0, 1, 2, 1, 3, 2]);
+}
+
+#[test]
+fn should_generate_smooth_normals() {
+    let mut cube = unshared_cube();
+    normals::smooth_normals(&mut cube).unwrap();
+
+    for (&vertex, &normal) in cube.vertices.iter().zip(cube.normals.iter()) {
+        assert_vec3_feq!(normal, vertex.normalize());
+    }
+
+    // the uvs still differ between the faces
+    weld::weld(&mut cube, 0.0).unwrap();
+    assert!(cube.vertices.len() > 8);
+    cube.uvs = vec![Vec2::init(0.0); cube.vertices.len()];
+    weld::weld(&mut cube, 0.0).unwrap();
+    assert_eq!(cube.vertices.len(), 8);
+}
+
+#[test]
+fn should_generate_tangents() {
+    // a quad in the xy plane, whose u axis points along y
+    let vertices = vec![
+        Vec3(0.0, 0.0, 0.0),
+        Vec3(1.0, 0.0, 0.0),
+        Vec3(1.0, 1.0, 0.0),
+        Vec3(0.0, 1.0, 0.0),
+    ];
+    let uvs = vec![
+        Vec2(0.0, 0.0),
+        Vec2(0.0, 1.0),
+        Vec2(1.0, 1.0),
+        Vec2(1.0, 0.0),
+    ];
+    let mut quad = mesh(vertices, uvs, vec![0, 1, 2, 0, 2, 3]);
+    tangents::generate_tangents(&mut quad).unwrap();
+
+    assert_eq!(quad.tangents.len(), 4);
+    for (tangent, normal) in quad.tangents.iter().zip(quad.normals.iter()) {
+        assert_vec3_feq!(Vec3(tangent.0, tangent.1, tangent.2), Vec3::forward());
+        assert_vec3_feq!(*normal, Vec3::up());
+
+        // the bitangent points along v, which is x
+        let bitangent = normal.cross(Vec3(tangent.0, tangent.1, tangent.2)) * tangent.3;
+        assert_vec3_feq!(bitangent, Vec3::right());
+    }
+}
+
+#[test]
+fn should_split_vertices_with_mirrored_uvs() {
+    // two quads, which share the edge at x = 1. the uvs are mirrored at that edge
+    let vertices = vec![
+        Vec3(0.0, 0.0, 0.0),
+        Vec3(1.0, 0.0, 0.0),
+        Vec3(2.0, 0.0, 0.0),
+        Vec3(0.0, 1.0, 0.0),
+        Vec3(1.0, 1.0, 0.0),
+        Vec3(2.0, 1.0, 0.0),
+    ];
+    let uvs = vec![
+        Vec2(0.0, 0.0),
+        Vec2(1.0, 0.0),
+        Vec2(0.0, 0.0),
+        Vec2(0.0, 1.0),
+        Vec2(1.0, 1.0),
+        Vec2(0.0, 1.0),
+    ];
+    let indices = vec![0, 1, 4, 0, 4, 3, 1, 2, 5, 1, 5, 4];
+    let mut quads = mesh(vertices, uvs, indices);
+    tangents::generate_tangents(&mut quads).unwrap();
+
+    // the shared vertices exist once for each handedness
+    assert_eq!(quads.vertices.len(), 8);
+    assert_eq!(quads.tangents.len(), 8);
+
+    for (i, vertex) in quads.vertices.iter().enumerate() {
+        let tangent = quads.tangents[i];
+        let is_left = quads
+            .indices
+            .triangles()
+            .filter(|&(a, b, c)| a == i || b == i || c == i)
+            .all(|(a, b, c)| {
+                let center = quads.vertices[a] + quads.vertices[b] + quads.vertices[c];
+                center.0 / 3.0 < 1.0
+            });
+
+        // u increases along x on the left and decreases on the right
+        if is_left {
+            assert_vec3_feq!(Vec3(tangent.0, tangent.1, tangent.2), Vec3::right());
+            assert_eq!(tangent.3, 1.0, "vertex {:?}", vertex);
+        } else {
+            assert_vec3_feq!(Vec3(tangent.0, tangent.1, tangent.2), Vec3::left());
+            assert_eq!(tangent.3, -1.0, "vertex {:?}", vertex);
+        }
+    }
+}
+
+#[test]
+fn should_optimize_vertex_cache() {
+    // a grid, whose triangles are shuffled
+    let size = 48;
+    let mut vertices = Vec::new();
+    for y in 0..=size {
+        for x in 0..=size {
+            vertices.push(Vec3(x as f32, y as f32, 0.0));
+        }
+    }
+
+    let mut triangles = Vec::new();
+    for y in 0..size {
+        for x in 0..size {
+            let i = (y * (size + 1) + x) as u32;
+            let row = (size + 1) as u32;
+            triangles.push([i, i + 1, i + row + 1]);
+            triangles.push([i, i + row + 1, i + row]);
+        }
+    }
+
+    let mut seed = 42u64;
+    for i in (1..triangles.len()).rev() {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let j = (seed >> 33) as usize % (i + 1);
+        triangles.swap(i, j);
+    }
+
+    let indices = triangles.into_iter().flatten().collect::<Vec<_>>();
+    let uvs = vec![Vec2::init(0.0); vertices.len()];
+    let mut grid = mesh(vertices, uvs, indices.clone());
+    let before = triangle_positions(&grid);
+    let acmr_before = vertex_cache::average_cache_miss_ratio(&indices, 16);
+
+    vertex_cache::optimize(&mut grid).unwrap();
+
+    let Indices::U32(optimized) = &grid.indices else {
+        panic!("expected u32 indices");
+    };
+    let acmr_after = vertex_cache::average_cache_miss_ratio(optimized, 16);
+    assert!(
+        acmr_after < 0.8 && acmr_after < acmr_before,
+        "before: {} after: {}",
+        acmr_before,
+        acmr_after,
+    );
+    assert_eq!(triangle_positions(&grid), before);
+
+    // the vertices are in the order of their first use
+    let mut next = 0;
+    for &index in optimized.iter() {
+        assert!(index <= next);
+        if index == next {
+            next += 1;
+        }
+    }
+}
+
+#[test]
+fn should_process_mesh() {
+    let mut cube = unshared_cube();
+    cube.indices = Indices::None;
+
+    let processed = mesh_processor::process(cube, &MeshProcessorSettings::default()).unwrap();
+    assert_eq!(processed.vertices.len(), 24);
+    assert_eq!(processed.tangents.len(), 24);
+    let Indices::U16(indices) = &processed.indices else {
+        panic!("expected u16 indices, but was {:?}", processed.indices);
+    };
+    assert_eq!(indices.len(), 36);
+
+    let settings = MeshProcessorSettings {
+        weld: false,
+        generate_tangents: false,
+        optimize_vertex_cache: false,
+        compact_indices: false,
+        ..Default::default()
+    };
+    let processed = mesh_processor::process(unshared_cube(), &settings).unwrap();
+    assert_eq!(processed.vertices.len(), 36);
+    assert!(processed.tangents.is_empty());
+    let Indices::U32(indices) = &processed.indices else {
+        panic!("expected u32 indices, but was {:?}", processed.indices);
+    };
+    assert_eq!(indices, &(0..36).collect::<Vec<_>>());
+}
+
+#[test]
+fn should_serialize_and_deserialize_mesh_with_tangents() {
+    let mut quad = mesh(
+        vec![Vec3::init(0.0), Vec3::right(), Vec3::forward()],
+        vec![Vec2::init(0.0), Vec2(1.0, 0.0), Vec2(0.0, 1.0)],
+        vec![0, 1, 2],
+    );
+    tangents::generate_tangents(&mut quad).unwrap();
+    quad.joints = vec![[0; 4]; 3];
+    quad.weights = vec![[1.0, 0.0, 0.0, 0.0]; 3];
+
+    let bytes = ris_mesh::serialize(&CpuMesh::try_from(quad).unwrap()).unwrap();
+    assert_eq!(bytes[..16], ris_mesh::TANGENT_MAGIC);
+
+    let deserialized = MeshPrototype::try_from(ris_mesh::deserialize(&bytes).unwrap()).unwrap();
+    assert_eq!(deserialized.joints.len(), 3);
+    let tangent = deserialized.tangents[2];
+    assert_eq!(
+        (tangent.0, tangent.1, tangent.2, tangent.3),
+        (1.0, 0.0, 0.0, 1.0)
+    );
+
+    let mut invalid = mesh(
+        vec![Vec3::init(0.0), Vec3::right(), Vec3::forward()],
+        vec![Vec2::init(0.0); 3],
+        vec![0, 1, 2],
+    );
+    invalid.tangents = vec![Vec4::init(0.0)];
+    assert!(CpuMesh::try_from(invalid).is_err());
+}
+
+#[test]
+fn should_import_with_sidecar() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+    std::fs::create_dir_all(&source).unwrap();
+
+    let obj = "v 0 0 0\nv 1 0 0\nv 0 1 0\nvt 0 0\nvt 1 0\nvt 0 1\nf 1/1 2/2 3/3\n";
+    std::fs::write(source.join("triangle.obj"), obj).unwrap();
+    let sidecar = asset_importer::sidecar_path(source.join("triangle.obj"));
+
+    let import = || {
+        let report = asset_importer::import_changed(
+            source.to_str().unwrap(),
+            imported.to_str().unwrap(),
+            None,
+        )
+        .unwrap();
+        let bytes = std::fs::read(imported.join("triangle.ris_mesh")).unwrap();
+        let mesh = MeshPrototype::try_from(ris_mesh::deserialize(&bytes).unwrap()).unwrap();
+        (report, mesh)
+    };
+
+    let (first, with_tangents) = import();
+    let (second, _) = import();
+    std::fs::write(&sidecar, "generate_tangents: false\n").unwrap();
+    let (third, without_tangents) = import();
+    std::fs::write(&sidecar, "generate_tangents: maybe\n").unwrap();
+    let invalid =
+        asset_importer::import_changed(source.to_str().unwrap(), imported.to_str().unwrap(), None);
+
+    let obj = PathBuf::from("triangle.obj");
+    assert_eq!(
+        sidecar.file_name().unwrap().to_str(),
+        Some("triangle.obj.ris_meta")
+    );
+    assert!(first.imported.contains(&obj));
+    assert!(!second.imported.contains(&obj));
+    assert!(third.imported.contains(&obj));
+    assert_eq!(with_tangents.tangents.len(), 3);
+    assert!(without_tangents.tangents.is_empty());
+    assert!(invalid.is_err());
+}
@@ -1,3 +1,20 @@
+pub mod asset_loader;
+pub mod asset_patch;
+pub mod asset_watcher;
+pub mod glb_importer;
+pub mod gltf_importer;
+pub mod import_cache;
 pub mod json;
+pub mod mesh_lod;
+pub mod mesh_processor;
+pub mod obj_importer;
+pub mod parallel;
+pub mod ply_importer;
 pub mod qoi;
+pub mod ris_animation;
+pub mod ris_material;
+pub mod ris_prefab;
 pub mod ris_scene;
+pub mod ris_scene_text;
+pub mod ris_texture;
+pub mod scene_diff;
@@ -0,0 +1,139 @@
+use ris_asset::assets::ris_mesh;
+use ris_asset::importer::obj_importer;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_math::vector::Vec3;
+use ris_util::assert_vec3_feq;
+
+fn u16_indices(mesh: &MeshPrototype) -> &[u16] {
+    let Indices::U16(indices) = &mesh.indices else {
+        panic!("expected u16 indices, but was {:?}", mesh.indices);
+    };
+    indices
+}
+
+#[test]
+fn should_import_obj() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("quad.obj");
+    let imported = dir.join("imported");
+
+    let obj = "\
+        # a quad without normals\n\
+        mtllib quad.mtl\n\
+        o quad\n\
+        v 0 0 0\n\
+        v 1 0 0\n\
+        v 1 1 0 # a comment\n\
+        v 0 1 0\n\
+        vt 0 0\n\
+        vt 1 0.25\n\
+        usemtl none\n\
+        s off\n\
+        f 1/1 2/2 3/2 4/1\n\
+        l 1 2\n";
+    std::fs::write(&source, obj).unwrap();
+
+    let outputs = obj_importer::import(&source, &imported).unwrap();
+    let bytes = std::fs::read(imported.join("quad.ris_mesh"));
+
+    assert_eq!(outputs, vec![imported.join("quad.ris_mesh")]);
+    let cpu_mesh = ris_mesh::deserialize(&bytes.unwrap()).unwrap();
+    let mesh = MeshPrototype::try_from(cpu_mesh).unwrap();
+
+    assert_eq!(mesh.vertices.len(), 4);
+    assert_eq!(u16_indices(&mesh).len(), 6);
+
+    // obj is y up, ris_engine is z up. the origin of obj uvs is at the bottom left
+    let expected = [
+        (Vec3(0.0, 0.0, 0.0), (0.0, 1.0)),
+        (Vec3(1.0, 0.0, 0.0), (1.0, 0.75)),
+        (Vec3(1.0, 0.0, 1.0), (1.0, 0.75)),
+        (Vec3(0.0, 0.0, 1.0), (0.0, 1.0)),
+    ];
+    for (position, uv) in expected {
+        let i = mesh
+            .vertices
+            .iter()
+            .position(|&x| (x - position).length() < 0.0001)
+            .unwrap_or_else(|| panic!("no vertex at {:?}", position));
+        assert_eq!((mesh.uvs[i].0, mesh.uvs[i].1), uv);
+    }
+    for &normal in mesh.normals.iter() {
+        assert_vec3_feq!(normal, Vec3(0.0, -1.0, 0.0));
+    }
+}
+
+#[test]
+fn should_share_vertices_and_resolve_negative_indices() {
+    let obj = "\
+        v 0 0 0\n\
+        v 1 0 0\n\
+        v 1 1 0\n\
+        v 0 1 0\n\
+        vn 0 0 2\n\
+        f 1//1 2//1 3//1\n\
+        f -4//-1 -2//-1 -1//-1\n";
+
+    let mesh = obj_importer::parse(obj).unwrap();
+
+    assert_eq!(mesh.vertices.len(), 4);
+    assert_eq!(u16_indices(&mesh), &[0, 1, 2, 0, 2, 3]);
+
+    // normals are taken from the file, but normalized
+    for &normal in mesh.normals.iter() {
+        assert_vec3_feq!(normal, Vec3(0.0, -1.0, 0.0));
+    }
+
+    // vertices with different normals are distinct
+    let obj = "\
+        v 0 0 0\n\
+        v 1 0 0\n\
+        v 0 1 0\n\
+        vn 0 0 1\n\
+        vn 0 0 -1\n\
+        f 1//1 2//1 3//1\n\
+        f 1//2 3//2 2//2\n";
+
+    let mesh = obj_importer::parse(obj).unwrap();
+    assert_eq!(mesh.vertices.len(), 6);
+}
+
+#[test]
+fn should_triangulate_concave_polygon() {
+    // an L shape, whose corner at (1, 1) is reflex. a fan around the first corner, at (2, 1),
+    // would cover the missing quarter
+    let obj = "\
+        v 0 0 0\n\
+        v 2 0 0\n\
+        v 2 1 0\n\
+        v 1 1 0\n\
+        v 1 2 0\n\
+        v 0 2 0\n\
+        f 3 4 5 6 1 2\n";
+
+    let mesh = obj_importer::parse(obj).unwrap();
+    let indices = u16_indices(&mesh);
+    assert_eq!(indices.len(), 12);
+
+    // every triangle keeps the winding of the polygon and the triangles cover its area
+    let mut area = 0.0;
+    for triangle in indices.chunks_exact(3) {
+        let a = mesh.vertices[triangle[0] as usize];
+        let b = mesh.vertices[triangle[1] as usize];
+        let c = mesh.vertices[triangle[2] as usize];
+        let normal = (b - a).cross(c - a);
+        assert!(normal.1 < 0.0, "triangle {:?} is flipped", triangle);
+        area += normal.length() * 0.5;
+    }
+    assert!((area - 3.0).abs() < 0.0001, "area: {}", area);
+}
+
+#[test]
+fn should_not_import_invalid_obj() {
+    assert!(obj_importer::parse("").is_err());
+    assert!(obj_importer::parse("v 0 0 0\nv 1 0 0\nf 1 2 3\n").is_err());
+    assert!(obj_importer::parse("v 0 0 0\nv 1 0 0\nv 0 1 0\nf 0 1 2\n").is_err());
+    assert!(obj_importer::parse("v 0 0\nf 1 1 1\n").is_err());
+    assert!(obj_importer::parse("v 0 0 0\nf 1/1 1 1\n").is_err());
+}
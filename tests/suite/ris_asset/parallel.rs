@@ -0,0 +1,147 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use ris_asset::RisHeader;
+use ris_asset::asset_compiler;
+use ris_asset::asset_compiler::CompileOptions;
+use ris_asset::asset_importer;
+use ris_asset_data::asset_id::AssetId;
+use ris_async::ThreadPool;
+use ris_async::ThreadPoolCreateInfo;
+use ris_async::ThreadPoolGuard;
+
+fn thread_pool() -> ThreadPoolGuard {
+    let create_info = ThreadPoolCreateInfo {
+        buffer_capacity: 256,
+        cpu_count: 4,
+        threads: 4,
+        set_affinity: false,
+        use_parking: true,
+    };
+    ThreadPool::init(create_info).unwrap()
+}
+
+fn write_png(path: impl AsRef<Path>, seed: u8) {
+    let path = path.as_ref();
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let file = std::fs::File::create(path).unwrap();
+    let mut encoder = png::Encoder::new(file, 4, 4);
+    encoder.set_color(png::ColorType::Rgba);
+    encoder.set_depth(png::BitDepth::Eight);
+    let mut writer = encoder.write_header().unwrap();
+    let pixels = (0..64u8).map(|x| x.wrapping_mul(seed)).collect::<Vec<_>>();
+    writer.write_image_data(&pixels).unwrap();
+}
+
+fn read_dir_recursive(dir: &Path) -> Vec<(PathBuf, Vec<u8>)> {
+    let mut result = Vec::new();
+    let mut directories = vec![dir.to_path_buf()];
+    while let Some(current) = directories.pop() {
+        for entry in std::fs::read_dir(current).unwrap() {
+            let path = entry.unwrap().path();
+            if path.is_dir() {
+                directories.push(path);
+            } else {
+                let content = std::fs::read(&path).unwrap();
+                result.push((path.strip_prefix(dir).unwrap().to_path_buf(), content));
+            }
+        }
+    }
+
+    result.sort();
+    result
+}
+
+#[test]
+fn should_map_in_order() {
+    let items = (0..100).collect::<Vec<_>>();
+
+    let sequential = ris_asset::util::map_parallel(items.clone(), |x| x * 2);
+    let parallel = {
+        let _g = thread_pool();
+        ris_asset::util::map_parallel(items, |x| x * 2)
+    };
+
+    let expected = (0..100).map(|x| x * 2).collect::<Vec<_>>();
+    assert_eq!(sequential, expected);
+    assert_eq!(parallel, expected);
+}
+
+#[test]
+fn should_compile_same_file_in_parallel() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    for i in 0..32 {
+        let path = source
+            .join(format!("dir_{}", i % 4))
+            .join(format!("{}.ris_asset", i));
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+
+        // every asset references the next one
+        let reference = format!("dir_{}/{}.ris_asset", (i + 1) % 4, (i + 1) % 32);
+        let header = RisHeader::new(*b"ris_test_asset\0\0", vec![AssetId::Path(reference)]);
+        let bytes = header.serialize(&[i as u8; 100]).unwrap();
+        std::fs::write(path, bytes).unwrap();
+    }
+
+    let options = CompileOptions {
+        include_original_paths: true,
+        registry: None,
+    };
+    let sequential_path = dir.join("sequential");
+    let parallel_path = dir.join("parallel");
+    asset_compiler::compile(
+        source.to_str().unwrap(),
+        sequential_path.to_str().unwrap(),
+        options.clone(),
+    )
+    .unwrap();
+    {
+        let _g = thread_pool();
+        asset_compiler::compile(
+            source.to_str().unwrap(),
+            parallel_path.to_str().unwrap(),
+            options,
+        )
+        .unwrap();
+    }
+
+    let sequential = std::fs::read(sequential_path).unwrap();
+    let parallel = std::fs::read(parallel_path).unwrap();
+    assert_eq!(sequential, parallel);
+}
+
+#[test]
+fn should_import_same_files_in_parallel() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    for i in 0..16 {
+        write_png(
+            source
+                .join(format!("dir_{}", i % 3))
+                .join(format!("{}.png", i)),
+            i + 1,
+        );
+    }
+
+    let sequential = dir.join("sequential");
+    let parallel = dir.join("parallel");
+    let sequential_report = asset_importer::import_changed(
+        source.to_str().unwrap(),
+        sequential.to_str().unwrap(),
+        None,
+    )
+    .unwrap();
+    let parallel_report = {
+        let _g = thread_pool();
+        asset_importer::import_changed(source.to_str().unwrap(), parallel.to_str().unwrap(), None)
+            .unwrap()
+    };
+
+    let sequential_files = read_dir_recursive(&sequential);
+    let parallel_files = read_dir_recursive(&parallel);
+    assert_eq!(sequential_report.imported.len(), 16);
+    assert_eq!(sequential_report.imported, parallel_report.imported);
+    assert_eq!(sequential_files.len(), 33);
+    assert_eq!(sequential_files, parallel_files);
+}
@@ -0,0 +1,176 @@
+use ris_asset::importer::ply_importer;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_math::vector::Vec3;
+use ris_util::assert_vec3_feq;
+
+fn u16_indices(mesh: &MeshPrototype) -> &[u16] {
+    let Indices::U16(indices) = &mesh.indices else {
+        panic!("expected u16 indices, but was {:?}", mesh.indices);
+    };
+    indices
+}
+
+/// A quad with uvs and vertex colors and an element, which is not imported.
+fn header(format: &str) -> String {
+    format!(
+        "ply\n\
+        format {} 1.0\n\
+        comment exported for a test\n\
+        element vertex 4\n\
+        property float x\n\
+        property float y\n\
+        property float z\n\
+        property uchar red\n\
+        property float s\n\
+        property float t\n\
+        element edge 1\n\
+        property int vertex1\n\
+        property int vertex2\n\
+        element face 1\n\
+        property list uchar int vertex_indices\n\
+        end_header\n",
+        format,
+    )
+}
+
+const POSITIONS: [[f32; 3]; 4] = [
+    [0.0, 0.0, 0.0],
+    [1.0, 0.0, 0.0],
+    [1.0, 1.0, 0.0],
+    [0.0, 1.0, 0.0],
+];
+
+fn binary(big_endian: bool) -> Vec<u8> {
+    let format = if big_endian {
+        "binary_big_endian"
+    } else {
+        "binary_little_endian"
+    };
+
+    let f32_bytes = |x: f32| match big_endian {
+        true => x.to_be_bytes(),
+        false => x.to_le_bytes(),
+    };
+    let i32_bytes = |x: i32| match big_endian {
+        true => x.to_be_bytes(),
+        false => x.to_le_bytes(),
+    };
+
+    let mut bytes = header(format).into_bytes();
+    for (i, position) in POSITIONS.iter().enumerate() {
+        for &value in position.iter() {
+            bytes.extend(f32_bytes(value));
+        }
+        bytes.push(255);
+        bytes.extend(f32_bytes(position[0]));
+        bytes.extend(f32_bytes(i as f32 * 0.25));
+    }
+    bytes.extend(i32_bytes(0));
+    bytes.extend(i32_bytes(1));
+    bytes.push(4);
+    for index in 0..4 {
+        bytes.extend(i32_bytes(index));
+    }
+
+    bytes
+}
+
+fn assert_quad(mesh: &MeshPrototype) {
+    assert_eq!(mesh.vertices.len(), 4);
+    assert_eq!(u16_indices(mesh).len(), 6);
+
+    // ply is z up, like ris_engine
+    for (&vertex, position) in mesh.vertices.iter().zip(POSITIONS.iter()) {
+        assert_vec3_feq!(vertex, Vec3(position[0], position[1], position[2]));
+    }
+    for &normal in mesh.normals.iter() {
+        assert_vec3_feq!(normal, Vec3(0.0, 0.0, 1.0));
+    }
+
+    // the origin of the uvs is at the bottom left
+    assert_eq!((mesh.uvs[3].0, mesh.uvs[3].1), (0.0, 0.25));
+}
+
+#[test]
+fn should_import_ascii_ply() {
+    let mut ply = header("ascii");
+    ply.push_str(
+        "0 0 0 255 0 0\n\
+        1 0 0 255 1 0.25\n\
+        1 1 0 255 1 0.5\n\
+        0 1 0 255 0 0.75\n\
+        0 1\n\
+        4 0 1 2 3\n",
+    );
+
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("quad.ply");
+    let imported = dir.join("imported");
+    std::fs::write(&source, ply.replace('\n', "\r\n")).unwrap();
+
+    let outputs = ply_importer::import(&source, &imported);
+    let exists = imported.join("quad.ris_mesh").exists();
+    let mesh = ply_importer::parse(ply.as_bytes());
+
+    assert_eq!(outputs.unwrap(), vec![imported.join("quad.ris_mesh")]);
+    assert!(exists);
+    assert_quad(&mesh.unwrap());
+}
+
+#[test]
+fn should_import_binary_ply() {
+    let little_endian = ply_importer::parse(&binary(false)).unwrap();
+    let big_endian = ply_importer::parse(&binary(true)).unwrap();
+
+    assert_quad(&little_endian);
+    assert_quad(&big_endian);
+}
+
+#[test]
+fn should_read_normals_from_ply() {
+    let ply = "ply\n\
+        format ascii 1.0\n\
+        element vertex 3\n\
+        property double x\n\
+        property double y\n\
+        property double z\n\
+        property float nx\n\
+        property float ny\n\
+        property float nz\n\
+        element face 1\n\
+        property list uchar uint vertex_index\n\
+        end_header\n\
+        0 0 0 2 0 0\n\
+        1 0 0 2 0 0\n\
+        0 1 0 2 0 0\n\
+        3 0 1 2\n";
+
+    let mesh = ply_importer::parse(ply.as_bytes()).unwrap();
+    assert_eq!(u16_indices(&mesh), &[0, 1, 2]);
+    for &normal in mesh.normals.iter() {
+        assert_vec3_feq!(normal, Vec3(1.0, 0.0, 0.0));
+    }
+    for uv in mesh.uvs.iter() {
+        assert_eq!((uv.0, uv.1), (0.0, 0.0));
+    }
+}
+
+#[test]
+fn should_not_import_invalid_ply() {
+    // truncated body
+    let mut bytes = binary(false);
+    bytes.pop();
+    assert!(ply_importer::parse(&bytes).is_err());
+
+    // face index out of range
+    let mut ply = header("ascii");
+    ply.push_str("0 0 0 0 0 0\n1 0 0 0 0 0\n1 1 0 0 0 0\n0 1 0 0 0 0\n0 1\n4 0 1 2 4\n");
+    assert!(ply_importer::parse(ply.as_bytes()).is_err());
+
+    // missing end of header
+    assert!(ply_importer::parse(b"ply\nformat ascii 1.0\nelement vertex 0\n").is_err());
+
+    // unknown format
+    assert!(ply_importer::parse(b"ply\nformat binary 1.0\nend_header\n").is_err());
+}
@@ -0,0 +1,342 @@
+use std::f32::consts::PI;
+
+use ris_asset::assets::ris_animation;
+use ris_asset::assets::ris_mesh;
+use ris_asset::assets::ris_skeleton;
+use ris_asset_data::AssetId;
+use ris_asset_data::animation::AnimationChannel;
+use ris_asset_data::animation::AnimationClip;
+use ris_asset_data::animation::ChannelPath;
+use ris_asset_data::animation::Interpolation;
+use ris_asset_data::mesh::CpuMesh;
+use ris_asset_data::mesh::Indices;
+use ris_asset_data::mesh::MeshPrototype;
+use ris_asset_data::skeleton::Joint;
+use ris_asset_data::skeleton::JointTransform;
+use ris_asset_data::skeleton::Pose;
+use ris_asset_data::skeleton::Skeleton;
+use ris_data::ecs::components::animator_component::AnimatorClip;
+use ris_data::ecs::components::animator_component::AnimatorComponent;
+use ris_math::affine;
+use ris_math::matrix::Mat4;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec2;
+use ris_math::vector::Vec3;
+
+fn assert_floats_eq(left: &[f32], right: &[f32]) {
+    assert_eq!(left.len(), right.len());
+    for (l, r) in left.iter().zip(right.iter()) {
+        assert!(
+            (l - r).abs() < 0.0001,
+            "left: {:?} right: {:?}",
+            left,
+            right
+        );
+    }
+}
+
+fn assert_translation_eq(matrix: Mat4, expected: Vec3) {
+    let t = affine::to_translation(matrix);
+    assert_floats_eq(&[t.0, t.1, t.2], &[expected.0, expected.1, expected.2]);
+}
+
+fn channel(
+    path: ChannelPath,
+    interpolation: Interpolation,
+    times: &[f32],
+    values: &[f32],
+) -> AnimationChannel {
+    AnimationChannel {
+        joint: 0,
+        path,
+        interpolation,
+        times: times.to_vec(),
+        values: values.to_vec(),
+    }
+}
+
+fn joint(name: &str, parent: Option<usize>, translation: Vec3) -> Joint {
+    Joint {
+        name: name.to_string(),
+        parent,
+        rest: JointTransform {
+            translation,
+            ..Default::default()
+        },
+        inverse_bind_matrix: Mat4::identity(),
+    }
+}
+
+fn translation_pose(translations: &[Vec3]) -> Pose {
+    Pose {
+        joints: translations
+            .iter()
+            .map(|&translation| JointTransform {
+                translation,
+                ..Default::default()
+            })
+            .collect(),
+    }
+}
+
+#[test]
+fn should_sample_step_channel() {
+    let channel = channel(
+        ChannelPath::Translation,
+        Interpolation::Step,
+        &[0.0, 1.0],
+        &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0],
+    );
+
+    channel.validate(1).unwrap();
+    assert_floats_eq(&channel.sample(0.9)[..3], &[1.0, 2.0, 3.0]);
+    assert_floats_eq(&channel.sample(1.0)[..3], &[4.0, 5.0, 6.0]);
+}
+
+#[test]
+fn should_sample_linear_channel() {
+    let channel = channel(
+        ChannelPath::Scale,
+        Interpolation::Linear,
+        &[1.0, 3.0],
+        &[1.0, 1.0, 1.0, 3.0, 5.0, 1.0],
+    );
+
+    assert_floats_eq(&channel.sample(2.0)[..3], &[2.0, 3.0, 1.0]);
+    // clamped outside of the keyframes
+    assert_floats_eq(&channel.sample(0.0)[..3], &[1.0, 1.0, 1.0]);
+    assert_floats_eq(&channel.sample(4.0)[..3], &[3.0, 5.0, 1.0]);
+}
+
+#[test]
+fn should_slerp_rotation_channel() {
+    let q0 = Quat::identity();
+    let q1 = Quat::angle_axis(0.5 * PI, Vec3::up());
+    let channel = channel(
+        ChannelPath::Rotation,
+        Interpolation::Linear,
+        &[0.0, 1.0],
+        &[q0.0, q0.1, q0.2, q0.3, q1.0, q1.1, q1.2, q1.3],
+    );
+
+    let expected = Quat::angle_axis(0.25 * PI, Vec3::up());
+    let sampled = channel.sample(0.5);
+    assert_floats_eq(&sampled, &[expected.0, expected.1, expected.2, expected.3]);
+}
+
+#[test]
+fn should_sample_cubic_spline_channel() {
+    // in-tangent, value, out-tangent per keyframe
+    let channel = channel(
+        ChannelPath::Translation,
+        Interpolation::CubicSpline,
+        &[0.0, 2.0],
+        &[
+            0.0, 0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 0.0, 0.0, //
+            0.0, 0.0, 0.0, 2.0, 0.0, 0.0, 0.0, 0.0, 0.0, //
+        ],
+    );
+
+    channel.validate(1).unwrap();
+    // hermite spline at t = 0.5: h00 * p0 + h10 * delta * m0 + h01 * p1 + h11 * delta * m1
+    // = 0.5 * 0 + 0.125 * 2 * 1 + 0.5 * 2 + (-0.125) * 2 * 0
+    assert_floats_eq(&channel.sample(1.0)[..3], &[1.25, 0.0, 0.0]);
+    assert_floats_eq(&channel.sample(2.0)[..3], &[2.0, 0.0, 0.0]);
+}
+
+#[test]
+fn should_not_validate_channel_with_missing_values() {
+    let channel = channel(
+        ChannelPath::Translation,
+        Interpolation::CubicSpline,
+        &[0.0, 1.0],
+        &[0.0; 6],
+    );
+
+    assert!(channel.validate(1).is_err());
+}
+
+#[test]
+fn should_blend_weighted_poses() {
+    let rest = translation_pose(&[Vec3::init(0.0)]);
+    let a = translation_pose(&[Vec3(2.0, 0.0, 0.0)]);
+    let b = translation_pose(&[Vec3(0.0, 4.0, 0.0)]);
+
+    let pose = Pose::blend_weighted(rest.clone(), &[(a.clone(), 1.0), (b.clone(), 3.0)]).unwrap();
+    let t = pose.joints[0].translation;
+    assert_floats_eq(&[t.0, t.1, t.2], &[0.5, 3.0, 0.0]);
+
+    let pose = Pose::blend_weighted(rest, &[(a, 0.0), (b, 0.0)]).unwrap();
+    let t = pose.joints[0].translation;
+    assert_floats_eq(&[t.0, t.1, t.2], &[0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn should_compute_joint_matrices() {
+    // the child is stored before its parent
+    let mut skeleton = Skeleton {
+        joints: vec![
+            joint("child", Some(1), Vec3(0.0, 1.0, 0.0)),
+            joint("root", None, Vec3(1.0, 0.0, 0.0)),
+        ],
+    };
+    skeleton.joints[0].inverse_bind_matrix = affine::from_translation(Vec3(-1.0, -1.0, 0.0));
+
+    let matrices = skeleton.rest_pose().joint_matrices(&skeleton).unwrap();
+    assert_translation_eq(matrices[0], Vec3::init(0.0));
+    assert_translation_eq(matrices[1], Vec3(1.0, 0.0, 0.0));
+
+    let pose = translation_pose(&[Vec3(0.0, 2.0, 0.0), Vec3(1.0, 0.0, 0.0)]);
+    let matrices = pose.joint_matrices(&skeleton).unwrap();
+    assert_translation_eq(matrices[0], Vec3(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn should_not_validate_skeleton_with_cycle() {
+    let skeleton = Skeleton {
+        joints: vec![
+            joint("a", Some(1), Vec3::init(0.0)),
+            joint("b", Some(0), Vec3::init(0.0)),
+        ],
+    };
+
+    assert!(skeleton.validate().is_err());
+    assert!(ris_skeleton::serialize(&skeleton).is_err());
+}
+
+#[test]
+fn should_serialize_and_deserialize_skeleton() {
+    let mut skeleton = Skeleton {
+        joints: vec![
+            joint("hip", None, Vec3(0.0, 0.0, 1.0)),
+            joint("knee", Some(0), Vec3(0.0, 0.0, -0.5)),
+        ],
+    };
+    skeleton.joints[1].rest.rotation = Quat::angle_axis(0.25 * PI, Vec3::right());
+    skeleton.joints[1].inverse_bind_matrix = affine::from_translation(Vec3(0.0, 0.0, -0.5));
+
+    let bytes = ris_skeleton::serialize(&skeleton).unwrap();
+    let deserialized = ris_skeleton::deserialize(&bytes).unwrap();
+
+    assert_eq!(deserialized.joints.len(), 2);
+    assert_eq!(deserialized.joints[1].name, "knee");
+    assert_eq!(deserialized.joints[0].parent, None);
+    assert_eq!(deserialized.joints[1].parent, Some(0));
+    assert_eq!(
+        deserialized.joints[1].rest.rotation,
+        skeleton.joints[1].rest.rotation
+    );
+    assert_eq!(
+        deserialized.joints[1].inverse_bind_matrix,
+        skeleton.joints[1].inverse_bind_matrix
+    );
+}
+
+#[test]
+fn should_serialize_and_deserialize_animation() {
+    let mut rotation = channel(
+        ChannelPath::Rotation,
+        Interpolation::Step,
+        &[0.0, 0.5],
+        &[0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 0.0],
+    );
+    rotation.joint = 3;
+    let clip = AnimationClip {
+        name: "walk".to_string(),
+        duration: 0.5,
+        channels: vec![
+            rotation,
+            channel(
+                ChannelPath::Translation,
+                Interpolation::CubicSpline,
+                &[0.0],
+                &[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0],
+            ),
+        ],
+    };
+    let skeleton = AssetId::Path("models/robot-rig-000.ris_skeleton".to_string());
+
+    let bytes = ris_animation::serialize(&clip, &skeleton).unwrap();
+    let (deserialized, deserialized_skeleton) = ris_animation::deserialize(&bytes).unwrap();
+
+    assert_eq!(deserialized, clip);
+    assert_eq!(deserialized_skeleton, skeleton);
+}
+
+#[test]
+fn should_serialize_and_deserialize_skinned_mesh() {
+    let prototype = || MeshPrototype {
+        vertices: vec![Vec3::init(0.0), Vec3::right(), Vec3::forward()],
+        normals: vec![Vec3::up(); 3],
+        uvs: vec![Vec2::init(0.0); 3],
+        indices: Indices::U16(vec![0, 1, 2]),
+        joints: Vec::new(),
+        weights: Vec::new(),
+        tangents: Vec::new(),
+        lods: Vec::new(),
+    };
+
+    let static_mesh = CpuMesh::try_from(prototype()).unwrap();
+    let bytes = ris_mesh::serialize(&static_mesh).unwrap();
+    assert_eq!(bytes[..16], ris_mesh::MAGIC);
+
+    let mut skinned = prototype();
+    skinned.joints = vec![[0, 1, 0, 0], [1, 0, 0, 0], [2, 0, 0, 0]];
+    skinned.weights = vec![
+        [0.5, 0.5, 0.0, 0.0],
+        [1.0, 0.0, 0.0, 0.0],
+        [1.0, 0.0, 0.0, 0.0],
+    ];
+    let skinned_mesh = CpuMesh::try_from(skinned).unwrap();
+    assert_eq!(skinned_mesh.p_vertices, static_mesh.p_vertices);
+    assert_eq!(skinned_mesh.p_indices, static_mesh.p_indices);
+
+    let bytes = ris_mesh::serialize(&skinned_mesh).unwrap();
+    assert_eq!(bytes[..16], ris_mesh::SKINNED_MAGIC);
+    let deserialized = MeshPrototype::try_from(ris_mesh::deserialize(&bytes).unwrap()).unwrap();
+    assert_eq!(deserialized.joints[0], [0, 1, 0, 0]);
+    assert_eq!(deserialized.weights[0], [0.5, 0.5, 0.0, 0.0]);
+    assert_eq!(deserialized.joints.len(), 3);
+
+    let mut invalid = prototype();
+    invalid.joints = vec![[0; 4]];
+    invalid.weights = vec![[1.0, 0.0, 0.0, 0.0]];
+    assert!(CpuMesh::try_from(invalid).is_err());
+}
+
+#[test]
+fn should_evaluate_animator() {
+    let skeleton = Skeleton {
+        joints: vec![joint("root", None, Vec3::init(0.0))],
+    };
+    let clip = AnimationClip {
+        name: "slide".to_string(),
+        duration: 2.0,
+        channels: vec![channel(
+            ChannelPath::Translation,
+            Interpolation::Linear,
+            &[0.0, 2.0],
+            &[0.0, 0.0, 0.0, 2.0, 0.0, 0.0],
+        )],
+    };
+
+    let mut animator = AnimatorComponent::default();
+    let clip_id = AssetId::Path("slide.ris_animation".to_string());
+    animator
+        .clips_mut()
+        .push(AnimatorClip::new(clip_id.clone()));
+    let mut clamped = AnimatorClip::new(clip_id);
+    clamped.looping = false;
+    clamped.weight = 0.0;
+    animator.clips_mut().push(clamped);
+
+    let matrices = animator.evaluate(0.5, &skeleton, &[&clip, &clip]).unwrap();
+    assert_translation_eq(matrices[0], Vec3(0.5, 0.0, 0.0));
+
+    // looping clips wrap around, the others stop at their end
+    animator.evaluate(2.0, &skeleton, &[&clip, &clip]).unwrap();
+    assert_floats_eq(&[animator.clips()[0].time], &[0.5]);
+    assert_floats_eq(&[animator.clips()[1].time], &[2.0]);
+
+    assert!(animator.evaluate(0.5, &skeleton, &[&clip]).is_err());
+}
@@ -0,0 +1,220 @@
+use std::sync::Arc;
+
+use ris_asset::asset_importer;
+use ris_asset::assets::ris_material;
+use ris_asset::assets::ris_scene_text;
+use ris_asset::assets::ris_texture;
+use ris_asset_data::AssetId;
+use ris_asset_data::material::AlphaMode;
+use ris_asset_data::material::CpuMaterial;
+use ris_asset_data::material::MaterialParams;
+use ris_asset_data::material::MaterialTextures;
+use ris_asset_data::texture::AddressMode;
+use ris_asset_data::texture::Filter;
+use ris_data::ecs::components::material_component::MaterialComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::decl::MaterialComponentHandle;
+use ris_data::ecs::game_object::GetFrom;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+
+use super::glb_importer::write_glb;
+
+fn path(value: &str) -> AssetId {
+    AssetId::Path(value.to_string())
+}
+
+fn scene() -> Scene {
+    let registry = Registry::new(Vec::new(), Vec::new()).unwrap();
+    let scene_create_info = SceneCreateInfo {
+        static_chunks: 1,
+        registry: Some(Arc::new(registry)),
+        ..Default::default()
+    };
+    Scene::new(scene_create_info).unwrap()
+}
+
+fn png_bytes(width: u32, height: u32, pixel: [u8; 4]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    {
+        let mut encoder = png::Encoder::new(&mut bytes, width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        let pixels = pixel.repeat((width * height) as usize);
+        writer.write_image_data(&pixels).unwrap();
+    }
+    bytes
+}
+
+#[test]
+fn should_serialize_and_deserialize_material() {
+    let material = CpuMaterial {
+        vertex_shader: path("shaders/lit.vert.spv"),
+        fragment_shader: path("shaders/lit.frag.spv"),
+        textures: MaterialTextures {
+            base_color: Some(path("textures/bricks.ris_texture")),
+            metallic_roughness: None,
+            normal: Some(path("textures/bricks_normal.ris_texture")),
+            occlusion: None,
+            emissive: Some(path("textures/glow.ris_texture")),
+        },
+        params: MaterialParams {
+            base_color: [1.0, 0.5, 0.25, 0.8],
+            metallic: 0.0,
+            roughness: 0.75,
+            emissive: [0.1, 0.2, 0.3],
+            normal_scale: 2.0,
+            occlusion_strength: 0.5,
+            alpha_mode: AlphaMode::Mask,
+            alpha_cutoff: 0.25,
+            double_sided: true,
+        },
+    };
+
+    let bytes = ris_material::serialize(&material).unwrap();
+    let deserialized = ris_material::deserialize(&bytes).unwrap();
+
+    assert_eq!(deserialized, material);
+}
+
+#[test]
+fn should_serialize_and_deserialize_default_material() {
+    let material = CpuMaterial::default();
+
+    let bytes = ris_material::serialize(&material).unwrap();
+    let deserialized = ris_material::deserialize(&bytes).unwrap();
+
+    assert_eq!(deserialized, material);
+    assert_eq!(deserialized.textures, MaterialTextures::default());
+}
+
+#[test]
+fn should_not_deserialize_other_assets() {
+    let texture = ris_asset_data::texture::CpuTexture::with_mips(
+        1,
+        1,
+        true,
+        Default::default(),
+        vec![255; 4],
+    )
+    .unwrap();
+    let bytes = ris_texture::serialize(&texture).unwrap();
+
+    assert!(ris_material::deserialize(&bytes).is_err());
+}
+
+#[test]
+fn should_serialize_material_component_as_text() {
+    let scene = scene();
+    let chunk = scene.reserve_chunk().unwrap();
+    let game_object = GameObjectHandle::new_static(&scene, chunk).unwrap();
+    let component: MaterialComponentHandle = game_object
+        .add_component::<MaterialComponent>(&scene)
+        .unwrap()
+        .into();
+    let asset_id = path("materials/wall.ris_material");
+    scene
+        .deref(component.0)
+        .unwrap()
+        .borrow_mut()
+        .set_asset_id(Some(asset_id.clone()));
+
+    let text = ris_scene_text::serialize(&scene, chunk).unwrap();
+    assert!(text.contains(
+        "component: MaterialComponent\nfield.has_asset: bool true\nfield.asset: asset materials/wall.ris_material\n"
+    ));
+
+    let other = self::scene();
+    let chunk = ris_scene_text::deserialize(&other, &text).unwrap().unwrap();
+    let game_objects = other.static_chunks[chunk]
+        .game_objects
+        .iter()
+        .filter(|x| x.borrow().is_alive)
+        .map(|x| GameObjectHandle::from(x.borrow().handle))
+        .collect::<Vec<_>>();
+    let material = game_objects[0]
+        .get_component::<MaterialComponent>(&other, GetFrom::This)
+        .unwrap()
+        .unwrap();
+    let deserialized = other.deref(material).unwrap().borrow().asset_id();
+    assert_eq!(deserialized, Some(asset_id));
+}
+
+#[test]
+fn should_import_materials_from_glb() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+
+    let png = png_bytes(4, 4, [200, 100, 50, 255]);
+    let json = format!(
+        r#"{{
+            "asset": {{ "version": "2.0" }},
+            "buffers": [{{ "byteLength": {0} }}],
+            "bufferViews": [{{ "buffer": 0, "byteOffset": 0, "byteLength": {0} }}],
+            "images": [{{ "bufferView": 0, "mimeType": "image/png", "name": "bricks" }}],
+            "samplers": [{{ "magFilter": 9728, "minFilter": 9987, "wrapS": 33071, "wrapT": 33071 }}],
+            "textures": [{{ "sampler": 0, "source": 0 }}],
+            "materials": [
+                {{
+                    "name": "wall",
+                    "pbrMetallicRoughness": {{
+                        "baseColorFactor": [1.0, 0.5, 0.25, 1.0],
+                        "baseColorTexture": {{ "index": 0 }},
+                        "metallicFactor": 0.0,
+                        "roughnessFactor": 0.75
+                    }},
+                    "normalTexture": {{ "index": 0, "scale": 2.0 }},
+                    "alphaMode": "MASK",
+                    "alphaCutoff": 0.25,
+                    "doubleSided": true
+                }},
+                {{ "name": "plain" }}
+            ]
+        }}"#,
+        png.len(),
+    );
+    write_glb(source.join("models").join("house.glb"), &json, &png);
+
+    asset_importer::import_changed(source.to_str().unwrap(), imported.to_str().unwrap(), None)
+        .unwrap();
+    let models = imported.join("models");
+    let wall = std::fs::read(models.join("house-wall-000.ris_material"));
+    let plain = std::fs::read(models.join("house-plain-001.ris_material"));
+    let srgb = std::fs::read(models.join("house-bricks-000-srgb.ris_texture"));
+    let linear = std::fs::read(models.join("house-bricks-000-linear.ris_texture"));
+
+    let wall = ris_material::deserialize(&wall.unwrap()).unwrap();
+    let plain = ris_material::deserialize(&plain.unwrap()).unwrap();
+    let srgb = ris_texture::deserialize(&srgb.unwrap()).unwrap();
+    let linear = ris_texture::deserialize(&linear.unwrap()).unwrap();
+
+    assert_eq!(wall.params.base_color, [1.0, 0.5, 0.25, 1.0]);
+    assert_eq!(wall.params.metallic, 0.0);
+    assert_eq!(wall.params.roughness, 0.75);
+    assert_eq!(wall.params.normal_scale, 2.0);
+    assert_eq!(wall.params.alpha_mode, AlphaMode::Mask);
+    assert_eq!(wall.params.alpha_cutoff, 0.25);
+    assert!(wall.params.double_sided);
+    assert_eq!(
+        wall.textures.base_color,
+        Some(path("models/house-bricks-000-srgb.ris_texture"))
+    );
+    assert_eq!(
+        wall.textures.normal,
+        Some(path("models/house-bricks-000-linear.ris_texture"))
+    );
+    assert_eq!(wall.textures.metallic_roughness, None);
+    assert_eq!(plain, CpuMaterial::default());
+
+    assert!(srgb.srgb);
+    assert!(!linear.srgb);
+    assert_eq!((srgb.width, srgb.height), (4, 4));
+    assert_eq!(srgb.sampler.mag_filter, Filter::Nearest);
+    assert_eq!(srgb.sampler.min_filter, Filter::Linear);
+    assert_eq!(srgb.sampler.mipmap_filter, Filter::Linear);
+    assert_eq!(srgb.sampler.address_mode, AddressMode::ClampToEdge);
+    assert_eq!(srgb.mips[0][0..4], [200, 100, 50, 255]);
+}
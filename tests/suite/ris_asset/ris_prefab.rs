@@ -0,0 +1,314 @@
+use std::sync::Arc;
+
+use ris_asset::assets::ris_prefab;
+use ris_asset::assets::ris_prefab::PrefabOverrides;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::id::Component;
+use ris_data::ecs::id::ComponentInspectData;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::script_prelude::*;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+
+#[derive(Debug, Default)]
+pub struct FollowComponent {
+    game_object: GameObjectHandle,
+    target: GameObjectHandle,
+}
+
+ris_data::declare_component!(FollowComponentHandle, FollowComponent);
+
+impl Component for FollowComponent {
+    fn destroy(&mut self, _scene: &Scene) {}
+
+    fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+
+    fn game_object_mut(&mut self) -> &mut GameObjectHandle {
+        &mut self.game_object
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        stream.write_game_object(self.target)?;
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        self.target = stream.read_game_object()?;
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ComponentInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct HealthScript {
+    health: i32,
+    health_on_start: Option<i32>,
+}
+
+impl Script for HealthScript {
+    fn start(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        self.health_on_start = Some(self.health);
+        Ok(())
+    }
+
+    fn update(&mut self, _data: ScriptUpdateData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn end(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        ris_io::write_i32(stream, self.health)?;
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        self.health = ris_io::read_i32(stream)?;
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ScriptInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
+
+fn scene() -> Scene {
+    let registry = Registry::new(
+        vec![Registry::component::<FollowComponent>().unwrap()],
+        vec![Registry::script::<HealthScript>().unwrap()],
+    )
+    .unwrap();
+
+    let scene_create_info = SceneCreateInfo {
+        dynamic_game_objects_per_page: 8,
+        static_chunks: 1,
+        static_game_objects_per_page: 8,
+        components_per_page: 8,
+        registry: Some(Arc::new(registry)),
+    };
+    Scene::new(scene_create_info).unwrap()
+}
+
+// root
+// |- body (follows head)
+// |  |- head (health 10)
+// |- tail
+fn build_prefab(scene: &Scene, root: GameObjectHandle) -> Vec<u8> {
+    let kind = root.scene_id().kind.try_into().unwrap();
+    let body = GameObjectHandle::new_with_kind(scene, kind).unwrap();
+    let head = GameObjectHandle::new_with_kind(scene, kind).unwrap();
+    let tail = GameObjectHandle::new_with_kind(scene, kind).unwrap();
+
+    root.set_name(scene, "root").unwrap();
+    body.set_name(scene, "body").unwrap();
+    head.set_name(scene, "head").unwrap();
+    tail.set_name(scene, "tail").unwrap();
+
+    body.set_parent(scene, Some(root), 0).unwrap();
+    tail.set_parent(scene, Some(root), 1).unwrap();
+    head.set_parent(scene, Some(body), 0).unwrap();
+
+    root.set_position(scene, Vec3(1.0, 2.0, 3.0)).unwrap();
+
+    let follow: FollowComponentHandle = body.add_component(scene).unwrap().into();
+    scene.deref(follow.0).unwrap().borrow_mut().target = head;
+
+    let health = head.add_script::<HealthScript>(scene).unwrap();
+    health.script_mut(scene).unwrap().health = 10;
+
+    ris_prefab::serialize(scene, root).unwrap()
+}
+
+fn child(scene: &Scene, game_object: GameObjectHandle, name: &str) -> GameObjectHandle {
+    game_object
+        .children(scene)
+        .unwrap()
+        .into_iter()
+        .find(|x| x.name(scene).unwrap() == name)
+        .unwrap()
+}
+
+#[test]
+fn should_instantiate_multiple_times() {
+    let scene = scene();
+    let original = GameObjectHandle::new(&scene).unwrap();
+    let bytes = build_prefab(&scene, original);
+
+    let instance_1 = ris_prefab::instantiate(&scene, &bytes, Default::default()).unwrap();
+    let instance_2 = ris_prefab::instantiate(&scene, &bytes, Default::default()).unwrap();
+
+    for instance in [instance_1, instance_2] {
+        assert_ne!(instance, original);
+        assert_eq!(instance.name(&scene).unwrap(), "root");
+        assert_eq!(instance.position(&scene).unwrap(), Vec3(1.0, 2.0, 3.0));
+        assert_eq!(instance.parent(&scene).unwrap(), None);
+
+        let children = instance.children(&scene).unwrap();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[0].name(&scene).unwrap(), "body");
+        assert_eq!(children[1].name(&scene).unwrap(), "tail");
+
+        let body = children[0];
+        let head = child(&scene, body, "head");
+
+        let follow: FollowComponentHandle = body
+            .get_component(&scene, GetFrom::This)
+            .unwrap()
+            .unwrap()
+            .into();
+        assert_eq!(scene.deref(follow.0).unwrap().borrow().target, head);
+
+        let health = head
+            .get_script::<HealthScript>(&scene, GetFrom::This)
+            .unwrap()
+            .unwrap();
+        assert_eq!(health.script(&scene).unwrap().health, 10);
+        assert_eq!(health.script(&scene).unwrap().health_on_start, Some(10));
+    }
+
+    let alive = scene.query().include_inactive().game_objects().unwrap();
+    assert_eq!(alive.len(), 12);
+}
+
+#[test]
+fn should_instantiate_static_prefab_as_dynamic_game_objects() {
+    let scene = scene();
+    scene.reserve_chunk().unwrap();
+    let original = GameObjectHandle::new_static(&scene, 0).unwrap();
+    let bytes = build_prefab(&scene, original);
+
+    let instance = ris_prefab::instantiate(&scene, &bytes, Default::default()).unwrap();
+
+    let dynamics = scene
+        .query()
+        .in_kind(ris_data::ecs::id::GameObjectKind::Dynamic)
+        .game_objects()
+        .unwrap();
+    assert_eq!(dynamics.len(), 4);
+    assert!(dynamics.contains(&instance));
+}
+
+#[test]
+fn should_override_transform() {
+    let scene = scene();
+    let original = GameObjectHandle::new(&scene).unwrap();
+    let bytes = build_prefab(&scene, original);
+    let parent = GameObjectHandle::new(&scene).unwrap();
+    let sibling = GameObjectHandle::new(&scene).unwrap();
+    sibling.set_parent(&scene, Some(parent), 0).unwrap();
+
+    let rotation = Quat::angle_axis(1.0, Vec3::up());
+    let mut overrides = PrefabOverrides::default();
+    overrides.parent = Some(parent);
+    overrides.name = Some("instance".to_string());
+    overrides.position = Some(Vec3(4.0, 5.0, 6.0));
+    overrides.rotation = Some(rotation);
+    overrides.scale = Some(Vec3::init(2.0));
+    let instance = ris_prefab::instantiate(&scene, &bytes, overrides).unwrap();
+
+    assert_eq!(instance.name(&scene).unwrap(), "instance");
+    assert_eq!(instance.position(&scene).unwrap(), Vec3(4.0, 5.0, 6.0));
+    assert_eq!(instance.rotation(&scene).unwrap(), rotation);
+    assert_eq!(instance.scale(&scene).unwrap(), Vec3::init(2.0));
+    assert_eq!(instance.parent(&scene).unwrap(), Some(parent));
+    assert_eq!(parent.children(&scene).unwrap(), vec![sibling, instance]);
+
+    // the original is untouched
+    assert_eq!(original.name(&scene).unwrap(), "root");
+    assert_eq!(original.position(&scene).unwrap(), Vec3(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn should_override_script_before_it_is_started() {
+    let scene = scene();
+    let original = GameObjectHandle::new(&scene).unwrap();
+    let bytes = build_prefab(&scene, original);
+
+    let overrides = PrefabOverrides::default().script::<HealthScript>("body/head", |script| {
+        script.health *= 5;
+        Ok(())
+    });
+    let instance = ris_prefab::instantiate(&scene, &bytes, overrides).unwrap();
+
+    let head = child(&scene, child(&scene, instance, "body"), "head");
+    let health = head
+        .get_script::<HealthScript>(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap();
+    assert_eq!(health.script(&scene).unwrap().health, 50);
+    assert_eq!(health.script(&scene).unwrap().health_on_start, Some(50));
+}
+
+#[test]
+fn should_not_instantiate_with_invalid_override_path() {
+    let scene = scene();
+    let original = GameObjectHandle::new(&scene).unwrap();
+    let bytes = build_prefab(&scene, original);
+
+    let overrides =
+        PrefabOverrides::default().script::<HealthScript>("body/neck", |_script| Ok(()));
+    let result = ris_prefab::instantiate(&scene, &bytes, overrides);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn should_not_leave_game_objects_behind_when_instantiating_truncated_prefab() {
+    let scene = scene();
+    let original = GameObjectHandle::new(&scene).unwrap();
+    let bytes = build_prefab(&scene, original);
+    let (references, uncompressed) =
+        ris_asset::assets::ris_scene::decompress(ris_prefab::MAGIC, &bytes).unwrap();
+
+    let alive_before = scene.query().include_inactive().game_objects().unwrap();
+
+    for len in [uncompressed.len() / 2, uncompressed.len() - 1] {
+        let truncated = ris_asset::assets::ris_scene::compress(
+            ris_prefab::MAGIC,
+            &uncompressed[..len],
+            references.clone(),
+        )
+        .unwrap();
+
+        let result = ris_prefab::instantiate(&scene, &truncated, Default::default());
+        assert!(result.is_err());
+
+        let alive_after = scene.query().include_inactive().game_objects().unwrap();
+        assert_eq!(alive_after, alive_before);
+    }
+}
+
+#[test]
+fn should_not_serialize_reference_outside_of_subtree() {
+    let scene = scene();
+    let root = GameObjectHandle::new(&scene).unwrap();
+    let outsider = GameObjectHandle::new(&scene).unwrap();
+
+    let follow: FollowComponentHandle = root.add_component(&scene).unwrap().into();
+    scene.deref(follow.0).unwrap().borrow_mut().target = outsider;
+
+    let result = ris_prefab::serialize(&scene, root);
+
+    assert!(result.is_err());
+}
+
+#[test]
+fn should_not_instantiate_scene() {
+    let scene = scene();
+    scene.reserve_chunk().unwrap();
+    GameObjectHandle::new_static(&scene, 0).unwrap();
+    let bytes = ris_asset::assets::ris_scene::serialize(&scene, 0).unwrap();
+
+    let result = ris_prefab::instantiate(&scene, &bytes, Default::default());
+
+    assert!(result.is_err());
+}
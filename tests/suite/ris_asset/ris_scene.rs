@@ -7,6 +7,7 @@ use ris_data::ecs::components::script_component::DynScriptComponent;
 use ris_data::ecs::decl::GameObjectHandle;
 use ris_data::ecs::handle::DynComponentHandle;
 use ris_data::ecs::id::Component;
+use ris_data::ecs::id::ComponentInspectData;
 use ris_data::ecs::registry::Registry;
 use ris_data::ecs::scene::Scene;
 use ris_data::ecs::scene::SceneCreateInfo;
@@ -92,21 +93,155 @@ impl Script for TestScript {
     }
 }
 
+#[derive(Debug, Default)]
+pub struct TargetComponent {
+    game_object: GameObjectHandle,
+    target: GameObjectHandle,
+    speed: f32,
+}
+
+ris_data::declare_component!(TargetComponentHandle, TargetComponent);
+
+impl Component for TargetComponent {
+    fn destroy(&mut self, _scene: &Scene) {}
+
+    fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+
+    fn game_object_mut(&mut self) -> &mut GameObjectHandle {
+        &mut self.game_object
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        stream.write_game_object(self.target)?;
+        ris_io::write_f32(stream, self.speed)?;
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        self.target = stream.read_game_object()?;
+        self.speed = ris_io::read_f32(stream)?;
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ComponentInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn should_serialize_user_defined_component() {
+    let registry = Registry::new(
+        vec![Registry::component::<TargetComponent>().unwrap()],
+        Vec::new(),
+    )
+    .unwrap();
+
+    let scene_create_info = SceneCreateInfo {
+        static_chunks: 2,
+        static_game_objects_per_page: 4,
+        components_per_page: 4,
+        registry: Some(Arc::new(registry)),
+        ..Default::default()
+    };
+    let scene = Scene::new(scene_create_info).unwrap();
+    assert_eq!(scene.reserve_chunk().unwrap(), 0);
+
+    let g0 = GameObjectHandle::new_static(&scene, 0).unwrap();
+    let g1 = GameObjectHandle::new_static(&scene, 0).unwrap();
+    g0.set_name(&scene, "follower").unwrap();
+    g1.set_name(&scene, "leader").unwrap();
+
+    let component: TargetComponentHandle = g0.add_component(&scene).unwrap().into();
+    {
+        let ptr = scene.deref(component.0).unwrap();
+        let mut aref_mut = ptr.borrow_mut();
+        aref_mut.target = g1;
+        aref_mut.speed = 4.2;
+    }
+
+    let serialized = ris_scene::serialize(&scene, 0).unwrap();
+    let index = ris_scene::deserialize(&scene, &serialized)
+        .unwrap()
+        .unwrap();
+    assert_eq!(index, 1);
+
+    let deserialized = scene.static_chunks[1]
+        .game_objects
+        .iter()
+        .map(|x| GameObjectHandle::from(x.borrow().handle))
+        .find(|x| x.name(&scene).map(|x| x == "follower").unwrap_or(false))
+        .unwrap();
+
+    let deserialized_component: TargetComponentHandle = deserialized
+        .get_component(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap()
+        .into();
+    let ptr = scene.deref(deserialized_component.0).unwrap();
+    let aref = ptr.borrow();
+
+    assert_ne!(aref.target, g1);
+    assert_eq!(aref.target.name(&scene).unwrap(), "leader");
+    assert_eq!(aref.speed, 4.2);
+}
+
+#[test]
+fn should_clear_chunk_when_deserializing_fails() {
+    let registry = Registry::new(
+        vec![Registry::component::<TargetComponent>().unwrap()],
+        Vec::new(),
+    )
+    .unwrap();
+
+    let scene_create_info = SceneCreateInfo {
+        static_chunks: 2,
+        static_game_objects_per_page: 4,
+        components_per_page: 4,
+        registry: Some(Arc::new(registry)),
+        ..Default::default()
+    };
+    let scene = Scene::new(scene_create_info).unwrap();
+    assert_eq!(scene.reserve_chunk().unwrap(), 0);
+
+    let g0 = GameObjectHandle::new_static(&scene, 0).unwrap();
+    let g1 = GameObjectHandle::new_static(&scene, 0).unwrap();
+    g1.set_parent(&scene, Some(g0), 0).unwrap();
+    let component: TargetComponentHandle = g0.add_component(&scene).unwrap().into();
+    scene.deref(component.0).unwrap().borrow_mut().target = g1;
+
+    // a truncated scene fails after some game objects are created already
+    let serialized = ris_scene::serialize(&scene, 0).unwrap();
+    let (references, bytes) = ris_scene::decompress(ris_scene::MAGIC, &serialized).unwrap();
+    let truncated = &bytes[..bytes.len() - 8];
+    let corrupt = ris_scene::compress(ris_scene::MAGIC, truncated, references).unwrap();
+    assert!(ris_scene::deserialize(&scene, &corrupt).is_err());
+
+    assert_eq!(scene.reserve_chunk(), Some(1));
+    let chunk = &scene.static_chunks[1];
+    assert!(chunk.game_objects.iter().all(|x| !x.borrow().is_alive));
+}
+
 #[test]
 fn should_serialize() {
     let mut rng = Rng::new(Seed::new());
 
-    let registry = Registry::new(vec![Registry::script::<TestScript>().unwrap()]).unwrap();
+    let registry =
+        Registry::new(Vec::new(), vec![Registry::script::<TestScript>().unwrap()]).unwrap();
 
     let count = 20;
     let scene_create_info = SceneCreateInfo {
         static_chunks: 2,
-        game_objects_per_static_chunk: count,
+        static_game_objects_per_page: count,
         registry: Some(Arc::new(registry)),
         ..Default::default()
     };
     let scene = Scene::new(scene_create_info).unwrap();
 
+    scene.static_chunks[0].game_objects.reserve(count).unwrap();
+    scene.static_chunks[1].game_objects.reserve(count).unwrap();
+
     // the first chunk will be reserved, such that the serializer doesn't to choose to create game
     // objects there
     assert_eq!(scene.reserve_chunk().unwrap(), 0);
@@ -117,14 +252,22 @@ fn should_serialize() {
     let mut to_unmark_1 = Vec::new();
     for _ in 0..(count / 2) {
         let index = rng.next_i32_between(0, count as i32 - 1) as usize;
-        let mut aref = scene.static_chunks[0].game_objects[index].borrow_mut();
+        let mut aref = scene.static_chunks[0]
+            .game_objects
+            .get(index)
+            .unwrap()
+            .borrow_mut();
         aref.is_alive = true;
         to_unmark_0.push(index);
     }
 
     for _ in 0..(count / 2) {
         let index = rng.next_i32_between(0, count as i32 - 1) as usize;
-        let mut aref = scene.static_chunks[1].game_objects[index].borrow_mut();
+        let mut aref = scene.static_chunks[1]
+            .game_objects
+            .get(index)
+            .unwrap()
+            .borrow_mut();
         aref.is_alive = true;
         to_unmark_1.push(index);
     }
@@ -142,7 +285,11 @@ fn should_serialize() {
     let g9 = GameObjectHandle::new_static(&scene, 0).unwrap();
 
     for index in to_unmark_0 {
-        let mut aref = scene.static_chunks[0].game_objects[index].borrow_mut();
+        let mut aref = scene.static_chunks[0]
+            .game_objects
+            .get(index)
+            .unwrap()
+            .borrow_mut();
         aref.is_alive = false;
     }
 
@@ -193,7 +340,11 @@ fn should_serialize() {
 
     // cleanup
     for index in to_unmark_1 {
-        let mut aref = scene.static_chunks[1].game_objects[index].borrow_mut();
+        let mut aref = scene.static_chunks[1]
+            .game_objects
+            .get(index)
+            .unwrap()
+            .borrow_mut();
         aref.is_alive = false;
     }
 
@@ -211,7 +362,10 @@ fn should_serialize() {
     assert_eq!(left_count, right_count);
 
     for i in 0..count {
-        let left: GameObjectHandle = scene.static_chunks[0].game_objects[i]
+        let left: GameObjectHandle = scene.static_chunks[0]
+            .game_objects
+            .get(i)
+            .unwrap()
             .borrow()
             .handle
             .into();
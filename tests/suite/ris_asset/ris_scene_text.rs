@@ -0,0 +1,388 @@
+use std::sync::Arc;
+
+use ris_asset::asset_compiler;
+use ris_asset::asset_compiler::CompileOptions;
+use ris_asset::assets::ris_scene;
+use ris_asset::assets::ris_scene_text;
+use ris_asset_data::asset_id::AssetId;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::id::Component;
+use ris_data::ecs::id::ComponentInspectData;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::script_prelude::*;
+use ris_math::quaternion::Quat;
+use ris_math::vector::Vec3;
+
+#[derive(Debug, Default)]
+pub struct SignComponent {
+    game_object: GameObjectHandle,
+    text: String,
+    points_to: Option<GameObjectHandle>,
+    model: Option<AssetId>,
+    offset: Vec3,
+    tilt: Quat,
+}
+
+ris_data::declare_component!(SignComponentHandle, SignComponent);
+
+impl Component for SignComponent {
+    fn destroy(&mut self, _scene: &Scene) {}
+
+    fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+
+    fn game_object_mut(&mut self) -> &mut GameObjectHandle {
+        &mut self.game_object
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        stream.write_field("text", self.text.as_str())?;
+        stream.write_field("points_to", self.points_to.is_some())?;
+        if let Some(points_to) = self.points_to {
+            stream.write_field("target", points_to)?;
+        }
+        stream.write_field("has_model", self.model.is_some())?;
+        if let Some(model) = self.model.clone() {
+            stream.write_field("model", model)?;
+        }
+        stream.write_field("offset", self.offset)?;
+        stream.write_field("tilt", self.tilt)?;
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        self.text = ris_io::read_string(stream)?;
+        if ris_io::read_bool(stream)? {
+            self.points_to = Some(stream.read_game_object()?);
+        }
+        if ris_io::read_bool(stream)? {
+            self.model = Some(stream.read_asset_id()?);
+        }
+        self.offset = ris_io::read_vec3(stream)?;
+        self.tilt = ris_io::read_quat(stream)?;
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ComponentInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct CounterScript {
+    count: i32,
+    step: usize,
+    speed: f32,
+    legacy: u8,
+}
+
+impl Script for CounterScript {
+    fn start(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, _data: ScriptUpdateData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn end(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        stream.write_field("count", self.count)?;
+        stream.write_field("step", self.step)?;
+        // written without a name, shows up as bytes
+        ris_io::write(stream, &[self.legacy])?;
+        stream.write_field("speed", self.speed)?;
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        self.count = ris_io::read_i32(stream)?;
+        self.step = ris_io::read_uint(stream)?;
+        let mut legacy = [0];
+        ris_io::read(stream, &mut legacy)?;
+        self.legacy = legacy[0];
+        self.speed = ris_io::read_f32(stream)?;
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ScriptInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
+
+fn registry() -> Arc<Registry> {
+    let registry = Registry::new(
+        vec![Registry::component::<SignComponent>().unwrap()],
+        vec![Registry::script::<CounterScript>().unwrap()],
+    )
+    .unwrap();
+    Arc::new(registry)
+}
+
+fn scene() -> Scene {
+    let scene_create_info = SceneCreateInfo {
+        static_chunks: 2,
+        registry: Some(registry()),
+        ..Default::default()
+    };
+    Scene::new(scene_create_info).unwrap()
+}
+
+// town # 1: the square
+// |- sign (points to fountain)
+// |- fountain (counter)
+fn build(scene: &Scene) -> usize {
+    let chunk = scene.reserve_chunk().unwrap();
+
+    let town = GameObjectHandle::new_static(scene, chunk).unwrap();
+    let sign = GameObjectHandle::new_static(scene, chunk).unwrap();
+    let fountain = GameObjectHandle::new_static(scene, chunk).unwrap();
+    town.set_name(scene, "town # 1: the square").unwrap();
+    sign.set_name(scene, "sign").unwrap();
+    fountain.set_name(scene, "fountain").unwrap();
+    sign.set_parent(scene, Some(town), 0).unwrap();
+    fountain.set_parent(scene, Some(town), 1).unwrap();
+
+    town.set_position(scene, Vec3(1.5, -2.0, 0.25)).unwrap();
+    sign.set_rotation(scene, Quat::angle_axis(0.5, Vec3::up()))
+        .unwrap();
+    fountain.set_scale(scene, Vec3::init(3.0)).unwrap();
+    fountain.set_active(scene, false).unwrap();
+
+    let component: SignComponentHandle = sign.add_component(scene).unwrap().into();
+    let ptr = scene.deref(component.0).unwrap();
+    let mut aref_mut = ptr.borrow_mut();
+    aref_mut.text = "welcome: 100% #1".to_string();
+    aref_mut.points_to = Some(fountain);
+    aref_mut.model = Some(AssetId::Path("models/sign.ris_mesh".to_string()));
+    aref_mut.offset = Vec3(0.0, 2.0, 0.0);
+    aref_mut.tilt = Quat::identity();
+    drop(aref_mut);
+
+    let counter = fountain.add_script::<CounterScript>(scene).unwrap();
+    let mut script = counter.script_mut(scene).unwrap();
+    script.count = -3;
+    script.step = 7;
+    script.speed = 0.1;
+    script.legacy = 0xab;
+
+    chunk
+}
+
+#[test]
+fn should_serialize_human_readable_text() {
+    let scene = scene();
+    let chunk = build(&scene);
+
+    let text = ris_scene_text::serialize(&scene, chunk).unwrap();
+
+    let expected = "\
+format: ris_scene_text
+version: 1
+
+game_object: 0
+name: town %23 1%3A the square
+is_active: true
+position: 1.5 -2.0 0.25
+rotation: 0.0 0.0 0.0 1.0
+scale: 1.0 1.0 1.0
+children: 1 2
+
+game_object: 1
+name: sign
+is_active: true
+position: 0.0 0.0 0.0
+rotation: 0.0 0.0 0.24740396 0.9689124
+scale: 1.0 1.0 1.0
+component: SignComponent
+field.text: string welcome%3A 100%25 %231
+field.points_to: bool true
+field.target: game_object 2
+field.has_model: bool true
+field.model: asset models/sign.ris_mesh
+field.offset: vec3 0.0 2.0 0.0
+field.tilt: quat 0.0 0.0 0.0 1.0
+
+game_object: 2
+name: fountain
+is_active: false
+position: 0.0 0.0 0.0
+rotation: 0.0 0.0 0.0 1.0
+scale: 3.0 3.0 3.0
+script: CounterScript
+field.count: i32 -3
+field.step: uint 7
+field: bytes ab
+field.speed: f32 0.1
+";
+    assert_eq!(text, expected);
+}
+
+#[test]
+fn should_convert_text_to_the_same_binary() {
+    let scene = scene();
+    let chunk = build(&scene);
+    let binary = ris_scene::serialize(&scene, chunk).unwrap();
+    let text = ris_scene_text::serialize(&scene, chunk).unwrap();
+
+    let converted = ris_scene_text::to_binary(registry(), &text).unwrap();
+
+    assert_eq!(converted, binary);
+}
+
+#[test]
+fn should_deserialize_hand_written_text() {
+    let text = "\
+format: ris_scene_text
+version: 1
+
+# ids don't need to be in order
+game_object: 10
+name: lamp
+children: 3
+script: CounterScript
+field.count: i32 5
+field.step: uint 1
+field: bytes 00
+field.speed: f32 2.5
+
+game_object: 3
+name: bulb
+position: 0 1 0
+component: SignComponent
+field.text: string hot
+field: bool true
+field: game_object 10
+field: bool false
+field: vec3 0 0 0
+field: quat 0 0 0 1
+";
+
+    let scene = scene();
+    let chunk = ris_scene_text::deserialize(&scene, text).unwrap().unwrap();
+
+    let game_objects = scene.static_chunks[chunk]
+        .game_objects
+        .iter()
+        .filter(|x| x.borrow().is_alive)
+        .map(|x| GameObjectHandle::from(x.borrow().handle))
+        .collect::<Vec<_>>();
+    let lamp = game_objects[0];
+    let bulb = game_objects[1];
+
+    assert_eq!(lamp.name(&scene).unwrap(), "lamp");
+    assert_eq!(lamp.children(&scene).unwrap(), vec![bulb]);
+    assert_eq!(bulb.position(&scene).unwrap(), Vec3(0.0, 1.0, 0.0));
+
+    let counter = lamp
+        .get_script::<CounterScript>(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap();
+    assert_eq!(counter.script(&scene).unwrap().count, 5);
+    assert_eq!(counter.script(&scene).unwrap().speed, 2.5);
+
+    let sign: SignComponentHandle = bulb
+        .get_component(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap()
+        .into();
+    let ptr = scene.deref(sign.0).unwrap();
+    assert_eq!(ptr.borrow().text, "hot");
+    assert_eq!(ptr.borrow().points_to, Some(lamp));
+    assert_eq!(ptr.borrow().model, None);
+}
+
+#[test]
+fn should_not_deserialize_invalid_text() {
+    let header = "format: ris_scene_text\nversion: 1\n";
+    let cases = [
+        // missing header
+        "game_object: 0\n".to_string(),
+        // unknown component
+        format!("{}game_object: 0\ncomponent: Unknown\n", header),
+        // fields too short
+        format!(
+            "{}game_object: 0\nscript: CounterScript\nfield: i32 1\n",
+            header
+        ),
+        // fields too long
+        format!(
+            "{}game_object: 0\nscript: CounterScript\nfield: i32 1\nfield: uint 1\nfield: bytes 00\nfield: f32 1\nfield: bool true\n",
+            header
+        ),
+        // reference to missing game object
+        format!("{}game_object: 0\nchildren: 1\n", header),
+        // duplicate id
+        format!("{}game_object: 0\ngame_object: 0\n", header),
+        // field outside of component
+        format!("{}game_object: 0\nfield: bool true\n", header),
+        // invalid value
+        format!("{}game_object: 0\nposition: 1 2\n", header),
+    ];
+
+    for case in cases {
+        let scene = scene();
+        let result = ris_scene_text::deserialize(&scene, &case);
+        assert!(result.is_err(), "{}", case);
+
+        // no half populated chunk is left behind
+        assert_eq!(scene.reserve_chunk(), Some(0), "{}", case);
+        let chunk = &scene.static_chunks[0];
+        assert!(
+            chunk.game_objects.iter().all(|x| !x.borrow().is_alive),
+            "{}",
+            case
+        );
+    }
+}
+
+#[test]
+fn should_compile_text_scene_to_binary_scene() {
+    let scene = scene();
+    let chunk = build(&scene);
+    let text = ris_scene_text::serialize(&scene, chunk).unwrap();
+    let binary = ris_scene::serialize(&scene, chunk).unwrap();
+
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let target = dir.join("ris_assets");
+    let decompiled = dir.join("decompiled");
+    std::fs::create_dir_all(source.join("scenes")).unwrap();
+    std::fs::create_dir_all(source.join("models")).unwrap();
+    std::fs::write(
+        source.join("models").join("sign.ris_mesh"),
+        "placeholder, not an actual mesh",
+    )
+    .unwrap();
+    std::fs::write(source.join("scenes").join("town.ris_scene_text"), text).unwrap();
+
+    let without_registry = asset_compiler::compile(
+        source.to_str().unwrap(),
+        target.to_str().unwrap(),
+        CompileOptions {
+            include_original_paths: true,
+            registry: None,
+        },
+    );
+    assert!(without_registry.is_err());
+
+    asset_compiler::compile(
+        source.to_str().unwrap(),
+        target.to_str().unwrap(),
+        CompileOptions {
+            include_original_paths: true,
+            registry: Some(registry()),
+        },
+    )
+    .unwrap();
+    asset_compiler::decompile(target.to_str().unwrap(), decompiled.to_str().unwrap()).unwrap();
+    let decompiled_scene = std::fs::read(decompiled.join("scenes").join("town.ris_scene"));
+
+    assert_eq!(decompiled_scene.unwrap(), binary);
+}
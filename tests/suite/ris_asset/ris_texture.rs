@@ -0,0 +1,98 @@
+use ris_asset::asset_importer;
+use ris_asset::assets::ris_texture;
+use ris_asset_data::texture;
+use ris_asset_data::texture::AddressMode;
+use ris_asset_data::texture::CpuTexture;
+use ris_asset_data::texture::Filter;
+use ris_asset_data::texture::SamplerSettings;
+
+fn checkerboard(width: usize, height: usize) -> Vec<u8> {
+    let mut pixels = Vec::with_capacity(width * height * 4);
+    for y in 0..height {
+        for x in 0..width {
+            let value = if (x + y) % 2 == 0 { 255 } else { 0 };
+            pixels.extend_from_slice(&[value, value, value, 255]);
+        }
+    }
+    pixels
+}
+
+#[test]
+fn should_count_mip_levels() {
+    assert_eq!(texture::mip_level_count(0, 0), 0);
+    assert_eq!(texture::mip_level_count(1, 1), 1);
+    assert_eq!(texture::mip_level_count(2, 2), 2);
+    assert_eq!(texture::mip_level_count(5, 3), 3);
+    assert_eq!(texture::mip_level_count(256, 16), 9);
+}
+
+#[test]
+fn should_generate_mip_chain() {
+    let texture =
+        CpuTexture::with_mips(5, 3, false, SamplerSettings::default(), checkerboard(5, 3)).unwrap();
+
+    texture.validate().unwrap();
+    let sizes = texture.mips.iter().map(|x| x.len()).collect::<Vec<_>>();
+    assert_eq!(sizes, [5 * 3 * 4, 2 * 4, 4]);
+}
+
+#[test]
+fn should_average_in_linear_space() {
+    let linear = texture::generate_mips(2, 2, false, checkerboard(2, 2)).unwrap();
+    let srgb = texture::generate_mips(2, 2, true, checkerboard(2, 2)).unwrap();
+
+    // half of the light of white is not 128 in srgb
+    assert_eq!(linear[1], [128, 128, 128, 255]);
+    assert_eq!(srgb[1], [188, 188, 188, 255]);
+}
+
+#[test]
+fn should_serialize_and_deserialize_texture() {
+    let sampler = SamplerSettings {
+        mag_filter: Filter::Nearest,
+        min_filter: Filter::Linear,
+        mipmap_filter: Filter::Nearest,
+        address_mode: AddressMode::ClampToEdge,
+    };
+    let texture = CpuTexture::with_mips(8, 4, true, sampler, checkerboard(8, 4)).unwrap();
+
+    let bytes = ris_texture::serialize(&texture).unwrap();
+    let deserialized = ris_texture::deserialize(&bytes).unwrap();
+
+    assert_eq!(deserialized, texture);
+}
+
+#[test]
+fn should_not_serialize_invalid_texture() {
+    let mut texture =
+        CpuTexture::with_mips(4, 4, true, SamplerSettings::default(), checkerboard(4, 4)).unwrap();
+    texture.mips[1].pop();
+
+    assert!(ris_texture::serialize(&texture).is_err());
+}
+
+#[test]
+fn should_import_png_as_texture() {
+    let dir = ris_util::prep_test_dir!();
+    let source = dir.join("source");
+    let imported = dir.join("imported");
+    std::fs::create_dir_all(&source).unwrap();
+    {
+        let file = std::fs::File::create(source.join("grass.png")).unwrap();
+        let mut encoder = png::Encoder::new(file, 4, 2);
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().unwrap();
+        writer.write_image_data(&[10; 4 * 2 * 3]).unwrap();
+    }
+
+    asset_importer::import_changed(source.to_str().unwrap(), imported.to_str().unwrap(), None)
+        .unwrap();
+    let bytes = std::fs::read(imported.join("grass.ris_texture"));
+
+    let texture = ris_texture::deserialize(&bytes.unwrap()).unwrap();
+    assert_eq!((texture.width, texture.height), (4, 2));
+    assert!(texture.srgb);
+    assert_eq!(texture.mips.len(), 3);
+    assert_eq!(texture.mips[2], [10, 10, 10, 255]);
+}
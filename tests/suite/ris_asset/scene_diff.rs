@@ -0,0 +1,440 @@
+use std::sync::Arc;
+
+use ris_asset::assets::ris_scene;
+use ris_asset::scene_diff;
+use ris_asset::scene_diff::Change;
+use ris_asset::scene_diff::MergeOutcome;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::id::Component;
+use ris_data::ecs::id::ComponentInspectData;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::script_prelude::*;
+use ris_math::vector::Vec3;
+
+#[derive(Debug, Default)]
+pub struct ChaseComponent {
+    game_object: GameObjectHandle,
+    target: Option<GameObjectHandle>,
+    speed: f32,
+}
+
+ris_data::declare_component!(ChaseComponentHandle, ChaseComponent);
+
+impl Component for ChaseComponent {
+    fn destroy(&mut self, _scene: &Scene) {}
+
+    fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+
+    fn game_object_mut(&mut self) -> &mut GameObjectHandle {
+        &mut self.game_object
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        stream.write_field("has_target", self.target.is_some())?;
+        if let Some(target) = self.target {
+            stream.write_game_object(target)?;
+        }
+        stream.write_field("speed", self.speed)?;
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        if ris_io::read_bool(stream)? {
+            self.target = Some(stream.read_game_object()?);
+        }
+        self.speed = ris_io::read_f32(stream)?;
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ComponentInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
+
+fn registry() -> Arc<Registry> {
+    let registry = Registry::new(
+        vec![Registry::component::<ChaseComponent>().unwrap()],
+        Vec::new(),
+    )
+    .unwrap();
+    Arc::new(registry)
+}
+
+struct Level {
+    scene: Scene,
+    player: GameObjectHandle,
+    enemy: GameObjectHandle,
+    camera: GameObjectHandle,
+}
+
+// player
+// |- camera
+// enemy (chases player)
+fn level() -> Level {
+    let scene = Scene::new(SceneCreateInfo::with_single_static_chunk(registry())).unwrap();
+    scene.reserve_chunk().unwrap();
+
+    let player = GameObjectHandle::new_static(&scene, 0).unwrap();
+    let enemy = GameObjectHandle::new_static(&scene, 0).unwrap();
+    let camera = GameObjectHandle::new_static(&scene, 0).unwrap();
+    player.set_name(&scene, "player").unwrap();
+    enemy.set_name(&scene, "enemy").unwrap();
+    camera.set_name(&scene, "camera").unwrap();
+    camera.set_parent(&scene, Some(player), 0).unwrap();
+
+    set_chase(&scene, enemy, Some(player), 1.0);
+
+    Level {
+        scene,
+        player,
+        enemy,
+        camera,
+    }
+}
+
+fn set_chase(
+    scene: &Scene,
+    game_object: GameObjectHandle,
+    target: Option<GameObjectHandle>,
+    speed: f32,
+) {
+    let chase = match game_object
+        .get_component::<ChaseComponent>(scene, GetFrom::This)
+        .unwrap()
+    {
+        Some(chase) => chase,
+        None => game_object.add_component::<ChaseComponent>(scene).unwrap(),
+    };
+
+    let ptr = scene.deref(chase).unwrap();
+    let mut aref_mut = ptr.borrow_mut();
+    aref_mut.target = target;
+    aref_mut.speed = speed;
+}
+
+fn serialize(level: &Level) -> Vec<u8> {
+    ris_scene::serialize(&level.scene, 0).unwrap()
+}
+
+fn base() -> Vec<u8> {
+    serialize(&level())
+}
+
+fn merged(outcome: MergeOutcome) -> Vec<u8> {
+    match outcome {
+        MergeOutcome::Merged(bytes) => bytes,
+        MergeOutcome::Conflicted(conflicts) => panic!("unexpected conflicts: {:?}", conflicts),
+    }
+}
+
+fn conflicts(outcome: MergeOutcome) -> Vec<String> {
+    match outcome {
+        MergeOutcome::Merged(_) => panic!("expected conflicts"),
+        MergeOutcome::Conflicted(conflicts) => conflicts.iter().map(|x| x.to_string()).collect(),
+    }
+}
+
+#[test]
+fn should_not_diff_equal_scenes() {
+    let changes = scene_diff::diff(registry(), &base(), &base()).unwrap();
+    assert!(changes.is_empty(), "{:?}", changes);
+}
+
+#[test]
+fn should_diff_game_objects() {
+    let level = level();
+    let scene = &level.scene;
+    level.camera.set_parent(scene, None, 0).unwrap();
+    level.enemy.destroy(scene);
+    let pickup = GameObjectHandle::new_static(scene, 0).unwrap();
+    pickup.set_name(scene, "pickup").unwrap();
+    pickup.set_parent(scene, Some(level.player), 0).unwrap();
+    level
+        .player
+        .set_position(scene, Vec3(1.0, 0.0, 0.0))
+        .unwrap();
+    level.player.set_active(scene, false).unwrap();
+
+    let changes = scene_diff::diff(registry(), &base(), &serialize(&level)).unwrap();
+
+    let expected = vec![
+        Change::PropertyChanged {
+            path: "player".to_string(),
+            property: "is active",
+            old: "true".to_string(),
+            new: "false".to_string(),
+        },
+        Change::PropertyChanged {
+            path: "player".to_string(),
+            property: "position",
+            old: format!("{:?}", Vec3::init(0.0)),
+            new: format!("{:?}", Vec3(1.0, 0.0, 0.0)),
+        },
+        Change::Removed {
+            path: "enemy".to_string(),
+        },
+        Change::Moved {
+            from: "player/camera".to_string(),
+            to: "camera".to_string(),
+        },
+        Change::Added {
+            path: "player/pickup".to_string(),
+        },
+    ];
+    assert_eq!(changes, expected);
+}
+
+#[test]
+fn should_diff_components() {
+    let level = level();
+    let scene = &level.scene;
+    set_chase(scene, level.enemy, Some(level.camera), 1.0);
+    set_chase(scene, level.player, None, 2.0);
+
+    let changes = scene_diff::diff(registry(), &base(), &serialize(&level)).unwrap();
+
+    assert_eq!(changes.len(), 2, "{:?}", changes);
+    assert_eq!(
+        changes[0],
+        Change::ComponentAdded {
+            path: "player".to_string(),
+            component: "ChaseComponent".to_string(),
+        },
+    );
+    let Change::ComponentChanged {
+        path,
+        component,
+        details,
+    } = &changes[1]
+    else {
+        panic!("unexpected change: {:?}", changes[1]);
+    };
+    assert_eq!(path, "enemy");
+    assert_eq!(component, "ChaseComponent");
+    assert_eq!(
+        details,
+        &vec!["references [player] -> [player/camera]".to_string()]
+    );
+}
+
+#[test]
+fn should_diff_component_fields_by_name() {
+    let level = level();
+    let scene = &level.scene;
+    set_chase(scene, level.enemy, Some(level.player), 3.0);
+
+    let changes = scene_diff::diff(registry(), &base(), &serialize(&level)).unwrap();
+
+    assert_eq!(
+        changes,
+        vec![Change::ComponentChanged {
+            path: "enemy".to_string(),
+            component: "ChaseComponent".to_string(),
+            details: vec!["speed: 1.0 -> 3.0".to_string()],
+        }],
+    );
+}
+
+#[test]
+fn should_diff_component_bytes_when_the_layout_changed() {
+    let level = level();
+    let scene = &level.scene;
+    set_chase(scene, level.enemy, None, 1.0);
+
+    let changes = scene_diff::diff(registry(), &base(), &serialize(&level)).unwrap();
+
+    assert_eq!(
+        changes,
+        vec![Change::ComponentChanged {
+            path: "enemy".to_string(),
+            component: "ChaseComponent".to_string(),
+            details: vec![
+                "references [player] -> []".to_string(),
+                "size 9 -> 5 bytes".to_string(),
+            ],
+        }],
+    );
+}
+
+#[test]
+fn should_not_diff_references_when_game_objects_are_reordered() {
+    let scene = Scene::new(SceneCreateInfo::with_single_static_chunk(registry())).unwrap();
+    scene.reserve_chunk().unwrap();
+    let decoy = GameObjectHandle::new_static(&scene, 0).unwrap();
+    decoy.set_name(&scene, "decoy").unwrap();
+    let player = GameObjectHandle::new_static(&scene, 0).unwrap();
+    player.set_name(&scene, "player").unwrap();
+    let enemy = GameObjectHandle::new_static(&scene, 0).unwrap();
+    enemy.set_name(&scene, "enemy").unwrap();
+    let camera = GameObjectHandle::new_static(&scene, 0).unwrap();
+    camera.set_name(&scene, "camera").unwrap();
+    camera.set_parent(&scene, Some(player), 0).unwrap();
+    set_chase(&scene, enemy, Some(player), 1.0);
+    let bytes = ris_scene::serialize(&scene, 0).unwrap();
+
+    let changes = scene_diff::diff(registry(), &base(), &bytes).unwrap();
+
+    assert_eq!(
+        changes,
+        vec![Change::Added {
+            path: "decoy".to_string()
+        }],
+    );
+}
+
+#[test]
+fn should_merge_changes_of_both_sides() {
+    let ours = level();
+    set_chase(&ours.scene, ours.enemy, Some(ours.player), 5.0);
+    ours.camera
+        .set_position(&ours.scene, Vec3(0.0, 1.0, 0.0))
+        .unwrap();
+
+    let theirs = level();
+    let pickup = GameObjectHandle::new_static(&theirs.scene, 0).unwrap();
+    pickup.set_name(&theirs.scene, "pickup").unwrap();
+    set_chase(&theirs.scene, pickup, Some(theirs.enemy), 0.0);
+    theirs
+        .camera
+        .set_scale(&theirs.scene, Vec3::init(2.0))
+        .unwrap();
+
+    let merged = merged(
+        scene_diff::merge(registry(), &base(), &serialize(&ours), &serialize(&theirs)).unwrap(),
+    );
+
+    let expected = level();
+    set_chase(&expected.scene, expected.enemy, Some(expected.player), 5.0);
+    expected
+        .camera
+        .set_position(&expected.scene, Vec3(0.0, 1.0, 0.0))
+        .unwrap();
+    expected
+        .camera
+        .set_scale(&expected.scene, Vec3::init(2.0))
+        .unwrap();
+    let pickup = GameObjectHandle::new_static(&expected.scene, 0).unwrap();
+    pickup.set_name(&expected.scene, "pickup").unwrap();
+    set_chase(&expected.scene, pickup, Some(expected.enemy), 0.0);
+
+    let changes = scene_diff::diff(registry(), &serialize(&expected), &merged).unwrap();
+    assert!(changes.is_empty(), "{:?}", changes);
+}
+
+#[test]
+fn should_merge_same_change_on_both_sides() {
+    let ours = level();
+    ours.enemy.destroy(&ours.scene);
+    let theirs = level();
+    theirs.enemy.destroy(&theirs.scene);
+
+    let merged = merged(
+        scene_diff::merge(registry(), &base(), &serialize(&ours), &serialize(&theirs)).unwrap(),
+    );
+
+    let changes = scene_diff::diff(registry(), &serialize(&ours), &merged).unwrap();
+    assert!(changes.is_empty(), "{:?}", changes);
+}
+
+#[test]
+fn should_merge_siblings_added_on_both_sides() {
+    // player
+    // |- camera
+    // |- hud
+    let with_hud = || {
+        let level = level();
+        let hud = GameObjectHandle::new_static(&level.scene, 0).unwrap();
+        hud.set_name(&level.scene, "hud").unwrap();
+        hud.set_parent(&level.scene, Some(level.player), 1).unwrap();
+        level
+    };
+    let add_child = |level: &Level, name: &str, sibling_index: usize| {
+        let child = GameObjectHandle::new_static(&level.scene, 0).unwrap();
+        child.set_name(&level.scene, name).unwrap();
+        child
+            .set_parent(&level.scene, Some(level.player), sibling_index)
+            .unwrap();
+    };
+
+    let ours = with_hud();
+    add_child(&ours, "light", 1);
+    add_child(&ours, "flashlight", 2);
+
+    let theirs = with_hud();
+    add_child(&theirs, "microphone", 1);
+
+    let merged = merged(
+        scene_diff::merge(
+            registry(),
+            &serialize(&with_hud()),
+            &serialize(&ours),
+            &serialize(&theirs),
+        )
+        .unwrap(),
+    );
+
+    let expected = with_hud();
+    add_child(&expected, "light", 1);
+    add_child(&expected, "flashlight", 2);
+    add_child(&expected, "microphone", 3);
+
+    let changes = scene_diff::diff(registry(), &serialize(&expected), &merged).unwrap();
+    assert!(changes.is_empty(), "{:?}", changes);
+}
+
+#[test]
+fn should_conflict_when_both_sides_change_the_same_property() {
+    let ours = level();
+    ours.player
+        .set_position(&ours.scene, Vec3(1.0, 0.0, 0.0))
+        .unwrap();
+    let theirs = level();
+    theirs
+        .player
+        .set_position(&theirs.scene, Vec3(2.0, 0.0, 0.0))
+        .unwrap();
+
+    let outcome =
+        scene_diff::merge(registry(), &base(), &serialize(&ours), &serialize(&theirs)).unwrap();
+
+    assert_eq!(
+        conflicts(outcome),
+        vec!["! player: position was changed on both sides".to_string()],
+    );
+}
+
+#[test]
+fn should_conflict_when_one_side_modifies_what_the_other_removes() {
+    let ours = level();
+    set_chase(&ours.scene, ours.enemy, Some(ours.player), 3.0);
+    let theirs = level();
+    theirs.enemy.destroy(&theirs.scene);
+
+    let outcome =
+        scene_diff::merge(registry(), &base(), &serialize(&ours), &serialize(&theirs)).unwrap();
+
+    assert_eq!(
+        conflicts(outcome),
+        vec!["! enemy: modified on one side, but removed on the other".to_string()],
+    );
+}
+
+#[test]
+fn should_conflict_when_reference_is_removed_on_the_other_side() {
+    let ours = level();
+    set_chase(&ours.scene, ours.player, Some(ours.camera), 1.0);
+    let theirs = level();
+    theirs.camera.destroy(&theirs.scene);
+
+    let outcome =
+        scene_diff::merge(registry(), &base(), &serialize(&ours), &serialize(&theirs)).unwrap();
+
+    assert_eq!(
+        conflicts(outcome),
+        vec!["! player: ChaseComponent references removed game object player/camera".to_string()],
+    );
+}
@@ -1,3 +1,4 @@
 pub mod channel;
+pub mod oneshot_channel;
 pub mod spin_lock;
 pub mod thread_pool;
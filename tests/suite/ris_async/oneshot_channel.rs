@@ -0,0 +1,41 @@
+use std::sync::Arc;
+
+#[test]
+fn should_send_and_receive() {
+    let (sender, receiver) = ris_async::oneshot_channel();
+    assert!(!sender.is_cancelled());
+    sender.send(42);
+    assert_eq!(receiver.receive().unwrap(), 42);
+}
+
+#[test]
+fn should_cancel_when_receiver_is_dropped() {
+    let (sender, receiver) = ris_async::oneshot_channel::<i32>();
+    drop(receiver);
+    assert!(sender.is_cancelled());
+}
+
+#[test]
+fn should_drop_value_that_was_never_received() {
+    let value = Arc::new(42);
+
+    let (sender, receiver) = ris_async::oneshot_channel();
+    sender.send(value.clone());
+    assert_eq!(Arc::strong_count(&value), 2);
+
+    drop(receiver);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
+
+#[test]
+fn should_not_drop_value_twice() {
+    let value = Arc::new(42);
+
+    let (sender, receiver) = ris_async::oneshot_channel();
+    sender.send(value.clone());
+    let received = receiver.receive().unwrap();
+    assert_eq!(Arc::strong_count(&value), 2);
+
+    drop(received);
+    assert_eq!(Arc::strong_count(&value), 1);
+}
@@ -89,3 +89,23 @@ fn should_run_when_dropped() {
         assert!(results.contains(&i));
     }
 }
+
+#[test]
+fn should_know_whether_caller_is_worker() {
+    assert!(!ThreadPool::is_worker());
+
+    let create_info = ThreadPoolCreateInfo {
+        buffer_capacity: 256,
+        cpu_count: 2,
+        threads: 2,
+        set_affinity: false,
+        use_parking: true,
+    };
+    let g = ThreadPool::init(create_info).unwrap();
+    let on_job = ThreadPool::submit(async { ThreadPool::is_worker() }).wait();
+    assert!(ThreadPool::is_worker());
+    assert!(on_job);
+
+    drop(g);
+    assert!(!ThreadPool::is_worker());
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use ris_core::god_job;
+use ris_data::ecs::components::mesh_component::MeshComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+
+fn alive_game_objects(scene: &Scene) -> usize {
+    scene
+        .query()
+        .include_inactive()
+        .game_objects()
+        .unwrap()
+        .len()
+}
+
+#[test]
+fn should_keep_applying_commands_after_one_failed() {
+    let mut info = SceneCreateInfo::empty();
+    info.dynamic_game_objects_per_page = 8;
+    info.components_per_page = 8;
+    info.registry = Some(Arc::new(Registry::new(Vec::new(), Vec::new()).unwrap()));
+    let scene = Scene::new(info).unwrap();
+    let destroyed = GameObjectHandle::new(&scene).unwrap();
+    destroyed.destroy(&scene);
+
+    // first frame
+    scene.commands.add_component::<MeshComponent>(destroyed);
+    god_job::apply_scene_commands(&scene);
+    assert_eq!(alive_game_objects(&scene), 0);
+
+    // next frame
+    scene.commands.spawn();
+    god_job::apply_scene_commands(&scene);
+    assert_eq!(alive_game_objects(&scene), 1);
+}
@@ -0,0 +1,499 @@
+use std::any::TypeId;
+use std::sync::Arc;
+
+use ris_core::ui_helper::history::EditorCommand;
+use ris_core::ui_helper::history::History;
+use ris_data::ecs::components::script_component::DynScriptComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::handle::ComponentHandle;
+use ris_data::ecs::id::Component;
+use ris_data::ecs::id::ComponentInspectData;
+use ris_data::ecs::id::GameObjectKind;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::script_prelude::*;
+use ris_math::vector::Vec3;
+
+#[derive(Debug, Default)]
+pub struct FollowComponent {
+    game_object: GameObjectHandle,
+    target: Option<GameObjectHandle>,
+}
+
+ris_data::declare_component!(FollowComponentHandle, FollowComponent);
+
+impl Component for FollowComponent {
+    fn destroy(&mut self, _scene: &Scene) {}
+
+    fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+
+    fn game_object_mut(&mut self) -> &mut GameObjectHandle {
+        &mut self.game_object
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        ris_io::write_bool(stream, self.target.is_some())?;
+        if let Some(target) = self.target {
+            stream.write_game_object(target)?;
+        }
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        if ris_io::read_bool(stream)? {
+            self.target = Some(stream.read_game_object()?);
+        }
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ComponentInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Debug, Default)]
+struct HealthScript {
+    health: i32,
+}
+
+impl Script for HealthScript {
+    fn start(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, _data: ScriptUpdateData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn end(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        ris_io::write_i32(stream, self.health)?;
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        self.health = ris_io::read_i32(stream)?;
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ScriptInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
+
+fn scene() -> Scene {
+    let registry = Registry::new(
+        vec![Registry::component::<FollowComponent>().unwrap()],
+        vec![Registry::script::<HealthScript>().unwrap()],
+    )
+    .unwrap();
+    let mut scene_create_info = SceneCreateInfo::empty();
+    scene_create_info.dynamic_game_objects_per_page = 8;
+    scene_create_info.registry = Some(Arc::new(registry));
+    Scene::new(scene_create_info).unwrap()
+}
+
+fn new_game_object(scene: &Scene, name: &str) -> GameObjectHandle {
+    let game_object = GameObjectHandle::new(scene).unwrap();
+    game_object.set_name(scene, name).unwrap();
+    game_object
+}
+
+fn names(scene: &Scene, handles: Vec<GameObjectHandle>) -> Vec<String> {
+    handles.iter().map(|x| x.name(scene).unwrap()).collect()
+}
+
+fn find(scene: &Scene, name: &str) -> GameObjectHandle {
+    scene
+        .query()
+        .include_inactive()
+        .game_objects()
+        .unwrap()
+        .into_iter()
+        .find(|x| x.name(scene).unwrap() == name)
+        .unwrap()
+}
+
+fn follow_factory(scene: &Scene) -> usize {
+    scene
+        .registry
+        .component_factories()
+        .iter()
+        .position(|x| x.component_id() == TypeId::of::<FollowComponent>())
+        .unwrap()
+}
+
+fn component_types(scene: &Scene, game_object: GameObjectHandle) -> Vec<TypeId> {
+    let components = game_object.components(scene).unwrap();
+    components.iter().map(|x| x.type_id()).collect()
+}
+
+fn labels(history: &History) -> (Vec<String>, Vec<String>) {
+    let undo = history.undo_entries().map(|x| x.label.clone()).collect();
+    let redo = history.redo_entries().map(|x| x.label.clone()).collect();
+    (undo, redo)
+}
+
+#[test]
+fn should_undo_and_redo_properties() {
+    let scene = scene();
+    let player = new_game_object(&scene, "player");
+    let mut history = History::default();
+
+    let command = EditorCommand::set_name(&scene, player, "hero").unwrap();
+    history.execute(&scene, command).unwrap();
+    history.seal();
+    let command = EditorCommand::set_active(&scene, player, false).unwrap();
+    history.execute(&scene, command).unwrap();
+    history.seal();
+    let command = EditorCommand::set_position(&scene, player, Vec3(1.0, 2.0, 3.0)).unwrap();
+    history.execute(&scene, command).unwrap();
+    history.seal();
+
+    assert_eq!(player.name(&scene).unwrap(), "hero");
+    assert!(!player.is_active(&scene).unwrap());
+    assert_eq!(player.position(&scene).unwrap(), Vec3(1.0, 2.0, 3.0));
+
+    history.undo(&scene).unwrap().unwrap();
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(player.position(&scene).unwrap(), Vec3::init(0.0));
+    assert!(player.is_active(&scene).unwrap());
+    assert_eq!(player.name(&scene).unwrap(), "hero");
+
+    history.redo(&scene).unwrap().unwrap();
+    assert!(!player.is_active(&scene).unwrap());
+    assert_eq!(player.position(&scene).unwrap(), Vec3::init(0.0));
+
+    history.undo(&scene).unwrap().unwrap();
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(player.name(&scene).unwrap(), "player");
+    assert!(history.undo(&scene).unwrap().is_none());
+
+    assert_eq!(
+        labels(&history),
+        (
+            Vec::new(),
+            vec![
+                "rename \"player\" to \"hero\"".to_string(),
+                "deactivate \"hero\"".to_string(),
+                "move \"hero\"".to_string(),
+            ]
+        ),
+    );
+}
+
+#[test]
+fn should_merge_consecutive_edits_until_sealed() {
+    let scene = scene();
+    let player = new_game_object(&scene, "player");
+    let enemy = new_game_object(&scene, "enemy");
+    let mut history = History::default();
+
+    // dragging over multiple frames
+    for i in 1..=3 {
+        let position = Vec3(i as f32, 0.0, 0.0);
+        let command = EditorCommand::set_position(&scene, player, position).unwrap();
+        history.execute(&scene, command).unwrap();
+    }
+
+    // a different game object is not merged
+    let command = EditorCommand::set_position(&scene, enemy, Vec3::init(5.0)).unwrap();
+    history.execute(&scene, command).unwrap();
+    history.seal();
+
+    let command = EditorCommand::set_position(&scene, enemy, Vec3::init(6.0)).unwrap();
+    history.execute(&scene, command).unwrap();
+
+    assert_eq!(history.undo_entries().count(), 3);
+
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(enemy.position(&scene).unwrap(), Vec3::init(5.0));
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(enemy.position(&scene).unwrap(), Vec3::init(0.0));
+    assert_eq!(player.position(&scene).unwrap(), Vec3(3.0, 0.0, 0.0));
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(player.position(&scene).unwrap(), Vec3::init(0.0));
+}
+
+#[test]
+fn should_undo_reparent_at_previous_sibling_index() {
+    let scene = scene();
+    let parent = new_game_object(&scene, "parent");
+    let a = new_game_object(&scene, "a");
+    let b = new_game_object(&scene, "b");
+    let c = new_game_object(&scene, "c");
+    for (i, child) in [a, b, c].into_iter().enumerate() {
+        child.set_parent(&scene, Some(parent), i).unwrap();
+    }
+    let mut history = History::default();
+
+    let command = EditorCommand::set_parent(&scene, b, None, usize::MAX).unwrap();
+    history.execute(&scene, command).unwrap();
+    assert_eq!(names(&scene, parent.children(&scene).unwrap()), ["a", "c"]);
+
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(
+        names(&scene, parent.children(&scene).unwrap()),
+        ["a", "b", "c"]
+    );
+    assert_eq!(labels(&history).1, ["unparent \"b\""]);
+}
+
+#[test]
+fn should_restore_destroyed_game_objects() {
+    let scene = scene();
+    let root = new_game_object(&scene, "root");
+    let player = new_game_object(&scene, "player");
+    let weapon = new_game_object(&scene, "weapon");
+    let camera = new_game_object(&scene, "camera");
+    player.set_parent(&scene, Some(root), 0).unwrap();
+    weapon.set_parent(&scene, Some(player), 0).unwrap();
+    camera.set_parent(&scene, Some(root), 1).unwrap();
+    let follow = camera.add_component::<FollowComponent>(&scene).unwrap();
+    scene.deref(follow).unwrap().borrow_mut().target = Some(weapon);
+    let mut history = History::default();
+
+    let command = EditorCommand::set_position(&scene, weapon, Vec3::init(1.0)).unwrap();
+    history.execute(&scene, command).unwrap();
+    history
+        .execute(&scene, EditorCommand::destroy(player))
+        .unwrap();
+    assert!(!player.is_alive(&scene));
+    assert!(!weapon.is_alive(&scene));
+
+    let remap = history.undo(&scene).unwrap().unwrap();
+
+    let restored_player = find(&scene, "player");
+    let restored_weapon = find(&scene, "weapon");
+    assert_eq!(
+        remap,
+        vec![(player, restored_player), (weapon, restored_weapon)]
+    );
+    assert_eq!(
+        names(&scene, root.children(&scene).unwrap()),
+        ["player", "camera"]
+    );
+    assert_eq!(
+        restored_player.children(&scene).unwrap(),
+        vec![restored_weapon]
+    );
+    assert_eq!(restored_weapon.position(&scene).unwrap(), Vec3::init(1.0));
+
+    // earlier commands operate on the restored game objects
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(restored_weapon.position(&scene).unwrap(), Vec3::init(0.0));
+
+    history.redo(&scene).unwrap().unwrap();
+    history.redo(&scene).unwrap().unwrap();
+    assert!(!restored_player.is_alive(&scene));
+    assert!(!restored_weapon.is_alive(&scene));
+    assert_eq!(names(&scene, root.children(&scene).unwrap()), ["camera"]);
+}
+
+#[test]
+fn should_restore_references_to_game_objects_outside_of_snapshot() {
+    let scene = scene();
+    let player = new_game_object(&scene, "player");
+    let camera = new_game_object(&scene, "camera");
+    let follow = camera.add_component::<FollowComponent>(&scene).unwrap();
+    scene.deref(follow).unwrap().borrow_mut().target = Some(player);
+    let mut history = History::default();
+
+    history
+        .execute(&scene, EditorCommand::destroy(camera))
+        .unwrap();
+    history.undo(&scene).unwrap().unwrap();
+
+    let restored_camera = find(&scene, "camera");
+    let restored_follow = restored_camera
+        .get_component::<FollowComponent>(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap();
+    let target = scene.deref(restored_follow).unwrap().borrow().target;
+    assert_eq!(target, Some(player));
+}
+
+#[test]
+fn should_undo_and_redo_create() {
+    let scene = scene();
+    let parent = new_game_object(&scene, "parent");
+    let mut history = History::default();
+
+    let command = EditorCommand::create(GameObjectKind::Dynamic, Some(parent));
+    history.execute(&scene, command).unwrap();
+    history.seal();
+    let child = parent.children(&scene).unwrap()[0];
+    let command = EditorCommand::set_name(&scene, child, "child").unwrap();
+    history.execute(&scene, command).unwrap();
+
+    history.undo(&scene).unwrap().unwrap();
+    history.undo(&scene).unwrap().unwrap();
+    assert!(!child.is_alive(&scene));
+    assert!(parent.children(&scene).unwrap().is_empty());
+
+    let remap = history.redo(&scene).unwrap().unwrap();
+    let recreated = parent.children(&scene).unwrap()[0];
+    assert_eq!(remap, vec![(child, recreated)]);
+
+    history.redo(&scene).unwrap().unwrap();
+    assert_eq!(recreated.name(&scene).unwrap(), "child");
+    assert_eq!(
+        labels(&history).0,
+        [
+            "create child of \"parent\"",
+            "rename \"game object\" to \"child\""
+        ]
+    );
+}
+
+#[test]
+fn should_undo_and_redo_adding_components_and_scripts() {
+    let scene = scene();
+    let player = new_game_object(&scene, "player");
+    let mut history = History::default();
+
+    let command = EditorCommand::add_component(player, follow_factory(&scene));
+    history.execute(&scene, command).unwrap();
+    history.seal();
+    let command = EditorCommand::attach_script(player, 0);
+    history.execute(&scene, command).unwrap();
+
+    let expected = vec![
+        TypeId::of::<FollowComponent>(),
+        TypeId::of::<DynScriptComponent>(),
+    ];
+    assert_eq!(component_types(&scene, player), expected);
+
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(
+        component_types(&scene, player),
+        [TypeId::of::<FollowComponent>()]
+    );
+    history.undo(&scene).unwrap().unwrap();
+    assert!(player.components(&scene).unwrap().is_empty());
+
+    history.redo(&scene).unwrap().unwrap();
+    history.redo(&scene).unwrap().unwrap();
+    assert_eq!(component_types(&scene, player), expected);
+    let health = player
+        .get_script::<HealthScript>(&scene, GetFrom::This)
+        .unwrap();
+    assert!(health.is_some());
+
+    assert_eq!(
+        labels(&history).0,
+        [
+            "add FollowComponent to \"player\"",
+            "attach HealthScript to \"player\"",
+        ]
+    );
+}
+
+#[test]
+fn should_restore_removed_components_and_scripts_at_their_index() {
+    let scene = scene();
+    let player = new_game_object(&scene, "player");
+    let enemy = new_game_object(&scene, "enemy");
+    let follow = player.add_component::<FollowComponent>(&scene).unwrap();
+    scene.deref(follow).unwrap().borrow_mut().target = Some(enemy);
+    let health = player.add_script::<HealthScript>(&scene).unwrap();
+    health.script_mut(&scene).unwrap().health = 7;
+    player.add_component::<FollowComponent>(&scene).unwrap();
+    let mut history = History::default();
+
+    let command =
+        EditorCommand::remove_component(&scene, player, follow.to_dyn_component()).unwrap();
+    history.execute(&scene, command).unwrap();
+    history.seal();
+    let command =
+        EditorCommand::detach_script(&scene, player, health.dyn_handle().to_dyn_component())
+            .unwrap();
+    history.execute(&scene, command).unwrap();
+    assert_eq!(
+        component_types(&scene, player),
+        [TypeId::of::<FollowComponent>()]
+    );
+    assert!(scene.deref(follow).is_err());
+
+    history.undo(&scene).unwrap().unwrap();
+    history.undo(&scene).unwrap().unwrap();
+    assert_eq!(
+        component_types(&scene, player),
+        [
+            TypeId::of::<FollowComponent>(),
+            TypeId::of::<DynScriptComponent>(),
+            TypeId::of::<FollowComponent>(),
+        ]
+    );
+
+    let restored_follow = player
+        .get_component::<FollowComponent>(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap();
+    let target = scene.deref(restored_follow).unwrap().borrow().target;
+    assert_eq!(target, Some(enemy));
+
+    let restored_health = player
+        .get_script::<HealthScript>(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap();
+    assert_eq!(restored_health.script(&scene).unwrap().health, 7);
+
+    assert_eq!(
+        labels(&history).1,
+        [
+            "remove FollowComponent from \"player\"",
+            "detach HealthScript from \"player\"",
+        ]
+    );
+
+    history.redo(&scene).unwrap().unwrap();
+    history.redo(&scene).unwrap().unwrap();
+    assert_eq!(
+        component_types(&scene, player),
+        [TypeId::of::<FollowComponent>()]
+    );
+}
+
+#[test]
+fn should_clear_redo_stack_when_executing() {
+    let scene = scene();
+    let player = new_game_object(&scene, "player");
+    let mut history = History::default();
+
+    let command = EditorCommand::set_scale(&scene, player, Vec3::init(2.0)).unwrap();
+    history.execute(&scene, command).unwrap();
+    history.undo(&scene).unwrap().unwrap();
+    assert!(history.can_redo());
+
+    let command = EditorCommand::set_scale(&scene, player, Vec3::init(3.0)).unwrap();
+    history.execute(&scene, command).unwrap();
+
+    assert!(!history.can_redo());
+    assert!(history.redo(&scene).unwrap().is_none());
+    assert_eq!(player.scale(&scene).unwrap(), Vec3::init(3.0));
+}
+
+#[test]
+fn should_forget_oldest_entries_when_full() {
+    let scene = scene();
+    let player = new_game_object(&scene, "player");
+    let mut history = History::new(2);
+
+    for i in 0..5 {
+        let command = EditorCommand::set_name(&scene, player, i.to_string()).unwrap();
+        history.execute(&scene, command).unwrap();
+        history.seal();
+    }
+
+    while history.undo(&scene).unwrap().is_some() {}
+
+    assert_eq!(player.name(&scene).unwrap(), "2");
+    assert_eq!(history.redo_entries().count(), 2);
+}
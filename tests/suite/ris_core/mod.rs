@@ -0,0 +1,2 @@
+pub mod god_job;
+pub mod history;
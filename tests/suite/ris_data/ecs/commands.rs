@@ -0,0 +1,218 @@
+use std::sync::Arc;
+
+use ris_async::ThreadPool;
+use ris_async::ThreadPoolCreateInfo;
+use ris_data::ecs::commands::CommandTarget;
+use ris_data::ecs::components::mesh_component::MeshComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::decl::MeshComponentHandle;
+use ris_data::ecs::handle::ComponentHandle;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::schedule::ScriptSchedule;
+use ris_data::ecs::script_prelude::*;
+use ris_data::gameloop::frame::FrameCalculator;
+use ris_data::god_state::GodState;
+use ris_data::settings::Settings;
+use ris_data::settings::simulation_settings::SimulationSettings;
+
+#[derive(Debug, Default)]
+struct SpawnerScript;
+
+impl Script for SpawnerScript {
+    fn start(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, data: ScriptUpdateData) -> RisResult<()> {
+        let commands = &data.state.scene.commands;
+        let spawned = commands.spawn();
+        commands.set_parent(spawned, Some(data.game_object.into()), 0);
+        Ok(())
+    }
+
+    fn end(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn serialize(&mut self, _stream: &mut SceneWriter) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn deserialize(&mut self, _stream: &mut SceneReader) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn inspect(&mut self, _data: ScriptInspectData) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn access(&self, _game_object: GameObjectHandle) -> ScriptAccess {
+        ScriptAccess::default()
+    }
+}
+
+fn scene_create_info() -> SceneCreateInfo {
+    let mut info = SceneCreateInfo::empty();
+    info.dynamic_game_objects_per_page = 8;
+    info.components_per_page = 8;
+    info.registry = Some(Arc::new(
+        Registry::new(
+            Vec::new(),
+            vec![Registry::script::<SpawnerScript>().unwrap()],
+        )
+        .unwrap(),
+    ));
+    info
+}
+
+fn alive_game_objects(scene: &Scene) -> usize {
+    scene
+        .query()
+        .include_inactive()
+        .game_objects()
+        .unwrap()
+        .len()
+}
+
+#[test]
+fn should_not_change_scene_before_commands_are_applied() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+
+    scene.commands.spawn();
+    scene.commands.destroy(g);
+
+    assert_eq!(scene.commands.len(), 2);
+    assert!(g.is_alive(&scene));
+    assert_eq!(alive_game_objects(&scene), 1);
+
+    let spawned = scene.apply_commands().unwrap();
+
+    assert!(scene.commands.is_empty());
+    assert!(!g.is_alive(&scene));
+    assert_eq!(spawned.len(), 1);
+    assert!(spawned[0].is_alive(&scene));
+    assert_eq!(alive_game_objects(&scene), 1);
+}
+
+#[test]
+fn should_operate_on_spawned_game_objects() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let root = GameObjectHandle::new(&scene).unwrap();
+
+    let child = scene.commands.spawn();
+    scene.commands.set_parent(child, Some(root.into()), 0);
+    scene.commands.add_component::<MeshComponent>(child);
+    scene.commands.add_script::<SpawnerScript>(child);
+    scene.commands.with(child, |scene, game_object| {
+        game_object.set_name(scene, "child")?;
+        Ok(())
+    });
+
+    let spawned = scene.apply_commands().unwrap();
+    let child = spawned[0];
+
+    assert_eq!(child.parent(&scene).unwrap(), Some(root));
+    assert_eq!(child.name(&scene).unwrap(), "child");
+    assert!(
+        child
+            .get_component::<MeshComponent>(&scene, GetFrom::This)
+            .unwrap()
+            .is_some()
+    );
+    assert!(
+        child
+            .get_script::<SpawnerScript>(&scene, GetFrom::This)
+            .unwrap()
+            .is_some()
+    );
+}
+
+#[test]
+fn should_apply_commands_in_order() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+    let mesh: MeshComponentHandle = g.add_component(&scene).unwrap().into();
+
+    let spawned = scene.commands.spawn();
+    scene.commands.destroy(spawned);
+    scene.commands.remove_component(g, mesh.to_dyn_component());
+    scene.commands.add_component::<MeshComponent>(g);
+
+    let spawned = scene.apply_commands().unwrap();
+
+    assert!(!spawned[0].is_alive(&scene));
+    assert!(!mesh.is_alive(&scene));
+    assert_eq!(g.components(&scene).unwrap().len(), 1);
+}
+
+#[test]
+fn should_not_resolve_spawned_game_object_of_previous_application() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+
+    let spawned = scene.commands.spawn();
+    scene.apply_commands().unwrap();
+
+    scene.commands.destroy(CommandTarget::Spawned(spawned));
+    let result = scene.apply_commands();
+
+    assert!(result.is_err());
+    assert_eq!(alive_game_objects(&scene), 1);
+}
+
+#[test]
+fn should_apply_remaining_commands_when_one_fails() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+    g.destroy(&scene);
+
+    scene.commands.add_component::<MeshComponent>(g);
+    scene.commands.spawn();
+    let result = scene.apply_commands();
+
+    assert!(result.is_err());
+    assert_eq!(alive_game_objects(&scene), 1);
+}
+
+#[test]
+fn should_record_commands_from_parallel_scripts() {
+    let count = ris_util::testing::miri_choose(32, 4);
+
+    let create_info = ThreadPoolCreateInfo {
+        buffer_capacity: 64,
+        cpu_count: 4,
+        threads: 4,
+        set_affinity: false,
+        use_parking: false,
+    };
+    let _g = ThreadPool::init(create_info).unwrap();
+
+    let state = GodState::new(Settings::default(), scene_create_info()).unwrap();
+    let scene = &state.scene;
+
+    let mut game_objects = Vec::new();
+    let mut scripts = Vec::new();
+    for _ in 0..count {
+        let g = GameObjectHandle::new(scene).unwrap();
+        let script = g.add_script::<SpawnerScript>(scene).unwrap();
+        game_objects.push(g);
+        scripts.push(script.dyn_handle());
+    }
+
+    let schedule = ScriptSchedule::new(scene, &scripts).unwrap();
+    let frame = FrameCalculator::default().bump_and_create_frame(&SimulationSettings::default());
+    schedule.run(frame, &state).unwrap();
+
+    assert_eq!(schedule.batches().len(), 1);
+    assert_eq!(alive_game_objects(scene), count);
+
+    let spawned = scene.apply_commands().unwrap();
+
+    assert_eq!(spawned.len(), count);
+    assert_eq!(alive_game_objects(scene), count * 2);
+    for g in game_objects {
+        assert_eq!(g.children(scene).unwrap().len(), 1);
+    }
+}
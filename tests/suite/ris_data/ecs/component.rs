@@ -1,18 +1,65 @@
 use std::sync::Arc;
 
+use ris_data::ecs::components::mesh_component::MeshComponent;
 use ris_data::ecs::decl::GameObjectHandle;
 use ris_data::ecs::decl::MeshComponentHandle;
+use ris_data::ecs::error::EcsError;
 use ris_data::ecs::game_object::GetFrom;
 use ris_data::ecs::handle::ComponentHandle;
+use ris_data::ecs::id::Component;
+use ris_data::ecs::id::ComponentInspectData;
 use ris_data::ecs::registry::Registry;
 use ris_data::ecs::scene::Scene;
 use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::scene_stream::SceneReader;
+use ris_data::ecs::scene_stream::SceneWriter;
+use ris_error::RisResult;
+
+#[derive(Debug, Default)]
+pub struct HealthComponent {
+    game_object: GameObjectHandle,
+    health: i32,
+}
+
+ris_data::declare_component!(HealthComponentHandle, HealthComponent);
+
+impl Component for HealthComponent {
+    fn destroy(&mut self, _scene: &Scene) {}
+
+    fn game_object(&self) -> GameObjectHandle {
+        self.game_object
+    }
+
+    fn game_object_mut(&mut self) -> &mut GameObjectHandle {
+        &mut self.game_object
+    }
+
+    fn serialize(&mut self, stream: &mut SceneWriter) -> RisResult<()> {
+        ris_io::write_i32(stream, self.health)?;
+        Ok(())
+    }
+
+    fn deserialize(&mut self, stream: &mut SceneReader) -> RisResult<()> {
+        self.health = ris_io::read_i32(stream)?;
+        Ok(())
+    }
+
+    fn inspect(&mut self, _data: ComponentInspectData) -> RisResult<()> {
+        Ok(())
+    }
+}
 
 fn scene_create_info() -> SceneCreateInfo {
     let mut info = SceneCreateInfo::empty();
-    info.dynamic_game_objects = 8;
-    info.mesh_renderer_components = 8;
-    info.registry = Some(Arc::new(Registry::new(Vec::new()).unwrap()));
+    info.dynamic_game_objects_per_page = 8;
+    info.components_per_page = 8;
+    info.registry = Some(Arc::new(
+        Registry::new(
+            vec![Registry::component::<HealthComponent>().unwrap()],
+            Vec::new(),
+        )
+        .unwrap(),
+    ));
     info
 }
 
@@ -24,7 +71,11 @@ fn should_add() {
     let mesh: MeshComponentHandle = g.add_component(&scene).unwrap().into();
 
     let index = mesh.scene_id().index;
-    let ptr = &scene.mesh_renderer_components[index];
+    let ptr = scene
+        .component_pool::<MeshComponent>()
+        .unwrap()
+        .get(index)
+        .unwrap();
     let mesh_: MeshComponentHandle = ptr.borrow().handle.into();
 
     assert!(ptr.borrow().is_alive);
@@ -36,7 +87,7 @@ fn build_scene() -> (Scene, Vec<GameObjectHandle>, Vec<MeshComponentHandle>) {
     let mut game_objects = Vec::new();
     let mut mesh_components = Vec::new();
 
-    for _ in 0..scene.dynamic_game_objects.len() {
+    for _ in 0..8 {
         let game_object = GameObjectHandle::new(&scene).unwrap();
         let mesh: MeshComponentHandle = game_object.add_component(&scene).unwrap().into();
 
@@ -268,3 +319,68 @@ fn should_destroy_components_when_game_object_is_destroyed() {
     g.destroy(&scene);
     assert!(!m.is_alive(&scene));
 }
+
+#[test]
+fn should_add_user_defined_component() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+
+    let health: HealthComponentHandle = g.add_component(&scene).unwrap().into();
+    scene.deref(health.0).unwrap().borrow_mut().health = 42;
+
+    let actual: HealthComponentHandle = g
+        .get_component(&scene, GetFrom::This)
+        .unwrap()
+        .unwrap()
+        .into();
+
+    assert_eq!(health, actual);
+    assert_eq!(health.game_object(&scene).unwrap(), g);
+    assert_eq!(scene.deref(actual.0).unwrap().borrow().health, 42);
+}
+
+#[test]
+fn should_store_user_defined_components_in_their_own_pool() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+
+    let mesh: MeshComponentHandle = g.add_component(&scene).unwrap().into();
+    let health: HealthComponentHandle = g.add_component(&scene).unwrap().into();
+
+    // both are the first of their kind, thus they occupy the same index in different pools
+    assert_eq!(mesh.scene_id(), health.scene_id());
+
+    let pool = scene.component_pool::<HealthComponent>().unwrap();
+    let alive = pool.iter().filter(|x| x.borrow().is_alive).count();
+    assert_eq!(alive, 1);
+
+    g.destroy(&scene);
+    assert!(!health.is_alive(&scene));
+    assert!(!mesh.is_alive(&scene));
+}
+
+#[test]
+fn should_not_add_unregistered_component() {
+    let mut info = scene_create_info();
+    info.registry = Some(Arc::new(Registry::new(Vec::new(), Vec::new()).unwrap()));
+    let scene = Scene::new(info).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+
+    let result = g.add_component::<HealthComponent>(&scene);
+
+    assert_eq!(result, Err(EcsError::ComponentIsNotRegistered));
+    assert!(g.components(&scene).unwrap().is_empty());
+}
+
+#[test]
+fn should_not_register_component_twice() {
+    let result = Registry::new(
+        vec![
+            Registry::component::<HealthComponent>().unwrap(),
+            Registry::component::<HealthComponent>().unwrap(),
+        ],
+        Vec::new(),
+    );
+
+    assert!(result.is_err());
+}
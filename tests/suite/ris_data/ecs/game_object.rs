@@ -4,6 +4,7 @@ use ris_data::ecs::decl::GameObjectHandle;
 use ris_data::ecs::registry::Registry;
 use ris_data::ecs::scene::Scene;
 use ris_data::ecs::scene::SceneCreateInfo;
+use ris_math::affine;
 use ris_math::quaternion::Quat;
 use ris_math::vector::Vec3;
 use ris_util::assert_quat_feq;
@@ -11,8 +12,8 @@ use ris_util::assert_vec3_feq;
 
 fn scene_create_info() -> SceneCreateInfo {
     let mut info = SceneCreateInfo::empty();
-    info.dynamic_game_objects = 5;
-    info.registry = Some(Arc::new(Registry::new(Vec::new()).unwrap()));
+    info.dynamic_game_objects_per_page = 5;
+    info.registry = Some(Arc::new(Registry::new(Vec::new(), Vec::new()).unwrap()));
     info
 }
 
@@ -36,7 +37,7 @@ fn should_not_deref_destroyed_handle() {
 }
 
 #[test]
-fn should_not_create_when_out_of_memory() {
+fn should_grow_when_page_is_full() {
     let scene = Scene::new(scene_create_info()).unwrap();
     let g0 = GameObjectHandle::new(&scene);
     let g1 = GameObjectHandle::new(&scene);
@@ -50,7 +51,48 @@ fn should_not_create_when_out_of_memory() {
     assert!(g2.is_ok());
     assert!(g3.is_ok());
     assert!(g4.is_ok());
-    assert!(g5.is_err());
+    assert!(g5.is_ok());
+    assert_eq!(scene.dynamic_game_objects.page_count(), 2);
+    assert_eq!(scene.dynamic_game_objects.len(), 10);
+}
+
+#[test]
+fn should_not_invalidate_pointers_when_growing() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+    g.set_name(&scene, "first").unwrap();
+
+    let ptr = scene.deref(g.into()).unwrap();
+    let addr = &*ptr as *const _;
+    drop(ptr);
+
+    for _ in 0..100 {
+        GameObjectHandle::new(&scene).unwrap();
+    }
+
+    let ptr_after_growth = scene.deref(g.into()).unwrap();
+    assert_eq!(addr, &*ptr_after_growth as *const _);
+    assert_eq!(g.name(&scene).unwrap(), "first");
+}
+
+#[test]
+fn should_not_deref_stale_handle_after_growing() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+    g.destroy(&scene);
+
+    let mut handles = Vec::new();
+    for _ in 0..100 {
+        handles.push(GameObjectHandle::new(&scene).unwrap());
+    }
+
+    assert!(!g.is_alive(&scene));
+    assert!(scene.deref(g.into()).is_err());
+
+    let reused = handles[0];
+    assert_eq!(reused.scene_id(), g.scene_id());
+    assert_ne!(reused, g);
+    assert!(reused.is_alive(&scene));
 }
 
 #[test]
@@ -110,6 +152,70 @@ fn should_get_and_set_scale() {
     assert_eq!(expected2, actual2);
 }
 
+#[test]
+fn should_interpolate_transforms_set_during_fixed_update() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+    g.set_position(&scene, Vec3(2.0, 0.0, 0.0)).unwrap();
+
+    scene.begin_fixed_update();
+    g.set_position(&scene, Vec3(4.0, 0.0, 0.0)).unwrap();
+    scene.end_fixed_update();
+
+    let model = g.interpolated_model(&scene, 0.25).unwrap();
+    assert_vec3_feq!(affine::to_translation(model), Vec3(2.5, 0.0, 0.0));
+
+    let model = g.interpolated_model(&scene, 1.0).unwrap();
+    assert_vec3_feq!(affine::to_translation(model), Vec3(4.0, 0.0, 0.0));
+}
+
+#[test]
+fn should_only_interpolate_the_last_fixed_update() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let moved_once = GameObjectHandle::new(&scene).unwrap();
+    let moved_twice = GameObjectHandle::new(&scene).unwrap();
+
+    scene.begin_fixed_update();
+    moved_once
+        .set_position(&scene, Vec3(2.0, 0.0, 0.0))
+        .unwrap();
+    moved_twice
+        .set_position(&scene, Vec3(2.0, 0.0, 0.0))
+        .unwrap();
+    scene.end_fixed_update();
+
+    scene.begin_fixed_update();
+    moved_twice
+        .set_position(&scene, Vec3(3.0, 0.0, 0.0))
+        .unwrap();
+    moved_twice
+        .set_position(&scene, Vec3(4.0, 0.0, 0.0))
+        .unwrap();
+    scene.end_fixed_update();
+
+    let model = moved_once.interpolated_model(&scene, 0.5).unwrap();
+    assert_vec3_feq!(affine::to_translation(model), Vec3(2.0, 0.0, 0.0));
+
+    let model = moved_twice.interpolated_model(&scene, 0.5).unwrap();
+    assert_vec3_feq!(affine::to_translation(model), Vec3(3.0, 0.0, 0.0));
+}
+
+#[test]
+fn should_not_interpolate_transforms_set_outside_fixed_update() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g = GameObjectHandle::new(&scene).unwrap();
+
+    scene.begin_fixed_update();
+    g.set_position(&scene, Vec3(4.0, 0.0, 0.0)).unwrap();
+    scene.end_fixed_update();
+
+    // teleport
+    g.set_position(&scene, Vec3(8.0, 0.0, 0.0)).unwrap();
+
+    let model = g.interpolated_model(&scene, 0.25).unwrap();
+    assert_vec3_feq!(affine::to_translation(model), Vec3(8.0, 0.0, 0.0));
+}
+
 #[test]
 fn should_set_parent() {
     let scene = Scene::new(scene_create_info()).unwrap();
@@ -229,7 +335,7 @@ fn should_not_assign_destroyed_parent() {
 fn should_not_set_parent_from_another_chunk() {
     let mut info = scene_create_info();
     info.static_chunks = 2;
-    info.game_objects_per_static_chunk = 2;
+    info.static_game_objects_per_page = 2;
     let scene = Scene::new(info).unwrap();
     let dynmic_parent = GameObjectHandle::new(&scene).unwrap();
     let static_0_parent = GameObjectHandle::new_static(&scene, 0).unwrap();
@@ -317,6 +423,30 @@ fn should_set_sibling_index() {
     assert_eq!(children[3], g2);
 }
 
+#[test]
+fn should_get_subtree_depth_first() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let root = GameObjectHandle::new(&scene).unwrap();
+    let g1 = GameObjectHandle::new(&scene).unwrap();
+    let g2 = GameObjectHandle::new(&scene).unwrap();
+    let g3 = GameObjectHandle::new(&scene).unwrap();
+    let g4 = GameObjectHandle::new(&scene).unwrap();
+
+    // root
+    // |- g1
+    // |  |- g3
+    // |- g2
+    //    |- g4
+    g1.set_parent(&scene, Some(root), usize::MAX).unwrap();
+    g2.set_parent(&scene, Some(root), usize::MAX).unwrap();
+    g3.set_parent(&scene, Some(g1), usize::MAX).unwrap();
+    g4.set_parent(&scene, Some(g2), usize::MAX).unwrap();
+
+    assert_eq!(root.subtree(&scene).unwrap(), vec![root, g1, g3, g2, g4]);
+    assert_eq!(g2.subtree(&scene).unwrap(), vec![g2, g4]);
+    assert_eq!(g3.subtree(&scene).unwrap(), vec![g3]);
+}
+
 #[test]
 fn should_destroy_child() {
     let scene = Scene::new(scene_create_info()).unwrap();
@@ -1,4 +1,7 @@
+pub mod commands;
 pub mod component;
 pub mod game_object;
 pub mod handle;
+pub mod query;
+pub mod schedule;
 pub mod script;
@@ -0,0 +1,231 @@
+use std::sync::Arc;
+
+use ris_data::ecs::components::mesh_component::MeshComponent;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::decl::MeshComponentHandle;
+use ris_data::ecs::error::EcsError;
+use ris_data::ecs::id::GameObjectKind;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::Scene;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::script_prelude::*;
+
+fn scene_create_info() -> SceneCreateInfo {
+    let mut info = SceneCreateInfo::empty();
+    info.dynamic_game_objects_per_page = 8;
+    info.static_chunks = 1;
+    info.static_game_objects_per_page = 8;
+    info.components_per_page = 8;
+    info.registry = Some(Arc::new(
+        Registry::new(
+            Vec::new(),
+            vec![
+                Registry::script::<TestScriptA>().unwrap(),
+                Registry::script::<TestScriptB>().unwrap(),
+            ],
+        )
+        .unwrap(),
+    ));
+    info
+}
+
+#[derive(Debug, Default)]
+struct TestScriptA;
+
+#[derive(Debug, Default)]
+struct TestScriptB;
+
+macro_rules! impl_test_script {
+    ($script:ident) => {
+        impl Script for $script {
+            fn start(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+                Ok(())
+            }
+
+            fn update(&mut self, _data: ScriptUpdateData) -> RisResult<()> {
+                Ok(())
+            }
+
+            fn end(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+                Ok(())
+            }
+
+            fn serialize(&mut self, _stream: &mut SceneWriter) -> RisResult<()> {
+                ris_error::new_result!("not implemented")
+            }
+
+            fn deserialize(&mut self, _stream: &mut SceneReader) -> RisResult<()> {
+                ris_error::new_result!("not implemented")
+            }
+
+            fn inspect(&mut self, _data: ScriptInspectData) -> RisResult<()> {
+                ris_error::new_result!("not implemented")
+            }
+        }
+    };
+}
+
+impl_test_script!(TestScriptA);
+impl_test_script!(TestScriptB);
+
+#[test]
+fn should_query_all_active_game_objects() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g0 = GameObjectHandle::new(&scene).unwrap();
+    let g1 = GameObjectHandle::new(&scene).unwrap();
+    let g2 = GameObjectHandle::new_static(&scene, 0).unwrap();
+    let g3 = GameObjectHandle::new(&scene).unwrap();
+    g3.destroy(&scene);
+
+    let actual = scene.query().game_objects().unwrap();
+
+    assert_eq!(actual, vec![g0, g1, g2]);
+}
+
+#[test]
+fn should_not_query_chunk_out_of_bounds() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    GameObjectHandle::new_static(&scene, 0).unwrap();
+
+    let result = scene
+        .query()
+        .in_kind(GameObjectKind::Static { chunk: 1 })
+        .game_objects();
+
+    assert!(matches!(result, Err(EcsError::OutOfBounds)));
+}
+
+#[test]
+fn should_not_query_inactive_game_objects() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g0 = GameObjectHandle::new(&scene).unwrap();
+    let g1 = GameObjectHandle::new(&scene).unwrap();
+    let g2 = GameObjectHandle::new(&scene).unwrap();
+    g1.set_parent(&scene, Some(g0), 0).unwrap();
+    g0.set_active(&scene, false).unwrap();
+
+    let active = scene.query().game_objects().unwrap();
+    let all = scene.query().include_inactive().game_objects().unwrap();
+
+    assert_eq!(active, vec![g2]);
+    assert_eq!(all, vec![g0, g1, g2]);
+}
+
+#[test]
+fn should_query_game_objects_with_components() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g0 = GameObjectHandle::new(&scene).unwrap();
+    let g1 = GameObjectHandle::new(&scene).unwrap();
+    let g2 = GameObjectHandle::new(&scene).unwrap();
+    let g3 = GameObjectHandle::new(&scene).unwrap();
+
+    g0.add_component::<MeshComponent>(&scene).unwrap();
+    g1.add_script::<TestScriptA>(&scene).unwrap();
+    g2.add_component::<MeshComponent>(&scene).unwrap();
+    g2.add_script::<TestScriptA>(&scene).unwrap();
+    g3.add_component::<MeshComponent>(&scene).unwrap();
+    g3.add_script::<TestScriptB>(&scene).unwrap();
+
+    let meshes = scene
+        .query()
+        .with::<MeshComponent>()
+        .game_objects()
+        .unwrap();
+    let scripts = scene
+        .query()
+        .with_script::<TestScriptA>()
+        .game_objects()
+        .unwrap();
+    let both = scene
+        .query()
+        .with::<MeshComponent>()
+        .with_script::<TestScriptA>()
+        .game_objects()
+        .unwrap();
+
+    assert_eq!(meshes, vec![g0, g2, g3]);
+    assert_eq!(scripts, vec![g1, g2]);
+    assert_eq!(both, vec![g2]);
+}
+
+#[test]
+fn should_query_components() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g0 = GameObjectHandle::new(&scene).unwrap();
+    let g1 = GameObjectHandle::new(&scene).unwrap();
+    let g2 = GameObjectHandle::new(&scene).unwrap();
+
+    let m0: MeshComponentHandle = g0.add_component(&scene).unwrap().into();
+    let _m1: MeshComponentHandle = g1.add_component(&scene).unwrap().into();
+    let m2: MeshComponentHandle = g2.add_component(&scene).unwrap().into();
+    g2.add_script::<TestScriptB>(&scene).unwrap();
+    g1.set_active(&scene, false).unwrap();
+
+    let all: Vec<MeshComponentHandle> = scene
+        .query()
+        .components::<MeshComponent>()
+        .unwrap()
+        .into_iter()
+        .map(|x| x.into())
+        .collect();
+    let with_script: Vec<MeshComponentHandle> = scene
+        .query()
+        .with_script::<TestScriptB>()
+        .components::<MeshComponent>()
+        .unwrap()
+        .into_iter()
+        .map(|x| x.into())
+        .collect();
+
+    assert_eq!(all, vec![m0, m2]);
+    assert_eq!(with_script, vec![m2]);
+}
+
+#[test]
+fn should_query_scripts() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g0 = GameObjectHandle::new(&scene).unwrap();
+    let g1 = GameObjectHandle::new(&scene).unwrap();
+
+    let a0 = g0.add_script::<TestScriptA>(&scene).unwrap();
+    let _b0 = g0.add_script::<TestScriptB>(&scene).unwrap();
+    let a1 = g1.add_script::<TestScriptA>(&scene).unwrap();
+    let a2 = g1.add_script::<TestScriptA>(&scene).unwrap();
+
+    let actual = scene
+        .query()
+        .scripts::<TestScriptA>()
+        .unwrap()
+        .into_iter()
+        .map(|x| x.dyn_handle())
+        .collect::<Vec<_>>();
+    let expected = vec![a0.dyn_handle(), a1.dyn_handle(), a2.dyn_handle()];
+
+    assert_eq!(actual, expected);
+}
+
+#[test]
+fn should_query_roots_in_kind() {
+    let scene = Scene::new(scene_create_info()).unwrap();
+    let g0 = GameObjectHandle::new(&scene).unwrap();
+    let g1 = GameObjectHandle::new(&scene).unwrap();
+    let s0 = GameObjectHandle::new_static(&scene, 0).unwrap();
+    let s1 = GameObjectHandle::new_static(&scene, 0).unwrap();
+    g1.set_parent(&scene, Some(g0), 0).unwrap();
+    s1.set_parent(&scene, Some(s0), 0).unwrap();
+
+    let dynamic_roots = scene
+        .query()
+        .in_kind(GameObjectKind::Dynamic)
+        .roots_only()
+        .game_objects()
+        .unwrap();
+    let static_objects = scene
+        .query()
+        .in_kind(GameObjectKind::Static { chunk: 0 })
+        .game_objects()
+        .unwrap();
+
+    assert_eq!(dynamic_roots, vec![g0]);
+    assert_eq!(static_objects, vec![s0, s1]);
+}
@@ -0,0 +1,340 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use ris_async::ThreadPool;
+use ris_async::ThreadPoolCreateInfo;
+use ris_data::ecs::decl::DynScriptComponentHandle;
+use ris_data::ecs::decl::GameObjectHandle;
+use ris_data::ecs::handle::ComponentHandle;
+use ris_data::ecs::registry::Registry;
+use ris_data::ecs::scene::SceneCreateInfo;
+use ris_data::ecs::schedule::ScriptSchedule;
+use ris_data::ecs::script_prelude::*;
+use ris_data::gameloop::fixed_frame::FixedFrameAccumulator;
+use ris_data::gameloop::frame::FrameCalculator;
+use ris_data::god_state::GodState;
+use ris_data::settings::Settings;
+use ris_data::settings::simulation_settings::SimulationSettings;
+
+#[derive(Debug, Default)]
+struct MoveScript {
+    phase: usize,
+    target: Option<GameObjectHandle>,
+}
+
+#[derive(Debug, Default)]
+struct ObserverScript {
+    value: f32,
+    fixed_steps: usize,
+}
+
+#[derive(Debug, Default)]
+struct ExclusiveScript;
+
+impl Script for MoveScript {
+    fn start(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, data: ScriptUpdateData) -> RisResult<()> {
+        let scene = &data.state.scene;
+        let target = self.target.unwrap_or(data.game_object);
+        let position = target.position(scene)?;
+        target.set_position(scene, position + ris_math::vector::Vec3::init(1.0))?;
+        Ok(())
+    }
+
+    fn end(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn serialize(&mut self, _stream: &mut SceneWriter) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn deserialize(&mut self, _stream: &mut SceneReader) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn inspect(&mut self, _data: ScriptInspectData) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn access(&self, game_object: GameObjectHandle) -> ScriptAccess {
+        ScriptAccess {
+            phase: self.phase,
+            writes: vec![ScriptResource::GameObject(
+                self.target.unwrap_or(game_object),
+            )],
+            ..Default::default()
+        }
+    }
+}
+
+impl Script for ObserverScript {
+    fn start(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, _data: ScriptUpdateData) -> RisResult<()> {
+        self.value += 1.0;
+        Ok(())
+    }
+
+    fn fixed_update(&mut self, _data: ScriptFixedUpdateData) -> RisResult<()> {
+        self.fixed_steps += 1;
+        Ok(())
+    }
+
+    fn end(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn serialize(&mut self, _stream: &mut SceneWriter) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn deserialize(&mut self, _stream: &mut SceneReader) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn inspect(&mut self, _data: ScriptInspectData) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn access(&self, _game_object: GameObjectHandle) -> ScriptAccess {
+        ScriptAccess {
+            reads: vec![ScriptResource::scripts::<MoveScript>()],
+            ..Default::default()
+        }
+    }
+}
+
+impl Script for ExclusiveScript {
+    fn start(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn update(&mut self, _data: ScriptUpdateData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn end(&mut self, _data: ScriptStartEndData) -> RisResult<()> {
+        Ok(())
+    }
+
+    fn serialize(&mut self, _stream: &mut SceneWriter) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn deserialize(&mut self, _stream: &mut SceneReader) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+
+    fn inspect(&mut self, _data: ScriptInspectData) -> RisResult<()> {
+        ris_error::new_result!("not implemented")
+    }
+}
+
+fn god_state() -> GodState {
+    let mut info = SceneCreateInfo::empty();
+    info.dynamic_game_objects_per_page = 8;
+    info.components_per_page = 8;
+    info.registry = Some(Arc::new(
+        Registry::new(
+            Vec::new(),
+            vec![
+                Registry::script::<MoveScript>().unwrap(),
+                Registry::script::<ObserverScript>().unwrap(),
+                Registry::script::<ExclusiveScript>().unwrap(),
+            ],
+        )
+        .unwrap(),
+    ));
+
+    GodState::new(Settings::default(), info).unwrap()
+}
+
+fn add_move_script(
+    state: &GodState,
+    game_object: GameObjectHandle,
+    phase: usize,
+    target: Option<GameObjectHandle>,
+) -> DynScriptComponentHandle {
+    let scene = &state.scene;
+    let script = game_object.add_script::<MoveScript>(scene).unwrap();
+    let mut script_mut = script.script_mut(scene).unwrap();
+    script_mut.phase = phase;
+    script_mut.target = target;
+    script.dyn_handle()
+}
+
+#[test]
+fn should_batch_scripts_without_conflicts() {
+    let state = god_state();
+    let scene = &state.scene;
+
+    let mut scripts = Vec::new();
+    for _ in 0..4 {
+        let g = GameObjectHandle::new(scene).unwrap();
+        scripts.push(add_move_script(&state, g, 0, None));
+    }
+
+    let schedule = ScriptSchedule::new(scene, &scripts).unwrap();
+
+    assert_eq!(schedule.batches().len(), 1);
+    assert_eq!(schedule.batches()[0], scripts);
+}
+
+#[test]
+fn should_separate_scripts_writing_the_same_game_object() {
+    let state = god_state();
+    let scene = &state.scene;
+    let g0 = GameObjectHandle::new(scene).unwrap();
+    let g1 = GameObjectHandle::new(scene).unwrap();
+
+    let s0 = add_move_script(&state, g0, 0, None);
+    let s1 = add_move_script(&state, g1, 0, Some(g0));
+    let s2 = add_move_script(&state, g1, 0, None);
+
+    let schedule = ScriptSchedule::new(scene, &[s0, s1, s2]).unwrap();
+
+    assert_eq!(schedule.batches(), &[vec![s0, s2], vec![s1]]);
+}
+
+#[test]
+fn should_run_exclusive_scripts_alone() {
+    let state = god_state();
+    let scene = &state.scene;
+    let g0 = GameObjectHandle::new(scene).unwrap();
+    let g1 = GameObjectHandle::new(scene).unwrap();
+
+    let s0 = add_move_script(&state, g0, 0, None);
+    let s1 = g1
+        .add_script::<ExclusiveScript>(scene)
+        .unwrap()
+        .dyn_handle();
+    let s2 = add_move_script(&state, g1, 0, None);
+
+    let schedule = ScriptSchedule::new(scene, &[s0, s1, s2]).unwrap();
+
+    assert_eq!(schedule.batches(), &[vec![s0], vec![s1], vec![s2]]);
+}
+
+#[test]
+fn should_order_phases() {
+    let state = god_state();
+    let scene = &state.scene;
+    let g0 = GameObjectHandle::new(scene).unwrap();
+    let g1 = GameObjectHandle::new(scene).unwrap();
+
+    let s0 = add_move_script(&state, g0, 1, None);
+    let s1 = add_move_script(&state, g1, 0, None);
+
+    let schedule = ScriptSchedule::new(scene, &[s0, s1]).unwrap();
+
+    assert_eq!(schedule.batches(), &[vec![s1], vec![s0]]);
+}
+
+#[test]
+fn should_separate_scripts_reading_other_scripts() {
+    let state = god_state();
+    let scene = &state.scene;
+    let g0 = GameObjectHandle::new(scene).unwrap();
+    let g1 = GameObjectHandle::new(scene).unwrap();
+
+    let s0 = add_move_script(&state, g0, 0, None);
+    let s1 = g1.add_script::<ObserverScript>(scene).unwrap().dyn_handle();
+    let s2 = g1.add_script::<ObserverScript>(scene).unwrap().dyn_handle();
+
+    let schedule = ScriptSchedule::new(scene, &[s0, s1, s2]).unwrap();
+
+    assert_eq!(schedule.batches(), &[vec![s0], vec![s1, s2]]);
+}
+
+#[test]
+fn should_skip_destroyed_scripts() {
+    let state = god_state();
+    let scene = &state.scene;
+    let g0 = GameObjectHandle::new(scene).unwrap();
+    let g1 = GameObjectHandle::new(scene).unwrap();
+
+    let s0 = add_move_script(&state, g0, 0, None);
+    let s1 = add_move_script(&state, g1, 0, None);
+    s0.destroy(scene);
+
+    let schedule = ScriptSchedule::new(scene, &[s0, s1]).unwrap();
+
+    assert_eq!(schedule.batches(), &[vec![s1]]);
+}
+
+#[test]
+fn should_run_on_thread_pool() {
+    let count = ris_util::testing::miri_choose(64, 4);
+
+    let create_info = ThreadPoolCreateInfo {
+        buffer_capacity: 64,
+        cpu_count: 4,
+        threads: 4,
+        set_affinity: false,
+        use_parking: false,
+    };
+    let _g = ThreadPool::init(create_info).unwrap();
+
+    let state = god_state();
+    let scene = &state.scene;
+
+    let mut game_objects = Vec::new();
+    let mut scripts = Vec::new();
+    for _ in 0..count {
+        let g = GameObjectHandle::new(scene).unwrap();
+        game_objects.push(g);
+        scripts.push(add_move_script(&state, g, 0, None));
+        scripts.push(add_move_script(&state, g, 1, None));
+    }
+
+    let frame = FrameCalculator::default().bump_and_create_frame(&SimulationSettings::default());
+    let schedule = ScriptSchedule::new(scene, &scripts).unwrap();
+    schedule.run(frame, &state).unwrap();
+
+    assert_eq!(schedule.batches().len(), 2);
+    for g in game_objects {
+        let position = g.position(scene).unwrap();
+        assert_eq!(position, ris_math::vector::Vec3::init(2.0));
+    }
+}
+
+#[test]
+fn should_run_fixed_update() {
+    let create_info = ThreadPoolCreateInfo {
+        buffer_capacity: 64,
+        cpu_count: 2,
+        threads: 2,
+        set_affinity: false,
+        use_parking: false,
+    };
+    let _g = ThreadPool::init(create_info).unwrap();
+
+    let state = god_state();
+    let scene = &state.scene;
+    let g = GameObjectHandle::new(scene).unwrap();
+    let observer = g.add_script::<ObserverScript>(scene).unwrap();
+    let mover = add_move_script(&state, g, 0, None);
+
+    let mut accumulator = FixedFrameAccumulator::default();
+    let fixed_frames = accumulator
+        .bump_and_create_fixed_frames(Duration::from_millis(70), state.settings.simulation());
+
+    let schedule = ScriptSchedule::new(scene, &[observer.dyn_handle(), mover]).unwrap();
+    for fixed_frame in fixed_frames {
+        schedule.run_fixed(fixed_frame, &state).unwrap();
+    }
+
+    assert_eq!(observer.script(scene).unwrap().fixed_steps, 3);
+    assert_eq!(observer.script(scene).unwrap().value, 0.0);
+    // move script only implements update
+    assert_eq!(
+        g.position(scene).unwrap(),
+        ris_math::vector::Vec3::init(0.0)
+    );
+}
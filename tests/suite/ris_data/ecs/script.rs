@@ -9,13 +9,16 @@ use ris_data::ecs::script_prelude::*;
 
 fn scene_create_info() -> SceneCreateInfo {
     let mut info = SceneCreateInfo::empty();
-    info.dynamic_game_objects = 5;
-    info.script_components = 5;
+    info.dynamic_game_objects_per_page = 5;
+    info.components_per_page = 5;
     info.registry = Some(Arc::new(
-        Registry::new(vec![
-            Registry::script::<TestScriptString>().unwrap(),
-            Registry::script::<TestScriptISize>().unwrap(),
-        ])
+        Registry::new(
+            Vec::new(),
+            vec![
+                Registry::script::<TestScriptString>().unwrap(),
+                Registry::script::<TestScriptISize>().unwrap(),
+            ],
+        )
         .unwrap(),
     ));
     info
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use ris_data::gameloop::fixed_frame::FixedFrameAccumulator;
+use ris_data::settings::simulation_settings::SimulationSettings;
+
+fn settings() -> SimulationSettings {
+    let mut settings = SimulationSettings::default();
+    settings.set_fixed_rate(50);
+    settings.set_max_fixed_steps(4);
+    settings
+}
+
+#[test]
+fn should_not_step_before_step_has_passed() {
+    let settings = settings();
+    let mut accumulator = FixedFrameAccumulator::default();
+
+    let fixed_frames =
+        accumulator.bump_and_create_fixed_frames(Duration::from_millis(10), &settings);
+
+    assert!(fixed_frames.is_empty());
+    assert!((fixed_frames.alpha() - 0.5).abs() < 0.001);
+}
+
+#[test]
+fn should_accumulate_until_step_has_passed() {
+    let settings = settings();
+    let mut accumulator = FixedFrameAccumulator::default();
+
+    let fixed_frames_1 =
+        accumulator.bump_and_create_fixed_frames(Duration::from_millis(15), &settings);
+    let fixed_frames_2 =
+        accumulator.bump_and_create_fixed_frames(Duration::from_millis(15), &settings);
+
+    assert_eq!(fixed_frames_1.len(), 0);
+    assert_eq!(fixed_frames_2.len(), 1);
+    assert!((fixed_frames_2.alpha() - 0.5).abs() < 0.001);
+}
+
+#[test]
+fn should_step_multiple_times_in_a_long_frame() {
+    let settings = settings();
+    let mut accumulator = FixedFrameAccumulator::default();
+
+    let fixed_frames =
+        accumulator.bump_and_create_fixed_frames(Duration::from_millis(65), &settings);
+
+    assert_eq!(fixed_frames.len(), 3);
+    for fixed_frame in fixed_frames {
+        assert_eq!(fixed_frame.step_duration(), Duration::from_millis(20));
+    }
+    assert!((fixed_frames.alpha() - 0.25).abs() < 0.001);
+}
+
+#[test]
+fn should_number_fixed_frames_continuously() {
+    let settings = settings();
+    let mut accumulator = FixedFrameAccumulator::default();
+
+    let mut numbers = Vec::new();
+    for _ in 0..10 {
+        let fixed_frames =
+            accumulator.bump_and_create_fixed_frames(Duration::from_millis(30), &settings);
+        numbers.extend(fixed_frames.iter().map(|x| x.number()));
+    }
+
+    let expected = (0..15).collect::<Vec<_>>();
+    assert_eq!(numbers, expected);
+}
+
+#[test]
+fn should_drop_time_when_exceeding_max_steps() {
+    let settings = settings();
+    let mut accumulator = FixedFrameAccumulator::default();
+
+    let fixed_frames_1 =
+        accumulator.bump_and_create_fixed_frames(Duration::from_millis(1_010), &settings);
+    let fixed_frames_2 =
+        accumulator.bump_and_create_fixed_frames(Duration::from_millis(5), &settings);
+
+    assert_eq!(fixed_frames_1.len(), 4);
+    assert!((fixed_frames_1.alpha() - 0.5).abs() < 0.001);
+    assert_eq!(fixed_frames_2.len(), 0);
+    assert!((fixed_frames_2.alpha() - 0.75).abs() < 0.001);
+}
+
+#[test]
+fn should_not_depend_on_frame_rate() {
+    let settings = settings();
+    let mut slow = FixedFrameAccumulator::default();
+    let mut fast = FixedFrameAccumulator::default();
+
+    let mut slow_steps = 0;
+    for _ in 0..30 {
+        slow_steps += slow
+            .bump_and_create_fixed_frames(Duration::from_micros(33_333), &settings)
+            .len();
+    }
+
+    let mut fast_steps = 0;
+    for _ in 0..144 {
+        fast_steps += fast
+            .bump_and_create_fixed_frames(Duration::from_micros(6_944), &settings)
+            .len();
+    }
+
+    assert_eq!(slow_steps, 49);
+    assert_eq!(fast_steps, 49);
+}
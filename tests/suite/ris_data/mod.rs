@@ -3,6 +3,7 @@ pub mod ecs;
 pub mod buttons;
 pub mod cell;
 pub mod counter;
+pub mod fixed_frame;
 pub mod keys;
 pub mod ptr;
 pub mod ris_map;
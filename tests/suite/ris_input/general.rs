@@ -21,7 +21,7 @@ impl TestContext {
 
         let mut scene_create_info =
             miri_choose(SceneCreateInfo::default(), SceneCreateInfo::empty());
-        scene_create_info.registry = Some(Arc::new(Registry::new(Vec::new()).unwrap()));
+        scene_create_info.registry = Some(Arc::new(Registry::new(Vec::new(), Vec::new()).unwrap()));
 
         let state = GodState::new(Settings::default(), scene_create_info).unwrap();
 
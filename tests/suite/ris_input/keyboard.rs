@@ -0,0 +1,43 @@
+use sdl2::keyboard::Mod;
+use sdl2::keyboard::Scancode;
+
+use ris_data::input::keyboard_data::KeyboardData;
+use ris_input::keyboard_logic;
+
+fn press(mod_state: Mod, scancode: Scancode) -> KeyboardData {
+    let mut keyboard_data = KeyboardData::default();
+    keyboard_data.keys.set(scancode);
+    keyboard_data.mod_state = mod_state;
+    keyboard_data
+}
+
+#[test]
+fn should_detect_ctrl_shortcut() {
+    let left = press(Mod::LCTRLMOD, Scancode::Z);
+    let right = press(Mod::RCTRLMOD | Mod::NUMMOD, Scancode::Z);
+
+    assert!(keyboard_logic::ctrl_shortcut_is_down(&left, Scancode::Z));
+    assert!(keyboard_logic::ctrl_shortcut_is_down(&right, Scancode::Z));
+    assert!(!keyboard_logic::ctrl_shortcut_is_down(&left, Scancode::Y));
+}
+
+#[test]
+fn should_not_detect_ctrl_shortcut_without_ctrl() {
+    let no_mod = press(Mod::NOMOD, Scancode::Z);
+    let shift = press(Mod::LSHIFTMOD, Scancode::Z);
+
+    assert!(!keyboard_logic::ctrl_shortcut_is_down(&no_mod, Scancode::Z));
+    assert!(!keyboard_logic::ctrl_shortcut_is_down(&shift, Scancode::Z));
+}
+
+#[test]
+fn should_not_detect_ctrl_shortcut_when_key_is_hold() {
+    let mut keyboard_data = press(Mod::LCTRLMOD, Scancode::Z);
+    keyboard_data.keys.clear();
+    keyboard_data.keys.set(Scancode::Z);
+
+    assert!(!keyboard_logic::ctrl_shortcut_is_down(
+        &keyboard_data,
+        Scancode::Z
+    ));
+}
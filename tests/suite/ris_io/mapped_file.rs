@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use ris_io::mapped_file::MappedFile;
+
+#[test]
+fn should_map_file() {
+    let bytes = (0..10_000).map(|x| (x % 251) as u8).collect::<Vec<_>>();
+    let path = ris_util::prep_test_dir!().join("bytes");
+    std::fs::write(&path, &bytes).unwrap();
+
+    let file = Arc::new(MappedFile::open(&path).unwrap());
+    assert_eq!(&file[..], &bytes[..]);
+
+    let handles = (0..4)
+        .map(|i| {
+            let file = file.clone();
+            std::thread::spawn(move || file[i * 1000..(i + 1) * 1000].to_vec())
+        })
+        .collect::<Vec<_>>();
+    for (i, handle) in handles.into_iter().enumerate() {
+        assert_eq!(handle.join().unwrap(), &bytes[i * 1000..(i + 1) * 1000]);
+    }
+}
+
+#[test]
+fn should_map_empty_file() {
+    let path = ris_util::prep_test_dir!().join("empty");
+    std::fs::write(&path, []).unwrap();
+
+    let file = MappedFile::open(&path).unwrap();
+    assert!(file.is_empty());
+}
+
+#[test]
+fn should_not_map_missing_file() {
+    let path = ris_util::prep_test_dir!().join("missing");
+    assert!(MappedFile::open(path).is_err());
+}
@@ -3,3 +3,5 @@ pub mod fallback_file_append;
 #[cfg(not(miri))]
 pub mod fallback_file_overwrite;
 pub mod io;
+#[cfg(not(miri))]
+pub mod mapped_file;
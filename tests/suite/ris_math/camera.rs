@@ -0,0 +1,27 @@
+use ris_math::camera::Camera;
+use ris_math::vector::Vec3;
+use ris_util::assert_feq;
+
+#[test]
+fn should_compute_screen_coverage() {
+    let camera = Camera {
+        fovy: 90f32.to_radians(),
+        ..Default::default()
+    };
+
+    // the silhouette of the sphere is 45 degrees away from its center
+    let center = Vec3(0.0, f32::sqrt(2.0), 0.0);
+    assert_feq!(camera.screen_coverage(center, 1.0), 1.0, 0.0001);
+
+    // far away, the coverage is inversely proportional to the distance
+    let near = camera.screen_coverage(Vec3(0.0, 100.0, 0.0), 1.0);
+    let far = camera.screen_coverage(Vec3(0.0, 200.0, 0.0), 1.0);
+    assert_feq!(near, 0.01, 0.0001);
+    assert_feq!(far * 2.0, near, 0.0001);
+
+    // the direction doesn't matter
+    let behind = camera.screen_coverage(Vec3(0.0, -100.0, 0.0), 1.0);
+    assert_feq!(behind, near, 0.0001);
+
+    assert_eq!(camera.screen_coverage(Vec3::init(0.5), 1.0), f32::INFINITY);
+}
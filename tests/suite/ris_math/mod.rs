@@ -1,4 +1,5 @@
 pub mod affine;
+pub mod camera;
 pub mod color;
 pub mod fast;
 pub mod matrix;
@@ -105,3 +105,34 @@ fn should_rotate_around_backward() {
     assert!(result.y() == 1.);
     assert!(result.z() == 0.);
 }
+
+#[test]
+fn should_slerp_between_rotations() {
+    let from = Quat::identity();
+    let to = Quat::angle_axis(0.5 * PI, Vec3::up());
+
+    let start = from.slerp(to, 0.0);
+    let end = from.slerp(to, 1.0);
+    let half = from.slerp(to, 0.5);
+    let expected = Quat::angle_axis(0.25 * PI, Vec3::up());
+
+    for i in 0..4 {
+        assert_feq!(start[i], from[i]);
+        assert_feq!(end[i], to[i]);
+        assert_feq!(half[i], expected[i]);
+    }
+}
+
+#[test]
+fn should_slerp_along_shortest_path() {
+    let from = Quat::identity();
+    let to = Quat::angle_axis(0.5 * PI, Vec3::up());
+    let negated_to = Quat(-to.0, -to.1, -to.2, -to.3);
+
+    let half = from.slerp(to, 0.5);
+    let negated_half = from.slerp(negated_to, 0.5);
+
+    for i in 0..4 {
+        assert_feq!(half[i], negated_half[i]);
+    }
+}